@@ -72,6 +72,22 @@ pub fn subagent_session_info_from_meta(meta: &Option<acp::Meta>) -> Option<Subag
         .and_then(|v| serde_json::from_value(v.clone()).ok())
 }
 
+/// Key used in ACP session info meta to store the session a thread was forked from
+pub const FORKED_FROM_META_KEY: &str = "forked_from";
+
+/// Helper to create meta with the session a thread was forked from
+pub fn meta_with_forked_from(session_id: &acp::SessionId) -> acp::Meta {
+    acp::Meta::from_iter([(FORKED_FROM_META_KEY.into(), session_id.to_string().into())])
+}
+
+/// Helper to extract the session a thread was forked from from ACP meta
+pub fn forked_from_from_meta(meta: &Option<acp::Meta>) -> Option<acp::SessionId> {
+    meta.as_ref()
+        .and_then(|m| m.get(FORKED_FROM_META_KEY))
+        .and_then(|v| v.as_str())
+        .map(|s| acp::SessionId::new(s.to_owned()))
+}
+
 #[derive(Debug)]
 pub struct UserMessage {
     pub id: Option<UserMessageId>,
@@ -158,6 +174,7 @@ pub enum AgentThreadEntry {
     UserMessage(UserMessage),
     AssistantMessage(AssistantMessage),
     ToolCall(ToolCall),
+    Compaction(Compaction),
 }
 
 impl AgentThreadEntry {
@@ -165,7 +182,7 @@ impl AgentThreadEntry {
         match self {
             Self::UserMessage(message) => message.indented,
             Self::AssistantMessage(message) => message.indented,
-            Self::ToolCall(_) => false,
+            Self::ToolCall(_) | Self::Compaction(_) => false,
         }
     }
 
@@ -174,6 +191,7 @@ impl AgentThreadEntry {
             Self::UserMessage(message) => message.to_markdown(cx),
             Self::AssistantMessage(message) => message.to_markdown(cx),
             Self::ToolCall(tool_call) => tool_call.to_markdown(cx),
+            Self::Compaction(compaction) => compaction.to_markdown(cx),
         }
     }
 
@@ -218,6 +236,18 @@ impl AgentThreadEntry {
     }
 }
 
+/// Marks the point at which the agent replaced older messages with a summary.
+#[derive(Debug)]
+pub struct Compaction {
+    pub summary: Entity<Markdown>,
+}
+
+impl Compaction {
+    fn to_markdown(&self, cx: &App) -> String {
+        format!("## Compacted\n\n{}\n\n", self.summary.read(cx).source())
+    }
+}
+
 #[derive(Debug)]
 pub struct ToolCall {
     pub id: acp::ToolCallId,
@@ -232,6 +262,8 @@ pub struct ToolCall {
     pub raw_output: Option<serde_json::Value>,
     pub tool_name: Option<SharedString>,
     pub subagent_session_info: Option<SubagentSessionInfo>,
+    /// A pending request from the tool for structured input from the user.
+    pub elicitation: Option<ToolCallElicitation>,
 }
 
 impl ToolCall {
@@ -286,6 +318,7 @@ impl ToolCall {
             raw_output: tool_call.raw_output,
             tool_name,
             subagent_session_info,
+            elicitation: None,
         };
         Ok(result)
     }
@@ -316,6 +349,9 @@ impl ToolCall {
 
         if let Some(status) = status {
             self.status = status.into();
+            if !matches!(self.status, ToolCallStatus::InProgress) {
+                self.elicitation = None;
+            }
         }
 
         if let Some(subagent_session_info) = subagent_session_info_from_meta(&meta) {
@@ -510,6 +546,27 @@ pub enum ToolCallStatus {
     Canceled,
 }
 
+/// A request for structured input, raised by a tool while it is running.
+///
+/// The schema is a flat JSON Schema object whose properties are strings, numbers,
+/// integers, booleans or string enums, as described by MCP's `elicitation/create`.
+#[derive(Debug)]
+pub struct ToolCallElicitation {
+    pub message: SharedString,
+    pub requested_schema: serde_json::Value,
+    respond_tx: oneshot::Sender<ElicitationOutcome>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElicitationOutcome {
+    /// The user submitted the form with the given values.
+    Accept(serde_json::Map<String, serde_json::Value>),
+    /// The user explicitly declined to provide the requested information.
+    Decline,
+    /// The user dismissed the request without making a choice.
+    Cancel,
+}
+
 impl From<acp::ToolCallStatus> for ToolCallStatus {
     fn from(status: acp::ToolCallStatus) -> Self {
         match status {
@@ -1273,7 +1330,13 @@ impl AcpThread {
                     status: ToolCallStatus::WaitingForConfirmation { .. },
                     ..
                 }) => return true,
-                AgentThreadEntry::ToolCall(_) | AgentThreadEntry::AssistantMessage(_) => {}
+                AgentThreadEntry::ToolCall(ToolCall {
+                    elicitation: Some(_),
+                    ..
+                }) => return true,
+                AgentThreadEntry::ToolCall(_)
+                | AgentThreadEntry::AssistantMessage(_)
+                | AgentThreadEntry::Compaction(_) => {}
             }
         }
         false
@@ -1295,7 +1358,9 @@ impl AcpThread {
                 ) if call.diffs().next().is_some() => {
                     return true;
                 }
-                AgentThreadEntry::ToolCall(_) | AgentThreadEntry::AssistantMessage(_) => {}
+                AgentThreadEntry::ToolCall(_)
+                | AgentThreadEntry::AssistantMessage(_)
+                | AgentThreadEntry::Compaction(_) => {}
             }
         }
 
@@ -1312,7 +1377,9 @@ impl AcpThread {
                 }) => {
                     return true;
                 }
-                AgentThreadEntry::ToolCall(_) | AgentThreadEntry::AssistantMessage(_) => {}
+                AgentThreadEntry::ToolCall(_)
+                | AgentThreadEntry::AssistantMessage(_)
+                | AgentThreadEntry::Compaction(_) => {}
             }
         }
 
@@ -1323,7 +1390,9 @@ impl AcpThread {
         for entry in self.entries.iter().rev() {
             match entry {
                 AgentThreadEntry::UserMessage(..) => return false,
-                AgentThreadEntry::AssistantMessage(..) => continue,
+                AgentThreadEntry::AssistantMessage(..) | AgentThreadEntry::Compaction(..) => {
+                    continue;
+                }
                 AgentThreadEntry::ToolCall(..) => return true,
             }
         }
@@ -1484,6 +1553,12 @@ impl AcpThread {
         }
     }
 
+    pub fn push_compaction(&mut self, summary: String, cx: &mut Context<Self>) {
+        let language_registry = self.project.read(cx).languages().clone();
+        let summary = cx.new(|cx| Markdown::new(summary.into(), Some(language_registry), None, cx));
+        self.push_entry(AgentThreadEntry::Compaction(Compaction { summary }), cx);
+    }
+
     fn push_entry(&mut self, entry: AgentThreadEntry, cx: &mut Context<Self>) {
         self.entries.push(entry);
         cx.emit(AcpThreadEvent::NewEntry);
@@ -1548,6 +1623,7 @@ impl AcpThread {
                     raw_output: None,
                     tool_name: None,
                     subagent_session_info: None,
+                    elicitation: None,
                 };
                 self.push_entry(AgentThreadEntry::ToolCall(failed_tool_call), cx);
                 return Ok(());
@@ -1848,6 +1924,52 @@ impl AcpThread {
         cx.emit(AcpThreadEvent::EntryUpdated(ix));
     }
 
+    pub fn request_tool_call_elicitation(
+        &mut self,
+        tool_call_id: acp::ToolCallId,
+        message: SharedString,
+        requested_schema: serde_json::Value,
+        cx: &mut Context<Self>,
+    ) -> Result<Task<ElicitationOutcome>> {
+        let (ix, call) = self
+            .tool_call_mut(&tool_call_id)
+            .context("tool call not found")?;
+        let (tx, rx) = oneshot::channel();
+        call.elicitation = Some(ToolCallElicitation {
+            message,
+            requested_schema,
+            respond_tx: tx,
+        });
+        cx.emit(AcpThreadEvent::EntryUpdated(ix));
+        cx.emit(AcpThreadEvent::ToolAuthorizationRequested(
+            tool_call_id.clone(),
+        ));
+
+        Ok(cx.spawn(async move |this, cx| {
+            let outcome = rx.await.unwrap_or(ElicitationOutcome::Cancel);
+            this.update(cx, |_this, cx| {
+                cx.emit(AcpThreadEvent::ToolAuthorizationReceived(tool_call_id))
+            })
+            .ok();
+            outcome
+        }))
+    }
+
+    pub fn respond_to_elicitation(
+        &mut self,
+        id: acp::ToolCallId,
+        outcome: ElicitationOutcome,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((ix, call)) = self.tool_call_mut(&id) else {
+            return;
+        };
+        if let Some(elicitation) = call.elicitation.take() {
+            elicitation.respond_tx.send(outcome).ok();
+        }
+        cx.emit(AcpThreadEvent::EntryUpdated(ix));
+    }
+
     pub fn plan(&self) -> &Plan {
         &self.plan
     }
@@ -1950,6 +2072,25 @@ impl AcpThread {
         self.connection.retry(&self.session_id, cx).is_some()
     }
 
+    pub fn can_compact(&self, cx: &App) -> bool {
+        self.connection.compact(&self.session_id, cx).is_some()
+    }
+
+    pub fn compact(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> BoxFuture<'static, Result<Option<acp::PromptResponse>>> {
+        self.run_turn(cx, async move |this, cx| {
+            this.update(cx, |this, cx| {
+                this.connection
+                    .compact(&this.session_id, cx)
+                    .map(|compact| compact.run(cx))
+            })?
+            .context("compacting a session is not supported")?
+            .await
+        })
+    }
+
     pub fn retry(
         &mut self,
         cx: &mut Context<Self>,
@@ -2103,6 +2244,7 @@ impl AcpThread {
 
                 if cancel {
                     call.status = ToolCallStatus::Canceled;
+                    call.elicitation = None;
                 }
             }
         }
@@ -119,6 +119,14 @@ pub trait AgentConnection {
         None
     }
 
+    fn compact(
+        &self,
+        _session_id: &acp::SessionId,
+        _cx: &App,
+    ) -> Option<Rc<dyn AgentSessionCompact>> {
+        None
+    }
+
     /// Returns this agent as an [Rc<dyn ModelSelector>] if the model selection capability is supported.
     ///
     /// If the agent does not support model selection, returns [None].
@@ -172,6 +180,10 @@ pub trait AgentSessionSetTitle {
     fn run(&self, title: SharedString, cx: &mut App) -> Task<Result<()>>;
 }
 
+pub trait AgentSessionCompact {
+    fn run(&self, cx: &mut App) -> Task<Result<acp::PromptResponse>>;
+}
+
 pub trait AgentTelemetry {
     /// A representation of the current thread state that can be serialized for
     /// storage with telemetry events.
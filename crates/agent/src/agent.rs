@@ -1,5 +1,6 @@
 mod db;
 mod edit_agent;
+mod hooks;
 mod legacy_thread;
 mod native_agent_server;
 pub mod outline;
@@ -42,8 +43,8 @@ use gpui::{
 use language_model::{IconOrSvg, LanguageModel, LanguageModelProvider, LanguageModelRegistry};
 use project::{Project, ProjectItem, ProjectPath, Worktree};
 use prompt_store::{
-    ProjectContext, PromptStore, RULES_FILE_NAMES, RulesFileContext, UserRulesContext,
-    WorktreeContext,
+    NestedRulesFileContext, ProjectContext, PromptStore, RULES_FILE_NAMES, RulesFileContext,
+    UserRulesContext, WorktreeContext,
 };
 use serde::{Deserialize, Serialize};
 use settings::{LanguageModelSelection, update_settings_file};
@@ -54,6 +55,7 @@ use std::sync::Arc;
 use util::ResultExt;
 use util::path_list::PathList;
 use util::rel_path::RelPath;
+use worktree::Snapshot;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ProjectSnapshot {
@@ -425,8 +427,12 @@ impl NativeAgent {
                     Self::build_project_context(&this.project, this.prompt_store.as_ref(), cx)
                 })?
                 .await;
+            // Update the existing entity so that running threads pick up the new context.
             this.update(cx, |this, cx| {
-                this.project_context = cx.new(|_| project_context);
+                this.project_context.update(cx, |context, cx| {
+                    *context = project_context;
+                    cx.notify();
+                });
             })?;
         }
 
@@ -506,77 +512,120 @@ impl NativeAgent {
         let tree = worktree.read(cx);
         let root_name = tree.root_name_str().into();
         let abs_path = tree.abs_path();
+        let snapshot = tree.snapshot();
 
         let mut context = WorktreeContext {
             root_name,
             abs_path,
             rules_file: None,
+            nested_rules_files: Vec::new(),
         };
 
-        let rules_task = Self::load_worktree_rules_file(worktree, project, cx);
-        let Some(rules_task) = rules_task else {
-            return Task::ready((context, None));
-        };
+        let rules_task = Self::load_rules_file(&worktree, &project, RelPath::empty(), cx);
+        cx.spawn(async move |cx| {
+            // Finding the nested rules files means visiting every entry, which is too slow to
+            // do on the main thread in large worktrees.
+            let nested_rules_directories = cx
+                .background_spawn(async move { nested_rules_directories(&snapshot) })
+                .await;
+            let nested_rules_tasks = cx.update(|cx| {
+                nested_rules_directories
+                    .into_iter()
+                    .filter_map(|directory| {
+                        let task = Self::load_rules_file(&worktree, &project, &directory, cx)?;
+                        Some(async move { (directory, task.await) })
+                    })
+                    .collect::<Vec<_>>()
+            });
 
-        cx.spawn(async move |_| {
-            let (rules_file, rules_file_error) = match rules_task.await {
-                Ok(rules_file) => (Some(rules_file), None),
-                Err(err) => (
-                    None,
-                    Some(RulesLoadingError {
-                        message: format!("{err}").into(),
+            let mut rules_file_error = None;
+            if let Some(rules_task) = rules_task {
+                match rules_task.await {
+                    Ok(rules_file) => context.rules_file = Some(rules_file),
+                    Err(err) => {
+                        rules_file_error = Some(RulesLoadingError {
+                            message: format!("{err}").into(),
+                        })
+                    }
+                }
+            }
+            for (directory, result) in future::join_all(nested_rules_tasks).await {
+                match result {
+                    Ok(rules_file) => context.nested_rules_files.push(NestedRulesFileContext {
+                        directory,
+                        rules_file,
                     }),
-                ),
-            };
-            context.rules_file = rules_file;
+                    Err(err) => {
+                        rules_file_error.get_or_insert(RulesLoadingError {
+                            message: format!("{err}").into(),
+                        });
+                    }
+                }
+            }
             (context, rules_file_error)
         })
     }
 
-    fn load_worktree_rules_file(
-        worktree: Entity<Worktree>,
-        project: Entity<Project>,
+    /// Loads the first of [`RULES_FILE_NAMES`] found in `directory`. A `.clinerules` directory
+    /// is also supported, in which case its files are combined in order.
+    fn load_rules_file(
+        worktree: &Entity<Worktree>,
+        project: &Entity<Project>,
+        directory: &RelPath,
         cx: &mut App,
     ) -> Option<Task<Result<RulesFileContext>>> {
         let worktree = worktree.read(cx);
         let worktree_id = worktree.id();
-        let selected_rules_file = RULES_FILE_NAMES
+        let (path_in_worktree, file_paths) = RULES_FILE_NAMES.iter().find_map(|name| {
+            let entry = worktree.entry_for_path(&directory.join(RelPath::unix(name).unwrap()))?;
+            if entry.is_file() {
+                Some((entry.path.clone(), vec![entry.path.clone()]))
+            } else if entry.is_dir() && *name == ".clinerules" {
+                let mut file_paths = worktree
+                    .child_entries(&entry.path)
+                    .filter(|child| child.is_file())
+                    .map(|child| child.path.clone())
+                    .collect::<Vec<_>>();
+                file_paths.sort();
+                (!file_paths.is_empty()).then(|| (entry.path.clone(), file_paths))
+            } else {
+                None
+            }
+        })?;
+
+        let rope_tasks = file_paths
             .into_iter()
-            .filter_map(|name| {
-                worktree
-                    .entry_for_path(RelPath::unix(name).unwrap())
-                    .filter(|entry| entry.is_file())
-                    .map(|entry| entry.path.clone())
-            })
-            .next();
-
-        // Note that Cline supports `.clinerules` being a directory, but that is not currently
-        // supported. This doesn't seem to occur often in GitHub repositories.
-        selected_rules_file.map(|path_in_worktree| {
-            let project_path = ProjectPath {
-                worktree_id,
-                path: path_in_worktree.clone(),
-            };
-            let buffer_task =
-                project.update(cx, |project, cx| project.open_buffer(project_path, cx));
-            let rope_task = cx.spawn(async move |cx| {
-                let buffer = buffer_task.await?;
-                let (project_entry_id, rope) = buffer.read_with(cx, |buffer, cx| {
-                    let project_entry_id = buffer.entry_id(cx).context("buffer has no file")?;
-                    anyhow::Ok((project_entry_id, buffer.as_rope().clone()))
-                })?;
-                anyhow::Ok((project_entry_id, rope))
-            });
-            // Build a string from the rope on a background thread.
-            cx.background_spawn(async move {
-                let (project_entry_id, rope) = rope_task.await?;
-                anyhow::Ok(RulesFileContext {
-                    path_in_worktree,
-                    text: rope.to_string().trim().to_string(),
-                    project_entry_id: project_entry_id.to_usize(),
+            .map(|path| {
+                let project_path = ProjectPath { worktree_id, path };
+                let buffer_task =
+                    project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+                cx.spawn(async move |cx| {
+                    let buffer = buffer_task.await?;
+                    buffer.read_with(cx, |buffer, cx| {
+                        let project_entry_id = buffer.entry_id(cx).context("buffer has no file")?;
+                        anyhow::Ok((project_entry_id, buffer.as_rope().clone()))
+                    })
                 })
             })
-        })
+            .collect::<Vec<_>>();
+        // Build a string from the ropes on a background thread.
+        Some(cx.background_spawn(async move {
+            let ropes = future::try_join_all(rope_tasks).await?;
+            let project_entry_id = ropes
+                .first()
+                .map(|(project_entry_id, _)| project_entry_id.to_usize())
+                .unwrap_or_default();
+            let text = ropes
+                .iter()
+                .map(|(_, rope)| rope.to_string().trim().to_string())
+                .filter(|text| !text.is_empty())
+                .join("\n\n");
+            anyhow::Ok(RulesFileContext {
+                path_in_worktree,
+                text,
+                project_entry_id,
+            })
+        }))
     }
 
     fn handle_thread_title_updated(
@@ -624,11 +673,7 @@ impl NativeAgent {
                 self.project_context_needs_refresh.send(()).ok();
             }
             project::Event::WorktreeUpdatedEntries(_, items) => {
-                if items.iter().any(|(path, _, _)| {
-                    RULES_FILE_NAMES
-                        .iter()
-                        .any(|name| path.as_ref() == RelPath::unix(name).unwrap())
-                }) {
+                if items.iter().any(|(path, _, _)| is_rules_path(path)) {
                     self.project_context_needs_refresh.send(()).ok();
                 }
             }
@@ -852,15 +897,7 @@ impl NativeAgent {
             return;
         };
 
-        let folder_paths = PathList::new(
-            &self
-                .project
-                .read(cx)
-                .visible_worktrees(cx)
-                .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
-                .collect::<Vec<_>>(),
-        );
-
+        let folder_paths = self.folder_paths(cx);
         let draft_prompt = session.acp_thread.read(cx).draft_prompt().map(Vec::from);
         let database_future = ThreadsDatabase::connect(cx);
         let db_thread = thread.update(cx, |thread, cx| {
@@ -881,6 +918,46 @@ impl NativeAgent {
         });
     }
 
+    /// Saves a copy of the given thread containing the messages before `until` (or all of them)
+    /// as a new thread, returning the new thread's session ID.
+    pub fn fork_thread(
+        &mut self,
+        id: acp::SessionId,
+        until: Option<UserMessageId>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<acp::SessionId>> {
+        let Some(session) = self.sessions.get(&id) else {
+            return Task::ready(Err(anyhow!("Session not found")));
+        };
+        let db_thread = match session.thread.read(cx).to_forked_db(until.as_ref(), cx) {
+            Ok(db_thread) => db_thread,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let folder_paths = self.folder_paths(cx);
+        let thread_store = self.thread_store.clone();
+        cx.spawn(async move |_, cx| {
+            let db_thread = db_thread.await;
+            let forked_id = acp::SessionId::new(uuid::Uuid::new_v4().to_string());
+            thread_store
+                .update(cx, |store, cx| {
+                    store.save_thread(forked_id.clone(), db_thread, folder_paths, cx)
+                })
+                .await?;
+            Ok(forked_id)
+        })
+    }
+
+    fn folder_paths(&self, cx: &App) -> PathList {
+        PathList::new(
+            &self
+                .project
+                .read(cx)
+                .visible_worktrees(cx)
+                .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+                .collect::<Vec<_>>(),
+        )
+    }
+
     fn send_mcp_prompt(
         &self,
         message_id: UserMessageId,
@@ -978,6 +1055,43 @@ impl NativeAgent {
     }
 }
 
+/// If `path` is one of [`RULES_FILE_NAMES`], returns the directory its rules apply to.
+fn rules_file_directory(path: &RelPath) -> Option<&RelPath> {
+    RULES_FILE_NAMES.iter().find_map(|name| {
+        let name = RelPath::unix(name).unwrap();
+        if !path.ends_with(name) {
+            return None;
+        }
+        let mut directory = path;
+        for _ in 0..name.len() {
+            directory = directory.parent()?;
+        }
+        Some(directory)
+    })
+}
+
+/// Whether a change to `path` could affect the rules files in the project context.
+fn is_rules_path(path: &RelPath) -> bool {
+    rules_file_directory(path).is_some()
+        || path
+            .parent()
+            .is_some_and(|parent| parent.file_name() == Some(".clinerules"))
+}
+
+/// Returns the subdirectories of the worktree containing rules files, sorted so that parent
+/// directories come before their children.
+fn nested_rules_directories(snapshot: &Snapshot) -> Vec<Arc<RelPath>> {
+    let mut directories = snapshot
+        .entries(false, 0)
+        .filter_map(|entry| rules_file_directory(&entry.path))
+        .filter(|directory| !directory.is_empty())
+        .map(|directory| directory.into_arc())
+        .collect::<Vec<_>>();
+    directories.sort();
+    directories.dedup();
+    directories
+}
+
 /// Wrapper struct that implements the AgentConnection trait
 #[derive(Clone)]
 pub struct NativeAgentConnection(pub Entity<NativeAgent>);
@@ -995,6 +1109,16 @@ impl NativeAgentConnection {
         self.0.update(cx, |this, cx| this.load_thread(id, cx))
     }
 
+    pub fn fork_thread(
+        &self,
+        id: acp::SessionId,
+        until: Option<UserMessageId>,
+        cx: &mut App,
+    ) -> Task<Result<acp::SessionId>> {
+        self.0
+            .update(cx, |this, cx| this.fork_thread(id, until, cx))
+    }
+
     fn run_turn(
         &self,
         session_id: acp::SessionId,
@@ -1075,6 +1199,25 @@ impl NativeAgentConnection {
                                 })
                                 .detach();
                             }
+                            ThreadEvent::ToolCallElicitation(ToolCallElicitation {
+                                tool_call_id,
+                                message,
+                                requested_schema,
+                                response,
+                            }) => {
+                                let outcome_task = acp_thread.update(cx, |thread, cx| {
+                                    thread.request_tool_call_elicitation(
+                                        tool_call_id,
+                                        message,
+                                        requested_schema,
+                                        cx,
+                                    )
+                                })??;
+                                cx.background_spawn(async move {
+                                    response.send(outcome_task.await).ok();
+                                })
+                                .detach();
+                            }
                             ThreadEvent::ToolCall(tool_call) => {
                                 acp_thread.update(cx, |thread, cx| {
                                     thread.upsert_tool_call(tool_call, cx)
@@ -1095,6 +1238,10 @@ impl NativeAgentConnection {
                                     thread.update_retry_status(status, cx)
                                 })?;
                             }
+                            ThreadEvent::Compacted(summary) => {
+                                acp_thread
+                                    .update(cx, |thread, cx| thread.push_compaction(summary, cx))?;
+                            }
                             ThreadEvent::Stop(stop_reason) => {
                                 log::debug!("Assistant message complete: {:?}", stop_reason);
                                 return Ok(acp::PromptResponse::new(stop_reason));
@@ -1431,6 +1578,17 @@ impl acp_thread::AgentConnection for NativeAgentConnection {
         })
     }
 
+    fn compact(
+        &self,
+        session_id: &acp::SessionId,
+        _cx: &App,
+    ) -> Option<Rc<dyn acp_thread::AgentSessionCompact>> {
+        Some(Rc::new(NativeAgentSessionCompact {
+            connection: self.clone(),
+            session_id: session_id.clone(),
+        }) as _)
+    }
+
     fn session_list(&self, cx: &mut App) -> Option<Rc<dyn AgentSessionList>> {
         let thread_store = self.0.read(cx).thread_store.clone();
         Some(Rc::new(NativeAgentSessionList::new(thread_store, cx)) as _)
@@ -1492,7 +1650,10 @@ impl NativeAgentSessionList {
             cwd: None,
             title: Some(entry.title),
             updated_at: Some(entry.updated_at),
-            meta: None,
+            meta: entry
+                .forked_from
+                .as_ref()
+                .map(acp_thread::meta_with_forked_from),
         }
     }
 
@@ -1586,6 +1747,20 @@ impl acp_thread::AgentSessionRetry for NativeAgentSessionRetry {
     }
 }
 
+struct NativeAgentSessionCompact {
+    connection: NativeAgentConnection,
+    session_id: acp::SessionId,
+}
+
+impl acp_thread::AgentSessionCompact for NativeAgentSessionCompact {
+    fn run(&self, cx: &mut App) -> Task<Result<acp::PromptResponse>> {
+        self.connection
+            .run_turn(self.session_id.clone(), cx, |thread, cx| {
+                thread.update(cx, |thread, cx| thread.compact(cx))
+            })
+    }
+}
+
 struct NativeAgentSessionSetTitle {
     thread: Entity<Thread>,
 }
@@ -1676,12 +1851,13 @@ impl ThreadEnvironment for NativeThreadEnvironment {
     fn create_terminal(
         &self,
         command: String,
+        env: Vec<acp::EnvVariable>,
         cwd: Option<PathBuf>,
         output_byte_limit: Option<u64>,
         cx: &mut AsyncApp,
     ) -> Task<Result<Rc<dyn TerminalHandle>>> {
         let task = self.acp_thread.update(cx, |thread, cx| {
-            thread.create_terminal(command, vec![], vec![], cwd, output_byte_limit, cx)
+            thread.create_terminal(command, vec![], env, cwd, output_byte_limit, cx)
         });
 
         let acp_thread = self.acp_thread.clone();
@@ -1960,7 +2136,8 @@ mod internal_tests {
                 vec![WorktreeContext {
                     root_name: "a".into(),
                     abs_path: Path::new("/a").into(),
-                    rules_file: None
+                    rules_file: None,
+                    nested_rules_files: Vec::new(),
                 }]
             )
         });
@@ -1982,12 +2159,116 @@ mod internal_tests {
                         path_in_worktree: rel_path(".rules").into(),
                         text: "".into(),
                         project_entry_id: rules_entry.id.to_usize()
-                    })
+                    }),
+                    nested_rules_files: Vec::new(),
                 }]
             )
         });
     }
 
+    #[gpui::test]
+    async fn test_nested_rules_files(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/a",
+            json!({
+                "AGENTS.md": "root rules",
+                "service": {
+                    "AGENTS.md": "service rules",
+                    "api": {
+                        ".clinerules": {
+                            "2-style.md": "style rules",
+                            "1-testing.md": "testing rules",
+                        },
+                        "lib.rs": "",
+                    },
+                    "main.rs": "",
+                },
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [Path::new("/a")], cx).await;
+        let thread_store = cx.new(|cx| ThreadStore::new(cx));
+        let agent = NativeAgent::new(
+            project.clone(),
+            thread_store,
+            Templates::new(),
+            None,
+            fs.clone(),
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+        cx.run_until_parked();
+
+        let nested_rules = |agent: &Entity<NativeAgent>, cx: &mut TestAppContext| {
+            agent.read_with(cx, |agent, cx| {
+                let worktrees = &agent.project_context.read(cx).worktrees;
+                worktrees[0]
+                    .nested_rules_files
+                    .iter()
+                    .map(|nested| {
+                        (
+                            nested.directory.as_unix_str().to_string(),
+                            nested.rules_file.path_in_worktree.as_unix_str().to_string(),
+                            nested.rules_file.text.clone(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        agent.read_with(cx, |agent, cx| {
+            let worktrees = &agent.project_context.read(cx).worktrees;
+            assert_eq!(worktrees[0].rules_file.as_ref().unwrap().text, "root rules");
+        });
+        assert_eq!(
+            nested_rules(&agent, cx),
+            vec![
+                (
+                    "service".to_string(),
+                    "service/AGENTS.md".to_string(),
+                    "service rules".to_string()
+                ),
+                (
+                    "service/api".to_string(),
+                    "service/api/.clinerules".to_string(),
+                    "testing rules\n\nstyle rules".to_string()
+                ),
+            ]
+        );
+
+        // Adding a file to a `.clinerules` directory updates the existing project context.
+        let project_context = agent.read_with(cx, |agent, _| agent.project_context.clone());
+        fs.insert_file(
+            "/a/service/api/.clinerules/3-docs.md",
+            b"docs rules".to_vec(),
+        )
+        .await;
+        cx.run_until_parked();
+        agent.read_with(cx, |agent, _| {
+            assert_eq!(agent.project_context, project_context);
+        });
+        assert_eq!(
+            nested_rules(&agent, cx)[1].2,
+            "testing rules\n\nstyle rules\n\ndocs rules"
+        );
+
+        // Removing a nested rules file removes it from the project context.
+        fs.remove_file(Path::new("/a/service/AGENTS.md"), Default::default())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            nested_rules(&agent, cx)
+                .into_iter()
+                .map(|(directory, _, _)| directory)
+                .collect::<Vec<_>>(),
+            vec!["service/api".to_string()]
+        );
+    }
+
     #[gpui::test]
     async fn test_listing_models(cx: &mut TestAppContext) {
         init_test(cx);
@@ -2634,6 +2915,107 @@ mod internal_tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_fork_thread(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/", json!({ "a": {} })).await;
+        let project = Project::test(fs.clone(), [path!("/a").as_ref()], cx).await;
+        let thread_store = cx.new(|cx| ThreadStore::new(cx));
+        let agent = NativeAgent::new(
+            project.clone(),
+            thread_store.clone(),
+            Templates::new(),
+            None,
+            fs.clone(),
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+        let connection = Rc::new(NativeAgentConnection(agent.clone()));
+
+        let acp_thread = cx
+            .update(|cx| {
+                connection
+                    .clone()
+                    .new_session(project.clone(), Path::new(""), cx)
+            })
+            .await
+            .unwrap();
+        let session_id = acp_thread.read_with(cx, |thread, _| thread.session_id().clone());
+        let thread = agent.read_with(cx, |agent, _| {
+            agent.sessions.get(&session_id).unwrap().thread.clone()
+        });
+        let model = Arc::new(FakeLanguageModel::default());
+        thread.update(cx, |thread, cx| thread.set_model(model.clone(), cx));
+
+        for (message, response) in [("Message 1", "Response 1"), ("Message 2", "Response 2")] {
+            let send = acp_thread.update(cx, |thread, cx| thread.send(vec![message.into()], cx));
+            let send = cx.foreground_executor().spawn(send);
+            cx.run_until_parked();
+            model.send_last_completion_stream_text_chunk(response);
+            model.end_last_completion_stream();
+            send.await.unwrap();
+        }
+        cx.run_until_parked();
+
+        let second_message_id = acp_thread.read_with(cx, |thread, _| {
+            thread
+                .entries()
+                .iter()
+                .rev()
+                .find_map(|entry| match entry {
+                    acp_thread::AgentThreadEntry::UserMessage(message) => message.id.clone(),
+                    _ => None,
+                })
+                .unwrap()
+        });
+        let forked_id = cx
+            .update(|cx| connection.fork_thread(session_id.clone(), Some(second_message_id), cx))
+            .await
+            .unwrap();
+        assert_ne!(forked_id, session_id);
+
+        let forked_entry = thread_store.read_with(cx, |store, _| {
+            store.thread_from_session_id(&forked_id).cloned().unwrap()
+        });
+        assert_eq!(forked_entry.forked_from, Some(session_id.clone()));
+        assert_eq!(forked_entry.parent_session_id, None);
+
+        // The fork only contains the messages before the one it was forked at,
+        // and the original thread is unaffected.
+        let forked_acp_thread = agent
+            .update(cx, |agent, cx| agent.open_thread(forked_id.clone(), cx))
+            .await
+            .unwrap();
+        forked_acp_thread.read_with(cx, |thread, cx| {
+            assert_eq!(
+                thread.to_markdown(cx),
+                formatdoc! {"
+                    ## User
+
+                    Message 1
+
+                    ## Assistant
+
+                    Response 1
+
+                "}
+            )
+        });
+        let forked_thread = agent.read_with(cx, |agent, _| {
+            agent.sessions.get(&forked_id).unwrap().thread.clone()
+        });
+        forked_thread.read_with(cx, |forked_thread, cx| {
+            assert_eq!(forked_thread.forked_from(), Some(&session_id));
+            assert_ne!(
+                forked_thread.action_log().entity_id(),
+                thread.read(cx).action_log().entity_id()
+            );
+        });
+        acp_thread.read_with(cx, |thread, _| assert_eq!(thread.entries().len(), 4));
+    }
+
     fn thread_entries(
         thread_store: &Entity<ThreadStore>,
         cx: &mut TestAppContext,
@@ -29,6 +29,9 @@ pub type DbLanguageModel = crate::legacy_thread::SerializedLanguageModel;
 pub struct DbThreadMetadata {
     pub id: acp::SessionId,
     pub parent_session_id: Option<acp::SessionId>,
+    /// The thread this one was forked from, if any.
+    #[serde(default)]
+    pub forked_from: Option<acp::SessionId>,
     #[serde(alias = "summary")]
     pub title: SharedString,
     pub updated_at: DateTime<Utc>,
@@ -66,6 +69,8 @@ pub struct DbThread {
     pub thinking_effort: Option<String>,
     #[serde(default)]
     pub draft_prompt: Option<Vec<acp::ContentBlock>>,
+    #[serde(default)]
+    pub forked_from: Option<acp::SessionId>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -108,6 +113,7 @@ impl SharedThread {
             thinking_enabled: false,
             thinking_effort: None,
             draft_prompt: None,
+            forked_from: None,
         }
     }
 
@@ -286,6 +292,7 @@ impl DbThread {
             thinking_enabled: false,
             thinking_effort: None,
             draft_prompt: None,
+            forked_from: None,
         })
     }
 }
@@ -398,6 +405,13 @@ impl ThreadsDatabase {
             s().ok();
         }
 
+        if let Ok(mut s) = connection.exec(indoc! {"
+            ALTER TABLE threads ADD COLUMN forked_from_id TEXT
+        "})
+        {
+            s().ok();
+        }
+
         let db = Self {
             executor,
             connection: Arc::new(Mutex::new(connection)),
@@ -427,6 +441,7 @@ impl ThreadsDatabase {
             .subagent_context
             .as_ref()
             .map(|ctx| ctx.parent_thread_id.0.clone());
+        let forked_from_id = thread.forked_from.as_ref().map(|id| id.0.clone());
         let serialized_folder_paths = folder_paths.serialize();
         let (folder_paths_str, folder_paths_order_str): (Option<String>, Option<String>) =
             if folder_paths.is_empty() {
@@ -448,13 +463,14 @@ impl ThreadsDatabase {
         let data_type = DataType::Zstd;
         let data = compressed;
 
-        let mut insert = connection.exec_bound::<(Arc<str>, Option<Arc<str>>, Option<String>, Option<String>, String, String, DataType, Vec<u8>)>(indoc! {"
-            INSERT OR REPLACE INTO threads (id, parent_id, folder_paths, folder_paths_order, summary, updated_at, data_type, data) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        let mut insert = connection.exec_bound::<(Arc<str>, Option<Arc<str>>, Option<Arc<str>>, Option<String>, Option<String>, String, String, DataType, Vec<u8>)>(indoc! {"
+            INSERT OR REPLACE INTO threads (id, parent_id, forked_from_id, folder_paths, folder_paths_order, summary, updated_at, data_type, data) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
         "})?;
 
         insert((
             id.0,
             parent_id,
+            forked_from_id,
             folder_paths_str,
             folder_paths_order_str,
             title,
@@ -473,14 +489,14 @@ impl ThreadsDatabase {
             let connection = connection.lock();
 
             let mut select = connection
-                .select_bound::<(), (Arc<str>, Option<Arc<str>>, Option<String>, Option<String>, String, String)>(indoc! {"
-                SELECT id, parent_id, folder_paths, folder_paths_order, summary, updated_at FROM threads ORDER BY updated_at DESC
+                .select_bound::<(), (Arc<str>, Option<Arc<str>>, Option<Arc<str>>, Option<String>, Option<String>, String, String)>(indoc! {"
+                SELECT id, parent_id, forked_from_id, folder_paths, folder_paths_order, summary, updated_at FROM threads ORDER BY updated_at DESC
             "})?;
 
             let rows = select(())?;
             let mut threads = Vec::new();
 
-            for (id, parent_id, folder_paths, folder_paths_order, summary, updated_at) in rows {
+            for (id, parent_id, forked_from_id, folder_paths, folder_paths_order, summary, updated_at) in rows {
                 let folder_paths = folder_paths
                     .map(|paths| {
                         PathList::deserialize(&util::path_list::SerializedPathList {
@@ -492,6 +508,7 @@ impl ThreadsDatabase {
                 threads.push(DbThreadMetadata {
                     id: acp::SessionId::new(id),
                     parent_session_id: parent_id.map(acp::SessionId::new),
+                    forked_from: forked_from_id.map(acp::SessionId::new),
                     title: summary.into(),
                     updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
                     folder_paths,
@@ -637,6 +654,7 @@ mod tests {
             thinking_enabled: false,
             thinking_effort: None,
             draft_prompt: None,
+            forked_from: None,
         }
     }
 
@@ -797,6 +815,37 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_forked_from_roundtrips_through_save_load(cx: &mut TestAppContext) {
+        let database = ThreadsDatabase::new(cx.executor()).unwrap();
+
+        let original_id = session_id("original-thread");
+        let fork_id = session_id("forked-thread");
+
+        let mut fork = make_thread(
+            "Forked Thread",
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        );
+        fork.forked_from = Some(original_id.clone());
+
+        database
+            .save_thread(fork_id.clone(), fork, PathList::default())
+            .await
+            .unwrap();
+
+        let threads = database.list_threads().await.unwrap();
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].forked_from, Some(original_id.clone()));
+        assert!(threads[0].parent_session_id.is_none());
+
+        let loaded = database
+            .load_thread(fork_id)
+            .await
+            .unwrap()
+            .expect("thread should exist");
+        assert_eq!(loaded.forked_from, Some(original_id));
+    }
+
     #[gpui::test]
     async fn test_folder_paths_roundtrip(cx: &mut TestAppContext) {
         let database = ThreadsDatabase::new(cx.executor()).unwrap();
@@ -1524,6 +1524,7 @@ impl EditAgentTest {
                 root_name: "root".to_string(),
                 abs_path: Path::new("/path/to/root").into(),
                 rules_file: None,
+                nested_rules_files: Vec::new(),
             }];
             let project_context = ProjectContext::new(worktrees, Vec::default());
             let tool_names = tools
@@ -0,0 +1,184 @@
+use agent_client_protocol as acp;
+use agent_settings::AgentHook;
+use anyhow::{Context as _, Result, bail};
+use futures::FutureExt as _;
+use gpui::AsyncApp;
+use std::{io::Write as _, path::PathBuf, rc::Rc};
+use tempfile::NamedTempFile;
+
+use crate::ThreadEnvironment;
+
+const HOOK_OUTPUT_LIMIT: u64 = 16 * 1024;
+
+/// The maximum length of the tool input and output passed to hooks in environment variables.
+/// Linux refuses to start processes with an environment variable longer than 128 KiB.
+const HOOK_ENV_VALUE_LIMIT: usize = 100 * 1024;
+
+/// The agent lifecycle event a hook is being run for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AgentHookEvent {
+    PreToolCall,
+    PostToolCall,
+    Stop,
+}
+
+impl AgentHookEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            AgentHookEvent::PreToolCall => "pre_tool_call",
+            AgentHookEvent::PostToolCall => "post_tool_call",
+            AgentHookEvent::Stop => "on_stop",
+        }
+    }
+}
+
+/// Describes the tool call a hook is being run for.
+#[derive(Clone, Debug)]
+pub struct AgentHookToolCall<'a> {
+    pub name: &'a str,
+    pub input: &'a serde_json::Value,
+    pub output: Option<&'a str>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AgentHookOutcome {
+    pub command: String,
+    pub exit_code: Option<u32>,
+    pub timed_out: bool,
+    pub cancelled: bool,
+    pub output: String,
+}
+
+impl AgentHookOutcome {
+    pub fn succeeded(&self) -> bool {
+        !self.timed_out && !self.cancelled && self.exit_code == Some(0)
+    }
+
+    /// Describes why the hook failed, including its output, so it can be shown to the model.
+    pub fn failure_message(&self) -> String {
+        let reason = if self.timed_out {
+            "timed out".to_string()
+        } else if self.cancelled {
+            "was cancelled".to_string()
+        } else if let Some(exit_code) = self.exit_code {
+            format!("exited with code {exit_code}")
+        } else {
+            "was terminated".to_string()
+        };
+        let output = self.output.trim();
+        if output.is_empty() {
+            format!("Hook `{}` {reason}.", self.command)
+        } else {
+            format!("Hook `{}` {reason}:\n\n```\n{output}\n```", self.command)
+        }
+    }
+}
+
+/// Runs a hook command in a terminal and waits for it to exit, killing it if it exceeds its
+/// timeout or `cancellation_rx` signals that the turn was cancelled.
+///
+/// Details about the event are passed to the command through `ZED_AGENT_*` environment variables.
+/// When the hook runs on this machine, the tool input and output are written to temporary files
+/// whose paths are passed instead, since they can be too large for an environment variable.
+/// Hooks of remote projects get them in environment variables, and fail to start when they exceed
+/// [`HOOK_ENV_VALUE_LIMIT`]: they are never truncated, so that hooks don't act on partial input.
+pub async fn run_agent_hook(
+    environment: &Rc<dyn ThreadEnvironment>,
+    hook: &AgentHook,
+    event: AgentHookEvent,
+    tool_call: Option<AgentHookToolCall<'_>>,
+    cwd: Option<PathBuf>,
+    is_local: bool,
+    cancellation_rx: Option<watch::Receiver<bool>>,
+    cx: &mut AsyncApp,
+) -> Result<AgentHookOutcome> {
+    let mut env = vec![acp::EnvVariable::new(
+        "ZED_AGENT_HOOK_EVENT",
+        event.as_str(),
+    )];
+    // Kept alive until the hook exits.
+    let mut payload_files = Vec::new();
+    if let Some(tool_call) = tool_call {
+        env.push(acp::EnvVariable::new("ZED_AGENT_TOOL_NAME", tool_call.name));
+        let payloads = [
+            ("ZED_AGENT_TOOL_INPUT", Some(tool_call.input.to_string())),
+            (
+                "ZED_AGENT_TOOL_OUTPUT",
+                tool_call.output.map(str::to_string),
+            ),
+        ];
+        for (name, payload) in payloads {
+            let Some(payload) = payload else {
+                continue;
+            };
+            if is_local {
+                let file = cx
+                    .background_spawn(async move {
+                        let mut file = NamedTempFile::with_prefix("zed-agent-hook-")?;
+                        file.write_all(payload.as_bytes())?;
+                        file.flush()?;
+                        anyhow::Ok(file)
+                    })
+                    .await
+                    .with_context(|| format!("writing `{name}` for the hook"))?;
+                env.push(acp::EnvVariable::new(
+                    format!("{name}_FILE"),
+                    file.path().to_string_lossy().into_owned(),
+                ));
+                payload_files.push(file);
+            } else if payload.len() > HOOK_ENV_VALUE_LIMIT {
+                bail!(
+                    "`{name}` is too large to pass to hooks in remote projects ({} bytes)",
+                    payload.len()
+                );
+            } else {
+                env.push(acp::EnvVariable::new(name, payload));
+            }
+        }
+    }
+
+    let terminal = environment
+        .create_terminal(hook.command.clone(), env, cwd, Some(HOOK_OUTPUT_LIMIT), cx)
+        .await?;
+
+    let wait_for_exit = terminal.wait_for_exit(cx)?;
+    let timeout = cx.background_executor().timer(hook.timeout);
+    let cancellation = async move {
+        let Some(mut cancellation_rx) = cancellation_rx else {
+            return futures::future::pending().await;
+        };
+        loop {
+            if *cancellation_rx.borrow() {
+                return;
+            }
+            if cancellation_rx.changed().await.is_err() {
+                return futures::future::pending().await;
+            }
+        }
+    };
+    let mut timed_out = false;
+    let mut cancelled = false;
+    let exit_status = futures::select! {
+        exit_status = wait_for_exit.clone().fuse() => exit_status,
+        _ = timeout.fuse() => {
+            timed_out = true;
+            terminal.kill(cx)?;
+            wait_for_exit.await
+        }
+        _ = cancellation.fuse() => {
+            cancelled = true;
+            terminal.kill(cx)?;
+            wait_for_exit.await
+        }
+    };
+    drop(payload_files);
+
+    let output = terminal.current_output(cx)?;
+    Ok(AgentHookOutcome {
+        command: hook.command.clone(),
+        exit_code: exit_status.exit_code,
+        timed_out,
+        cancelled,
+        output: output.output,
+    })
+}
@@ -196,6 +196,7 @@ impl crate::ThreadEnvironment for FakeThreadEnvironment {
     fn create_terminal(
         &self,
         _command: String,
+        _env: Vec<acp::EnvVariable>,
         _cwd: Option<std::path::PathBuf>,
         _output_byte_limit: Option<u64>,
         _cx: &mut AsyncApp,
@@ -237,6 +238,7 @@ impl crate::ThreadEnvironment for MultiTerminalEnvironment {
     fn create_terminal(
         &self,
         _command: String,
+        _env: Vec<acp::EnvVariable>,
         _cwd: Option<std::path::PathBuf>,
         _output_byte_limit: Option<u64>,
         cx: &mut AsyncApp,
@@ -965,6 +967,177 @@ async fn test_tool_hallucination(cx: &mut TestAppContext) {
     assert_eq!(update.fields.status, Some(acp::ToolCallStatus::Failed));
 }
 
+#[gpui::test]
+async fn test_pre_tool_call_hook_denies_tool_call(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    set_agent_hooks(cx, |hooks| {
+        hooks.pre_tool_call.push(agent_settings::AgentHook {
+            command: "./check-policy.sh".into(),
+            tool: agent_settings::CompiledRegex::new("^echo$", true),
+            timeout: agent_settings::AgentHook::DEFAULT_TIMEOUT,
+        });
+    });
+
+    let environment = Rc::new(cx.update(|cx| {
+        FakeThreadEnvironment::default()
+            .with_terminal(FakeTerminalHandle::new_with_immediate_exit(cx, 1))
+    }));
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.add_default_tools(environment, cx);
+            thread.add_tool(EchoTool);
+            thread.send(UserMessageId::new(), ["Use the echo tool"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_id_1".into(),
+            name: EchoTool::NAME.into(),
+            raw_input: json!({"text": "test"}).to_string(),
+            input: json!({"text": "test"}),
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+
+    expect_tool_call(&mut events).await;
+    let update = expect_tool_call_update_fields(&mut events).await;
+    assert_eq!(update.fields.status, Some(acp::ToolCallStatus::Failed));
+
+    cx.run_until_parked();
+    let completion = fake_model.pending_completions().pop().unwrap();
+    let message = completion.messages.last().unwrap();
+    let reason = "Denied by a pre-tool-call hook. Hook `./check-policy.sh` exited with code 1:\n\n\
+                  ```\ncommand output\n```";
+    assert_eq!(
+        message.content,
+        vec![language_model::MessageContent::ToolResult(
+            LanguageModelToolResult {
+                tool_use_id: "tool_id_1".into(),
+                tool_name: EchoTool::NAME.into(),
+                is_error: true,
+                content: reason.into(),
+                output: Some(reason.into()),
+            }
+        )]
+    );
+}
+
+#[gpui::test]
+async fn test_post_tool_call_hook_failure_is_reported(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    set_agent_hooks(cx, |hooks| {
+        hooks.post_tool_call.push(agent_settings::AgentHook {
+            command: "cargo fmt --check".into(),
+            tool: None,
+            timeout: agent_settings::AgentHook::DEFAULT_TIMEOUT,
+        });
+    });
+
+    let environment = Rc::new(cx.update(|cx| {
+        FakeThreadEnvironment::default()
+            .with_terminal(FakeTerminalHandle::new_with_immediate_exit(cx, 1))
+    }));
+    thread
+        .update(cx, |thread, cx| {
+            thread.add_default_tools(environment, cx);
+            thread.add_tool(EchoTool);
+            thread.send(UserMessageId::new(), ["Use the echo tool"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_id_1".into(),
+            name: EchoTool::NAME.into(),
+            raw_input: json!({"text": "test"}).to_string(),
+            input: json!({"text": "test"}),
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    let completion = fake_model.pending_completions().pop().unwrap();
+    let message = completion.messages.last().unwrap();
+    assert_eq!(
+        message.content,
+        vec![language_model::MessageContent::ToolResult(
+            LanguageModelToolResult {
+                tool_use_id: "tool_id_1".into(),
+                tool_name: EchoTool::NAME.into(),
+                is_error: false,
+                content: "test\n\nHook `cargo fmt --check` exited with code 1:\n\n\
+                          ```\ncommand output\n```"
+                    .into(),
+                output: Some("test".into()),
+            }
+        )]
+    );
+}
+
+#[gpui::test]
+async fn test_pre_tool_call_hook_is_killed_on_cancellation(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    set_agent_hooks(cx, |hooks| {
+        hooks.pre_tool_call.push(agent_settings::AgentHook {
+            command: "./check-policy.sh".into(),
+            tool: None,
+            timeout: agent_settings::AgentHook::DEFAULT_TIMEOUT,
+        });
+    });
+
+    let environment = Rc::new(cx.update(|cx| {
+        FakeThreadEnvironment::default().with_terminal(FakeTerminalHandle::new_never_exits(cx))
+    }));
+    let handle = environment.terminal_handle.clone().unwrap();
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.add_default_tools(environment, cx);
+            thread.add_tool(EchoTool);
+            thread.send(UserMessageId::new(), ["Use the echo tool"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_id_1".into(),
+            name: EchoTool::NAME.into(),
+            raw_input: json!({"text": "test"}).to_string(),
+            input: json!({"text": "test"}),
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    cx.run_until_parked();
+    assert!(!handle.was_killed());
+
+    thread.update(cx, |thread, cx| thread.cancel(cx)).detach();
+    let remaining_events = collect_events_until_stop(&mut events, cx).await;
+    assert!(handle.was_killed(), "expected the hook to be killed");
+    assert_eq!(
+        stop_events(remaining_events),
+        vec![acp::StopReason::Cancelled]
+    );
+}
+
+fn set_agent_hooks(cx: &mut TestAppContext, f: impl FnOnce(&mut agent_settings::AgentHooks)) {
+    cx.update(|cx| {
+        let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+        f(&mut settings.hooks);
+        agent_settings::AgentSettings::override_global(settings, cx);
+    });
+}
+
 async fn expect_tool_call(events: &mut UnboundedReceiver<Result<ThreadEvent>>) -> acp::ToolCall {
     let event = events
         .next()
@@ -3028,6 +3201,190 @@ async fn test_truncate_second_message(cx: &mut TestAppContext) {
     assert_first_message_state(cx);
 }
 
+#[gpui::test]
+async fn test_compact_thread(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    for (message, response) in [
+        ("Message 1", "Message 1 response"),
+        ("Message 2", "Message 2 response"),
+    ] {
+        thread
+            .update(cx, |thread, cx| {
+                thread.send(UserMessageId::new(), [message], cx)
+            })
+            .unwrap();
+        cx.run_until_parked();
+        fake_model.send_last_completion_stream_text_chunk(response);
+        fake_model.end_last_completion_stream();
+        cx.run_until_parked();
+    }
+
+    let summary_model = Arc::new(FakeLanguageModel::default());
+    thread.update(cx, |thread, cx| {
+        thread.set_summarization_model(Some(summary_model.clone()), cx)
+    });
+
+    let events = thread.update(cx, |thread, cx| thread.compact(cx)).unwrap();
+    cx.run_until_parked();
+
+    // Everything up to the latest user message is handed to the summarization model.
+    let completion = summary_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.messages[..2]
+            .iter()
+            .map(|message| (message.role, message.string_contents()))
+            .collect::<Vec<_>>(),
+        vec![
+            (Role::User, "Message 1".into()),
+            (Role::Assistant, "Message 1 response".into()),
+        ]
+    );
+    assert_eq!(completion.messages.len(), 3);
+    summary_model.send_last_completion_stream_text_chunk("Summary of Message 1");
+    summary_model.end_last_completion_stream();
+    let events = events.collect::<Vec<_>>().await;
+    assert!(events.iter().any(
+        |event| matches!(event, Ok(ThreadEvent::Compacted(summary)) if summary == "Summary of Message 1")
+    ));
+    assert_eq!(stop_events(events), vec![acp::StopReason::EndTurn]);
+
+    // The full history is still shown, followed by the summary.
+    thread.read_with(cx, |thread, _| {
+        assert_eq!(
+            thread.to_markdown(),
+            indoc! {"
+                ## User
+
+                Message 1
+
+                ## Assistant
+
+                Message 1 response
+
+                ## User
+
+                Message 2
+
+                ## Assistant
+
+                Message 2 response
+
+                [compacted]
+
+                Summary of Message 1
+            "}
+        );
+    });
+
+    // Subsequent requests replace the older messages with the summary.
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Message 3"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.messages[1..]
+            .iter()
+            .map(|message| (message.role, message.string_contents()))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                Role::User,
+                "The earlier part of this conversation was compacted to save space. \
+                Here is a summary of it:\n\nSummary of Message 1"
+                    .into()
+            ),
+            (Role::User, "Message 2".into()),
+            (Role::Assistant, "Message 2 response".into()),
+            (Role::User, "Message 3".into()),
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_auto_compaction(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+    let summary_model = Arc::new(FakeLanguageModel::default());
+    thread.update(cx, |thread, cx| {
+        thread.set_summarization_model(Some(summary_model.clone()), cx)
+    });
+
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Message 1"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_text_chunk("Message 1 response");
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+        language_model::TokenUsage {
+            input_tokens: 850_000,
+            output_tokens: 50_000,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+    thread.read_with(cx, |thread, _| {
+        assert_eq!(
+            thread.latest_token_usage().unwrap().ratio(),
+            acp_thread::TokenUsageRatio::Warning
+        );
+    });
+
+    // The next turn compacts the thread before sending the new message.
+    thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Message 2"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    assert!(fake_model.pending_completions().is_empty());
+    let completion = summary_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.intent,
+        Some(CompletionIntent::ThreadContextSummarization)
+    );
+    assert_eq!(
+        completion.messages[..2]
+            .iter()
+            .map(|message| (message.role, message.string_contents()))
+            .collect::<Vec<_>>(),
+        vec![
+            (Role::User, "Message 1".into()),
+            (Role::Assistant, "Message 1 response".into()),
+        ]
+    );
+    assert_eq!(completion.messages.len(), 3);
+    summary_model.send_last_completion_stream_text_chunk("Summary of Message 1");
+    summary_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // The request that follows starts with the summary, followed by the retained message.
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.messages[1..]
+            .iter()
+            .map(|message| (message.role, message.string_contents()))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                Role::User,
+                "The earlier part of this conversation was compacted to save space. \
+                Here is a summary of it:\n\nSummary of Message 1"
+                    .into()
+            ),
+            (Role::User, "Message 2".into()),
+        ]
+    );
+}
+
 #[gpui::test]
 async fn test_title_generation(cx: &mut TestAppContext) {
     let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
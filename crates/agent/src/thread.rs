@@ -4,7 +4,8 @@ use crate::{
     ListDirectoryTool, MovePathTool, NowTool, OpenTool, ProjectSnapshot, ReadFileTool,
     RestoreFileFromDiskTool, SaveFileTool, SpawnAgentTool, StreamingEditFileTool,
     SystemPromptTemplate, Template, Templates, TerminalTool, ToolPermissionDecision, WebSearchTool,
-    decide_permission_from_settings,
+    decide_permission_from_hook, decide_permission_from_settings,
+    hooks::{AgentHookEvent, AgentHookToolCall, run_agent_hook},
 };
 use acp_thread::{MentionUri, UserMessageId};
 use action_log::ActionLog;
@@ -12,8 +13,8 @@ use feature_flags::{FeatureFlagAppExt as _, StreamingEditFileToolFeatureFlag};
 
 use agent_client_protocol as acp;
 use agent_settings::{
-    AgentProfileId, AgentProfileSettings, AgentSettings, SUMMARIZE_THREAD_DETAILED_PROMPT,
-    SUMMARIZE_THREAD_PROMPT,
+    AgentProfileId, AgentProfileSettings, AgentSettings, COMPACT_THREAD_PROMPT,
+    SUMMARIZE_THREAD_DETAILED_PROMPT, SUMMARIZE_THREAD_PROMPT,
 };
 use anyhow::{Context as _, Result, anyhow};
 use chrono::{DateTime, Utc};
@@ -41,7 +42,7 @@ use language_model::{
     LanguageModelToolUseId, Role, SelectedModel, Speed, StopReason, TokenUsage,
     ZED_CLOUD_PROVIDER_ID,
 };
-use project::Project;
+use project::{Project, ProjectPath};
 use prompt_store::ProjectContext;
 use schemars::{JsonSchema, Schema};
 use serde::de::DeserializeOwned;
@@ -50,6 +51,7 @@ use settings::{LanguageModelSelection, Settings, ToolPermissionMode, update_sett
 use smol::stream::StreamExt;
 use std::{
     collections::BTreeMap,
+    iter,
     marker::PhantomData,
     ops::RangeInclusive,
     path::Path,
@@ -113,6 +115,7 @@ pub enum Message {
     User(UserMessage),
     Agent(AgentMessage),
     Resume,
+    Compaction(CompactionMessage),
 }
 
 impl Message {
@@ -139,6 +142,7 @@ impl Message {
                 cache: false,
                 reasoning_details: None,
             }],
+            Message::Compaction(message) => vec![message.to_request()],
         }
     }
 
@@ -147,17 +151,50 @@ impl Message {
             Message::User(message) => message.to_markdown(),
             Message::Agent(message) => message.to_markdown(),
             Message::Resume => "[resume]\n".into(),
+            Message::Compaction(message) => message.to_markdown(),
         }
     }
 
     pub fn role(&self) -> Role {
         match self {
-            Message::User(_) | Message::Resume => Role::User,
+            Message::User(_) | Message::Resume | Message::Compaction(_) => Role::User,
             Message::Agent(_) => Role::Assistant,
         }
     }
 }
 
+/// Marks the point at which older messages were replaced by a summary to save context.
+///
+/// When building requests, everything before the most recent compaction is replaced by its
+/// summary, except for the messages starting at `retained_from`, which are kept verbatim.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionMessage {
+    pub summary: String,
+    pub retained_from: UserMessageId,
+}
+
+impl CompactionMessage {
+    pub fn to_markdown(&self) -> String {
+        format!("[compacted]\n\n{}\n", self.summary)
+    }
+
+    fn to_request(&self) -> LanguageModelRequestMessage {
+        LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![
+                format!(
+                    "The earlier part of this conversation was compacted to save space. \
+                    Here is a summary of it:\n\n{}",
+                    self.summary
+                )
+                .into(),
+            ],
+            cache: false,
+            reasoning_details: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UserMessage {
     pub id: UserMessageId,
@@ -616,6 +653,7 @@ pub trait ThreadEnvironment {
     fn create_terminal(
         &self,
         command: String,
+        env: Vec<acp::EnvVariable>,
         cwd: Option<PathBuf>,
         output_byte_limit: Option<u64>,
         cx: &mut AsyncApp,
@@ -642,8 +680,10 @@ pub enum ThreadEvent {
     ToolCall(acp::ToolCall),
     ToolCallUpdate(acp_thread::ToolCallUpdate),
     ToolCallAuthorization(ToolCallAuthorization),
+    ToolCallElicitation(ToolCallElicitation),
     SubagentSpawned(acp::SessionId),
     Retry(acp_thread::RetryStatus),
+    Compacted(String),
     Stop(acp::StopReason),
 }
 
@@ -846,6 +886,14 @@ pub struct ToolCallAuthorization {
     pub context: Option<ToolPermissionContext>,
 }
 
+#[derive(Debug)]
+pub struct ToolCallElicitation {
+    pub tool_call_id: acp::ToolCallId,
+    pub message: SharedString,
+    pub requested_schema: serde_json::Value,
+    pub response: oneshot::Sender<acp_thread::ElicitationOutcome>,
+}
+
 #[derive(Debug, thiserror::Error)]
 enum CompletionError {
     #[error("max tokens")]
@@ -895,10 +943,16 @@ pub struct Thread {
     pub(crate) action_log: Entity<ActionLog>,
     /// Tracks the last time files were read by the agent, to detect external modifications
     pub(crate) file_read_times: HashMap<PathBuf, fs::MTime>,
+    /// Absolute paths of nested rules files that have already been provided to the model
+    shown_nested_rules: HashSet<PathBuf>,
     /// True if this thread was imported from a shared thread and can be synced.
     imported: bool,
     /// If this is a subagent thread, contains context about the parent
     subagent_context: Option<SubagentContext>,
+    /// The thread this one was forked from, if any.
+    forked_from: Option<acp::SessionId>,
+    /// Used to run hook commands; set when the default tools are added.
+    environment: Option<Rc<dyn ThreadEnvironment>>,
     /// The user's unsent prompt text, persisted so it can be restored when reloading the thread.
     draft_prompt: Option<Vec<acp::ContentBlock>>,
     /// Weak references to running subagent threads for cancellation propagation
@@ -1014,8 +1068,11 @@ impl Thread {
             project,
             action_log,
             file_read_times: HashMap::default(),
+            shown_nested_rules: HashSet::default(),
             imported: false,
             subagent_context: None,
+            forked_from: None,
+            environment: None,
             draft_prompt: None,
             running_subagents: Vec::new(),
         }
@@ -1030,6 +1087,11 @@ impl Thread {
         self.imported
     }
 
+    /// Returns the ID of the thread this one was forked from, if any.
+    pub fn forked_from(&self) -> Option<&acp::SessionId> {
+        self.forked_from.as_ref()
+    }
+
     pub fn replay(
         &mut self,
         cx: &mut Context<Self>,
@@ -1059,6 +1121,7 @@ impl Thread {
                     }
                 }
                 Message::Resume => {}
+                Message::Compaction(compaction) => stream.send_compaction(&compaction.summary),
             }
         }
         rx
@@ -1230,8 +1293,11 @@ impl Thread {
             prompt_capabilities_tx,
             prompt_capabilities_rx,
             file_read_times: HashMap::default(),
+            shown_nested_rules: HashSet::default(),
             imported: db_thread.imported,
             subagent_context: db_thread.subagent_context,
+            forked_from: db_thread.forked_from,
+            environment: None,
             draft_prompt: db_thread.draft_prompt,
             running_subagents: Vec::new(),
         }
@@ -1258,6 +1324,7 @@ impl Thread {
             thinking_enabled: self.thinking_enabled,
             thinking_effort: self.thinking_effort.clone(),
             draft_prompt: self.draft_prompt.clone(),
+            forked_from: self.forked_from.clone(),
         };
 
         cx.background_spawn(async move {
@@ -1267,6 +1334,46 @@ impl Thread {
         })
     }
 
+    /// Serializes a fork of this thread that keeps the messages before `until`,
+    /// or every message if `until` is `None`.
+    pub fn to_forked_db(&self, until: Option<&UserMessageId>, cx: &App) -> Result<Task<DbThread>> {
+        let end = match until {
+            Some(message_id) => self
+                .messages
+                .iter()
+                .position(|message| {
+                    matches!(message, Message::User(UserMessage { id, .. }) if id == message_id)
+                })
+                .context("Message not found")?,
+            None => self.messages.len(),
+        };
+        anyhow::ensure!(end > 0, "Nothing to fork");
+
+        let messages = self.messages[..end].to_vec();
+        let request_token_usage = messages
+            .iter()
+            .filter_map(|message| match message {
+                Message::User(message) => Some((
+                    message.id.clone(),
+                    *self.request_token_usage.get(&message.id)?,
+                )),
+                Message::Agent(_) | Message::Resume | Message::Compaction(_) => None,
+            })
+            .collect();
+        let forked_from = self.id.clone();
+        let thread = self.to_db(cx);
+        Ok(cx.background_spawn(async move {
+            let mut thread = thread.await;
+            thread.messages = messages;
+            thread.request_token_usage = request_token_usage;
+            thread.updated_at = Utc::now();
+            thread.detailed_summary = None;
+            thread.draft_prompt = None;
+            thread.forked_from = Some(forked_from);
+            thread
+        }))
+    }
+
     /// Create a snapshot of the current project state including git information and unsaved buffers.
     fn project_snapshot(
         project: Entity<Project>,
@@ -1287,6 +1394,44 @@ impl Thread {
         &self.project_context
     }
 
+    /// Returns the contents of rules files in subdirectories containing `path` that haven't
+    /// been provided to the model yet in this thread, marking them as provided.
+    pub fn take_nested_rules_for_path(&mut self, path: &ProjectPath, cx: &App) -> Option<String> {
+        let worktree = self
+            .project
+            .read(cx)
+            .worktree_for_id(path.worktree_id, cx)?;
+        let worktree_abs_path = worktree.read(cx).abs_path();
+        let worktree_context = self
+            .project_context
+            .read(cx)
+            .worktrees
+            .iter()
+            .find(|worktree_context| worktree_context.abs_path == worktree_abs_path)?;
+
+        let mut rules = String::new();
+        for nested in &worktree_context.nested_rules_files {
+            if !path.path.starts_with(&nested.directory) {
+                continue;
+            }
+            let rules_abs_path =
+                worktree_abs_path.join(nested.rules_file.path_in_worktree.as_std_path());
+            if !self.shown_nested_rules.insert(rules_abs_path) {
+                continue;
+            }
+            writeln!(
+                rules,
+                "The following rules from `{}` apply to files in `{}`:\n\n{}\n",
+                nested.rules_file.path_in_worktree.as_unix_str(),
+                nested.directory.as_unix_str(),
+                nested.rules_file.text
+            )
+            .ok();
+        }
+
+        (!rules.is_empty()).then(|| rules.trim_end().to_string())
+    }
+
     pub fn project(&self) -> &Entity<Project> {
         &self.project
     }
@@ -1453,8 +1598,9 @@ impl Thread {
         self.add_tool(WebSearchTool);
 
         if self.depth() < MAX_SUBAGENT_DEPTH {
-            self.add_tool(SpawnAgentTool::new(environment));
+            self.add_tool(SpawnAgentTool::new(environment.clone()));
         }
+        self.environment = Some(environment);
     }
 
     pub fn add_tool<T: AgentTool>(&mut self, tool: T) {
@@ -1552,7 +1698,7 @@ impl Thread {
                 Message::User(message) => {
                     self.request_token_usage.remove(&message.id);
                 }
-                Message::Agent(_) | Message::Resume => {}
+                Message::Agent(_) | Message::Resume | Message::Compaction(_) => {}
             }
         }
         self.clear_summary();
@@ -1640,6 +1786,48 @@ impl Thread {
         self.run_turn(cx)
     }
 
+    /// Replaces older messages with a summary generated by the summarization model, so that they
+    /// take up less of the model's context window. The most recent turn is kept verbatim.
+    pub fn compact(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Result<mpsc::UnboundedReceiver<Result<ThreadEvent>>> {
+        self.flush_pending_message(cx);
+        let compaction = self.build_compaction_request(cx)?;
+        self.cancel(cx).detach();
+
+        let (events_tx, events_rx) = mpsc::unbounded::<Result<ThreadEvent>>();
+        let event_stream = ThreadEventStream(events_tx);
+        let (cancellation_tx, cancellation_rx) = watch::channel(false);
+        self.running_turn = Some(RunningTurn {
+            event_stream: event_stream.clone(),
+            tools: BTreeMap::default(),
+            cancellation_tx,
+            streaming_tool_inputs: HashMap::default(),
+            _task: cx.spawn(async move |this, cx| {
+                let result = Self::run_compaction(
+                    &this,
+                    compaction,
+                    &event_stream,
+                    cancellation_rx.clone(),
+                    cx,
+                )
+                .await;
+
+                if *cancellation_rx.borrow() {
+                    return;
+                }
+
+                match result {
+                    Ok(()) => event_stream.send_stop(acp::StopReason::EndTurn),
+                    Err(error) => event_stream.send_error(error),
+                }
+                _ = this.update(cx, |this, _| this.running_turn.take());
+            }),
+        });
+        Ok(events_rx)
+    }
+
     /// Sending a message results in the model streaming a response, which could include tool calls.
     /// After calling tools, the model will stops and waits for any outstanding tool calls to be completed and their results sent.
     /// The returned channel will report all the occurrences in which the model stops before erroring or ending its turn.
@@ -1771,6 +1959,7 @@ impl Thread {
                     Ok(()) => {
                         log::debug!("Turn execution completed");
                         event_stream.send_stop(acp::StopReason::EndTurn);
+                        _ = this.update(cx, |this, cx| this.run_stop_hooks(cx));
                     }
                     Err(error) => {
                         log::error!("Turn execution failed: {:?}", error);
@@ -1805,6 +1994,17 @@ impl Thread {
         let mut attempt = 0;
         let mut intent = CompletionIntent::UserPrompt;
         loop {
+            let compaction = this.update(cx, |this, cx| this.auto_compaction_request(cx))?;
+            if let Some(compaction) = compaction {
+                Self::run_compaction(this, compaction, event_stream, cancellation_rx.clone(), cx)
+                    .await
+                    .context("failed to compact thread")
+                    .log_err();
+                if *cancellation_rx.borrow() {
+                    return Ok(());
+                }
+            }
+
             let request =
                 this.update(cx, |this, cx| this.build_completion_request(intent, cx))??;
 
@@ -2173,7 +2373,8 @@ impl Thread {
         };
 
         if !tool_use.is_input_complete {
-            if tool.supports_input_streaming() {
+            // Hooks need the complete input, so tools with hooks aren't streamed.
+            if tool.supports_input_streaming() && !self.has_tool_call_hooks(&tool_use.name, cx) {
                 let running_turn = self.running_turn.as_mut()?;
                 if let Some(sender) = running_turn.streaming_tool_inputs.get(&tool_use.id) {
                     sender.send_partial(tool_use.input);
@@ -2213,10 +2414,9 @@ impl Thread {
         }
 
         log::debug!("Running tool {}", tool_use.name);
-        let tool_input = ToolInput::ready(tool_use.input);
-        Some(self.run_tool(
+        Some(self.run_tool_with_hooks(
             tool,
-            tool_input,
+            tool_use.input,
             tool_use.id,
             tool_use.name,
             event_stream,
@@ -2225,6 +2425,193 @@ impl Thread {
         ))
     }
 
+    fn has_tool_call_hooks(&self, tool_name: &str, cx: &App) -> bool {
+        let hooks = &AgentSettings::get_global(cx).hooks;
+        self.environment.is_some()
+            && (hooks.pre_tool_call_hooks(tool_name).next().is_some()
+                || hooks.post_tool_call_hooks(tool_name).next().is_some())
+    }
+
+    fn hook_working_directory(&self, cx: &App) -> Option<PathBuf> {
+        let project = self.project.read(cx);
+        let worktree = project.visible_worktrees(cx).next()?;
+        Some(worktree.read(cx).abs_path().to_path_buf())
+    }
+
+    /// Runs a tool whose input is complete, surrounded by the configured `pre_tool_call`
+    /// and `post_tool_call` hooks. A failing pre-tool-call hook denies the tool call, and
+    /// post-tool-call hook failures are appended to the tool result so the model can react.
+    fn run_tool_with_hooks(
+        &self,
+        tool: Arc<dyn AnyAgentTool>,
+        input: serde_json::Value,
+        tool_use_id: LanguageModelToolUseId,
+        tool_name: Arc<str>,
+        event_stream: &ThreadEventStream,
+        cancellation_rx: watch::Receiver<bool>,
+        cx: &mut Context<Self>,
+    ) -> Task<LanguageModelToolResult> {
+        let hooks = &AgentSettings::get_global(cx).hooks;
+        let pre_tool_call_hooks = hooks
+            .pre_tool_call_hooks(&tool_name)
+            .cloned()
+            .collect::<Vec<_>>();
+        let post_tool_call_hooks = hooks
+            .post_tool_call_hooks(&tool_name)
+            .cloned()
+            .collect::<Vec<_>>();
+        let Some(environment) = self
+            .environment
+            .clone()
+            .filter(|_| !pre_tool_call_hooks.is_empty() || !post_tool_call_hooks.is_empty())
+        else {
+            return self.run_tool(
+                tool,
+                ToolInput::ready(input),
+                tool_use_id,
+                tool_name,
+                event_stream,
+                cancellation_rx,
+                cx,
+            );
+        };
+
+        let cwd = self.hook_working_directory(cx);
+        let is_local = self.project.read(cx).is_local();
+        let event_stream = event_stream.clone();
+        cx.spawn(async move |this, cx| {
+            for hook in &pre_tool_call_hooks {
+                let tool_call = AgentHookToolCall {
+                    name: &tool_name,
+                    input: &input,
+                    output: None,
+                };
+                let outcome = run_agent_hook(
+                    &environment,
+                    hook,
+                    AgentHookEvent::PreToolCall,
+                    Some(tool_call),
+                    cwd.clone(),
+                    is_local,
+                    Some(cancellation_rx.clone()),
+                    cx,
+                )
+                .await;
+                if let ToolPermissionDecision::Deny(reason) =
+                    decide_permission_from_hook(&hook.command, &outcome)
+                {
+                    return LanguageModelToolResult {
+                        tool_use_id,
+                        tool_name,
+                        is_error: true,
+                        content: LanguageModelToolResultContent::Text(reason.as_str().into()),
+                        output: Some(serde_json::Value::String(reason)),
+                    };
+                }
+            }
+
+            let tool_task = this.update(cx, |this, cx| {
+                this.run_tool(
+                    tool,
+                    ToolInput::ready(input.clone()),
+                    tool_use_id.clone(),
+                    tool_name.clone(),
+                    &event_stream,
+                    cancellation_rx.clone(),
+                    cx,
+                )
+            });
+            let mut result = match tool_task {
+                Ok(tool_task) => tool_task.await,
+                Err(error) => {
+                    return LanguageModelToolResult {
+                        tool_use_id,
+                        tool_name,
+                        is_error: true,
+                        content: LanguageModelToolResultContent::Text(error.to_string().into()),
+                        output: None,
+                    };
+                }
+            };
+            if result.is_error {
+                return result;
+            }
+
+            let mut failures = Vec::new();
+            for hook in &post_tool_call_hooks {
+                let tool_call = AgentHookToolCall {
+                    name: &tool_name,
+                    input: &input,
+                    output: result.content.to_str(),
+                };
+                match run_agent_hook(
+                    &environment,
+                    hook,
+                    AgentHookEvent::PostToolCall,
+                    Some(tool_call),
+                    cwd.clone(),
+                    is_local,
+                    Some(cancellation_rx.clone()),
+                    cx,
+                )
+                .await
+                {
+                    Ok(outcome) if outcome.succeeded() => {}
+                    Ok(outcome) => failures.push(outcome.failure_message()),
+                    Err(error) => {
+                        log::error!("Failed to run hook `{}`: {error:#}", hook.command)
+                    }
+                }
+            }
+
+            if !failures.is_empty()
+                && let LanguageModelToolResultContent::Text(text) = &result.content
+            {
+                let mut content = text.to_string();
+                for failure in failures {
+                    content.push_str("\n\n");
+                    content.push_str(&failure);
+                }
+                result.content = LanguageModelToolResultContent::Text(content.into());
+            }
+            result
+        })
+    }
+
+    /// Runs the configured `on_stop` hooks in the background once a turn has completed.
+    fn run_stop_hooks(&self, cx: &mut Context<Self>) {
+        if self.is_subagent() {
+            return;
+        }
+        let hooks = AgentSettings::get_global(cx).hooks.on_stop.clone();
+        let Some(environment) = self.environment.clone().filter(|_| !hooks.is_empty()) else {
+            return;
+        };
+        let cwd = self.hook_working_directory(cx);
+        let is_local = self.project.read(cx).is_local();
+        cx.spawn(async move |_, cx| {
+            for hook in &hooks {
+                match run_agent_hook(
+                    &environment,
+                    hook,
+                    AgentHookEvent::Stop,
+                    None,
+                    cwd.clone(),
+                    is_local,
+                    None,
+                    cx,
+                )
+                .await
+                {
+                    Ok(outcome) if outcome.succeeded() => {}
+                    Ok(outcome) => log::warn!("{}", outcome.failure_message()),
+                    Err(error) => log::error!("Failed to run hook `{}`: {error:#}", hook.command),
+                }
+            }
+        })
+        .detach();
+    }
+
     fn run_tool(
         &self,
         tool: Arc<dyn AnyAgentTool>,
@@ -2381,7 +2768,7 @@ impl Thread {
             ..Default::default()
         };
 
-        for message in &self.messages {
+        for message in self.messages_for_request() {
             request.messages.extend(message.to_request());
         }
 
@@ -2506,6 +2893,7 @@ impl Thread {
                 Message::User(user_message) => Some(user_message),
                 Message::Agent(_) => None,
                 Message::Resume => None,
+                Message::Compaction(_) => None,
             })
     }
 
@@ -2751,6 +3139,177 @@ impl Thread {
         self.running_turn.is_none()
     }
 
+    /// Returns the messages to send to the model. If the thread has been compacted, this starts
+    /// with the most recent compaction, followed by the messages it retained.
+    fn messages_for_request(&self) -> Vec<&Message> {
+        let Some(compaction_ix) = self
+            .messages
+            .iter()
+            .rposition(|message| matches!(message, Message::Compaction(_)))
+        else {
+            return self.messages.iter().collect();
+        };
+        let Message::Compaction(compaction) = &self.messages[compaction_ix] else {
+            unreachable!()
+        };
+        let retained_ix = self.messages[..compaction_ix]
+            .iter()
+            .position(|message| {
+                matches!(message, Message::User(message) if message.id == compaction.retained_from)
+            })
+            .unwrap_or(compaction_ix);
+
+        iter::once(&self.messages[compaction_ix])
+            .chain(&self.messages[retained_ix..compaction_ix])
+            .chain(&self.messages[compaction_ix + 1..])
+            .collect()
+    }
+
+    fn build_compaction_request(&self, cx: &App) -> Result<CompactionRequest> {
+        let model = self
+            .summarization_model
+            .clone()
+            .context("No summarization model configured")?;
+        let messages = self.messages_for_request();
+        let (retained_ix, retained_from) = messages
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(ix, message)| match message {
+                Message::User(message) => Some((ix, message.id.clone())),
+                _ => None,
+            })
+            .context("Nothing to compact")?;
+        anyhow::ensure!(
+            messages[..retained_ix]
+                .iter()
+                .any(|message| !matches!(message, Message::Compaction(_))),
+            "Nothing to compact"
+        );
+
+        let mut request = LanguageModelRequest {
+            intent: Some(CompletionIntent::ThreadContextSummarization),
+            temperature: AgentSettings::temperature_for_model(&model, cx),
+            ..Default::default()
+        };
+        for message in &messages[..retained_ix] {
+            request.messages.extend(message.to_request());
+        }
+        request.messages.push(LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![COMPACT_THREAD_PROMPT.into()],
+            cache: false,
+            reasoning_details: None,
+        });
+
+        Ok(CompactionRequest {
+            model,
+            retained_from,
+            request,
+        })
+    }
+
+    /// Returns a compaction request if automatic compaction is enabled and the last request
+    /// came close to the model's context window.
+    fn auto_compaction_request(&self, cx: &App) -> Option<CompactionRequest> {
+        if !AgentSettings::get_global(cx).auto_compact {
+            return None;
+        }
+        // Only consider requests made since the last compaction.
+        let usage = self
+            .messages
+            .iter()
+            .rev()
+            .take_while(|message| !matches!(message, Message::Compaction(_)))
+            .find_map(|message| match message {
+                Message::User(message) => self.request_token_usage.get(&message.id),
+                _ => None,
+            })?;
+        let model = self.model.as_ref()?;
+        let usage = acp_thread::TokenUsage {
+            max_tokens: model.max_token_count(),
+            max_output_tokens: model.max_output_tokens(),
+            used_tokens: usage.total_tokens(),
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+        };
+        if usage.ratio() == acp_thread::TokenUsageRatio::Normal {
+            return None;
+        }
+        self.build_compaction_request(cx).ok()
+    }
+
+    async fn run_compaction(
+        this: &WeakEntity<Self>,
+        compaction: CompactionRequest,
+        event_stream: &ThreadEventStream,
+        mut cancellation_rx: watch::Receiver<bool>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let CompactionRequest {
+            model,
+            retained_from,
+            request,
+        } = compaction;
+        log::debug!("Compacting thread with model: {:?}", model.name());
+
+        let summary = {
+            let summarize = async {
+                let mut summary = String::new();
+                let mut events = model.stream_completion(request, cx).await?;
+                while let Some(event) = events.next().await {
+                    if let LanguageModelCompletionEvent::Text(text) = event? {
+                        summary.push_str(&text);
+                    }
+                }
+                anyhow::Ok(summary)
+            };
+            futures::select! {
+                summary = summarize.fuse() => summary?,
+                _ = cancellation_rx.changed().fuse() => {
+                    log::debug!("Compaction cancelled, exiting");
+                    return Ok(());
+                }
+            }
+        };
+        anyhow::ensure!(
+            !summary.trim().is_empty(),
+            "Model returned an empty summary"
+        );
+
+        this.update(cx, |this, cx| {
+            this.finish_compaction(retained_from, summary.trim().to_string(), event_stream, cx)
+        })?
+    }
+
+    fn finish_compaction(
+        &mut self,
+        retained_from: UserMessageId,
+        summary: String,
+        event_stream: &ThreadEventStream,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        anyhow::ensure!(
+            self.messages.iter().any(
+                |message| matches!(message, Message::User(message) if message.id == retained_from)
+            ),
+            "Thread was truncated during compaction"
+        );
+        self.flush_pending_message(cx);
+
+        event_stream.send_compaction(&summary);
+        // The last request's usage no longer reflects what will be sent to the model.
+        self.request_token_usage.remove(&retained_from);
+        self.messages.push(Message::Compaction(CompactionMessage {
+            summary,
+            retained_from,
+        }));
+        self.clear_summary();
+        cx.emit(TokenUsageUpdated(self.latest_token_usage()));
+        cx.notify();
+        Ok(())
+    }
+
     fn build_request_messages(
         &self,
         available_tools: Vec<SharedString>,
@@ -2775,7 +3334,7 @@ impl Thread {
             cache: false,
             reasoning_details: None,
         }];
-        for message in &self.messages {
+        for message in self.messages_for_request() {
             messages.extend(message.to_request());
         }
 
@@ -2799,7 +3358,7 @@ impl Thread {
             match message {
                 Message::User(_) => markdown.push_str("## User\n\n"),
                 Message::Agent(_) => markdown.push_str("## Assistant\n\n"),
-                Message::Resume => {}
+                Message::Resume | Message::Compaction(_) => {}
             }
             markdown.push_str(&message.to_markdown());
         }
@@ -2921,6 +3480,12 @@ impl Thread {
     }
 }
 
+struct CompactionRequest {
+    model: Arc<dyn LanguageModel>,
+    retained_from: UserMessageId,
+    request: LanguageModelRequest,
+}
+
 struct RunningTurn {
     /// Holds the task that handles agent interaction until the end of the turn.
     /// Survives across multiple requests as the model performs tool calls and
@@ -3325,6 +3890,12 @@ impl ThreadEventStream {
         self.0.unbounded_send(Ok(ThreadEvent::Retry(status))).ok();
     }
 
+    fn send_compaction(&self, summary: &str) {
+        self.0
+            .unbounded_send(Ok(ThreadEvent::Compacted(summary.to_string())))
+            .ok();
+    }
+
     fn send_stop(&self, reason: acp::StopReason) {
         self.0.unbounded_send(Ok(ThreadEvent::Stop(reason))).ok();
     }
@@ -3449,6 +4020,30 @@ impl ToolCallEventStream {
             .ok();
     }
 
+    /// Asks the user to fill in a form described by `requested_schema` while the tool is running.
+    ///
+    /// Resolves to [`acp_thread::ElicitationOutcome::Cancel`] if the request is dismissed,
+    /// e.g. because the turn was canceled.
+    pub async fn elicit(
+        &self,
+        message: impl Into<SharedString>,
+        requested_schema: serde_json::Value,
+    ) -> acp_thread::ElicitationOutcome {
+        let (response_tx, response_rx) = oneshot::channel();
+        self.stream
+            .0
+            .unbounded_send(Ok(ThreadEvent::ToolCallElicitation(ToolCallElicitation {
+                tool_call_id: acp::ToolCallId::new(self.tool_use_id.to_string()),
+                message: message.into(),
+                requested_schema,
+                response: response_tx,
+            })))
+            .ok();
+        response_rx
+            .await
+            .unwrap_or(acp_thread::ElicitationOutcome::Cancel)
+    }
+
     pub fn subagent_spawned(&self, id: acp::SessionId) {
         self.stream
             .0
@@ -3708,6 +4303,15 @@ impl ToolCallEventStreamReceiver {
         }
     }
 
+    pub async fn expect_elicitation(&mut self) -> ToolCallElicitation {
+        let event = self.0.next().await;
+        if let Some(Ok(ThreadEvent::ToolCallElicitation(elicitation))) = event {
+            elicitation
+        } else {
+            panic!("Expected ToolCallElicitation but got: {:?}", event);
+        }
+    }
+
     pub async fn expect_update_fields(&mut self) -> acp::ToolCallUpdateFields {
         let event = self.0.next().await;
         if let Some(Ok(ThreadEvent::ToolCallUpdate(acp_thread::ToolCallUpdate::UpdateFields(
@@ -146,6 +146,7 @@ mod tests {
             thinking_enabled: false,
             thinking_effort: None,
             draft_prompt: None,
+            forked_from: None,
         }
     }
 
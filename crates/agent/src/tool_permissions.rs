@@ -1,4 +1,5 @@
 use crate::AgentTool;
+use crate::hooks::AgentHookOutcome;
 use crate::tools::TerminalTool;
 use agent_settings::{AgentSettings, CompiledRegex, ToolPermissions, ToolRules};
 use settings::ToolPermissionMode;
@@ -442,6 +443,26 @@ pub fn decide_permission_from_settings(
     )
 }
 
+/// Decides permission for a tool call from the result of running a `pre_tool_call` hook.
+///
+/// The tool call is only allowed when the hook exits successfully. A hook that fails,
+/// times out, or can't be started denies the call, with the hook's output as the reason.
+pub fn decide_permission_from_hook(
+    command: &str,
+    outcome: &anyhow::Result<AgentHookOutcome>,
+) -> ToolPermissionDecision {
+    match outcome {
+        Ok(outcome) if outcome.succeeded() => ToolPermissionDecision::Allow,
+        Ok(outcome) => ToolPermissionDecision::Deny(format!(
+            "Denied by a pre-tool-call hook. {}",
+            outcome.failure_message()
+        )),
+        Err(error) => ToolPermissionDecision::Deny(format!(
+            "Denied because the pre-tool-call hook `{command}` could not be run: {error}"
+        )),
+    }
+}
+
 /// Normalizes a path by collapsing `.` and `..` segments without touching the filesystem.
 pub fn normalize_path(raw: &str) -> String {
     let is_absolute = Path::new(raw).has_root();
@@ -560,6 +581,8 @@ mod tests {
             message_editor_min_lines: 1,
             tool_permissions,
             show_turn_stats: false,
+            auto_compact: true,
+            hooks: Default::default(),
         }
     }
 
@@ -2265,4 +2288,43 @@ mod tests {
         let decision = path_perm(EditFileTool::NAME, "src/../.env", &["^\\.env"], &[], &[]);
         assert!(matches!(decision, ToolPermissionDecision::Deny(_)));
     }
+
+    #[test]
+    fn decide_permission_from_hook_outcomes() {
+        let outcome = |exit_code, timed_out, output: &str| {
+            anyhow::Ok(AgentHookOutcome {
+                command: "./policy.sh".into(),
+                exit_code,
+                timed_out,
+                cancelled: false,
+                output: output.into(),
+            })
+        };
+
+        assert_eq!(
+            decide_permission_from_hook("./policy.sh", &outcome(Some(0), false, "ok")),
+            ToolPermissionDecision::Allow
+        );
+        assert_eq!(
+            decide_permission_from_hook(
+                "./policy.sh",
+                &outcome(Some(2), false, "editing vendor/ is not allowed")
+            ),
+            ToolPermissionDecision::Deny(
+                "Denied by a pre-tool-call hook. Hook `./policy.sh` exited with code 2:\n\n\
+                 ```\nediting vendor/ is not allowed\n```"
+                    .into()
+            )
+        );
+        assert_eq!(
+            decide_permission_from_hook("./policy.sh", &outcome(Some(0), true, "")),
+            ToolPermissionDecision::Deny(
+                "Denied by a pre-tool-call hook. Hook `./policy.sh` timed out.".into()
+            )
+        );
+        assert!(matches!(
+            decide_permission_from_hook("./policy.sh", &Err(anyhow::anyhow!("no shell"))),
+            ToolPermissionDecision::Deny(reason) if reason.contains("no shell")
+        ));
+    }
 }
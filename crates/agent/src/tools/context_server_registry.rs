@@ -2,9 +2,11 @@ use crate::{AgentToolOutput, AnyAgentTool, ToolCallEventStream, ToolInput};
 use agent_client_protocol::ToolKind;
 use anyhow::Result;
 use collections::{BTreeMap, HashMap};
+use context_server::types::{ElicitationAction, ElicitationCreateResponse, requests};
 use context_server::{ContextServerId, client::NotificationSubscription};
 use futures::FutureExt as _;
 use gpui::{App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Task};
+use parking_lot::Mutex;
 use project::context_server_store::{ContextServerStatus, ContextServerStore};
 use std::sync::Arc;
 use util::ResultExt;
@@ -39,9 +41,53 @@ struct RegisteredContextServer {
     prompts: BTreeMap<SharedString, ContextServerPrompt>,
     load_tools: Task<Result<()>>,
     load_prompts: Task<Result<()>>,
+    running_tool_calls: RunningToolCalls,
     _tools_updated_subscription: Option<NotificationSubscription>,
 }
 
+/// Tool calls currently running on a context server.
+///
+/// Each call is sent with its tool use id as the progress token, which servers can echo in the
+/// `_meta` of requests such as elicitation to tie them to the call that caused them.
+#[derive(Clone, Default)]
+struct RunningToolCalls(Arc<Mutex<Vec<ToolCallEventStream>>>);
+
+const PROGRESS_TOKEN_KEY: &str = "progressToken";
+
+impl RunningToolCalls {
+    fn push(&self, event_stream: ToolCallEventStream) {
+        self.0.lock().push(event_stream);
+    }
+
+    fn remove(&self, event_stream: &ToolCallEventStream) {
+        self.0
+            .lock()
+            .retain(|running| running.tool_use_id() != event_stream.tool_use_id());
+    }
+
+    /// Returns the tool call that a server-initiated request belongs to: the one whose progress
+    /// token the request carries, or else the only running call, if there is exactly one.
+    fn for_request(
+        &self,
+        meta: Option<&HashMap<String, serde_json::Value>>,
+    ) -> Option<ToolCallEventStream> {
+        let running = self.0.lock();
+        if let Some(progress_token) = meta
+            .and_then(|meta| meta.get(PROGRESS_TOKEN_KEY))
+            .and_then(|token| token.as_str())
+        {
+            return running
+                .iter()
+                .find(|running| running.tool_use_id().to_string() == progress_token)
+                .cloned();
+        }
+        match running.as_slice() {
+            [running] => Some(running.clone()),
+            _ => None,
+        }
+    }
+}
+
 impl ContextServerRegistry {
     pub fn new(server_store: Entity<ContextServerStore>, cx: &mut Context<Self>) -> Self {
         let mut this = Self {
@@ -121,6 +167,15 @@ impl ContextServerRegistry {
         server_store: &Entity<ContextServerStore>,
         cx: &mut Context<Self>,
     ) -> RegisteredContextServer {
+        let running_tool_calls = RunningToolCalls::default();
+        let client = server_store
+            .read(cx)
+            .get_running_server(server_id)
+            .and_then(|server| server.client());
+        if let Some(client) = client.as_ref() {
+            Self::handle_elicitation_requests(client, running_tool_calls.clone());
+        }
+
         let tools_updated_subscription = server_store
             .read(cx)
             .get_running_server(server_id)
@@ -158,10 +213,47 @@ impl ContextServerRegistry {
             prompts: BTreeMap::default(),
             load_tools: Task::ready(Ok(())),
             load_prompts: Task::ready(Ok(())),
+            running_tool_calls,
             _tools_updated_subscription: tools_updated_subscription,
         }
     }
 
+    fn handle_elicitation_requests(
+        client: &context_server::protocol::InitializedContextServerProtocol,
+        running_tool_calls: RunningToolCalls,
+    ) {
+        client.on_request::<requests::ElicitationCreate>(move |params, cx| {
+            let Some(event_stream) = running_tool_calls.for_request(params.meta.as_ref()) else {
+                log::warn!(
+                    "Declining MCP elicitation request that can't be tied to a running tool call"
+                );
+                return Task::ready(Ok(ElicitationCreateResponse {
+                    action: ElicitationAction::Decline,
+                    content: None,
+                    meta: None,
+                }));
+            };
+            cx.spawn(async move |_| {
+                let requested_schema = serde_json::to_value(&params.requested_schema)?;
+                let (action, content) = match event_stream
+                    .elicit(params.message, requested_schema)
+                    .await
+                {
+                    acp_thread::ElicitationOutcome::Accept(content) => {
+                        (ElicitationAction::Accept, Some(content))
+                    }
+                    acp_thread::ElicitationOutcome::Decline => (ElicitationAction::Decline, None),
+                    acp_thread::ElicitationOutcome::Cancel => (ElicitationAction::Cancel, None),
+                };
+                Ok(ElicitationCreateResponse {
+                    action,
+                    content,
+                    meta: None,
+                })
+            })
+        });
+    }
+
     fn reload_tools_for_server(&mut self, server_id: ContextServerId, cx: &mut Context<Self>) {
         let Some(server) = self.server_store.read(cx).get_running_server(&server_id) else {
             return;
@@ -192,6 +284,7 @@ impl ContextServerRegistry {
                             this.server_store.clone(),
                             server.id(),
                             tool,
+                            registered_server.running_tool_calls.clone(),
                         ));
                         registered_server.tools.insert(tool.name(), tool);
                     }
@@ -277,6 +370,7 @@ struct ContextServerTool {
     store: Entity<ContextServerStore>,
     server_id: ContextServerId,
     tool: context_server::types::Tool,
+    running_tool_calls: RunningToolCalls,
 }
 
 impl ContextServerTool {
@@ -284,11 +378,13 @@ impl ContextServerTool {
         store: Entity<ContextServerStore>,
         server_id: ContextServerId,
         tool: context_server::types::Tool,
+        running_tool_calls: RunningToolCalls,
     ) -> Self {
         Self {
             store,
             server_id,
             tool,
+            running_tool_calls,
         }
     }
 }
@@ -342,6 +438,7 @@ impl AnyAgentTool for ContextServerTool {
         let initial_title = self.initial_title(serde_json::Value::Null, cx);
         let authorize =
             event_stream.authorize_third_party_tool(initial_title, tool_id, display_name, cx);
+        let running_tool_calls = self.running_tool_calls.clone();
 
         cx.spawn(async move |_cx| {
             let input = input.recv().await.map_err(|e| {
@@ -370,10 +467,20 @@ impl AnyAgentTool for ContextServerTool {
                 context_server::types::CallToolParams {
                     name: tool_name,
                     arguments,
-                    meta: None,
+                    meta: Some(HashMap::from_iter([(
+                        PROGRESS_TOKEN_KEY.to_string(),
+                        serde_json::Value::String(event_stream.tool_use_id().to_string()),
+                    )])),
                 },
             );
 
+            running_tool_calls.push(event_stream.clone());
+            let _running_tool_call = util::defer({
+                let running_tool_calls = running_tool_calls.clone();
+                let event_stream = event_stream.clone();
+                move || running_tool_calls.remove(&event_stream)
+            });
+
             let response = futures::select! {
                 response = request.fuse() => response.map_err(|e| AgentToolOutput::from_error(e.to_string()))?,
                 _ = event_stream.cancelled_by_user().fuse() => {
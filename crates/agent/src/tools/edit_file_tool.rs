@@ -104,6 +104,9 @@ pub enum EditFileToolOutput {
         old_text: Arc<String>,
         #[serde(default)]
         diff: String,
+        /// Rules from subdirectories containing the edited file that the model hasn't seen yet.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        nested_rules: Option<String>,
     },
     Error {
         error: String,
@@ -114,17 +117,24 @@ impl std::fmt::Display for EditFileToolOutput {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             EditFileToolOutput::Success {
-                diff, input_path, ..
+                diff,
+                input_path,
+                nested_rules,
+                ..
             } => {
                 if diff.is_empty() {
-                    write!(f, "No edits were made.")
+                    write!(f, "No edits were made.")?;
                 } else {
                     write!(
                         f,
                         "Edited {}:\n\n```diff\n{diff}\n```",
                         input_path.display()
-                    )
+                    )?;
+                }
+                if let Some(nested_rules) = nested_rules {
+                    write!(f, "\n\n{nested_rules}")?;
                 }
+                Ok(())
             }
             EditFileToolOutput::Error { error } => write!(f, "{error}"),
         }
@@ -521,11 +531,16 @@ impl AgentTool for EditFileTool {
                     );
                 }
 
+                let nested_rules = self.thread.update(cx, |thread, cx| {
+                    thread.take_nested_rules_for_path(&project_path, cx)
+                })?;
+
                 anyhow::Ok(EditFileToolOutput::Success {
                     input_path: input.path,
                     new_text,
                     old_text,
                     diff: unified_diff,
+                    nested_rules,
                 })
             }.await;
             result
@@ -354,7 +354,23 @@ impl AgentTool for ReadFileTool {
                 }
             });
 
-            result
+            match result {
+                Ok(LanguageModelToolResultContent::Text(text)) => {
+                    // Provide any rules files that apply to this file's directory the first time
+                    // it's read.
+                    let nested_rules = thread
+                        .update(cx, |thread, cx| {
+                            thread.take_nested_rules_for_path(&project_path, cx)
+                        })
+                        .ok()
+                        .flatten();
+                    match nested_rules {
+                        Some(nested_rules) => Ok(format!("{text}\n\n{nested_rules}").into()),
+                        None => Ok(LanguageModelToolResultContent::Text(text)),
+                    }
+                }
+                result => result,
+            }
         })
     }
 }
@@ -368,12 +384,12 @@ mod test {
     use gpui::{AppContext, TestAppContext, UpdateGlobal as _};
     use language_model::fake_provider::FakeLanguageModel;
     use project::{FakeFs, Project};
-    use prompt_store::ProjectContext;
+    use prompt_store::{NestedRulesFileContext, ProjectContext, RulesFileContext, WorktreeContext};
     use serde_json::json;
     use settings::SettingsStore;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use std::sync::Arc;
-    use util::path;
+    use util::{path, rel_path::rel_path};
 
     #[gpui::test]
     async fn test_read_nonexistent_file(cx: &mut TestAppContext) {
@@ -688,6 +704,84 @@ mod test {
         }
     }
 
+    #[gpui::test]
+    async fn test_read_file_includes_nested_rules_once(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "service": {
+                    "AGENTS.md": "Use tabs.",
+                    "a.txt": "a",
+                    "b.txt": "b",
+                },
+                "other.txt": "other",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let context_server_registry =
+            cx.new(|cx| ContextServerRegistry::new(project.read(cx).context_server_store(), cx));
+        let project_context = ProjectContext::new(
+            vec![WorktreeContext {
+                root_name: "root".into(),
+                abs_path: Path::new(path!("/root")).into(),
+                rules_file: None,
+                nested_rules_files: vec![NestedRulesFileContext {
+                    directory: rel_path("service").into(),
+                    rules_file: RulesFileContext {
+                        path_in_worktree: rel_path("service/AGENTS.md").into(),
+                        text: "Use tabs.".into(),
+                        project_entry_id: 0,
+                    },
+                }],
+            }],
+            Vec::new(),
+        );
+        let model = Arc::new(FakeLanguageModel::default());
+        let thread = cx.new(|cx| {
+            Thread::new(
+                project.clone(),
+                cx.new(|_cx| project_context),
+                context_server_registry,
+                Templates::new(),
+                Some(model),
+                cx,
+            )
+        });
+        let tool = Arc::new(ReadFileTool::new(thread.downgrade(), project, action_log));
+        let read = |path: &str, cx: &mut TestAppContext| {
+            let input = ReadFileToolInput {
+                path: path.into(),
+                start_line: None,
+                end_line: None,
+            };
+            cx.update(|cx| {
+                tool.clone().run(
+                    ToolInput::resolved(input),
+                    ToolCallEventStream::test().0,
+                    cx,
+                )
+            })
+        };
+
+        let result = read("root/other.txt", cx).await.unwrap();
+        assert_eq!(result, "other".into());
+
+        let result = read("root/service/a.txt", cx).await.unwrap();
+        assert_eq!(
+            result,
+            "a\n\nThe following rules from `service/AGENTS.md` apply to files in `service`:\n\nUse tabs."
+                .into()
+        );
+
+        let result = read("root/service/b.txt", cx).await.unwrap();
+        assert_eq!(result, "b".into());
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings_store = SettingsStore::test(cx);
@@ -145,6 +145,9 @@ pub enum StreamingEditFileToolOutput {
         old_text: Arc<String>,
         #[serde(default)]
         diff: String,
+        /// Rules from subdirectories containing the edited file that the model hasn't seen yet.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        nested_rules: Option<String>,
     },
     Error {
         error: String,
@@ -163,17 +166,24 @@ impl std::fmt::Display for StreamingEditFileToolOutput {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             StreamingEditFileToolOutput::Success {
-                diff, input_path, ..
+                diff,
+                input_path,
+                nested_rules,
+                ..
             } => {
                 if diff.is_empty() {
-                    write!(f, "No edits were made.")
+                    write!(f, "No edits were made.")?;
                 } else {
                     write!(
                         f,
                         "Edited {}:\n\n```diff\n{diff}\n```",
                         input_path.display()
-                    )
+                    )?;
+                }
+                if let Some(nested_rules) = nested_rules {
+                    write!(f, "\n\n{nested_rules}")?;
                 }
+                Ok(())
             }
             StreamingEditFileToolOutput::Error { error } => write!(f, "{error}"),
         }
@@ -662,11 +672,24 @@ impl EditSession {
             })
             .await;
 
+        let nested_rules = tool
+            .thread
+            .update(cx, |thread, cx| {
+                let project_path = thread
+                    .project()
+                    .read(cx)
+                    .find_project_path(abs_path.as_path(), cx)?;
+                thread.take_nested_rules_for_path(&project_path, cx)
+            })
+            .ok()
+            .flatten();
+
         let output = StreamingEditFileToolOutput::Success {
             input_path: PathBuf::from(input.path),
             new_text,
             old_text: old_text.clone(),
             diff: unified_diff,
+            nested_rules,
         };
         Ok(output)
     }
@@ -132,6 +132,7 @@ impl AgentTool for TerminalTool {
                 .environment
                 .create_terminal(
                     input.command.clone(),
+                    vec![],
                     working_dir,
                     Some(COMMAND_OUTPUT_LIMIT),
                     cx,
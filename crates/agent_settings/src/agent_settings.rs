@@ -2,6 +2,7 @@ mod agent_profile;
 
 use std::path::{Component, Path};
 use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 
 use agent_client_protocol::ModelId;
 use collections::{HashSet, IndexMap};
@@ -20,6 +21,7 @@ pub use crate::agent_profile::*;
 pub const SUMMARIZE_THREAD_PROMPT: &str = include_str!("prompts/summarize_thread_prompt.txt");
 pub const SUMMARIZE_THREAD_DETAILED_PROMPT: &str =
     include_str!("prompts/summarize_thread_detailed_prompt.txt");
+pub const COMPACT_THREAD_PROMPT: &str = include_str!("prompts/compact_thread_prompt.txt");
 
 #[derive(Clone, Debug, RegisterSetting)]
 pub struct AgentSettings {
@@ -50,7 +52,9 @@ pub struct AgentSettings {
     pub use_modifier_to_send: bool,
     pub message_editor_min_lines: usize,
     pub show_turn_stats: bool,
+    pub auto_compact: bool,
     pub tool_permissions: ToolPermissions,
+    pub hooks: AgentHooks,
 }
 
 impl AgentSettings {
@@ -133,6 +137,47 @@ impl ToolPermissions {
     }
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct AgentHooks {
+    pub pre_tool_call: Vec<AgentHook>,
+    pub post_tool_call: Vec<AgentHook>,
+    pub on_stop: Vec<AgentHook>,
+}
+
+impl AgentHooks {
+    pub fn pre_tool_call_hooks(&self, tool_name: &str) -> impl Iterator<Item = &AgentHook> {
+        self.pre_tool_call
+            .iter()
+            .filter(move |hook| hook.applies_to_tool(tool_name))
+    }
+
+    pub fn post_tool_call_hooks(&self, tool_name: &str) -> impl Iterator<Item = &AgentHook> {
+        self.post_tool_call
+            .iter()
+            .filter(move |hook| hook.applies_to_tool(tool_name))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AgentHook {
+    pub command: String,
+    /// Restricts tool call hooks to matching tool names. `None` matches every tool,
+    /// which is also what happens when the configured pattern fails to compile, so
+    /// that a typo can't silently disable a policy check.
+    pub tool: Option<CompiledRegex>,
+    pub timeout: Duration,
+}
+
+impl AgentHook {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+    pub fn applies_to_tool(&self, tool_name: &str) -> bool {
+        self.tool
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_match(tool_name))
+    }
+}
+
 /// Represents a regex pattern that failed to compile.
 #[derive(Clone, Debug)]
 pub struct InvalidRegexPattern {
@@ -437,7 +482,9 @@ impl Settings for AgentSettings {
             use_modifier_to_send: agent.use_modifier_to_send.unwrap(),
             message_editor_min_lines: agent.message_editor_min_lines.unwrap(),
             show_turn_stats: agent.show_turn_stats.unwrap(),
+            auto_compact: agent.auto_compact.unwrap(),
             tool_permissions: compile_tool_permissions(agent.tool_permissions),
+            hooks: compile_agent_hooks(agent.hooks),
         }
     }
 }
@@ -504,6 +551,46 @@ fn compile_tool_permissions(content: Option<settings::ToolPermissionsContent>) -
     }
 }
 
+fn compile_agent_hooks(content: Option<settings::AgentHooksContent>) -> AgentHooks {
+    let Some(content) = content else {
+        return AgentHooks::default();
+    };
+
+    let compile = |hooks: Option<Vec<settings::AgentHookContent>>, event: &str| {
+        hooks
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|hook| !hook.command.trim().is_empty())
+            .map(|hook| {
+                let tool = hook.tool.as_deref().and_then(|pattern| {
+                    CompiledRegex::try_new(pattern, false)
+                        .inspect_err(|error| {
+                            log::error!(
+                                "Invalid tool pattern in {event} hook '{}': '{pattern}' - {error}",
+                                hook.command,
+                            );
+                        })
+                        .ok()
+                });
+                AgentHook {
+                    command: hook.command,
+                    tool,
+                    timeout: hook
+                        .timeout_ms
+                        .map(Duration::from_millis)
+                        .unwrap_or(AgentHook::DEFAULT_TIMEOUT),
+                }
+            })
+            .collect()
+    };
+
+    AgentHooks {
+        pre_tool_call: compile(content.pre_tool_call, "pre_tool_call"),
+        post_tool_call: compile(content.post_tool_call, "post_tool_call"),
+        on_stop: compile(content.on_stop, "on_stop"),
+    }
+}
+
 fn compile_regex_rules(
     rules: Vec<settings::ToolRegexRule>,
     rule_type: &str,
@@ -615,6 +702,44 @@ mod tests {
         assert_eq!(permissions.default, ToolPermissionMode::Confirm);
     }
 
+    #[test]
+    fn test_agent_hooks_parsing() {
+        let content: settings::AgentHooksContent = serde_json::from_value(json!({
+            "pre_tool_call": [
+                { "command": "./check-policy.sh" },
+                { "command": "   " }
+            ],
+            "post_tool_call": [
+                { "command": "cargo fmt", "tool": "^(edit_file|streaming_edit_file)$", "timeout_ms": 5000 },
+                { "command": "./lint.sh", "tool": "[invalid" }
+            ],
+            "on_stop": [{ "command": "notify-send done" }]
+        }))
+        .unwrap();
+        let hooks = compile_agent_hooks(Some(content));
+
+        assert_eq!(hooks.pre_tool_call.len(), 1);
+        assert_eq!(hooks.pre_tool_call_hooks("terminal").count(), 1);
+        assert_eq!(hooks.pre_tool_call[0].timeout, AgentHook::DEFAULT_TIMEOUT);
+
+        let edit_hooks = hooks
+            .post_tool_call_hooks("edit_file")
+            .map(|hook| hook.command.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(edit_hooks, vec!["cargo fmt", "./lint.sh"]);
+        assert_eq!(hooks.post_tool_call[0].timeout, Duration::from_millis(5000));
+
+        // Hooks with an invalid tool pattern run for every tool.
+        let terminal_hooks = hooks
+            .post_tool_call_hooks("terminal")
+            .map(|hook| hook.command.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(terminal_hooks, vec!["./lint.sh"]);
+
+        assert_eq!(hooks.on_stop.len(), 1);
+        assert!(compile_agent_hooks(None).pre_tool_call.is_empty());
+    }
+
     #[test]
     fn test_tool_rules_default_returns_confirm() {
         let default_rules = ToolRules::default();
@@ -880,6 +880,24 @@ impl AgentPanel {
         );
     }
 
+    /// Opens a thread that was forked from another one, pre-filling the message editor with
+    /// `initial_content` when the fork was made at a user message.
+    pub(crate) fn open_forked_thread(
+        &mut self,
+        thread: AgentSessionInfo,
+        initial_content: Option<AgentInitialContent>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.external_thread(
+            Some(crate::ExternalAgent::NativeAgent),
+            Some(thread),
+            initial_content,
+            window,
+            cx,
+        );
+    }
+
     pub(crate) fn context_server_registry(&self) -> &Entity<ContextServerRegistry> {
         &self.context_server_registry
     }
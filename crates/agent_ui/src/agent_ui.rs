@@ -9,6 +9,7 @@ mod config_options;
 pub(crate) mod connection_view;
 mod context;
 mod context_server_configuration;
+mod elicitation_form;
 mod entry_view_state;
 mod favorite_models;
 mod inline_assistant;
@@ -142,6 +143,8 @@ actions!(
         OpenAddContextMenu,
         /// Continues the current thread.
         ContinueThread,
+        /// Summarizes older messages in the current thread to free up space in the context window.
+        CompactThread,
         /// Interrupts the current generation and sends the message immediately.
         SendImmediately,
         /// Sends the next queued message immediately.
@@ -563,6 +566,8 @@ mod tests {
             message_editor_min_lines: 1,
             tool_permissions: Default::default(),
             show_turn_stats: false,
+            auto_compact: true,
+            hooks: Default::default(),
         };
 
         cx.update(|cx| {
@@ -222,6 +222,7 @@ pub struct ThreadView {
     pub expanded_tool_calls: HashSet<agent_client_protocol::ToolCallId>,
     pub expanded_tool_call_raw_inputs: HashSet<agent_client_protocol::ToolCallId>,
     pub expanded_thinking_blocks: HashSet<(usize, usize)>,
+    pub expanded_compactions: HashSet<usize>,
     pub subagent_scroll_handles: RefCell<HashMap<agent_client_protocol::SessionId, ScrollHandle>>,
     pub edits_expanded: bool,
     pub plan_expanded: bool,
@@ -447,6 +448,7 @@ impl ThreadView {
             expanded_tool_calls: HashSet::default(),
             expanded_tool_call_raw_inputs: HashSet::default(),
             expanded_thinking_blocks: HashSet::default(),
+            expanded_compactions: HashSet::default(),
             subagent_scroll_handles: RefCell::new(HashMap::default()),
             edits_expanded: false,
             plan_expanded: false,
@@ -1076,6 +1078,99 @@ impl ThreadView {
         .detach();
     }
 
+    pub fn compact_thread(&mut self, cx: &mut Context<Self>) {
+        self.thread_error.take();
+
+        let thread = &self.thread;
+        if !thread.read(cx).can_compact(cx) {
+            return;
+        }
+
+        let task = thread.update(cx, |thread, cx| thread.compact(cx));
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+
+            this.update(cx, |this, cx| {
+                if let Err(err) = result {
+                    this.handle_thread_error(err, cx);
+                }
+            })
+        })
+        .detach();
+    }
+
+    fn can_fork(&self, cx: &App) -> bool {
+        !self.is_subagent() && self.as_native_connection(cx).is_some()
+    }
+
+    /// Forks the thread into a new one that shares its history up to the given entry.
+    ///
+    /// Forking at a user message keeps everything before it and pre-fills the new thread's
+    /// message editor with that message, while forking at an agent response keeps the whole
+    /// turn it belongs to.
+    pub fn fork_thread(&mut self, entry_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.can_fork(cx) {
+            return;
+        }
+        let Some(connection) = self.as_native_connection(cx) else {
+            return;
+        };
+
+        let thread = self.thread.read(cx);
+        let entries = thread.entries();
+        let (until, initial_content) = match entries.get(entry_ix) {
+            Some(AgentThreadEntry::UserMessage(message)) => {
+                let Some(message_id) = message.id.clone() else {
+                    return;
+                };
+                (
+                    Some(message_id),
+                    Some(AgentInitialContent::ContentBlock {
+                        blocks: message.chunks.clone(),
+                        auto_submit: false,
+                    }),
+                )
+            }
+            Some(_) => (
+                entries[entry_ix + 1..]
+                    .iter()
+                    .find_map(|entry| match entry {
+                        AgentThreadEntry::UserMessage(message) => message.id.clone(),
+                        _ => None,
+                    }),
+                None,
+            ),
+            None => return,
+        };
+
+        let title = thread.title();
+        let task = connection.fork_thread(thread.session_id().clone(), until, cx);
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_this, cx| {
+            let session_id = task.await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                    panel.update(cx, |panel, cx| {
+                        panel.open_forked_thread(
+                            AgentSessionInfo {
+                                session_id,
+                                cwd: None,
+                                title: Some(title),
+                                updated_at: Some(chrono::Utc::now()),
+                                meta: None,
+                            },
+                            initial_content,
+                            window,
+                            cx,
+                        )
+                    });
+                }
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn regenerate(
         &mut self,
         entry_ix: usize,
@@ -3760,6 +3855,20 @@ impl ThreadView {
                                 } else if message.id.is_some() {
                                     this.child(
                                         base_container
+                                            .when(entry_ix > 0 && self.can_fork(cx), |this| {
+                                                this.child(
+                                                    IconButton::new("fork", IconName::GitBranch)
+                                                        .disabled(is_loading_contents)
+                                                        .icon_color(Color::Muted)
+                                                        .icon_size(IconSize::XSmall)
+                                                        .tooltip(Tooltip::text(
+                                                            "Fork the thread into a new one starting from this message."
+                                                        ))
+                                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                                            this.fork_thread(entry_ix, window, cx);
+                                                        }))
+                                                )
+                                            })
                                             .child(
                                                 IconButton::new("cancel", IconName::Close)
                                                     .disabled(is_loading_contents)
@@ -3897,6 +4006,9 @@ impl ThreadView {
                     cx,
                 )
                 .into_any(),
+            AgentThreadEntry::Compaction(compaction) => {
+                self.render_compaction(entry_ix, compaction, window, cx)
+            }
         };
 
         let is_subagent_output = self.is_subagent()
@@ -4533,6 +4645,67 @@ impl ThreadView {
             .into_any_element()
     }
 
+    fn render_compaction(
+        &self,
+        entry_ix: usize,
+        compaction: &acp_thread::Compaction,
+        window: &Window,
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let is_open = self.expanded_compactions.contains(&entry_ix);
+        let toggle = cx.listener(move |this, _event, _window, cx| {
+            if is_open {
+                this.expanded_compactions.remove(&entry_ix);
+            } else {
+                this.expanded_compactions.insert(entry_ix);
+            }
+            cx.notify();
+        });
+
+        v_flex()
+            .px_5()
+            .py_1p5()
+            .gap_1()
+            .child(
+                h_flex()
+                    .id(("compaction-header", entry_ix))
+                    .gap_2()
+                    .child(Divider::horizontal())
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                Icon::new(IconName::ListCollapse)
+                                    .color(Color::Muted)
+                                    .size(IconSize::Small),
+                            )
+                            .child(
+                                Label::new("Thread Compacted")
+                                    .size(LabelSize::Custom(self.tool_name_font_size()))
+                                    .color(Color::Muted),
+                            )
+                            .child(
+                                Disclosure::new(("compaction-disclosure", entry_ix), is_open)
+                                    .opened_icon(IconName::ChevronUp)
+                                    .closed_icon(IconName::ChevronDown)
+                                    .on_click(toggle.clone()),
+                            ),
+                    )
+                    .child(Divider::horizontal())
+                    .on_click(toggle)
+                    .tooltip(Tooltip::text(
+                        "Messages above this line were replaced by a summary to save context.",
+                    )),
+            )
+            .when(is_open, |this| {
+                this.child(div().text_ui_sm(cx).child(self.render_markdown(
+                    compaction.summary.clone(),
+                    MarkdownStyle::themed(MarkdownFont::Agent, window, cx),
+                )))
+            })
+            .into_any_element()
+    }
+
     fn render_message_context_menu(
         &self,
         entry_ix: usize,
@@ -4588,6 +4761,19 @@ impl ThreadView {
                             }
                         });
 
+                    let can_fork = this.can_fork(cx);
+                    let fork_from_here = ContextMenuEntry::new("Fork From Here")
+                        .icon(IconName::GitBranch)
+                        .icon_color(Color::Muted)
+                        .handler({
+                            let entity = entity.clone();
+                            move |window, cx| {
+                                entity.update(cx, |this, cx| {
+                                    this.fork_thread(entry_ix, window, cx);
+                                });
+                            }
+                        });
+
                     let scroll_item = if is_at_top {
                         ContextMenuEntry::new("Scroll to Bottom").handler({
                             let entity = entity.clone();
@@ -4630,6 +4816,7 @@ impl ThreadView {
                             Box::new(markdown::CopyAsMarkdown),
                         )
                         .item(copy_this_agent_response)
+                        .when(can_fork, |menu| menu.separator().item(fork_from_here))
                         .separator()
                         .item(scroll_item)
                         .item(open_thread_as_markdown)
@@ -4714,7 +4901,9 @@ impl ThreadView {
                         return false;
                     }
                 }
-                AgentThreadEntry::ToolCall(_) | AgentThreadEntry::AssistantMessage(_) => {}
+                AgentThreadEntry::ToolCall(_)
+                | AgentThreadEntry::AssistantMessage(_)
+                | AgentThreadEntry::Compaction(_) => {}
             }
         }
 
@@ -5142,10 +5331,15 @@ impl ThreadView {
             _ => false,
         };
 
+        let elicitation_form = self
+            .entry_view_state
+            .read(cx)
+            .entry(entry_ix)
+            .and_then(|entry| entry.elicitation_form());
         let needs_confirmation = matches!(
             tool_call.status,
             ToolCallStatus::WaitingForConfirmation { .. }
-        );
+        ) || elicitation_form.is_some();
         let is_terminal_tool = matches!(tool_call.kind, acp::ToolKind::Execute);
 
         let is_edit =
@@ -5553,6 +5747,7 @@ impl ThreadView {
                 }
             })
             .children(tool_output_display)
+            .children(elicitation_form)
     }
 
     fn render_permission_buttons(
@@ -7440,7 +7635,12 @@ impl ThreadView {
             ),
         };
 
-        let description = "To continue, start a new thread from a summary.";
+        let can_compact = self.thread.read(cx).can_compact(cx);
+        let description = if can_compact {
+            "To continue, compact this thread or start a new thread from a summary."
+        } else {
+            "To continue, start a new thread from a summary."
+        };
 
         Some(
             Callout::new()
@@ -7449,20 +7649,31 @@ impl ThreadView {
                 .title(title)
                 .description(description)
                 .actions_slot(
-                    h_flex().gap_0p5().child(
-                        Button::new("start-new-thread", "Start New Thread")
-                            .label_size(LabelSize::Small)
-                            .on_click(cx.listener(|this, _, window, cx| {
-                                let session_id = this.thread.read(cx).session_id().clone();
-                                window.dispatch_action(
-                                    crate::NewNativeAgentThreadFromSummary {
-                                        from_session_id: session_id,
-                                    }
-                                    .boxed_clone(),
-                                    cx,
-                                );
-                            })),
-                    ),
+                    h_flex()
+                        .gap_0p5()
+                        .when(can_compact, |this| {
+                            this.child(
+                                Button::new("compact-thread", "Compact Thread")
+                                    .label_size(LabelSize::Small)
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.compact_thread(cx);
+                                    })),
+                            )
+                        })
+                        .child(
+                            Button::new("start-new-thread", "Start New Thread")
+                                .label_size(LabelSize::Small)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    let session_id = this.thread.read(cx).session_id().clone();
+                                    window.dispatch_action(
+                                        crate::NewNativeAgentThreadFromSummary {
+                                            from_session_id: session_id,
+                                        }
+                                        .boxed_clone(),
+                                        cx,
+                                    );
+                                })),
+                        ),
                 )
                 .dismiss_action(self.dismiss_error_button(cx)),
         )
@@ -7610,6 +7821,9 @@ impl Render for ThreadView {
             .on_action(cx.listener(Self::handle_authorize_tool_call))
             .on_action(cx.listener(Self::open_permission_dropdown))
             .on_action(cx.listener(Self::open_add_context_menu))
+            .on_action(cx.listener(|this, _: &CompactThread, _window, cx| {
+                this.compact_thread(cx);
+            }))
             .on_action(cx.listener(|this, _: &ToggleFastMode, _window, cx| {
                 this.toggle_fast_mode(cx);
             }))
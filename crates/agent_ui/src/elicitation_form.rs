@@ -0,0 +1,464 @@
+use acp_thread::{AcpThread, ElicitationOutcome};
+use agent_client_protocol as acp;
+use anyhow::{Result, anyhow};
+use context_server::types::{ElicitationSchema, PrimitiveSchema};
+use editor::Editor;
+use gpui::{Entity, WeakEntity};
+use serde_json::{Map, Value};
+use ui::{Checkbox, TintColor, ToggleState, prelude::*};
+
+/// A form rendered inside a tool call card when a tool (e.g. an MCP server)
+/// asks the user for structured input.
+pub struct ElicitationForm {
+    thread: WeakEntity<AcpThread>,
+    tool_call_id: acp::ToolCallId,
+    message: SharedString,
+    fields: Vec<ElicitationField>,
+    error: Option<SharedString>,
+}
+
+struct ElicitationField {
+    name: String,
+    label: SharedString,
+    description: Option<SharedString>,
+    required: bool,
+    input: FieldInput,
+}
+
+enum FieldInput {
+    Text {
+        editor: Entity<Editor>,
+        kind: TextKind,
+    },
+    Boolean(bool),
+    Enum {
+        values: Vec<String>,
+        labels: Vec<SharedString>,
+        selected: Option<usize>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TextKind {
+    String {
+        min_length: Option<usize>,
+        max_length: Option<usize>,
+    },
+    Number {
+        minimum: Option<f64>,
+        maximum: Option<f64>,
+    },
+    Integer {
+        minimum: Option<i64>,
+        maximum: Option<i64>,
+    },
+}
+
+impl ElicitationForm {
+    pub fn new(
+        thread: WeakEntity<AcpThread>,
+        tool_call_id: acp::ToolCallId,
+        message: SharedString,
+        requested_schema: &Value,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let (schema, error) =
+            match serde_json::from_value::<ElicitationSchema>(requested_schema.clone()) {
+                Ok(schema) => (schema, None),
+                Err(error) => (
+                    ElicitationSchema::default(),
+                    Some(format!("Unsupported form schema: {error}").into()),
+                ),
+            };
+
+        let fields = schema
+            .properties
+            .iter()
+            .map(|(name, property)| {
+                let label: SharedString = property.title().unwrap_or(name).to_string().into();
+                let input = match property {
+                    PrimitiveSchema::String {
+                        enum_values: Some(values),
+                        enum_names,
+                        default,
+                        ..
+                    } => {
+                        let labels = values
+                            .iter()
+                            .enumerate()
+                            .map(|(ix, value)| {
+                                enum_names
+                                    .as_ref()
+                                    .and_then(|names| names.get(ix))
+                                    .unwrap_or(value)
+                                    .clone()
+                                    .into()
+                            })
+                            .collect();
+                        let selected = default
+                            .as_ref()
+                            .and_then(|default| values.iter().position(|value| value == default));
+                        FieldInput::Enum {
+                            values: values.clone(),
+                            labels,
+                            selected,
+                        }
+                    }
+                    PrimitiveSchema::String {
+                        min_length,
+                        max_length,
+                        default,
+                        ..
+                    } => Self::text_input(
+                        TextKind::String {
+                            min_length: *min_length,
+                            max_length: *max_length,
+                        },
+                        default.clone(),
+                        &label,
+                        window,
+                        cx,
+                    ),
+                    PrimitiveSchema::Number {
+                        minimum,
+                        maximum,
+                        default,
+                        ..
+                    } => Self::text_input(
+                        TextKind::Number {
+                            minimum: *minimum,
+                            maximum: *maximum,
+                        },
+                        default.map(|default| default.to_string()),
+                        &label,
+                        window,
+                        cx,
+                    ),
+                    PrimitiveSchema::Integer {
+                        minimum,
+                        maximum,
+                        default,
+                        ..
+                    } => Self::text_input(
+                        TextKind::Integer {
+                            minimum: *minimum,
+                            maximum: *maximum,
+                        },
+                        default.map(|default| default.to_string()),
+                        &label,
+                        window,
+                        cx,
+                    ),
+                    PrimitiveSchema::Boolean { default, .. } => {
+                        FieldInput::Boolean(default.unwrap_or(false))
+                    }
+                };
+
+                ElicitationField {
+                    name: name.clone(),
+                    label,
+                    description: property
+                        .description()
+                        .map(|description| description.to_string().into()),
+                    required: schema.required.contains(name),
+                    input,
+                }
+            })
+            .collect();
+
+        Self {
+            thread,
+            tool_call_id,
+            message,
+            fields,
+            error,
+        }
+    }
+
+    fn text_input(
+        kind: TextKind,
+        default: Option<String>,
+        placeholder: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> FieldInput {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(placeholder, window, cx);
+            if let Some(default) = default {
+                editor.set_text(default, window, cx);
+            }
+            editor
+        });
+        FieldInput::Text { editor, kind }
+    }
+
+    fn collect_content(&self, cx: &App) -> Result<Map<String, Value>> {
+        let mut content = Map::new();
+        for field in &self.fields {
+            let value = match &field.input {
+                FieldInput::Text { editor, kind } => {
+                    parse_text_value(*kind, &editor.read(cx).text(cx))
+                        .map_err(|error| anyhow!("{}: {error}", field.label))?
+                }
+                FieldInput::Boolean(value) => Some(Value::Bool(*value)),
+                FieldInput::Enum {
+                    values, selected, ..
+                } => selected.map(|ix| Value::String(values[ix].clone())),
+            };
+            match value {
+                Some(value) => {
+                    content.insert(field.name.clone(), value);
+                }
+                None if field.required => {
+                    return Err(anyhow!("{} is required", field.label));
+                }
+                None => {}
+            }
+        }
+        Ok(content)
+    }
+
+    fn respond(&mut self, outcome: ElicitationOutcome, cx: &mut Context<Self>) {
+        let tool_call_id = self.tool_call_id.clone();
+        self.thread
+            .update(cx, |thread, cx| {
+                thread.respond_to_elicitation(tool_call_id, outcome, cx)
+            })
+            .ok();
+    }
+
+    fn accept(&mut self, cx: &mut Context<Self>) {
+        match self.collect_content(cx) {
+            Ok(content) => {
+                self.error = None;
+                self.respond(ElicitationOutcome::Accept(content), cx);
+            }
+            Err(error) => {
+                self.error = Some(error.to_string().into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn render_field(&self, ix: usize, field: &ElicitationField, cx: &Context<Self>) -> Div {
+        let label = h_flex()
+            .gap_0p5()
+            .child(Label::new(field.label.clone()).size(LabelSize::Small))
+            .when(field.required, |this| {
+                this.child(Label::new("*").size(LabelSize::Small).color(Color::Error))
+            });
+
+        let input = match &field.input {
+            FieldInput::Text { editor, .. } => div()
+                .px_2()
+                .py_1()
+                .rounded_sm()
+                .border_1()
+                .border_color(cx.theme().colors().border_variant)
+                .bg(cx.theme().colors().editor_background)
+                .child(editor.clone())
+                .into_any_element(),
+            FieldInput::Boolean(value) => Checkbox::new(("elicitation-bool", ix), (*value).into())
+                .label(field.label.clone())
+                .on_click(cx.listener(move |this, state: &ToggleState, _, cx| {
+                    if let Some(FieldInput::Boolean(value)) =
+                        this.fields.get_mut(ix).map(|field| &mut field.input)
+                    {
+                        *value = state.selected();
+                        cx.notify();
+                    }
+                }))
+                .into_any_element(),
+            FieldInput::Enum {
+                labels, selected, ..
+            } => h_flex()
+                .flex_wrap()
+                .gap_1()
+                .children(labels.iter().enumerate().map(|(option_ix, label)| {
+                    Button::new(("elicitation-enum", ix * 1000 + option_ix), label.clone())
+                        .label_size(LabelSize::Small)
+                        .style(ButtonStyle::Outlined)
+                        .selected_style(ButtonStyle::Tinted(TintColor::Accent))
+                        .toggle_state(*selected == Some(option_ix))
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            if let Some(FieldInput::Enum { selected, .. }) =
+                                this.fields.get_mut(ix).map(|field| &mut field.input)
+                            {
+                                *selected = Some(option_ix);
+                                cx.notify();
+                            }
+                        }))
+                }))
+                .into_any_element(),
+        };
+
+        v_flex()
+            .gap_1()
+            .when(!matches!(field.input, FieldInput::Boolean(_)), |this| {
+                this.child(label)
+            })
+            .child(input)
+            .when_some(field.description.clone(), |this, description| {
+                this.child(
+                    Label::new(description)
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted),
+                )
+            })
+    }
+}
+
+impl Render for ElicitationForm {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .p_2()
+            .gap_2()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new(self.message.clone()).size(LabelSize::Small))
+            .children(
+                self.fields
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, field)| self.render_field(ix, field, cx)),
+            )
+            .when_some(self.error.clone(), |this, error| {
+                this.child(Label::new(error).size(LabelSize::Small).color(Color::Error))
+            })
+            .child(
+                h_flex()
+                    .gap_1()
+                    .justify_end()
+                    .child(
+                        Button::new("elicitation-cancel", "Cancel")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.respond(ElicitationOutcome::Cancel, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("elicitation-decline", "Decline")
+                            .label_size(LabelSize::Small)
+                            .icon(IconName::Close)
+                            .icon_position(IconPosition::Start)
+                            .icon_size(IconSize::XSmall)
+                            .icon_color(Color::Error)
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.respond(ElicitationOutcome::Decline, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("elicitation-accept", "Submit")
+                            .label_size(LabelSize::Small)
+                            .icon(IconName::Check)
+                            .icon_position(IconPosition::Start)
+                            .icon_size(IconSize::XSmall)
+                            .icon_color(Color::Success)
+                            .disabled(self.fields.is_empty() && self.error.is_some())
+                            .on_click(cx.listener(|this, _, _, cx| this.accept(cx))),
+                    ),
+            )
+    }
+}
+
+/// Parses the text of a form input, returning `None` if the input is empty.
+fn parse_text_value(kind: TextKind, text: &str) -> Result<Option<Value>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let value = match kind {
+        TextKind::String {
+            min_length,
+            max_length,
+        } => {
+            let len = text.chars().count();
+            if let Some(min_length) = min_length
+                && len < min_length
+            {
+                return Err(anyhow!("must be at least {min_length} characters"));
+            }
+            if let Some(max_length) = max_length
+                && len > max_length
+            {
+                return Err(anyhow!("must be at most {max_length} characters"));
+            }
+            Value::String(text.to_string())
+        }
+        TextKind::Number { minimum, maximum } => {
+            let number: f64 = text.parse().map_err(|_| anyhow!("must be a number"))?;
+            check_range(number, minimum, maximum)?;
+            serde_json::Number::from_f64(number)
+                .map(Value::Number)
+                .ok_or_else(|| anyhow!("must be a finite number"))?
+        }
+        TextKind::Integer { minimum, maximum } => {
+            let number: i64 = text.parse().map_err(|_| anyhow!("must be an integer"))?;
+            check_range(number, minimum, maximum)?;
+            Value::from(number)
+        }
+    };
+    Ok(Some(value))
+}
+
+fn check_range<T: PartialOrd + std::fmt::Display>(
+    value: T,
+    minimum: Option<T>,
+    maximum: Option<T>,
+) -> Result<()> {
+    if let Some(minimum) = minimum
+        && value < minimum
+    {
+        return Err(anyhow!("must be at least {minimum}"));
+    }
+    if let Some(maximum) = maximum
+        && value > maximum
+    {
+        return Err(anyhow!("must be at most {maximum}"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_text_value() {
+        let string = TextKind::String {
+            min_length: Some(2),
+            max_length: Some(4),
+        };
+        assert_eq!(parse_text_value(string, "  ").unwrap(), None);
+        assert_eq!(
+            parse_text_value(string, "prod").unwrap(),
+            Some(Value::String("prod".into()))
+        );
+        assert!(parse_text_value(string, "a").is_err());
+        assert!(parse_text_value(string, "staging").is_err());
+
+        let integer = TextKind::Integer {
+            minimum: Some(1),
+            maximum: None,
+        };
+        assert_eq!(
+            parse_text_value(integer, "3").unwrap(),
+            Some(Value::from(3))
+        );
+        assert!(parse_text_value(integer, "0").is_err());
+        assert!(parse_text_value(integer, "1.5").is_err());
+
+        let number = TextKind::Number {
+            minimum: None,
+            maximum: Some(1.0),
+        };
+        assert_eq!(
+            parse_text_value(number, "0.5").unwrap(),
+            Some(Value::from(0.5))
+        );
+        assert!(parse_text_value(number, "2").is_err());
+        assert!(parse_text_value(number, "abc").is_err());
+    }
+}
@@ -20,6 +20,7 @@ use theme::ThemeSettings;
 use ui::{Context, TextSize};
 use workspace::Workspace;
 
+use crate::elicitation_form::ElicitationForm;
 use crate::message_editor::{MessageEditor, MessageEditorEvent};
 
 pub struct EntryViewState {
@@ -125,24 +126,51 @@ impl EntryViewState {
                 let id = tool_call.id.clone();
                 let terminals = tool_call.terminals().cloned().collect::<Vec<_>>();
                 let diffs = tool_call.diffs().cloned().collect::<Vec<_>>();
+                let elicitation = tool_call.elicitation.as_ref().map(|elicitation| {
+                    (
+                        elicitation.message.clone(),
+                        elicitation.requested_schema.clone(),
+                    )
+                });
+                let is_tool_call_completed =
+                    matches!(tool_call.status, acp_thread::ToolCallStatus::Completed);
 
-                let views = if let Some(Entry::ToolCall(tool_call)) = self.entries.get_mut(index) {
-                    &mut tool_call.content
+                let entry = if let Some(Entry::ToolCall(tool_call)) = self.entries.get_mut(index) {
+                    tool_call
                 } else {
                     self.set_entry(
                         index,
                         Entry::ToolCall(ToolCallEntry {
                             content: HashMap::default(),
+                            elicitation: None,
                         }),
                     );
                     let Some(Entry::ToolCall(tool_call)) = self.entries.get_mut(index) else {
                         unreachable!()
                     };
-                    &mut tool_call.content
+                    tool_call
                 };
 
-                let is_tool_call_completed =
-                    matches!(tool_call.status, acp_thread::ToolCallStatus::Completed);
+                match elicitation {
+                    Some((message, requested_schema)) => {
+                        if entry.elicitation.is_none() {
+                            let thread = thread.downgrade();
+                            entry.elicitation = Some(cx.new(|cx| {
+                                ElicitationForm::new(
+                                    thread,
+                                    id.clone(),
+                                    message,
+                                    &requested_schema,
+                                    window,
+                                    cx,
+                                )
+                            }));
+                        }
+                    }
+                    None => entry.elicitation = None,
+                }
+
+                let views = &mut entry.content;
 
                 for terminal in terminals {
                     match views.entry(terminal.entity_id()) {
@@ -236,6 +264,9 @@ impl EntryViewState {
                 };
                 entry.sync(message);
             }
+            AgentThreadEntry::Compaction(_) => {
+                self.set_entry(index, Entry::Compaction);
+            }
         };
     }
 
@@ -254,8 +285,8 @@ impl EntryViewState {
     pub fn agent_ui_font_size_changed(&mut self, cx: &mut App) {
         for entry in self.entries.iter() {
             match entry {
-                Entry::UserMessage { .. } | Entry::AssistantMessage { .. } => {}
-                Entry::ToolCall(ToolCallEntry { content }) => {
+                Entry::UserMessage { .. } | Entry::AssistantMessage { .. } | Entry::Compaction => {}
+                Entry::ToolCall(ToolCallEntry { content, .. }) => {
                     for view in content.values() {
                         if let Ok(diff_editor) = view.clone().downcast::<Editor>() {
                             diff_editor.update(cx, |diff_editor, cx| {
@@ -313,6 +344,7 @@ impl AssistantMessageEntry {
 #[derive(Debug)]
 pub struct ToolCallEntry {
     content: HashMap<EntityId, AnyEntity>,
+    elicitation: Option<Entity<ElicitationForm>>,
 }
 
 #[derive(Debug)]
@@ -320,20 +352,21 @@ pub enum Entry {
     UserMessage(Entity<MessageEditor>),
     AssistantMessage(AssistantMessageEntry),
     ToolCall(ToolCallEntry),
+    Compaction,
 }
 
 impl Entry {
     pub fn focus_handle(&self, cx: &App) -> Option<FocusHandle> {
         match self {
             Self::UserMessage(editor) => Some(editor.read(cx).focus_handle(cx)),
-            Self::AssistantMessage(_) | Self::ToolCall(_) => None,
+            Self::AssistantMessage(_) | Self::ToolCall(_) | Self::Compaction => None,
         }
     }
 
     pub fn message_editor(&self) -> Option<&Entity<MessageEditor>> {
         match self {
             Self::UserMessage(editor) => Some(editor),
-            Self::AssistantMessage(_) | Self::ToolCall(_) => None,
+            Self::AssistantMessage(_) | Self::ToolCall(_) | Self::Compaction => None,
         }
     }
 
@@ -354,19 +387,26 @@ impl Entry {
             .map(|entity| entity.downcast::<TerminalView>().unwrap())
     }
 
+    pub fn elicitation_form(&self) -> Option<Entity<ElicitationForm>> {
+        match self {
+            Self::ToolCall(tool_call) => tool_call.elicitation.clone(),
+            Self::UserMessage(_) | Self::AssistantMessage(_) | Self::Compaction => None,
+        }
+    }
+
     pub fn scroll_handle_for_assistant_message_chunk(
         &self,
         chunk_ix: usize,
     ) -> Option<ScrollHandle> {
         match self {
             Self::AssistantMessage(message) => message.scroll_handle_for_chunk(chunk_ix),
-            Self::UserMessage(_) | Self::ToolCall(_) => None,
+            Self::UserMessage(_) | Self::ToolCall(_) | Self::Compaction => None,
         }
     }
 
     fn content_map(&self) -> Option<&HashMap<EntityId, AnyEntity>> {
         match self {
-            Self::ToolCall(ToolCallEntry { content }) => Some(content),
+            Self::ToolCall(ToolCallEntry { content, .. }) => Some(content),
             _ => None,
         }
     }
@@ -374,8 +414,8 @@ impl Entry {
     #[cfg(test)]
     pub fn has_content(&self) -> bool {
         match self {
-            Self::ToolCall(ToolCallEntry { content }) => !content.is_empty(),
-            Self::UserMessage(_) | Self::AssistantMessage(_) => false,
+            Self::ToolCall(ToolCallEntry { content, .. }) => !content.is_empty(),
+            Self::UserMessage(_) | Self::AssistantMessage(_) | Self::Compaction => false,
         }
     }
 }
@@ -351,6 +351,18 @@ impl ThreadHistory {
             .cloned()
     }
 
+    /// Returns the title of the thread the given entry was forked from, if it was forked.
+    fn forked_from_title(&self, entry: &AgentSessionInfo) -> Option<SharedString> {
+        let forked_from = acp_thread::forked_from_from_meta(&entry.meta)?;
+        Some(
+            self.sessions
+                .iter()
+                .find(|session| session.session_id == forked_from)
+                .map(|session| thread_title(session).clone())
+                .unwrap_or_else(|| "a deleted thread".into()),
+        )
+    }
+
     pub(crate) fn sessions(&self) -> &[AgentSessionInfo] {
         &self.sessions
     }
@@ -655,11 +667,16 @@ impl ThreadHistory {
         };
 
         let title = thread_title(entry).clone();
+        let forked_from = self.forked_from_title(entry);
         let full_date = entry_time
             .map(|time| {
                 EntryTimeFormat::DateAndTime.format_timestamp(time.timestamp(), self.local_timezone)
             })
             .unwrap_or_else(|| "Unknown".to_string());
+        let tooltip_meta = match &forked_from {
+            Some(forked_from) => format!("{full_date} · Forked from {forked_from}"),
+            None => full_date,
+        };
 
         h_flex()
             .w_full()
@@ -675,9 +692,24 @@ impl ThreadHistory {
                             .gap_2()
                             .justify_between()
                             .child(
-                                HighlightedLabel::new(thread_title(entry), highlight_positions)
-                                    .size(LabelSize::Small)
-                                    .truncate(),
+                                h_flex()
+                                    .min_w_0()
+                                    .gap_1()
+                                    .when(forked_from.is_some(), |this| {
+                                        this.child(
+                                            Icon::new(IconName::GitBranch)
+                                                .size(IconSize::XSmall)
+                                                .color(Color::Muted),
+                                        )
+                                    })
+                                    .child(
+                                        HighlightedLabel::new(
+                                            thread_title(entry),
+                                            highlight_positions,
+                                        )
+                                        .size(LabelSize::Small)
+                                        .truncate(),
+                                    ),
                             )
                             .child(
                                 Label::new(display_text)
@@ -686,7 +718,7 @@ impl ThreadHistory {
                             ),
                     )
                     .tooltip(move |_, cx| {
-                        Tooltip::with_meta(title.clone(), None, full_date.clone(), cx)
+                        Tooltip::with_meta(title.clone(), None, tooltip_meta.clone(), cx)
                     })
                     .on_hover(cx.listener(move |this, is_hovered, _window, cx| {
                         if *is_hovered {
@@ -1230,6 +1262,45 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    async fn test_forked_from_title(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let original = test_session("session-1", "Original");
+        let fork = AgentSessionInfo {
+            meta: Some(acp_thread::meta_with_forked_from(&original.session_id)),
+            ..test_session("session-2", "Fork")
+        };
+        let orphan = AgentSessionInfo {
+            meta: Some(acp_thread::meta_with_forked_from(&acp::SessionId::new(
+                "missing",
+            ))),
+            ..test_session("session-3", "Orphan")
+        };
+        let session_list = Rc::new(TestSessionList::new(vec![
+            original.clone(),
+            fork.clone(),
+            orphan.clone(),
+        ]));
+
+        let (history, cx) = cx.add_window_view(|window, cx| {
+            ThreadHistory::new(Some(session_list.clone()), window, cx)
+        });
+        cx.run_until_parked();
+
+        history.update(cx, |history, _cx| {
+            assert_eq!(history.forked_from_title(&original), None);
+            assert_eq!(
+                history.forked_from_title(&fork),
+                Some(SharedString::from("Original"))
+            );
+            assert_eq!(
+                history.forked_from_title(&orphan),
+                Some(SharedString::from("a deleted thread"))
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_refresh_only_loads_first_page_by_default(cx: &mut TestAppContext) {
         init_test(cx);
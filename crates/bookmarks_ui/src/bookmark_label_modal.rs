@@ -0,0 +1,126 @@
+use editor::{Editor, ToPoint as _};
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Styled, div,
+    prelude::*,
+};
+use language::Buffer;
+use project::bookmark_store::BookmarkStore;
+use text::Point;
+use theme::ActiveTheme;
+use ui::prelude::*;
+use workspace::{ModalView, Workspace};
+
+/// A modal for naming the bookmark on the cursor's line, creating the bookmark if there isn't one.
+pub struct BookmarkLabelModal {
+    label_editor: Entity<Editor>,
+    bookmark_store: Entity<BookmarkStore>,
+    buffer: Entity<Buffer>,
+    position: text::Anchor,
+    row: u32,
+}
+
+impl ModalView for BookmarkLabelModal {}
+
+impl Focusable for BookmarkLabelModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.label_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarkLabelModal {}
+
+impl BookmarkLabelModal {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Option<()> {
+        let editor = workspace.active_item(cx)?.act_as::<Editor>(cx)?;
+        let multi_buffer = editor.read(cx).buffer().read(cx);
+        let head = editor.read(cx).selections.newest_anchor().head();
+        let row = head.to_point(&multi_buffer.snapshot(cx)).row;
+        let (buffer, position) = multi_buffer.text_anchor_for_position(Point::new(row, 0), cx)?;
+        let bookmark_store = workspace.project().read(cx).bookmark_store();
+        workspace.toggle_modal(window, cx, move |window, cx| {
+            BookmarkLabelModal::new(bookmark_store, buffer, position, window, cx)
+        });
+        Some(())
+    }
+
+    fn new(
+        bookmark_store: Entity<BookmarkStore>,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.summary::<Point>(&snapshot).row;
+        let row_range = snapshot.anchor_before(Point::new(row, 0))
+            ..snapshot.anchor_after(Point::new(row, snapshot.line_len(row)));
+        let current_label = bookmark_store
+            .read(cx)
+            .bookmarks(&buffer, Some(row_range), &snapshot, cx)
+            .find_map(|bookmark| bookmark.label.clone());
+
+        let label_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Bookmark label", window, cx);
+            if let Some(label) = current_label {
+                editor.set_text(label.to_string(), window, cx);
+                editor.select_all(&editor::actions::SelectAll, window, cx);
+            }
+            editor
+        });
+
+        Self {
+            label_editor,
+            bookmark_store,
+            buffer,
+            position,
+            row,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let label = self.label_editor.read(cx).text(cx);
+        self.bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.set_label(
+                self.buffer.clone(),
+                self.position,
+                Some(label.trim().to_string().into()),
+                cx,
+            );
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for BookmarkLabelModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("BookmarkLabelModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.label_editor.clone()),
+            )
+            .child(
+                h_flex().px_2().py_1().gap_1().child(
+                    Label::new(format!("Label the bookmark on line {}", self.row + 1))
+                        .color(Color::Muted),
+                ),
+            )
+    }
+}
@@ -0,0 +1,345 @@
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{
+    Action, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable, Pixels,
+    Render, Subscription, Task, WeakEntity, Window, actions,
+};
+use project::{
+    Fs,
+    bookmark_store::{BookmarkEntry, BookmarkStore},
+};
+use serde::{Deserialize, Serialize};
+use settings::{RegisterSetting, Settings};
+use std::sync::Arc;
+use ui::{ListItem, Tab, Tooltip, prelude::*};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+use crate::{ClearAll, display_path, open_bookmark};
+
+const BOOKMARKS_PANEL_KEY: &str = "BookmarksPanel";
+
+actions!(
+    bookmarks_panel,
+    [
+        /// Toggles the bookmarks panel.
+        Toggle,
+        /// Toggles focus on the bookmarks panel.
+        ToggleFocus
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<BookmarksPanel>(window, cx);
+        });
+        workspace.register_action(|workspace, _: &Toggle, window, cx| {
+            if !workspace.toggle_panel_focus::<BookmarksPanel>(window, cx) {
+                workspace.close_panel::<BookmarksPanel>(window, cx);
+            }
+        });
+    })
+    .detach();
+}
+
+#[derive(Debug, RegisterSetting)]
+pub struct BookmarksPanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+impl Settings for BookmarksPanelSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let panel = content.bookmarks_panel.as_ref().unwrap();
+
+        Self {
+            button: panel.button.unwrap(),
+            dock: panel.dock.unwrap().into(),
+            default_width: panel.default_width.map(px).unwrap(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedBookmarksPanel {
+    width: Option<Pixels>,
+}
+
+/// A panel listing the bookmarks of every file in the project, grouped by file.
+pub struct BookmarksPanel {
+    workspace: WeakEntity<Workspace>,
+    bookmark_store: Entity<BookmarkStore>,
+    fs: Arc<dyn Fs>,
+    width: Option<Pixels>,
+    focus_handle: FocusHandle,
+    pending_serialization: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl BookmarksPanel {
+    pub fn new(workspace: &mut Workspace, cx: &mut Context<Workspace>) -> Entity<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let bookmark_store = workspace.project().read(cx).bookmark_store();
+        let workspace_handle = workspace.weak_handle();
+
+        cx.new(|cx| Self {
+            _subscriptions: vec![cx.observe(&bookmark_store, |_, _, cx| cx.notify())],
+            workspace: workspace_handle,
+            bookmark_store,
+            fs,
+            width: None,
+            focus_handle: cx.focus_handle(),
+            pending_serialization: Task::ready(None),
+        })
+    }
+
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            let serialized_panel = if let Some(panel) = cx
+                .background_spawn(async move { KEY_VALUE_STORE.read_kvp(BOOKMARKS_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedBookmarksPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        BOOKMARKS_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedBookmarksPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn render_file_header(&self, entry: &BookmarkEntry, cx: &App) -> impl IntoElement {
+        let path = display_path(entry, cx);
+        h_flex()
+            .px_2()
+            .pt_2()
+            .pb_1()
+            .gap_1()
+            .child(
+                Icon::new(IconName::File)
+                    .size(IconSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                Label::new(path)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .truncate(),
+            )
+    }
+
+    fn render_bookmark(
+        &self,
+        ix: usize,
+        entry: BookmarkEntry,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let title: SharedString = entry
+            .bookmark
+            .label
+            .clone()
+            .unwrap_or_else(|| entry.line.clone());
+        let has_label = entry.bookmark.label.is_some();
+        let row_label = format!("{}", entry.row + 1);
+        let buffer = entry.buffer.clone();
+        let position = entry.bookmark.position;
+
+        ListItem::new(("bookmark", ix))
+            .inset(true)
+            .start_slot(
+                Icon::new(IconName::Bookmark)
+                    .size(IconSize::Small)
+                    .color(if has_label {
+                        Color::Accent
+                    } else {
+                        Color::Muted
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .min_w_0()
+                    .child(Label::new(title).truncate())
+                    .child(
+                        Label::new(row_label)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .end_hover_slot(
+                IconButton::new(("remove_bookmark", ix), IconName::Close)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text("Remove Bookmark"))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.bookmark_store.update(cx, |bookmark_store, cx| {
+                            bookmark_store.remove_bookmark(buffer.clone(), position, cx);
+                        });
+                    })),
+            )
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.workspace
+                    .update(cx, |workspace, cx| {
+                        open_bookmark(workspace, &entry, true, window, cx);
+                    })
+                    .log_err();
+            }))
+    }
+}
+
+impl Render for BookmarksPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entries = self.bookmark_store.read(cx).all_bookmarks(cx);
+
+        v_flex()
+            .key_context("BookmarksPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    // Match the height of the tab bar so they line up.
+                    .h(Tab::container_height(cx))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Bookmarks"))
+                    .child(
+                        IconButton::new("clear_bookmarks", IconName::Trash)
+                            .icon_size(IconSize::Small)
+                            .disabled(entries.is_empty())
+                            .tooltip(Tooltip::for_action_title("Clear All Bookmarks", &ClearAll))
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(ClearAll.boxed_clone(), cx)
+                            }),
+                    ),
+            )
+            .map(|this| {
+                if entries.is_empty() {
+                    this.child(
+                        v_flex().p_4().child(
+                            div().flex().w_full().items_center().child(
+                                Label::new("No bookmarks yet.")
+                                    .color(Color::Muted)
+                                    .size(LabelSize::Small),
+                            ),
+                        ),
+                    )
+                } else {
+                    let mut children = Vec::new();
+                    let mut previous_path = None;
+                    for (ix, entry) in entries.into_iter().enumerate() {
+                        if previous_path.as_ref() != Some(&entry.path) {
+                            previous_path = Some(entry.path.clone());
+                            children.push(self.render_file_header(&entry, cx).into_any_element());
+                        }
+                        children.push(self.render_bookmark(ix, entry, cx).into_any_element());
+                    }
+                    this.child(
+                        v_flex()
+                            .id("bookmarks")
+                            .size_full()
+                            .overflow_y_scroll()
+                            .pb_2()
+                            .children(children),
+                    )
+                }
+            })
+    }
+}
+
+impl Focusable for BookmarksPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for BookmarksPanel {}
+
+impl Panel for BookmarksPanel {
+    fn persistent_name() -> &'static str {
+        "BookmarksPanel"
+    }
+
+    fn panel_key() -> &'static str {
+        BOOKMARKS_PANEL_KEY
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        BookmarksPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.bookmarks_panel.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| BookmarksPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        BookmarksPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::Bookmark)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _cx: &App) -> Option<&'static str> {
+        Some("Bookmarks Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        7
+    }
+}
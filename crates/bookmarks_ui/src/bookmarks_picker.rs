@@ -0,0 +1,270 @@
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ParentElement,
+    Render, Styled, Task, WeakEntity, Window,
+};
+use picker::{Picker, PickerDelegate};
+use project::bookmark_store::BookmarkEntry;
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::{display_path, open_bookmark};
+
+pub struct BookmarksPicker {
+    picker: Entity<Picker<BookmarksPickerDelegate>>,
+}
+
+impl BookmarksPicker {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let handle = workspace.weak_handle();
+        let entries = workspace
+            .project()
+            .read(cx)
+            .bookmark_store()
+            .read(cx)
+            .all_bookmarks(cx);
+        workspace.toggle_modal(window, cx, move |window, cx| {
+            BookmarksPicker::new(handle, entries, window, cx)
+        });
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        entries: Vec<BookmarkEntry>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate =
+            BookmarksPickerDelegate::new(cx.entity().downgrade(), workspace, entries, cx);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+impl Render for BookmarksPicker {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("BookmarksPicker")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for BookmarksPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarksPicker {}
+impl ModalView for BookmarksPicker {}
+
+struct BookmarkCandidate {
+    entry: BookmarkEntry,
+    title: String,
+    location: String,
+}
+
+pub struct BookmarksPickerDelegate {
+    bookmarks_picker: WeakEntity<BookmarksPicker>,
+    workspace: WeakEntity<Workspace>,
+    bookmarks: Vec<BookmarkCandidate>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl BookmarksPickerDelegate {
+    fn new(
+        bookmarks_picker: WeakEntity<BookmarksPicker>,
+        workspace: WeakEntity<Workspace>,
+        entries: Vec<BookmarkEntry>,
+        cx: &App,
+    ) -> Self {
+        let bookmarks = entries
+            .into_iter()
+            .map(|entry| {
+                let title = entry
+                    .bookmark
+                    .label
+                    .clone()
+                    .unwrap_or_else(|| entry.line.clone())
+                    .to_string();
+                let location = format!("{}:{}", display_path(&entry, cx), entry.row + 1);
+                BookmarkCandidate {
+                    entry,
+                    title,
+                    location,
+                }
+            })
+            .collect::<Vec<_>>();
+        let candidates = bookmarks
+            .iter()
+            .enumerate()
+            .map(|(id, bookmark)| {
+                StringMatchCandidate::new(id, &format!("{} {}", bookmark.title, bookmark.location))
+            })
+            .collect();
+
+        Self {
+            bookmarks_picker,
+            workspace,
+            bookmarks,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for BookmarksPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Go to bookmark…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some(if self.bookmarks.is_empty() {
+            "No bookmarks in this project".into()
+        } else {
+            "No matching bookmarks".into()
+        })
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let entry = self.bookmarks[mat.candidate_id].entry.clone();
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    open_bookmark(workspace, &entry, true, window, cx);
+                })
+                .log_err();
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.bookmarks_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        let bookmark = self.bookmarks.get(mat.candidate_id)?;
+        // The candidate string is the title and the location separated by a space.
+        let location_start = bookmark.title.len() + 1;
+        let title_positions = mat
+            .positions
+            .iter()
+            .copied()
+            .filter(|position| *position < bookmark.title.len())
+            .collect::<Vec<_>>();
+        let location_positions = mat
+            .positions
+            .iter()
+            .filter_map(|position| position.checked_sub(location_start))
+            .collect::<Vec<_>>();
+        let icon_color = if bookmark.entry.bookmark.label.is_some() {
+            Color::Accent
+        } else {
+            Color::Muted
+        };
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(IconName::Bookmark).color(icon_color))
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            bookmark.title.clone(),
+                            title_positions,
+                        ))
+                        .child(
+                            HighlightedLabel::new(bookmark.location.clone(), location_positions)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
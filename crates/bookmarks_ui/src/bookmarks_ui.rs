@@ -0,0 +1,87 @@
+mod bookmark_label_modal;
+mod bookmarks_panel;
+mod bookmarks_picker;
+
+pub use bookmark_label_modal::BookmarkLabelModal;
+pub use bookmarks_panel::{BookmarksPanel, BookmarksPanelSettings};
+pub use bookmarks_picker::BookmarksPicker;
+
+use editor::{Editor, SelectionEffects, scroll::Autoscroll};
+use gpui::{App, Context, Window, actions};
+use project::bookmark_store::BookmarkEntry;
+use text::Point;
+use workspace::Workspace;
+
+actions!(
+    bookmarks,
+    [
+        /// Opens a picker listing the bookmarks in every file of the project.
+        Toggle,
+        /// Sets or edits the label of the bookmark on the current line.
+        EditLabel,
+        /// Removes every bookmark in the project.
+        ClearAll
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    bookmarks_panel::init(cx);
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &Toggle, window, cx| {
+            BookmarksPicker::toggle(workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &EditLabel, window, cx| {
+            BookmarkLabelModal::toggle(workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &ClearAll, _, cx| {
+            workspace
+                .project()
+                .read(cx)
+                .bookmark_store()
+                .update(cx, |bookmark_store, cx| bookmark_store.clear_bookmarks(cx));
+        });
+    })
+    .detach();
+}
+
+/// Opens the file containing `entry` in the active pane and moves the cursor to the bookmarked row.
+pub(crate) fn open_bookmark(
+    workspace: &mut Workspace,
+    entry: &BookmarkEntry,
+    focus: bool,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let pane = workspace.active_pane().clone();
+    let editor = workspace.open_project_item::<Editor>(
+        pane,
+        entry.buffer.clone(),
+        true,
+        focus,
+        true,
+        true,
+        window,
+        cx,
+    );
+    let position = Point::new(entry.row, 0);
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(
+            SelectionEffects::scroll(Autoscroll::center()),
+            window,
+            cx,
+            |s| s.select_ranges([position..position]),
+        );
+    });
+}
+
+/// Returns the path of the bookmarked file relative to the project, falling back to its absolute path.
+pub(crate) fn display_path(entry: &BookmarkEntry, cx: &App) -> String {
+    entry
+        .buffer
+        .read(cx)
+        .file()
+        .map(|file| file.full_path(cx))
+        .unwrap_or_else(|| entry.path.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
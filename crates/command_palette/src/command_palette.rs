@@ -9,7 +9,7 @@ use std::{
 
 use client::parse_zed_link;
 use command_palette_hooks::{
-    CommandInterceptItem, CommandInterceptResult, CommandPaletteFilter,
+    CommandInterceptItem, CommandInterceptResult, CommandPaletteCommands, CommandPaletteFilter,
     GlobalCommandPaletteInterceptor,
 };
 
@@ -98,7 +98,7 @@ impl CommandPalette {
     ) -> Self {
         let filter = CommandPaletteFilter::try_global(cx);
 
-        let commands = window
+        let mut commands = window
             .available_actions(cx)
             .into_iter()
             .filter_map(|action| {
@@ -111,7 +111,18 @@ impl CommandPalette {
                     action,
                 })
             })
-            .collect();
+            .collect::<Vec<_>>();
+        if let Some(extra_commands) = CommandPaletteCommands::try_global(cx) {
+            commands.extend(
+                extra_commands
+                    .commands()
+                    .filter(|(_, action)| window.is_action_available(*action, cx))
+                    .map(|(name, action)| Command {
+                        name: name.to_string(),
+                        action: action.boxed_clone(),
+                    }),
+            );
+        }
 
         let delegate = CommandPaletteDelegate::new(
             cx.entity().downgrade(),
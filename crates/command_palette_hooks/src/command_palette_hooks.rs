@@ -4,7 +4,7 @@
 
 use std::{any::TypeId, rc::Rc};
 
-use collections::HashSet;
+use collections::{BTreeMap, HashSet};
 use derive_more::{Deref, DerefMut};
 use gpui::{Action, App, BorrowAppContext, Global, Task, WeakEntity};
 use workspace::Workspace;
@@ -12,6 +12,8 @@ use workspace::Workspace;
 /// Initializes the command palette hooks.
 pub fn init(cx: &mut App) {
     cx.set_global(GlobalCommandPaletteFilter::default());
+    // Commands may have been added before the command palette was initialized.
+    cx.default_global::<GlobalCommandPaletteCommands>();
 }
 
 /// A filter for the command palette.
@@ -92,6 +94,51 @@ impl CommandPaletteFilter {
     }
 }
 
+/// Commands to show in the command palette in addition to the available actions,
+/// such as the commands that extensions provide.
+#[derive(Default)]
+pub struct CommandPaletteCommands {
+    commands: BTreeMap<String, Box<dyn Action>>,
+}
+
+#[derive(Deref, DerefMut, Default)]
+struct GlobalCommandPaletteCommands(CommandPaletteCommands);
+
+impl Global for GlobalCommandPaletteCommands {}
+
+impl CommandPaletteCommands {
+    /// Returns the global [`CommandPaletteCommands`], if set.
+    pub fn try_global(cx: &App) -> Option<&CommandPaletteCommands> {
+        cx.try_global::<GlobalCommandPaletteCommands>()
+            .map(|commands| &commands.0)
+    }
+
+    /// Returns a mutable reference to the global [`CommandPaletteCommands`],
+    /// inserting an empty one if none is set.
+    pub fn global_mut(cx: &mut App) -> &mut Self {
+        cx.default_global::<GlobalCommandPaletteCommands>()
+    }
+
+    /// Adds a command with the given name, which dispatches the given action.
+    ///
+    /// The command is only shown when its action is available.
+    pub fn add_command(&mut self, name: String, action: Box<dyn Action>) {
+        self.commands.insert(name, action);
+    }
+
+    /// Removes the command with the given name.
+    pub fn remove_command(&mut self, name: &str) {
+        self.commands.remove(name);
+    }
+
+    /// Returns the names of the commands and the actions they dispatch.
+    pub fn commands(&self) -> impl Iterator<Item = (&str, &dyn Action)> {
+        self.commands
+            .iter()
+            .map(|(name, action)| (name.as_str(), action.as_ref()))
+    }
+}
+
 /// The result of intercepting a command palette command.
 #[derive(Debug)]
 pub struct CommandInterceptItem {
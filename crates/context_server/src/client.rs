@@ -53,13 +53,13 @@ pub(crate) struct Client {
     name: Arc<str>,
     subscription_set: Arc<Mutex<NotificationSubscriptionSet>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
     #[allow(clippy::type_complexity)]
     #[allow(dead_code)]
     io_tasks: Mutex<Option<(Task<Option<()>>, Task<Option<()>>)>>,
     #[allow(dead_code)]
     output_done_rx: Mutex<Option<barrier::Receiver>>,
     executor: BackgroundExecutor,
-    #[allow(dead_code)]
     transport: Arc<dyn Transport>,
     request_timeout: Option<Duration>,
 }
@@ -101,7 +101,6 @@ struct AnyResponse<'a> {
 }
 
 #[derive(Serialize, Deserialize)]
-#[allow(dead_code)]
 pub(crate) struct Response<T> {
     pub jsonrpc: &'static str,
     pub id: RequestId,
@@ -114,7 +113,6 @@ pub(crate) struct Response<T> {
 pub(crate) enum CspResult<T> {
     #[serde(rename = "result")]
     Ok(Option<T>),
-    #[allow(dead_code)]
     Error(Option<Error>),
 }
 
@@ -238,6 +236,7 @@ impl Client {
             server_id,
             subscription_set,
             response_handlers,
+            request_handlers,
             name: server_name,
             next_id: Default::default(),
             outbound_tx,
@@ -452,6 +451,58 @@ impl Client {
         Ok(())
     }
 
+    /// Tells the transport which protocol version was negotiated with the context server.
+    pub fn set_protocol_version(&self, version: &str) {
+        self.transport.set_protocol_version(version);
+    }
+
+    /// Registers a handler for requests sent by the context server to the client.
+    ///
+    /// The handler's result is serialized and sent back to the server as the response,
+    /// or as a JSON-RPC error if the handler fails.
+    pub fn on_request<Params, Res>(
+        &self,
+        method: &'static str,
+        mut f: impl 'static + Send + FnMut(Params, AsyncApp) -> Task<Result<Res>>,
+    ) where
+        Params: DeserializeOwned,
+        Res: Serialize + 'static,
+    {
+        let outbound_tx = self.outbound_tx.clone();
+        self.request_handlers.lock().insert(
+            method,
+            Box::new(move |id, params, cx| {
+                let params = match serde_json::from_str::<Params>(params.get()) {
+                    Ok(params) => params,
+                    Err(error) => {
+                        send_response::<()>(
+                            &outbound_tx,
+                            id,
+                            CspResult::Error(Some(Error {
+                                message: error.to_string(),
+                                code: INVALID_PARAMS,
+                            })),
+                        );
+                        return;
+                    }
+                };
+                let response = f(params, cx.clone());
+                let outbound_tx = outbound_tx.clone();
+                cx.spawn(async move |_| {
+                    let value = match response.await {
+                        Ok(result) => CspResult::Ok(Some(result)),
+                        Err(error) => CspResult::Error(Some(Error {
+                            message: error.to_string(),
+                            code: INTERNAL_ERROR,
+                        })),
+                    };
+                    send_response(&outbound_tx, id, value);
+                })
+                .detach();
+            }),
+        );
+    }
+
     #[must_use]
     pub fn on_notification(
         &self,
@@ -467,6 +518,23 @@ impl Client {
     }
 }
 
+fn send_response<T: Serialize>(
+    outbound_tx: &channel::Sender<String>,
+    id: RequestId,
+    value: CspResult<T>,
+) {
+    let response = serde_json::to_string(&Response {
+        jsonrpc: JSON_RPC_VERSION,
+        id,
+        value,
+    })
+    .unwrap();
+    outbound_tx
+        .try_send(response)
+        .context("failed to write to context server's stdin")
+        .log_err();
+}
+
 #[derive(Debug)]
 pub struct RequestCanceled;
 
@@ -9,7 +9,7 @@ use std::time::Duration;
 
 use anyhow::Result;
 use futures::channel::oneshot;
-use gpui::AsyncApp;
+use gpui::{AsyncApp, Task};
 use serde_json::Value;
 
 use crate::client::{Client, NotificationSubscription};
@@ -27,6 +27,7 @@ impl ModelContextProtocol {
     fn supported_protocols() -> Vec<types::ProtocolVersion> {
         vec![
             types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
+            types::ProtocolVersion(types::VERSION_2025_03_26.to_string()),
             types::ProtocolVersion(types::VERSION_2024_11_05.to_string()),
         ]
     }
@@ -41,6 +42,7 @@ impl ModelContextProtocol {
                 experimental: None,
                 sampling: None,
                 roots: None,
+                elicitation: Some(serde_json::json!({})),
             },
             meta: None,
             client_info,
@@ -58,6 +60,8 @@ impl ModelContextProtocol {
         );
 
         log::trace!("mcp server info {:?}", response.server_info);
+        self.inner
+            .set_protocol_version(&response.protocol_version.0);
 
         let initialized_protocol = InitializedContextServerProtocol {
             inner: self.inner,
@@ -115,6 +119,14 @@ impl InitializedContextServerProtocol {
         self.inner.notify(T::METHOD, params)
     }
 
+    /// Handles requests of type `T` initiated by the server, such as elicitation.
+    pub fn on_request<T: Request>(
+        &self,
+        f: impl 'static + Send + FnMut(T::Params, AsyncApp) -> Task<Result<T::Response>>,
+    ) {
+        self.inner.on_request(T::METHOD, f)
+    }
+
     pub fn on_notification(
         &self,
         method: &'static str,
@@ -14,4 +14,8 @@ pub trait Transport: Send + Sync {
     async fn send(&self, message: String) -> Result<()>;
     fn receive(&self) -> Pin<Box<dyn Stream<Item = String> + Send>>;
     fn receive_err(&self) -> Pin<Box<dyn Stream<Item = String> + Send>>;
+
+    /// Called with the protocol version negotiated during initialization, for transports that
+    /// need to include it in the messages they send afterwards.
+    fn set_protocol_version(&self, _version: &str) {}
 }
@@ -162,6 +162,7 @@ impl DebugTaskDefinition {
             label: self.label.clone(),
             adapter: self.adapter.clone().into(),
             build: None,
+            inputs: Vec::new(),
             tcp_connection: self.tcp_connection.clone(),
             config: self.config.clone(),
         }
@@ -447,6 +448,7 @@ impl DebugAdapter for FakeAdapter {
             label: zed_scenario.label,
             build: None,
             config,
+            inputs: Vec::new(),
             tcp_connection: None,
         })
     }
@@ -129,6 +129,7 @@ impl DebugAdapter for CodeLldbDebugAdapter {
             label: zed_scenario.label,
             config: configuration,
             build: None,
+            inputs: Vec::new(),
             tcp_connection: None,
         })
     }
@@ -67,6 +67,7 @@ impl DebugAdapter for GdbDebugAdapter {
             label: zed_scenario.label,
             build: None,
             config: serde_json::Value::Object(obj),
+            inputs: Vec::new(),
             tcp_connection: None,
         })
     }
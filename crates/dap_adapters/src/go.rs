@@ -399,6 +399,7 @@ impl DebugAdapter for GoDebugAdapter {
             label: zed_scenario.label,
             build: None,
             config: args,
+            inputs: Vec::new(),
             tcp_connection: None,
         })
     }
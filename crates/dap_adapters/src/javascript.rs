@@ -229,6 +229,7 @@ impl DebugAdapter for JsDebugAdapter {
             label: zed_scenario.label,
             build: None,
             config: args,
+            inputs: Vec::new(),
             tcp_connection: None,
         })
     }
@@ -464,6 +464,7 @@ impl DebugAdapter for PythonDebugAdapter {
             label: zed_scenario.label,
             config: args,
             build: None,
+            inputs: Vec::new(),
             tcp_connection: None,
         })
     }
@@ -183,6 +183,39 @@ impl DebugPanel {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if !scenario.inputs.is_empty() {
+            let Some(workspace) = self.workspace.upgrade() else {
+                return;
+            };
+            let prompt = workspace.update(cx, |workspace, cx| {
+                workspace.prompt_for_task_inputs(
+                    &scenario.label,
+                    scenario.inputs.clone(),
+                    &task_context,
+                    window,
+                    cx,
+                )
+            });
+            cx.spawn_in(window, async move |this, cx| {
+                let Some(input_values) = prompt.await else {
+                    return;
+                };
+                this.update_in(cx, |this, window, cx| {
+                    this.start_session(
+                        scenario.with_input_values(&input_values),
+                        task_context,
+                        active_buffer,
+                        worktree_id,
+                        window,
+                        cx,
+                    )
+                })
+                .ok();
+            })
+            .detach();
+            return;
+        }
+
         let dap_store = self.project.read(cx).dap_store();
         let Some(adapter) = DapRegistry::global(cx).adapter(&scenario.adapter) else {
             return;
@@ -1268,6 +1268,8 @@ impl RunningState {
             show_summary: false,
             show_command: false,
             show_rerun: false,
+            problem_matchers: Vec::new(),
+            group: None,
         };
 
         let workspace = self.workspace.clone();
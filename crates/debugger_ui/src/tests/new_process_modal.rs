@@ -141,6 +141,7 @@ async fn test_debug_session_substitutes_variables_and_relativizes_paths(
                 "cwd": input_path,
                 "otherField": input_path
             }),
+            inputs: Vec::new(),
             tcp_connection: None,
         };
 
@@ -6,14 +6,18 @@ use std::{
 
 use node_runtime::NodeRuntime;
 use serde::Deserialize;
-use settings::DevContainerConnection;
+use settings::{DevContainerConnection, SshPortForwardOption};
 use smol::fs;
 use util::command::Command;
 use util::rel_path::RelPath;
 use workspace::Workspace;
 use worktree::Snapshot;
 
-use crate::{DevContainerContext, DevContainerFeature, DevContainerTemplate};
+use crate::{
+    DevContainerContext, DevContainerFeature, DevContainerTemplate,
+    devcontainer_json::{DevContainerJson, parse_devcontainer_json},
+    native::{self, DockerCli},
+};
 
 /// Represents a discovered devcontainer configuration
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -59,6 +63,34 @@ pub(crate) struct DevContainerApply {
 #[serde(rename_all = "camelCase")]
 pub(crate) struct DevContainerConfiguration {
     name: Option<String>,
+    #[serde(default)]
+    forward_ports: Vec<DevContainerForwardPort>,
+}
+
+/// An entry of `forwardPorts`, which is either a port number or a `"host:port"` string.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum DevContainerForwardPort {
+    Port(u16),
+    HostAndPort(String),
+}
+
+impl DevContainerForwardPort {
+    pub(crate) fn to_port_forward(&self) -> Option<SshPortForwardOption> {
+        let (remote_host, remote_port) = match self {
+            DevContainerForwardPort::Port(port) => (None, *port),
+            DevContainerForwardPort::HostAndPort(host_and_port) => {
+                let (host, port) = host_and_port.rsplit_once(':')?;
+                (Some(host.to_string()), port.parse().ok()?)
+            }
+        };
+        Some(SshPortForwardOption {
+            local_host: None,
+            local_port: remote_port,
+            remote_host,
+            remote_port,
+        })
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -243,8 +275,29 @@ pub async fn start_dev_container_with_config(
     config: Option<DevContainerConfig>,
 ) -> Result<(DevContainerConnection, String), DevContainerError> {
     check_for_docker(context.use_podman).await?;
+    let config_path = match config {
+        Some(config) => Some(context.project_directory.join(&config.config_path)),
+        None => find_default_config_path(&context.project_directory).await,
+    };
+
+    if let Some(config_path) = &config_path {
+        let content = fs::read_to_string(config_path).await.map_err(|e| {
+            log::error!("Unable to read {}: {e:?}", config_path.display());
+            DevContainerError::DevContainerNotFound
+        })?;
+        let configuration = parse_devcontainer_json(&content, &context.project_directory)?;
+        // Features are installed by the devcontainer CLI, so only configurations without
+        // them are started natively.
+        if configuration.features.is_empty() {
+            return start_native_dev_container(&context, config_path, configuration).await;
+        }
+        log::info!(
+            "{} uses features, falling back to the devcontainer CLI",
+            config_path.display()
+        );
+    }
+
     let cli = ensure_devcontainer_cli(&context.node_runtime).await?;
-    let config_path = config.map(|c| context.project_directory.join(&c.config_path));
 
     match devcontainer_up(&context, &cli, config_path.as_deref()).await {
         Ok(DevContainerUp {
@@ -253,23 +306,33 @@ pub async fn start_dev_container_with_config(
             remote_user,
             ..
         }) => {
-            let project_name =
-                match read_devcontainer_configuration(&context, &cli, config_path.as_deref()).await
-                {
-                    Ok(DevContainerConfigurationOutput {
-                        configuration:
-                            DevContainerConfiguration {
-                                name: Some(project_name),
-                            },
-                    }) => project_name,
-                    _ => get_backup_project_name(&remote_workspace_folder, &container_id),
-                };
+            let configuration =
+                read_devcontainer_configuration(&context, &cli, config_path.as_deref())
+                    .await
+                    .ok()
+                    .map(|output| output.configuration);
+            let port_forwards = configuration
+                .as_ref()
+                .map(|configuration| {
+                    configuration
+                        .forward_ports
+                        .iter()
+                        .filter_map(DevContainerForwardPort::to_port_forward)
+                        .collect::<Vec<_>>()
+                })
+                .filter(|port_forwards| !port_forwards.is_empty());
+            let project_name = configuration
+                .and_then(|configuration| configuration.name)
+                .unwrap_or_else(|| {
+                    get_backup_project_name(&remote_workspace_folder, &container_id)
+                });
 
             let connection = DevContainerConnection {
                 name: project_name,
                 container_id,
                 use_podman: context.use_podman,
                 remote_user,
+                port_forwards,
             };
 
             Ok((connection, remote_workspace_folder))
@@ -281,6 +344,55 @@ pub async fn start_dev_container_with_config(
     }
 }
 
+async fn find_default_config_path(project_directory: &Path) -> Option<PathBuf> {
+    for config in [
+        DevContainerConfig::default_config(),
+        DevContainerConfig::root_config(),
+    ] {
+        let path = project_directory.join(&config.config_path);
+        if fs::metadata(&path).await.is_ok() {
+            return Some(path);
+        }
+    }
+    None
+}
+
+async fn start_native_dev_container(
+    context: &DevContainerContext,
+    config_path: &Path,
+    configuration: DevContainerJson,
+) -> Result<(DevContainerConnection, String), DevContainerError> {
+    let up = native::devcontainer_up(
+        &DockerCli::new(context.use_podman),
+        &context.project_directory,
+        config_path,
+        &configuration,
+        paths::devcontainer_dir(),
+    )
+    .await?;
+
+    let port_forwards = Some(
+        configuration
+            .forward_ports
+            .iter()
+            .filter_map(DevContainerForwardPort::to_port_forward)
+            .collect::<Vec<_>>(),
+    )
+    .filter(|port_forwards| !port_forwards.is_empty());
+    let name = configuration
+        .name
+        .unwrap_or_else(|| get_backup_project_name(&up.remote_workspace_folder, &up.container_id));
+
+    let connection = DevContainerConnection {
+        name,
+        container_id: up.container_id,
+        use_podman: context.use_podman,
+        remote_user: up.remote_user,
+        port_forwards,
+    };
+    Ok((connection, up.remote_workspace_folder))
+}
+
 #[cfg(not(target_os = "windows"))]
 fn dev_container_cli() -> String {
     "devcontainer".to_string()
@@ -605,13 +717,14 @@ mod tests {
     use std::path::PathBuf;
 
     use crate::devcontainer_api::{
-        DevContainerConfig, DevContainerUp, find_configs_in_snapshot, parse_json_from_cli,
+        DevContainerConfig, DevContainerConfigurationOutput, DevContainerUp,
+        find_configs_in_snapshot, parse_json_from_cli,
     };
     use fs::FakeFs;
     use gpui::TestAppContext;
     use project::Project;
     use serde_json::json;
-    use settings::SettingsStore;
+    use settings::{SettingsStore, SshPortForwardOption};
     use util::path;
 
     fn init_test(cx: &mut TestAppContext) {
@@ -645,6 +758,36 @@ mod tests {
         assert_eq!(up.remote_workspace_folder, "/workspaces/zed");
     }
 
+    #[test]
+    fn should_parse_forward_ports_from_configuration() {
+        let json =
+            r#"{"configuration":{"name":"app","forwardPorts":[3000,"db:5432","not-a-port"]}}"#;
+        let output: DevContainerConfigurationOutput = parse_json_from_cli(json).unwrap();
+        let port_forwards = output
+            .configuration
+            .forward_ports
+            .iter()
+            .filter_map(|port| port.to_port_forward())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            port_forwards,
+            vec![
+                SshPortForwardOption {
+                    local_host: None,
+                    local_port: 3000,
+                    remote_host: None,
+                    remote_port: 3000,
+                },
+                SshPortForwardOption {
+                    local_host: None,
+                    local_port: 5432,
+                    remote_host: Some("db".to_string()),
+                    remote_port: 5432,
+                },
+            ]
+        );
+    }
+
     #[gpui::test]
     async fn test_find_configs_root_devcontainer_json(cx: &mut TestAppContext) {
         init_test(cx);
@@ -0,0 +1,447 @@
+//! Native interpretation of `devcontainer.json`, used to start dev containers without
+//! the Node-based devcontainer CLI.
+//!
+//! See <https://containers.dev/implementors/json_reference/> for the reference this follows.
+
+use std::{collections::BTreeMap, path::Path};
+
+use serde::Deserialize;
+
+use crate::devcontainer_api::{DevContainerError, DevContainerForwardPort};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DevContainerJson {
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub build: Option<DevContainerBuild>,
+    /// Legacy top-level spelling of `build.dockerfile`.
+    #[serde(rename = "dockerFile")]
+    pub docker_file: Option<String>,
+    /// Legacy top-level spelling of `build.context`.
+    pub context: Option<String>,
+    pub docker_compose_file: Option<OneOrMany>,
+    pub service: Option<String>,
+    pub run_services: Option<Vec<String>>,
+    pub workspace_folder: Option<String>,
+    pub workspace_mount: Option<String>,
+    #[serde(default)]
+    pub mounts: Vec<DevContainerMount>,
+    #[serde(default)]
+    pub container_env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub remote_env: BTreeMap<String, Option<String>>,
+    pub remote_user: Option<String>,
+    pub container_user: Option<String>,
+    pub override_command: Option<bool>,
+    #[serde(default)]
+    pub run_args: Vec<String>,
+    #[serde(default)]
+    pub forward_ports: Vec<DevContainerForwardPort>,
+    #[serde(default)]
+    pub features: serde_json::Map<String, serde_json::Value>,
+    pub initialize_command: Option<LifecycleCommand>,
+    pub on_create_command: Option<LifecycleCommand>,
+    pub update_content_command: Option<LifecycleCommand>,
+    pub post_create_command: Option<LifecycleCommand>,
+    pub post_start_command: Option<LifecycleCommand>,
+    pub post_attach_command: Option<LifecycleCommand>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DevContainerBuild {
+    pub dockerfile: Option<String>,
+    pub context: Option<String>,
+    #[serde(default)]
+    pub args: BTreeMap<String, String>,
+    pub target: Option<String>,
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    pub fn to_vec(&self) -> Vec<String> {
+        match self {
+            OneOrMany::One(value) => vec![value.clone()],
+            OneOrMany::Many(values) => values.clone(),
+        }
+    }
+}
+
+/// An entry of `mounts`, either in `docker run --mount` syntax or as an object.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum DevContainerMount {
+    Raw(String),
+    Object {
+        #[serde(rename = "type")]
+        kind: Option<String>,
+        source: Option<String>,
+        target: String,
+    },
+}
+
+impl DevContainerMount {
+    /// The mount in the syntax accepted by `docker run --mount`.
+    pub fn to_mount_arg(&self) -> String {
+        match self {
+            DevContainerMount::Raw(raw) => raw.clone(),
+            DevContainerMount::Object {
+                kind,
+                source,
+                target,
+            } => {
+                let mut parts = vec![format!("type={}", kind.as_deref().unwrap_or("bind"))];
+                if let Some(source) = source {
+                    parts.push(format!("source={source}"));
+                }
+                parts.push(format!("target={target}"));
+                parts.join(",")
+            }
+        }
+    }
+
+    /// The mount in the long volume syntax of a compose file.
+    pub fn to_compose_volume(&self) -> serde_json::Value {
+        let mut volume = serde_json::Map::new();
+        for part in self.to_mount_arg().split(',') {
+            let (key, value) = part.split_once('=').unwrap_or((part, "true"));
+            let key = match key {
+                "src" => "source",
+                "dst" | "destination" => "target",
+                "readonly" | "ro" => "read_only",
+                key => key,
+            };
+            let value = match key {
+                "read_only" => serde_json::Value::Bool(value != "false"),
+                _ => serde_json::Value::String(value.to_string()),
+            };
+            volume.insert(key.to_string(), value);
+        }
+        serde_json::Value::Object(volume)
+    }
+}
+
+/// A lifecycle hook such as `postCreateCommand`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum LifecycleCommand {
+    /// Run through a shell.
+    Shell(String),
+    /// Run directly, without a shell.
+    Exec(Vec<String>),
+    /// Named commands that run in parallel.
+    Parallel(BTreeMap<String, LifecycleCommand>),
+}
+
+impl DevContainerJson {
+    /// The folder the project is mounted at inside the container. `parse_devcontainer_json`
+    /// fills in the default when the configuration doesn't set one.
+    pub fn container_workspace_folder(&self) -> String {
+        self.workspace_folder
+            .clone()
+            .unwrap_or_else(|| "/".to_string())
+    }
+
+    pub fn dockerfile(&self) -> Option<&str> {
+        self.build
+            .as_ref()
+            .and_then(|build| build.dockerfile.as_deref())
+            .or(self.docker_file.as_deref())
+    }
+
+    pub fn build_context(&self) -> &str {
+        self.build
+            .as_ref()
+            .and_then(|build| build.context.as_deref())
+            .or(self.context.as_deref())
+            .unwrap_or(".")
+    }
+
+    pub fn compose_files(&self) -> Vec<String> {
+        self.docker_compose_file
+            .as_ref()
+            .map(OneOrMany::to_vec)
+            .unwrap_or_default()
+    }
+}
+
+/// Parses the contents of a `devcontainer.json`, substituting the `${...}` variables that
+/// can be resolved on the host.
+pub(crate) fn parse_devcontainer_json(
+    content: &str,
+    local_workspace_folder: &Path,
+) -> Result<DevContainerJson, DevContainerError> {
+    let mut value =
+        settings::parse_json_with_comments::<serde_json::Value>(content).map_err(|e| {
+            log::error!("Unable to parse devcontainer.json: {e:?}");
+            DevContainerError::DevContainerParseFailed
+        })?;
+
+    let local_variables = Variables {
+        local_workspace_folder,
+        container_workspace_folder: None,
+    };
+    let default_workspace_folder = if value.get("dockerComposeFile").is_some() {
+        "/".to_string()
+    } else {
+        format!(
+            "/workspaces/{}",
+            local_variables.local_workspace_folder_basename()
+        )
+    };
+    let container_workspace_folder = value
+        .get("workspaceFolder")
+        .and_then(|folder| folder.as_str())
+        .map(|folder| local_variables.substitute(folder))
+        .unwrap_or(default_workspace_folder);
+
+    let variables = Variables {
+        local_workspace_folder,
+        container_workspace_folder: Some(&container_workspace_folder),
+    };
+    substitute_value(&mut value, &variables);
+    if let Some(object) = value.as_object_mut() {
+        object.insert(
+            "workspaceFolder".into(),
+            serde_json::Value::String(container_workspace_folder.clone()),
+        );
+    }
+
+    serde_json::from_value(value).map_err(|e| {
+        log::error!("Invalid devcontainer.json: {e:?}");
+        DevContainerError::DevContainerParseFailed
+    })
+}
+
+struct Variables<'a> {
+    local_workspace_folder: &'a Path,
+    container_workspace_folder: Option<&'a str>,
+}
+
+impl Variables<'_> {
+    fn local_workspace_folder_basename(&self) -> String {
+        self.local_workspace_folder
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    fn resolve(&self, name: &str) -> Option<String> {
+        match name {
+            "localWorkspaceFolder" => Some(self.local_workspace_folder.display().to_string()),
+            "localWorkspaceFolderBasename" => Some(self.local_workspace_folder_basename()),
+            "containerWorkspaceFolder" => self.container_workspace_folder.map(str::to_string),
+            "containerWorkspaceFolderBasename" => self
+                .container_workspace_folder
+                .and_then(|folder| folder.rsplit('/').find(|part| !part.is_empty()))
+                .map(str::to_string),
+            _ => {
+                let (scope, rest) = name.split_once(':')?;
+                if scope != "localEnv" && scope != "env" {
+                    return None;
+                }
+                let (variable, default) = match rest.split_once(':') {
+                    Some((variable, default)) => (variable, Some(default)),
+                    None => (rest, None),
+                };
+                Some(
+                    std::env::var(variable)
+                        .ok()
+                        .or_else(|| default.map(str::to_string))
+                        .unwrap_or_default(),
+                )
+            }
+        }
+    }
+
+    /// Replaces every known `${...}` variable in `text`, leaving unknown ones (such as
+    /// `${containerEnv:PATH}`) for the container to resolve.
+    fn substitute(&self, text: &str) -> String {
+        substitute_variables(text, |name| self.resolve(name))
+    }
+}
+
+pub(crate) fn substitute_variables(
+    text: &str,
+    mut resolve: impl FnMut(&str) -> Option<String>,
+) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let after_start = &rest[start + 2..];
+        let Some(end) = after_start.find('}') else {
+            result.push_str(&rest[start..]);
+            return result;
+        };
+        let name = &after_start[..end];
+        match resolve(name) {
+            Some(value) => result.push_str(&value),
+            None => result.push_str(&rest[start..start + end + 3]),
+        }
+        rest = &after_start[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+fn substitute_value(value: &mut serde_json::Value, variables: &Variables) {
+    match value {
+        serde_json::Value::String(text) => *text = variables.substitute(text),
+        serde_json::Value::Array(values) => {
+            for value in values {
+                substitute_value(value, variables);
+            }
+        }
+        serde_json::Value::Object(object) => {
+            for value in object.values_mut() {
+                substitute_value(value, variables);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_image_configuration_with_comments() {
+        let config = parse_devcontainer_json(
+            r#"{
+                // A comment
+                "name": "Rust",
+                "image": "mcr.microsoft.com/devcontainers/rust:1",
+                "containerEnv": { "PROJECT": "${localWorkspaceFolderBasename}" },
+                "mounts": [
+                    "source=cache,target=/cache,type=volume",
+                    { "source": "${localWorkspaceFolder}/data", "target": "/data", "type": "bind" },
+                ],
+                "remoteUser": "vscode",
+                "postCreateCommand": "cargo fetch",
+                "postStartCommand": ["git", "status"],
+                "postAttachCommand": {
+                    "server": "echo ${containerWorkspaceFolder}",
+                    "env": "echo ${containerEnv:PATH}",
+                },
+            }"#,
+            Path::new("/home/user/my-project"),
+        )
+        .unwrap();
+
+        assert_eq!(config.name.as_deref(), Some("Rust"));
+        assert_eq!(
+            config.container_workspace_folder(),
+            "/workspaces/my-project"
+        );
+        assert_eq!(
+            config.container_env.get("PROJECT").map(String::as_str),
+            Some("my-project")
+        );
+        assert_eq!(
+            config
+                .mounts
+                .iter()
+                .map(DevContainerMount::to_mount_arg)
+                .collect::<Vec<_>>(),
+            vec![
+                "source=cache,target=/cache,type=volume".to_string(),
+                "type=bind,source=/home/user/my-project/data,target=/data".to_string(),
+            ]
+        );
+        assert_eq!(config.remote_user.as_deref(), Some("vscode"));
+        assert_eq!(
+            config.post_create_command,
+            Some(LifecycleCommand::Shell("cargo fetch".into()))
+        );
+        assert_eq!(
+            config.post_start_command,
+            Some(LifecycleCommand::Exec(vec!["git".into(), "status".into()]))
+        );
+        assert_eq!(
+            config.post_attach_command,
+            Some(LifecycleCommand::Parallel(BTreeMap::from_iter([
+                (
+                    "env".to_string(),
+                    LifecycleCommand::Shell("echo ${containerEnv:PATH}".into())
+                ),
+                (
+                    "server".to_string(),
+                    LifecycleCommand::Shell("echo /workspaces/my-project".into())
+                ),
+            ])))
+        );
+    }
+
+    #[test]
+    fn should_parse_build_and_compose_configurations() {
+        let config = parse_devcontainer_json(
+            r#"{
+                "build": { "dockerfile": "Dockerfile", "context": "..", "args": { "VARIANT": "1" } },
+                "workspaceFolder": "/src/${localWorkspaceFolderBasename}",
+            }"#,
+            Path::new("/projects/app"),
+        )
+        .unwrap();
+        assert_eq!(config.dockerfile(), Some("Dockerfile"));
+        assert_eq!(config.build_context(), "..");
+        assert_eq!(config.container_workspace_folder(), "/src/app");
+
+        let config = parse_devcontainer_json(
+            r#"{ "dockerComposeFile": ["compose.yml", "compose.dev.yml"], "service": "app" }"#,
+            Path::new("/projects/app"),
+        )
+        .unwrap();
+        assert_eq!(
+            config.compose_files(),
+            vec!["compose.yml", "compose.dev.yml"]
+        );
+        assert_eq!(config.service.as_deref(), Some("app"));
+        assert_eq!(config.container_workspace_folder(), "/");
+    }
+
+    #[test]
+    fn should_substitute_variables() {
+        let resolve = |name: &str| match name {
+            "known" => Some("value".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            substitute_variables("a ${known} b ${unknown} c", resolve),
+            "a value b ${unknown} c"
+        );
+        assert_eq!(substitute_variables("${known", resolve), "${known");
+
+        let variables = Variables {
+            local_workspace_folder: Path::new("/a/b"),
+            container_workspace_folder: Some("/workspaces/b/"),
+        };
+        assert_eq!(
+            variables.substitute("${containerWorkspaceFolderBasename}"),
+            "b"
+        );
+        assert_eq!(
+            variables.substitute("${localEnv:ZED_DEVCONTAINER_UNSET_VARIABLE:fallback}"),
+            "fallback"
+        );
+    }
+
+    #[test]
+    fn should_convert_mounts_to_compose_volumes() {
+        let mount = DevContainerMount::Raw("type=bind,src=/a,dst=/b,readonly".into());
+        assert_eq!(
+            mount.to_compose_volume(),
+            serde_json::json!({ "type": "bind", "source": "/a", "target": "/b", "read_only": true })
+        );
+    }
+}
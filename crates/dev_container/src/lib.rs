@@ -42,6 +42,8 @@ use http::Request;
 use http_client::{AsyncBody, HttpClient};
 
 mod devcontainer_api;
+mod devcontainer_json;
+mod native;
 
 use devcontainer_api::ensure_devcontainer_cli;
 use devcontainer_api::read_devcontainer_configuration;
@@ -0,0 +1,698 @@
+//! Starts dev containers by driving the docker (or podman) CLI directly, following the
+//! behavior of the reference devcontainer CLI for the subset of `devcontainer.json` that
+//! doesn't require features.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+use futures::future::{BoxFuture, FutureExt, try_join_all};
+use util::command::Command;
+
+use crate::{
+    devcontainer_api::DevContainerError,
+    devcontainer_json::{DevContainerJson, LifecycleCommand, substitute_variables},
+};
+
+const LOCAL_FOLDER_LABEL: &str = "devcontainer.local_folder";
+const CONFIG_FILE_LABEL: &str = "devcontainer.config_file";
+
+/// Keeps the container alive when `overrideCommand` is set, like the devcontainer CLI does.
+const KEEP_ALIVE_SCRIPT: &str =
+    "echo Container started; trap \"exit 0\" 15; while sleep 1 & wait $!; do :; done";
+
+pub(crate) struct DockerCli {
+    program: PathBuf,
+}
+
+impl DockerCli {
+    pub fn new(use_podman: bool) -> Self {
+        Self::with_program(if use_podman { "podman" } else { "docker" })
+    }
+
+    pub fn with_program(program: impl Into<PathBuf>) -> Self {
+        Self {
+            program: program.into(),
+        }
+    }
+
+    fn command(&self) -> Command {
+        util::command::new_command(self.program.display().to_string())
+    }
+
+    /// Runs the CLI with the given arguments and returns its trimmed stdout.
+    async fn run<I, S>(&self, args: I) -> Result<String, DevContainerError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<std::ffi::OsStr>,
+    {
+        let mut command = self.command();
+        command.args(args);
+        run_command(command).await
+    }
+}
+
+async fn run_command(mut command: Command) -> Result<String, DevContainerError> {
+    log::info!("Running dev container command: {:?}", command);
+    match command.output().await {
+        Ok(output) if output.status.success() => {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        }
+        Ok(output) => {
+            let message = format!(
+                "Non-success status running {:?}: out: {}, err: {}",
+                command,
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            log::error!("{}", &message);
+            Err(DevContainerError::DevContainerUpFailed(message))
+        }
+        Err(e) => {
+            let message = format!("Error running {:?}: {:?}", command, e);
+            log::error!("{}", &message);
+            Err(DevContainerError::DevContainerUpFailed(message))
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct NativeDevContainerUp {
+    pub container_id: String,
+    pub remote_user: String,
+    pub remote_workspace_folder: String,
+}
+
+/// Finds or creates the container described by `config`, then runs its lifecycle hooks.
+///
+/// `scratch_directory` holds generated files, such as the compose override file.
+pub(crate) async fn devcontainer_up(
+    docker: &DockerCli,
+    project_directory: &Path,
+    config_path: &Path,
+    config: &DevContainerJson,
+    scratch_directory: &Path,
+) -> Result<NativeDevContainerUp, DevContainerError> {
+    if let Some(command) = &config.initialize_command {
+        run_on_host(command, project_directory).await?;
+    }
+
+    let labels = [
+        format!("{LOCAL_FOLDER_LABEL}={}", project_directory.display()),
+        format!("{CONFIG_FILE_LABEL}={}", config_path.display()),
+    ];
+    let remote_workspace_folder = config.container_workspace_folder();
+
+    let mut find_args = vec!["ps".to_string(), "-a".to_string()];
+    for label in &labels {
+        find_args.extend(["--filter".to_string(), format!("label={label}")]);
+    }
+    find_args.extend(["--format".to_string(), "{{.ID}}".to_string()]);
+    let existing = docker.run(&find_args).await?;
+
+    let (container_id, created, started) = match existing.lines().next() {
+        Some(container_id) => {
+            let container_id = container_id.trim().to_string();
+            let running = docker
+                .run([
+                    "inspect",
+                    "--format",
+                    "{{.State.Running}}",
+                    container_id.as_str(),
+                ])
+                .await?;
+            let started = running != "true";
+            if started {
+                docker.run(["start", container_id.as_str()]).await?;
+            }
+            (container_id, false, started)
+        }
+        None => {
+            let container_id = if config.docker_compose_file.is_some() {
+                compose_up(
+                    docker,
+                    project_directory,
+                    config_path,
+                    config,
+                    &labels,
+                    scratch_directory,
+                )
+                .await?
+            } else {
+                run_container(docker, project_directory, config_path, config, &labels).await?
+            };
+            (container_id, true, true)
+        }
+    };
+
+    let remote_user = match config
+        .remote_user
+        .clone()
+        .or_else(|| config.container_user.clone())
+    {
+        Some(user) => user,
+        None => {
+            let user = docker
+                .run([
+                    "inspect",
+                    "--format",
+                    "{{.Config.User}}",
+                    container_id.as_str(),
+                ])
+                .await?;
+            if user.is_empty() {
+                "root".to_string()
+            } else {
+                user
+            }
+        }
+    };
+
+    let hooks = [
+        (&config.on_create_command, created),
+        (&config.update_content_command, created),
+        (&config.post_create_command, created),
+        (&config.post_start_command, started),
+        (&config.post_attach_command, true),
+    ];
+    if hooks
+        .iter()
+        .any(|(command, should_run)| *should_run && command.is_some())
+    {
+        let exec = ContainerExec {
+            docker,
+            container_id: &container_id,
+            user: &remote_user,
+            workspace_folder: &remote_workspace_folder,
+            env: remote_env(docker, &container_id, config).await?,
+        };
+        for (command, should_run) in hooks {
+            if let Some(command) = command
+                && should_run
+            {
+                exec.run(command).await?;
+            }
+        }
+    }
+
+    Ok(NativeDevContainerUp {
+        container_id,
+        remote_user,
+        remote_workspace_folder,
+    })
+}
+
+/// Builds the image if needed and starts a new container from it.
+async fn run_container(
+    docker: &DockerCli,
+    project_directory: &Path,
+    config_path: &Path,
+    config: &DevContainerJson,
+    labels: &[String],
+) -> Result<String, DevContainerError> {
+    let config_directory = config_path.parent().unwrap_or(project_directory);
+    let image = if let Some(dockerfile) = config.dockerfile() {
+        let tag = image_tag(project_directory, config_path);
+        let mut args = vec![
+            "build".to_string(),
+            "-f".to_string(),
+            config_directory.join(dockerfile).display().to_string(),
+            "-t".to_string(),
+            tag.clone(),
+        ];
+        if let Some(build) = &config.build {
+            for (name, value) in &build.args {
+                args.extend(["--build-arg".to_string(), format!("{name}={value}")]);
+            }
+            if let Some(target) = &build.target {
+                args.extend(["--target".to_string(), target.clone()]);
+            }
+            args.extend(build.options.iter().cloned());
+        }
+        args.push(
+            config_directory
+                .join(config.build_context())
+                .display()
+                .to_string(),
+        );
+        docker.run(&args).await?;
+        tag
+    } else if let Some(image) = &config.image {
+        image.clone()
+    } else {
+        return Err(DevContainerError::DevContainerUpFailed(
+            "devcontainer.json must specify an image, a Dockerfile, or a compose file".into(),
+        ));
+    };
+
+    let mut args = vec!["run".to_string(), "-d".to_string()];
+    for label in labels {
+        args.extend(["--label".to_string(), label.clone()]);
+    }
+    let workspace_mount = config.workspace_mount.clone().unwrap_or_else(|| {
+        format!(
+            "type=bind,source={},target={}",
+            project_directory.display(),
+            config.container_workspace_folder()
+        )
+    });
+    if !workspace_mount.is_empty() {
+        args.extend(["--mount".to_string(), workspace_mount]);
+    }
+    for mount in &config.mounts {
+        args.extend(["--mount".to_string(), mount.to_mount_arg()]);
+    }
+    for (name, value) in &config.container_env {
+        args.extend(["-e".to_string(), format!("{name}={value}")]);
+    }
+    if let Some(user) = &config.container_user {
+        args.extend(["-u".to_string(), user.clone()]);
+    }
+    args.extend(config.run_args.iter().cloned());
+    if config.override_command.unwrap_or(true) {
+        args.extend(["--entrypoint".to_string(), "/bin/sh".to_string()]);
+        args.push(image);
+        args.extend(["-c".to_string(), KEEP_ALIVE_SCRIPT.to_string()]);
+    } else {
+        args.push(image);
+    }
+
+    let output = docker.run(&args).await?;
+    output
+        .lines()
+        .last()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| {
+            DevContainerError::DevContainerUpFailed(
+                "docker run did not print a container id".into(),
+            )
+        })
+}
+
+/// Starts the compose project, with an override file that applies the labels, environment,
+/// and mounts from `devcontainer.json` to the dev container's service.
+async fn compose_up(
+    docker: &DockerCli,
+    project_directory: &Path,
+    config_path: &Path,
+    config: &DevContainerJson,
+    labels: &[String],
+    scratch_directory: &Path,
+) -> Result<String, DevContainerError> {
+    let Some(service) = &config.service else {
+        return Err(DevContainerError::DevContainerUpFailed(
+            "devcontainer.json must specify a service when using dockerComposeFile".into(),
+        ));
+    };
+    let config_directory = config_path.parent().unwrap_or(project_directory);
+    let project_name = compose_project_name(project_directory);
+
+    let mut service_override = serde_json::Map::new();
+    service_override.insert("labels".into(), serde_json::json!(labels));
+    if !config.container_env.is_empty() {
+        service_override.insert(
+            "environment".into(),
+            serde_json::json!(config.container_env),
+        );
+    }
+    if !config.mounts.is_empty() {
+        let volumes = config
+            .mounts
+            .iter()
+            .map(|mount| mount.to_compose_volume())
+            .collect::<Vec<_>>();
+        service_override.insert("volumes".into(), serde_json::Value::Array(volumes));
+    }
+    if let Some(user) = &config.container_user {
+        service_override.insert("user".into(), serde_json::json!(user));
+    }
+    if config.override_command.unwrap_or(false) {
+        service_override.insert("entrypoint".into(), serde_json::json!(["/bin/sh", "-c"]));
+        service_override.insert("command".into(), serde_json::json!([KEEP_ALIVE_SCRIPT]));
+    }
+    let mut services = serde_json::Map::new();
+    services.insert(service.clone(), serde_json::Value::Object(service_override));
+    // JSON is valid YAML, so compose accepts the override as-is.
+    let compose_override = serde_json::json!({ "services": services });
+    let override_path = scratch_directory.join(format!("{project_name}.compose.json"));
+    let write_override = async {
+        smol::fs::create_dir_all(scratch_directory).await?;
+        smol::fs::write(&override_path, compose_override.to_string()).await
+    };
+    write_override.await.map_err(|e| {
+        DevContainerError::DevContainerUpFailed(format!(
+            "Failed to write compose override file: {e:?}"
+        ))
+    })?;
+
+    let mut args = vec![
+        "compose".to_string(),
+        "-p".to_string(),
+        project_name.clone(),
+    ];
+    for file in config.compose_files() {
+        args.extend([
+            "-f".to_string(),
+            config_directory.join(file).display().to_string(),
+        ]);
+    }
+    args.extend(["-f".to_string(), override_path.display().to_string()]);
+    args.extend(["up".to_string(), "-d".to_string()]);
+    if let Some(run_services) = &config.run_services {
+        args.extend(run_services.iter().cloned());
+        if !run_services.contains(service) {
+            args.push(service.clone());
+        }
+    }
+    docker.run(&args).await?;
+
+    let project_filter = format!("label=com.docker.compose.project={project_name}");
+    let service_filter = format!("label=com.docker.compose.service={service}");
+    let container_id = docker
+        .run([
+            "ps",
+            "-q",
+            "--filter",
+            project_filter.as_str(),
+            "--filter",
+            service_filter.as_str(),
+        ])
+        .await?;
+    container_id
+        .lines()
+        .next()
+        .map(|id| id.trim().to_string())
+        .ok_or_else(|| {
+            DevContainerError::DevContainerUpFailed(format!(
+                "No container found for compose service {service}"
+            ))
+        })
+}
+
+/// Resolves `remoteEnv`, including `${containerEnv:...}` references.
+async fn remote_env(
+    docker: &DockerCli,
+    container_id: &str,
+    config: &DevContainerJson,
+) -> Result<BTreeMap<String, String>, DevContainerError> {
+    let needs_container_env = config
+        .remote_env
+        .values()
+        .flatten()
+        .any(|value| value.contains("${containerEnv:"));
+    let container_env = if needs_container_env {
+        parse_env_output(&docker.run(["exec", container_id, "env"]).await?)
+    } else {
+        HashMap::default()
+    };
+
+    Ok(config
+        .remote_env
+        .iter()
+        .filter_map(|(name, value)| {
+            let value = substitute_variables(value.as_ref()?, |variable| {
+                let (scope, name) = variable.split_once(':')?;
+                if scope != "containerEnv" {
+                    return None;
+                }
+                let (name, default) = name.split_once(':').unwrap_or((name, ""));
+                Some(
+                    container_env
+                        .get(name)
+                        .cloned()
+                        .unwrap_or_else(|| default.to_string()),
+                )
+            });
+            Some((name.clone(), value))
+        })
+        .collect())
+}
+
+fn parse_env_output(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+struct ContainerExec<'a> {
+    docker: &'a DockerCli,
+    container_id: &'a str,
+    user: &'a str,
+    workspace_folder: &'a str,
+    env: BTreeMap<String, String>,
+}
+
+impl ContainerExec<'_> {
+    fn run<'a>(
+        &'a self,
+        command: &'a LifecycleCommand,
+    ) -> BoxFuture<'a, Result<(), DevContainerError>> {
+        async move {
+            let program = match command {
+                LifecycleCommand::Shell(script) => {
+                    vec!["/bin/sh".to_string(), "-c".to_string(), script.clone()]
+                }
+                LifecycleCommand::Exec(program) => program.clone(),
+                LifecycleCommand::Parallel(commands) => {
+                    try_join_all(commands.values().map(|command| self.run(command))).await?;
+                    return Ok(());
+                }
+            };
+            if program.is_empty() {
+                return Ok(());
+            }
+
+            let mut args = vec![
+                "exec".to_string(),
+                "-u".to_string(),
+                self.user.to_string(),
+                "-w".to_string(),
+                self.workspace_folder.to_string(),
+            ];
+            for (name, value) in &self.env {
+                args.extend(["-e".to_string(), format!("{name}={value}")]);
+            }
+            args.push(self.container_id.to_string());
+            args.extend(program);
+            self.docker.run(&args).await?;
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+/// Runs `initializeCommand`, which runs on the host rather than in the container.
+fn run_on_host<'a>(
+    command: &'a LifecycleCommand,
+    project_directory: &'a Path,
+) -> BoxFuture<'a, Result<(), DevContainerError>> {
+    async move {
+        let mut process = match command {
+            LifecycleCommand::Shell(script) => {
+                let mut process = if cfg!(windows) {
+                    let mut process = util::command::new_command("cmd");
+                    process.arg("/C");
+                    process
+                } else {
+                    let mut process = util::command::new_command("/bin/sh");
+                    process.arg("-c");
+                    process
+                };
+                process.arg(script);
+                process
+            }
+            LifecycleCommand::Exec(program) => {
+                let Some((program, args)) = program.split_first() else {
+                    return Ok(());
+                };
+                let mut process = util::command::new_command(program);
+                process.args(args);
+                process
+            }
+            LifecycleCommand::Parallel(commands) => {
+                try_join_all(
+                    commands
+                        .values()
+                        .map(|command| run_on_host(command, project_directory)),
+                )
+                .await?;
+                return Ok(());
+            }
+        };
+        process.current_dir(project_directory);
+        run_command(process).await?;
+        Ok(())
+    }
+    .boxed()
+}
+
+fn image_tag(project_directory: &Path, config_path: &Path) -> String {
+    let mut hasher = DefaultHasher::new();
+    config_path.hash(&mut hasher);
+    format!(
+        "zed-dev-container-{}-{:016x}",
+        sanitized_name(project_directory),
+        hasher.finish()
+    )
+}
+
+fn compose_project_name(project_directory: &Path) -> String {
+    format!("{}_devcontainer", sanitized_name(project_directory))
+}
+
+/// The project directory's name, restricted to what image and compose project names allow.
+fn sanitized_name(project_directory: &Path) -> String {
+    let name = project_directory
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect::<String>();
+    if name.is_empty() {
+        "project".to_string()
+    } else {
+        name
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+    use crate::devcontainer_json::parse_devcontainer_json;
+
+    /// A fake docker CLI that logs its arguments and answers the queries `devcontainer_up`
+    /// makes. `ps` prints the contents of the `existing` file, if any.
+    const FAKE_DOCKER: &str = r#"#!/bin/sh
+dir="$(dirname "$0")"
+echo "$@" >> "$dir/calls.log"
+case "$1" in
+  ps) cat "$dir/existing" 2>/dev/null || true ;;
+  run) echo new-container ;;
+  inspect)
+    case "$3" in
+      *Running*) echo false ;;
+      *) echo ;;
+    esac ;;
+esac
+"#;
+
+    fn fake_docker() -> (tempfile::TempDir, DockerCli) {
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("docker");
+        std::fs::write(&program, FAKE_DOCKER).unwrap();
+        std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+        (dir, DockerCli::with_program(program))
+    }
+
+    fn calls(dir: &tempfile::TempDir) -> Vec<String> {
+        std::fs::read_to_string(dir.path().join("calls.log"))
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn should_create_container_and_run_lifecycle_hooks() {
+        let (dir, docker) = fake_docker();
+        let project = Path::new("/projects/app");
+        let config_path = project.join(".devcontainer/devcontainer.json");
+        let config = parse_devcontainer_json(
+            r#"{
+                "image": "rust:1",
+                "containerEnv": { "A": "1" },
+                "mounts": ["type=volume,source=cache,target=/cache"],
+                "runArgs": ["--init"],
+                "postCreateCommand": "make setup",
+                "postAttachCommand": ["echo", "attached"],
+            }"#,
+            project,
+        )
+        .unwrap();
+
+        let up = smol::block_on(devcontainer_up(
+            &docker,
+            project,
+            &config_path,
+            &config,
+            dir.path(),
+        ))
+        .unwrap();
+
+        assert_eq!(
+            up,
+            NativeDevContainerUp {
+                container_id: "new-container".into(),
+                remote_user: "root".into(),
+                remote_workspace_folder: "/workspaces/app".into(),
+            }
+        );
+        let labels = "devcontainer.local_folder=/projects/app \
+            --label devcontainer.config_file=/projects/app/.devcontainer/devcontainer.json";
+        assert_eq!(
+            calls(&dir),
+            vec![
+                "ps -a --filter label=devcontainer.local_folder=/projects/app \
+                 --filter label=devcontainer.config_file=/projects/app/.devcontainer/devcontainer.json \
+                 --format {{.ID}}"
+                    .to_string(),
+                format!(
+                    "run -d --label {labels} \
+                     --mount type=bind,source=/projects/app,target=/workspaces/app \
+                     --mount type=volume,source=cache,target=/cache -e A=1 --init \
+                     --entrypoint /bin/sh rust:1 -c {KEEP_ALIVE_SCRIPT}"
+                ),
+                "inspect --format {{.Config.User}} new-container".to_string(),
+                "exec -u root -w /workspaces/app new-container /bin/sh -c make setup".to_string(),
+                "exec -u root -w /workspaces/app new-container echo attached".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_restart_existing_container_without_create_hooks() {
+        let (dir, docker) = fake_docker();
+        std::fs::write(dir.path().join("existing"), "old-container\n").unwrap();
+        let project = Path::new("/projects/app");
+        let config_path = project.join(".devcontainer.json");
+        let config = parse_devcontainer_json(
+            r#"{
+                "image": "rust:1",
+                "remoteUser": "dev",
+                "postCreateCommand": "make setup",
+                "postStartCommand": "make start",
+            }"#,
+            project,
+        )
+        .unwrap();
+
+        let up = smol::block_on(devcontainer_up(
+            &docker,
+            project,
+            &config_path,
+            &config,
+            dir.path(),
+        ))
+        .unwrap();
+
+        assert_eq!(up.container_id, "old-container");
+        assert_eq!(up.remote_user, "dev");
+        let calls = calls(&dir);
+        assert_eq!(
+            &calls[1..],
+            &[
+                "inspect --format {{.State.Running}} old-container".to_string(),
+                "start old-container".to_string(),
+                "exec -u dev -w /workspaces/app old-container /bin/sh -c make start".to_string(),
+            ]
+        );
+    }
+}
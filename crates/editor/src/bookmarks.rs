@@ -0,0 +1,226 @@
+use std::ops::Range;
+
+use collections::{BTreeSet, HashMap};
+use gpui::Entity;
+use language::{Buffer, Point};
+use multi_buffer::MultiBufferRow;
+use project::bookmark_store::Bookmark;
+use text::Bias;
+use ui::{Tooltip, prelude::*};
+
+use crate::{
+    Direction, DisplayPoint, DisplayRow, Editor, SelectionEffects, ToPoint as _,
+    actions::{GoToNextBookmark, GoToPreviousBookmark, ToggleBookmark},
+    display_map::ToDisplayPoint as _,
+    scroll::Autoscroll,
+};
+
+impl Editor {
+    pub fn toggle_bookmark(
+        &mut self,
+        _: &ToggleBookmark,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return;
+        };
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let rows = self
+            .selections
+            .disjoint_anchors_arc()
+            .iter()
+            .map(|selection| selection.head().to_point(&snapshot).row)
+            .collect::<BTreeSet<_>>();
+        for row in rows {
+            let Some((buffer, position)) = self
+                .buffer
+                .read(cx)
+                .text_anchor_for_position(Point::new(row, 0), cx)
+            else {
+                continue;
+            };
+            bookmark_store.update(cx, |bookmark_store, cx| {
+                bookmark_store.toggle_bookmark(buffer, position, cx);
+            });
+        }
+    }
+
+    pub fn go_to_next_bookmark(
+        &mut self,
+        _: &GoToNextBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_bookmark(Direction::Next, window, cx);
+    }
+
+    pub fn go_to_previous_bookmark(
+        &mut self,
+        _: &GoToPreviousBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_bookmark(Direction::Prev, window, cx);
+    }
+
+    /// Moves the cursor to the closest bookmarked row in `direction`, wrapping around at either end.
+    fn go_to_bookmark(
+        &mut self,
+        direction: Direction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let rows = self.bookmarked_rows(cx);
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let cursor_row = self
+            .selections
+            .newest_anchor()
+            .head()
+            .to_point(&snapshot)
+            .row;
+        let target = match direction {
+            Direction::Next => rows
+                .iter()
+                .find(|row| row.0 > cursor_row)
+                .or_else(|| rows.first()),
+            Direction::Prev => rows
+                .iter()
+                .rev()
+                .find(|row| row.0 < cursor_row)
+                .or_else(|| rows.last()),
+        };
+        let Some(row) = target else {
+            return;
+        };
+
+        let destination = Point::new(row.0, 0);
+        self.unfold_ranges(&[destination..destination], false, false, cx);
+        self.change_selections(
+            SelectionEffects::scroll(Autoscroll::center()),
+            window,
+            cx,
+            |s| s.select_ranges([destination..destination]),
+        );
+    }
+
+    /// Returns the sorted multibuffer rows that have a bookmark.
+    fn bookmarked_rows(&self, cx: &App) -> Vec<MultiBufferRow> {
+        let Some(bookmark_store) = self.bookmark_store.as_ref() else {
+            return Vec::new();
+        };
+
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut rows = Vec::new();
+        for (buffer_snapshot, range, excerpt_id) in
+            snapshot.range_to_buffer_ranges(Point::zero()..=snapshot.max_point())
+        {
+            let Some(buffer) = multi_buffer.buffer(buffer_snapshot.remote_id()) else {
+                continue;
+            };
+            let range =
+                buffer_snapshot.anchor_before(range.start)..buffer_snapshot.anchor_after(range.end);
+            for bookmark in
+                bookmark_store
+                    .read(cx)
+                    .bookmarks(&buffer, Some(range), buffer_snapshot, cx)
+            {
+                if let Some(anchor) = snapshot.anchor_in_excerpt(excerpt_id, bookmark.position) {
+                    rows.push(MultiBufferRow(anchor.to_point(&snapshot).row));
+                }
+            }
+        }
+        rows.sort_unstable();
+        rows.dedup();
+        rows
+    }
+
+    /// Returns the bookmarks to display in the gutter for the given range of display rows.
+    pub(crate) fn active_bookmarks(
+        &self,
+        range: Range<DisplayRow>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> HashMap<DisplayRow, (Entity<Buffer>, Bookmark)> {
+        let mut bookmark_display_points = HashMap::default();
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return bookmark_display_points;
+        };
+
+        let snapshot = self.snapshot(window, cx);
+        let multi_buffer_snapshot = snapshot.buffer_snapshot();
+        let range = snapshot.display_point_to_point(DisplayPoint::new(range.start, 0), Bias::Left)
+            ..snapshot.display_point_to_point(DisplayPoint::new(range.end, 0), Bias::Right);
+
+        for (buffer_snapshot, range, excerpt_id) in
+            multi_buffer_snapshot.range_to_buffer_ranges(range.start..=range.end)
+        {
+            let Some(buffer) = self.buffer.read(cx).buffer(buffer_snapshot.remote_id()) else {
+                continue;
+            };
+            let bookmarks = bookmark_store.read(cx).bookmarks(
+                &buffer,
+                Some(
+                    buffer_snapshot.anchor_before(range.start)
+                        ..buffer_snapshot.anchor_after(range.end),
+                ),
+                buffer_snapshot,
+                cx,
+            );
+            for bookmark in bookmarks {
+                let Some(anchor) =
+                    multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, bookmark.position)
+                else {
+                    continue;
+                };
+                let display_row = anchor
+                    .to_point(multi_buffer_snapshot)
+                    .to_display_point(&snapshot)
+                    .row();
+                bookmark_display_points.insert(display_row, (buffer.clone(), bookmark.clone()));
+            }
+        }
+
+        bookmark_display_points
+    }
+
+    pub(crate) fn render_bookmark(
+        &self,
+        row: DisplayRow,
+        buffer: Entity<Buffer>,
+        bookmark: &Bookmark,
+        cx: &mut Context<Self>,
+    ) -> IconButton {
+        let position = bookmark.position;
+        let label = bookmark
+            .label
+            .clone()
+            .unwrap_or_else(|| SharedString::from("Bookmark"));
+        let focus_handle = self.focus_handle.clone();
+
+        IconButton::new(("bookmark_indicator", row.0 as usize), IconName::Bookmark)
+            .icon_size(IconSize::XSmall)
+            .size(ButtonSize::None)
+            .icon_color(Color::Accent)
+            .style(ButtonStyle::Transparent)
+            .on_click(cx.listener(move |editor, _, window, cx| {
+                window.focus(&editor.focus_handle, cx);
+                if let Some(bookmark_store) = editor.bookmark_store.as_ref() {
+                    bookmark_store.update(cx, |bookmark_store, cx| {
+                        bookmark_store.remove_bookmark(buffer.clone(), position, cx);
+                    });
+                }
+            }))
+            .tooltip(move |_window, cx| {
+                Tooltip::with_meta_in(
+                    label.clone(),
+                    Some(&ToggleBookmark),
+                    "Click to remove",
+                    &focus_handle,
+                    cx,
+                )
+            })
+    }
+}
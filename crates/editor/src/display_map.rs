@@ -156,6 +156,7 @@ pub enum HighlightKey {
     DocumentHighlightWrite,
     EditPredictionHighlight,
     Editor,
+    FilterLinesView,
     HighlightOnYank,
     HighlightsTreeView(usize),
     HoverState,
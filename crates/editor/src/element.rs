@@ -34,7 +34,7 @@ use crate::{
     },
 };
 use buffer_diff::{DiffHunkStatus, DiffHunkStatusKind};
-use collections::{BTreeMap, HashMap};
+use collections::{BTreeMap, HashMap, HashSet};
 use feature_flags::{DiffReviewFeatureFlag, FeatureFlagAppExt as _};
 use file_icons::FileIcons;
 use git::{Oid, blame::BlameEntry, commit::ParsedCommitMessage, status::FileStatus};
@@ -52,7 +52,7 @@ use gpui::{
     transparent_black,
 };
 use itertools::Itertools;
-use language::{IndentGuideSettings, language_settings::ShowWhitespaceSetting};
+use language::{Buffer, IndentGuideSettings, language_settings::ShowWhitespaceSetting};
 use markdown::Markdown;
 use multi_buffer::{
     Anchor, ExcerptId, ExcerptInfo, ExpandExcerptDirection, ExpandInfo, MultiBufferPoint,
@@ -63,6 +63,7 @@ use edit_prediction_types::EditPredictionGranularity;
 
 use project::{
     DisableAiSettings, Entry, ProjectPath,
+    bookmark_store::Bookmark,
     debugger::breakpoint_store::{Breakpoint, BreakpointSessionState},
     project_settings::ProjectSettings,
 };
@@ -649,6 +650,9 @@ impl EditorElement {
         register_action(editor, window, Editor::edit_log_breakpoint);
         register_action(editor, window, Editor::enable_breakpoint);
         register_action(editor, window, Editor::disable_breakpoint);
+        register_action(editor, window, Editor::toggle_bookmark);
+        register_action(editor, window, Editor::go_to_next_bookmark);
+        register_action(editor, window, Editor::go_to_previous_bookmark);
         register_action(editor, window, Editor::toggle_read_only);
         if editor.read(cx).enable_wrap_selections_in_tag(cx) {
             register_action(editor, window, Editor::wrap_selections_in_tag);
@@ -3189,6 +3193,78 @@ impl EditorElement {
         })
     }
 
+    fn layout_bookmarks(
+        &self,
+        line_height: Pixels,
+        range: Range<DisplayRow>,
+        scroll_position: gpui::Point<ScrollOffset>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        snapshot: &EditorSnapshot,
+        bookmarks: HashMap<DisplayRow, (Entity<Buffer>, Bookmark)>,
+        occupied_rows: &HashSet<DisplayRow>,
+        show_runnables: bool,
+        row_infos: &[RowInfo],
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<AnyElement> {
+        if self.split_side == Some(SplitSide::Left) {
+            return Vec::new();
+        }
+
+        self.editor.update(cx, |editor, cx| {
+            bookmarks
+                .into_iter()
+                .filter_map(|(display_row, (buffer, bookmark))| {
+                    if range.start > display_row
+                        || range.end < display_row
+                        || occupied_rows.contains(&display_row)
+                    {
+                        return None;
+                    }
+
+                    let row_info =
+                        row_infos.get((display_row.0.saturating_sub(range.start.0)) as usize);
+                    if row_info.is_some_and(|row_info| {
+                        row_info.expand_info.is_some()
+                            || row_info
+                                .diff_status
+                                .is_some_and(|status| status.is_deleted())
+                    }) {
+                        return None;
+                    }
+                    if show_runnables
+                        && let Some(row_info) = row_info
+                        && let Some(buffer_id) = row_info.buffer_id
+                        && let Some(buffer_row) = row_info.buffer_row
+                        && editor.tasks.contains_key(&(buffer_id, buffer_row))
+                    {
+                        return None;
+                    }
+
+                    let row =
+                        MultiBufferRow(DisplayPoint::new(display_row, 0).to_point(snapshot).row);
+                    if snapshot.is_line_folded(row) {
+                        return None;
+                    }
+
+                    let button = editor.render_bookmark(display_row, buffer, &bookmark, cx);
+                    let button = prepaint_gutter_button(
+                        button.into_any_element(),
+                        display_row,
+                        line_height,
+                        gutter_dimensions,
+                        scroll_position,
+                        gutter_hitbox,
+                        window,
+                        cx,
+                    );
+                    Some(button)
+                })
+                .collect_vec()
+        })
+    }
+
     fn should_render_diff_review_button(
         &self,
         range: Range<DisplayRow>,
@@ -6419,6 +6495,10 @@ impl EditorElement {
                 }
             });
 
+            for bookmark in layout.bookmarks.iter_mut() {
+                bookmark.paint(window, cx);
+            }
+
             for breakpoint in layout.breakpoints.iter_mut() {
                 breakpoint.paint(window, cx);
             }
@@ -10127,6 +10207,9 @@ impl Element for EditorElement {
                     let mut breakpoint_rows = self.editor.update(cx, |editor, cx| {
                         editor.active_breakpoints(start_row..end_row, window, cx)
                     });
+                    let bookmark_rows = self.editor.update(cx, |editor, cx| {
+                        editor.active_bookmarks(start_row..end_row, window, cx)
+                    });
                     for (display_row, (_, bp, state)) in &breakpoint_rows {
                         if bp.is_enabled() && state.is_none_or(|s| s.verified) {
                             active_rows.entry(*display_row).or_default().breakpoint = true;
@@ -10758,6 +10841,11 @@ impl Element for EditorElement {
                         cx,
                     );
 
+                    // Bookmarks share the gutter slot used by breakpoints and run indicators,
+                    // so they're only shown on rows where neither of those is displayed.
+                    let occupied_gutter_rows =
+                        breakpoint_rows.keys().copied().collect::<HashSet<_>>();
+
                     let test_indicators = if gutter_settings.runnables {
                         self.layout_run_indicators(
                             line_height,
@@ -10795,6 +10883,25 @@ impl Element for EditorElement {
                         Vec::new()
                     };
 
+                    let bookmarks = if show_breakpoints || gutter_settings.runnables {
+                        self.layout_bookmarks(
+                            line_height,
+                            start_row..end_row,
+                            scroll_position,
+                            &gutter_dimensions,
+                            &gutter_hitbox,
+                            &snapshot,
+                            bookmark_rows,
+                            &occupied_gutter_rows,
+                            gutter_settings.runnables,
+                            &row_infos,
+                            window,
+                            cx,
+                        )
+                    } else {
+                        Vec::new()
+                    };
+
                     let git_gutter_width = Self::gutter_strip_width(line_height)
                         + gutter_dimensions
                             .git_blame_entries_width
@@ -11084,6 +11191,7 @@ impl Element for EditorElement {
                         mouse_context_menu,
                         test_indicators,
                         breakpoints,
+                        bookmarks,
                         diff_review_button,
                         crease_toggles,
                         crease_trailers,
@@ -11271,6 +11379,7 @@ pub struct EditorLayout {
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     test_indicators: Vec<AnyElement>,
     breakpoints: Vec<AnyElement>,
+    bookmarks: Vec<AnyElement>,
     diff_review_button: Option<AnyElement>,
     crease_toggles: Vec<Option<AnyElement>>,
     expand_toggles: Vec<Option<(AnyElement, gpui::Point<Pixels>)>>,
@@ -0,0 +1,312 @@
+//! Runs the editor commands that extensions provide from the command palette.
+
+use std::{ops::Range, sync::Arc};
+
+use anyhow::{Context as _, Result, bail};
+use collections::HashMap;
+use command_palette_hooks::CommandPaletteCommands;
+use editor::{Editor, MultiBufferOffset, SelectionEffects};
+use extension::{
+    EditorCommandBuffer, EditorCommandEdit, EditorCommandOutput, Extension,
+    ExtensionEditorCommandProxy, ExtensionHostProxy,
+};
+use gpui::{Action, App, Context, Global, Window};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use workspace::{Toast, notifications::NotificationId};
+
+/// Runs an editor command provided by an extension on the active buffer.
+#[derive(Clone, Debug, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = extensions)]
+#[serde(deny_unknown_fields)]
+pub struct RunEditorCommand {
+    /// The name of the command, as declared in the extension manifest.
+    pub command: String,
+}
+
+pub fn init(extension_host_proxy: Arc<ExtensionHostProxy>, cx: &mut App) {
+    extension_host_proxy.register_editor_command_proxy(EditorCommandRegistryProxy);
+
+    cx.observe_new(|editor: &mut Editor, _, cx: &mut Context<Editor>| {
+        if !editor.mode().is_full() {
+            return;
+        }
+        let editor_handle = cx.weak_entity();
+        editor
+            .register_action(move |action: &RunEditorCommand, window, cx| {
+                editor_handle
+                    .update(cx, |editor, cx| {
+                        run_editor_command(editor, action, window, cx)
+                    })
+                    .ok();
+            })
+            .detach();
+    })
+    .detach();
+}
+
+/// The editor commands of the installed extensions, by command name.
+#[derive(Default)]
+struct EditorCommandRegistry {
+    commands: HashMap<Arc<str>, RegisteredCommand>,
+}
+
+impl Global for EditorCommandRegistry {}
+
+struct RegisteredCommand {
+    extension: Arc<dyn Extension>,
+    /// The name the command is listed under in the command palette.
+    palette_name: String,
+}
+
+struct EditorCommandRegistryProxy;
+
+impl ExtensionEditorCommandProxy for EditorCommandRegistryProxy {
+    fn register_editor_command(
+        &self,
+        extension: Arc<dyn Extension>,
+        command_name: Arc<str>,
+        description: String,
+        cx: &mut App,
+    ) {
+        let palette_name = format!(
+            "{}: {description}",
+            extension.manifest().name.to_lowercase()
+        );
+        let commands = CommandPaletteCommands::global_mut(cx);
+        commands.add_command(
+            palette_name.clone(),
+            Box::new(RunEditorCommand {
+                command: command_name.to_string(),
+            }),
+        );
+        let previous = cx
+            .default_global::<EditorCommandRegistry>()
+            .commands
+            .insert(
+                command_name,
+                RegisteredCommand {
+                    extension,
+                    palette_name: palette_name.clone(),
+                },
+            );
+        if let Some(previous) = previous
+            && previous.palette_name != palette_name
+        {
+            CommandPaletteCommands::global_mut(cx).remove_command(&previous.palette_name);
+        }
+    }
+
+    fn unregister_editor_command(&self, command_name: Arc<str>, cx: &mut App) {
+        let removed = cx
+            .default_global::<EditorCommandRegistry>()
+            .commands
+            .remove(&command_name);
+        if let Some(removed) = removed {
+            CommandPaletteCommands::global_mut(cx).remove_command(&removed.palette_name);
+        }
+    }
+}
+
+fn run_editor_command(
+    editor: &mut Editor,
+    action: &RunEditorCommand,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let command_name = action.command.as_str();
+    let Some(extension) = cx
+        .try_global::<EditorCommandRegistry>()
+        .and_then(|registry| registry.commands.get(command_name))
+        .map(|command| command.extension.clone())
+    else {
+        show_error(
+            editor,
+            anyhow::anyhow!("No extension provides the editor command `{command_name}`"),
+            cx,
+        );
+        return;
+    };
+    let (buffer, anchor_snapshot) = match command_buffer(editor, cx) {
+        Ok(buffer) => buffer,
+        Err(e) => {
+            show_error(editor, e, cx);
+            return;
+        }
+    };
+
+    let command_name: Arc<str> = command_name.into();
+    let buffer_text = buffer.text.clone();
+    cx.spawn_in(window, async move |editor, cx| {
+        let result = extension
+            .run_editor_command(command_name.clone(), buffer)
+            .await
+            .and_then(|output| {
+                validate_edits(&buffer_text, &output.edits)?;
+                Ok(output)
+            })
+            .with_context(|| format!("Failed to run the editor command `{command_name}`"));
+        editor
+            .update_in(cx, |editor, window, cx| match result {
+                Ok(output) => apply_output(editor, &anchor_snapshot, output, window, cx),
+                Err(e) => show_error(editor, e, cx),
+            })
+            .ok();
+    })
+    .detach();
+}
+
+/// Collects what the command can read from the editor's buffer,
+/// along with the snapshot that the command's offsets refer to.
+fn command_buffer(
+    editor: &mut Editor,
+    cx: &mut Context<Editor>,
+) -> Result<(EditorCommandBuffer, editor::MultiBufferSnapshot)> {
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        bail!("Editor commands only run in editors with a single buffer");
+    };
+    if editor.read_only(cx) {
+        bail!("Editor commands can't run in read-only editors");
+    }
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    if snapshot.len().0 > u32::MAX as usize {
+        bail!("The buffer is too large for editor commands");
+    }
+    let selections = editor
+        .selections
+        .all::<MultiBufferOffset>(&editor.display_snapshot(cx))
+        .into_iter()
+        .map(|selection| selection.start.0..selection.end.0)
+        .collect();
+    let buffer = buffer.read(cx);
+    let command_buffer = EditorCommandBuffer {
+        text: snapshot.text(),
+        selections,
+        language: buffer
+            .language()
+            .map(|language| language.name().to_string()),
+        path: buffer
+            .file()
+            .and_then(|file| file.as_local())
+            .map(|file| file.abs_path(cx).to_string_lossy().into_owned()),
+    };
+    Ok((command_buffer, snapshot))
+}
+
+/// Checks that the edits are within the text, on character boundaries, and don't overlap.
+fn validate_edits(text: &str, edits: &[EditorCommandEdit]) -> Result<()> {
+    let mut ranges = edits
+        .iter()
+        .map(|edit| edit.range.clone())
+        .collect::<Vec<_>>();
+    ranges.sort_by_key(|range| (range.start, range.end));
+    for range in &ranges {
+        validate_range(text, range)?;
+    }
+    for pair in ranges.windows(2) {
+        if pair[0].end > pair[1].start {
+            bail!("Edits {:?} and {:?} overlap", pair[0], pair[1]);
+        }
+    }
+    Ok(())
+}
+
+fn validate_range(text: &str, range: &Range<usize>) -> Result<()> {
+    if range.start > range.end || range.end > text.len() {
+        bail!("Range {range:?} is out of bounds");
+    }
+    if !text.is_char_boundary(range.start) || !text.is_char_boundary(range.end) {
+        bail!("Range {range:?} is not on character boundaries");
+    }
+    Ok(())
+}
+
+/// Applies the edits and selections in a single transaction, so that they're undone together.
+///
+/// The edits refer to the buffer as it was when the command started, so they're anchored to that
+/// snapshot to account for the changes made while the command ran.
+fn apply_output(
+    editor: &mut Editor,
+    snapshot: &editor::MultiBufferSnapshot,
+    output: EditorCommandOutput,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let edits = output
+        .edits
+        .into_iter()
+        .map(|edit| {
+            let start = snapshot.anchor_after(MultiBufferOffset(edit.range.start));
+            let end = snapshot.anchor_before(MultiBufferOffset(edit.range.end));
+            (start..end, edit.new_text)
+        })
+        .collect::<Vec<_>>();
+    editor.transact(window, cx, |editor, window, cx| {
+        editor.edit(edits, cx);
+        let Some(selections) = output
+            .selections
+            .filter(|selections| !selections.is_empty())
+        else {
+            return;
+        };
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let selections = selections
+            .into_iter()
+            .map(|range| {
+                let start = snapshot.clip_offset(
+                    MultiBufferOffset(range.start.min(snapshot.len().0)),
+                    editor::Bias::Left,
+                );
+                let end = snapshot.clip_offset(
+                    MultiBufferOffset(range.end.min(snapshot.len().0)),
+                    editor::Bias::Right,
+                );
+                start..end.max(start)
+            })
+            .collect::<Vec<_>>();
+        editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+            s.select_ranges(selections)
+        });
+    });
+}
+
+fn show_error(editor: &Editor, error: anyhow::Error, cx: &mut Context<Editor>) {
+    log::error!("{error:#}");
+    if let Some(workspace) = editor.workspace() {
+        workspace.update(cx, |workspace, cx| {
+            let id = NotificationId::unique::<RunEditorCommand>();
+            workspace.show_toast(Toast::new(id, format!("{error:#}")), cx);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(range: Range<usize>) -> EditorCommandEdit {
+        EditorCommandEdit {
+            range,
+            new_text: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_validate_edits() {
+        let text = "let café = 1;";
+        assert!(validate_edits(text, &[edit(11..12), edit(0..3)]).is_ok());
+        assert!(validate_edits(text, &[edit(3..3), edit(3..3)]).is_ok());
+        assert!(
+            validate_edits(text, &[edit(0..4), edit(3..5)]).is_err(),
+            "Overlapping edits should be rejected"
+        );
+        assert!(
+            validate_edits(text, &[edit(10..20)]).is_err(),
+            "Edits past the end of the text should be rejected"
+        );
+        assert!(
+            validate_edits(text, &[edit(8..9)]).is_err(),
+            "Edits inside a multibyte character should be rejected"
+        );
+    }
+}
@@ -328,12 +328,19 @@ impl ExampleContext {
                         "{}Bug: Tool confirmation should not be required in eval",
                         log_prefix
                     ),
+                    ThreadEvent::ToolCallElicitation(_) => panic!(
+                        "{}Bug: Tool elicitation should not be required in eval",
+                        log_prefix
+                    ),
                     ThreadEvent::SubagentSpawned(session) => {
                         println!("{log_prefix} Got subagent spawn: {session:?}");
                     }
                     ThreadEvent::Retry(status) => {
                         println!("{log_prefix} Got retry: {status:?}");
                     }
+                    ThreadEvent::Compacted(_) => {
+                        println!("{log_prefix} Thread compacted");
+                    }
                     ThreadEvent::Stop(stop_reason) => match stop_reason {
                         acp::StopReason::EndTurn => {}
                         acp::StopReason::MaxTokens => {
@@ -302,6 +302,7 @@ impl ExampleInstance {
                         root_name,
                         abs_path,
                         rules_file: None,
+                        nested_rules_files: Vec::new(),
                     }
                 }).collect::<Vec<_>>();
                 let project_context = cx.new(|_cx| ProjectContext::new(worktrees, vec![]));
@@ -654,6 +655,7 @@ impl agent::ThreadEnvironment for EvalThreadEnvironment {
     fn create_terminal(
         &self,
         command: String,
+        env: Vec<acp::EnvVariable>,
         cwd: Option<PathBuf>,
         output_byte_limit: Option<u64>,
         cx: &mut AsyncApp,
@@ -663,8 +665,9 @@ impl agent::ThreadEnvironment for EvalThreadEnvironment {
             let language_registry =
                 project.read_with(cx, |project, _cx| project.languages().clone());
             let id = acp::TerminalId::new(uuid::Uuid::new_v4().to_string());
+            let env = env.into_iter().map(|var| (var.name, var.value)).collect();
             let terminal =
-                acp_thread::create_terminal_entity(command, &[], vec![], cwd.clone(), &project, cx)
+                acp_thread::create_terminal_entity(command, &[], env, cwd.clone(), &project, cx)
                     .await?;
             let terminal = cx.new(|cx| {
                 acp_thread::Terminal::new(
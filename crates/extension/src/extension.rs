@@ -15,7 +15,7 @@ use fs::normalize_path;
 use gpui::{App, Task};
 use language::LanguageName;
 use semver::Version;
-use task::{SpawnInTerminal, ZedDebugConfig};
+use task::{SpawnInTerminal, TaskTemplates, TaskVariables, ZedDebugConfig};
 use util::rel_path::RelPath;
 
 pub use crate::capabilities::*;
@@ -168,6 +168,52 @@ pub trait Extension: Send + Sync + 'static {
         locator_name: String,
         config: SpawnInTerminal,
     ) -> Result<DebugRequest>;
+
+    /// Returns the task templates that the task provider offers for the worktree.
+    async fn task_templates(
+        &self,
+        provider_name: Arc<str>,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<TaskTemplates>;
+
+    /// Returns the custom task variables of the task provider, given the variables
+    /// of the location that tasks are resolved for.
+    async fn task_variables(
+        &self,
+        provider_name: Arc<str>,
+        variables: TaskVariables,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<TaskVariables>;
+
+    /// Runs the editor command on the buffer, returning the changes to make to it.
+    async fn run_editor_command(
+        &self,
+        command_name: Arc<str>,
+        buffer: EditorCommandBuffer,
+    ) -> Result<EditorCommandOutput>;
+
+    /// Returns how the formatter formats the buffer.
+    async fn format_buffer(
+        &self,
+        formatter_name: Arc<str>,
+        buffer: BufferContents,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<FormatterOutput>;
+
+    /// Returns how the linter checks the buffer.
+    async fn lint_buffer(
+        &self,
+        linter_name: Arc<str>,
+        buffer: BufferContents,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<LinterOutput>;
+
+    /// Returns the diagnostics that the linter's command reported in its output.
+    async fn linter_diagnostics(
+        &self,
+        linter_name: Arc<str>,
+        output: LinterCommandOutput,
+    ) -> Result<Vec<LintDiagnostic>>;
 }
 
 pub fn parse_wasm_extension_version(extension_id: &str, wasm_bytes: &[u8]) -> Result<Version> {
@@ -32,6 +32,9 @@ pub struct ExtensionHostProxy {
     slash_command_proxy: RwLock<Option<Arc<dyn ExtensionSlashCommandProxy>>>,
     context_server_proxy: RwLock<Option<Arc<dyn ExtensionContextServerProxy>>>,
     debug_adapter_provider_proxy: RwLock<Option<Arc<dyn ExtensionDebugAdapterProviderProxy>>>,
+    task_provider_proxy: RwLock<Option<Arc<dyn ExtensionTaskProviderProxy>>>,
+    editor_command_proxy: RwLock<Option<Arc<dyn ExtensionEditorCommandProxy>>>,
+    formatter_proxy: RwLock<Option<Arc<dyn ExtensionFormatterProxy>>>,
     language_model_provider_proxy: RwLock<Option<Arc<dyn ExtensionLanguageModelProviderProxy>>>,
 }
 
@@ -58,6 +61,9 @@ impl ExtensionHostProxy {
             slash_command_proxy: RwLock::default(),
             context_server_proxy: RwLock::default(),
             debug_adapter_provider_proxy: RwLock::default(),
+            task_provider_proxy: RwLock::default(),
+            editor_command_proxy: RwLock::default(),
+            formatter_proxy: RwLock::default(),
             language_model_provider_proxy: RwLock::default(),
         }
     }
@@ -96,6 +102,18 @@ impl ExtensionHostProxy {
             .replace(Arc::new(proxy));
     }
 
+    pub fn register_task_provider_proxy(&self, proxy: impl ExtensionTaskProviderProxy) {
+        self.task_provider_proxy.write().replace(Arc::new(proxy));
+    }
+
+    pub fn register_editor_command_proxy(&self, proxy: impl ExtensionEditorCommandProxy) {
+        self.editor_command_proxy.write().replace(Arc::new(proxy));
+    }
+
+    pub fn register_formatter_proxy(&self, proxy: impl ExtensionFormatterProxy) {
+        self.formatter_proxy.write().replace(Arc::new(proxy));
+    }
+
     pub fn register_language_model_provider_proxy(
         &self,
         proxy: impl ExtensionLanguageModelProviderProxy,
@@ -463,6 +481,142 @@ impl ExtensionDebugAdapterProviderProxy for ExtensionHostProxy {
     }
 }
 
+pub trait ExtensionTaskProviderProxy: Send + Sync + 'static {
+    fn register_task_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_name: Arc<str>,
+        cx: &mut App,
+    );
+
+    fn unregister_task_provider(&self, provider_name: Arc<str>, cx: &mut App);
+}
+
+impl ExtensionTaskProviderProxy for ExtensionHostProxy {
+    fn register_task_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_name: Arc<str>,
+        cx: &mut App,
+    ) {
+        let Some(proxy) = self.task_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_task_provider(extension, provider_name, cx)
+    }
+
+    fn unregister_task_provider(&self, provider_name: Arc<str>, cx: &mut App) {
+        let Some(proxy) = self.task_provider_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_task_provider(provider_name, cx)
+    }
+}
+
+pub trait ExtensionEditorCommandProxy: Send + Sync + 'static {
+    fn register_editor_command(
+        &self,
+        extension: Arc<dyn Extension>,
+        command_name: Arc<str>,
+        description: String,
+        cx: &mut App,
+    );
+
+    fn unregister_editor_command(&self, command_name: Arc<str>, cx: &mut App);
+}
+
+impl ExtensionEditorCommandProxy for ExtensionHostProxy {
+    fn register_editor_command(
+        &self,
+        extension: Arc<dyn Extension>,
+        command_name: Arc<str>,
+        description: String,
+        cx: &mut App,
+    ) {
+        let Some(proxy) = self.editor_command_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_editor_command(extension, command_name, description, cx)
+    }
+
+    fn unregister_editor_command(&self, command_name: Arc<str>, cx: &mut App) {
+        let Some(proxy) = self.editor_command_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_editor_command(command_name, cx)
+    }
+}
+
+pub trait ExtensionFormatterProxy: Send + Sync + 'static {
+    fn register_formatter(
+        &self,
+        extension: Arc<dyn Extension>,
+        formatter_name: Arc<str>,
+        cx: &mut App,
+    );
+
+    fn unregister_formatter(&self, formatter_name: Arc<str>, cx: &mut App);
+
+    fn register_linter(
+        &self,
+        extension: Arc<dyn Extension>,
+        linter_name: Arc<str>,
+        languages: Vec<LanguageName>,
+        cx: &mut App,
+    );
+
+    fn unregister_linter(&self, linter_name: Arc<str>, cx: &mut App);
+}
+
+impl ExtensionFormatterProxy for ExtensionHostProxy {
+    fn register_formatter(
+        &self,
+        extension: Arc<dyn Extension>,
+        formatter_name: Arc<str>,
+        cx: &mut App,
+    ) {
+        let Some(proxy) = self.formatter_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_formatter(extension, formatter_name, cx)
+    }
+
+    fn unregister_formatter(&self, formatter_name: Arc<str>, cx: &mut App) {
+        let Some(proxy) = self.formatter_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_formatter(formatter_name, cx)
+    }
+
+    fn register_linter(
+        &self,
+        extension: Arc<dyn Extension>,
+        linter_name: Arc<str>,
+        languages: Vec<LanguageName>,
+        cx: &mut App,
+    ) {
+        let Some(proxy) = self.formatter_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.register_linter(extension, linter_name, languages, cx)
+    }
+
+    fn unregister_linter(&self, linter_name: Arc<str>, cx: &mut App) {
+        let Some(proxy) = self.formatter_proxy.read().clone() else {
+            return;
+        };
+
+        proxy.unregister_linter(linter_name, cx)
+    }
+}
+
 pub trait ExtensionLanguageModelProviderProxy: Send + Sync + 'static {
     fn register_language_model_provider(
         &self,
@@ -118,6 +118,14 @@ pub struct ExtensionManifest {
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub debug_locators: BTreeMap<Arc<str>, DebugLocatorManifestEntry>,
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub task_providers: BTreeMap<Arc<str>, TaskProviderManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub editor_commands: BTreeMap<Arc<str>, EditorCommandManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub formatters: BTreeMap<Arc<str>, FormatterManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub linters: BTreeMap<Arc<str>, LinterManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub language_model_providers: BTreeMap<Arc<str>, LanguageModelProviderManifestEntry>,
 }
 
@@ -356,6 +364,25 @@ pub struct DebugAdapterManifestEntry {
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct DebugLocatorManifestEntry {}
 
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct TaskProviderManifestEntry {}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct EditorCommandManifestEntry {
+    /// The description of the command, shown in the command palette.
+    pub description: String,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct FormatterManifestEntry {}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct LinterManifestEntry {
+    /// The languages whose buffers the linter checks.
+    #[serde(default)]
+    pub languages: Vec<LanguageName>,
+}
+
 /// Manifest entry for a language model provider.
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct LanguageModelProviderManifestEntry {
@@ -436,6 +463,10 @@ fn manifest_from_old_manifest(
         capabilities: Vec::new(),
         debug_adapters: Default::default(),
         debug_locators: Default::default(),
+        task_providers: Default::default(),
+        editor_commands: Default::default(),
+        formatters: Default::default(),
+        linters: Default::default(),
         language_model_providers: Default::default(),
     }
 }
@@ -470,6 +501,10 @@ mod tests {
             capabilities: vec![],
             debug_adapters: Default::default(),
             debug_locators: Default::default(),
+            task_providers: Default::default(),
+            editor_commands: Default::default(),
+            formatters: Default::default(),
+            linters: Default::default(),
             language_model_providers: BTreeMap::default(),
         }
     }
@@ -1,5 +1,7 @@
 mod context_server;
 mod dap;
+mod editor_command;
+mod formatting;
 mod lsp;
 mod slash_command;
 
@@ -9,6 +11,8 @@ use util::redact::should_redact;
 
 pub use context_server::*;
 pub use dap::*;
+pub use editor_command::*;
+pub use formatting::*;
 pub use lsp::*;
 pub use slash_command::*;
 
@@ -0,0 +1,32 @@
+use std::ops::Range;
+
+/// The buffer that an editor command runs on.
+#[derive(Debug, Clone)]
+pub struct EditorCommandBuffer {
+    /// The text of the buffer.
+    pub text: String,
+    /// The selections in the buffer, as byte ranges into its text.
+    pub selections: Vec<Range<usize>>,
+    /// The name of the buffer's language, if it has one.
+    pub language: Option<String>,
+    /// The absolute path of the buffer's file, if it's a file on the local disk.
+    pub path: Option<String>,
+}
+
+/// A replacement of a range of the buffer's text.
+#[derive(Debug, Clone)]
+pub struct EditorCommandEdit {
+    /// The range to replace, as byte offsets into the text the command received.
+    pub range: Range<usize>,
+    /// The text to replace the range with.
+    pub new_text: String,
+}
+
+/// The changes an editor command makes to the buffer.
+#[derive(Debug, Clone, Default)]
+pub struct EditorCommandOutput {
+    /// The edits to apply to the buffer, as a single undoable transaction.
+    pub edits: Vec<EditorCommandEdit>,
+    /// The selections to set once the edits are applied, as byte ranges into the edited text.
+    pub selections: Option<Vec<Range<usize>>>,
+}
@@ -0,0 +1,75 @@
+use crate::Command;
+
+/// The buffer that a formatter or linter runs on.
+#[derive(Debug, Clone)]
+pub struct BufferContents {
+    /// The text of the buffer.
+    pub text: String,
+    /// The absolute path of the buffer's file.
+    pub path: String,
+    /// The name of the buffer's language, if it has one.
+    pub language: Option<String>,
+}
+
+/// How a formatter formats a buffer.
+#[derive(Debug)]
+pub enum FormatterOutput {
+    /// Pipe the buffer's text through the command, and replace it with what the command prints.
+    Command(Command),
+    /// Replace the buffer's text with the given text.
+    Text(String),
+}
+
+/// How a linter checks a buffer.
+#[derive(Debug)]
+pub enum LinterOutput {
+    /// Pipe the buffer's text through the command, and let the extension turn its output into
+    /// diagnostics.
+    Command(Command),
+    /// Report the given diagnostics.
+    Diagnostics(Vec<LintDiagnostic>),
+}
+
+/// The output of a linter's command.
+#[derive(Debug, Clone)]
+pub struct LinterCommandOutput {
+    /// The exit code of the command, if it exited normally.
+    pub status: Option<i32>,
+    /// What the command printed to stdout.
+    pub stdout: String,
+    /// What the command printed to stderr.
+    pub stderr: String,
+}
+
+/// A problem that a linter found in a buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintDiagnostic {
+    /// The start of the problem's range.
+    pub start: LintPosition,
+    /// The end of the problem's range.
+    pub end: LintPosition,
+    /// The severity of the problem.
+    pub severity: LintSeverity,
+    /// The description of the problem.
+    pub message: String,
+    /// The code of the rule that reported the problem, if any.
+    pub code: Option<String>,
+}
+
+/// A position in a buffer, as a zero-based line and a zero-based column in UTF-16 code units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LintPosition {
+    /// The line of the position.
+    pub line: u32,
+    /// The column of the position.
+    pub column: u32,
+}
+
+/// The severity of a [`LintDiagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintSeverity {
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
@@ -25,6 +25,11 @@ pub use wit::{
         LaunchRequest, StartDebuggingRequestArguments, StartDebuggingRequestArgumentsRequest,
         TaskTemplate, TcpArguments, TcpArgumentsTemplate, resolve_tcp_template,
     },
+    zed::extension::editor_command::{EditorCommandBuffer, EditorCommandEdit, EditorCommandOutput},
+    zed::extension::formatting::{
+        BufferContents, FormatterOutput, LintDiagnostic, LintPosition, LintSeverity,
+        LinterCommandOutput, LinterOutput,
+    },
     zed::extension::github::{
         GithubRelease, GithubReleaseAsset, GithubReleaseOptions, github_release_by_tag_name,
         latest_github_release,
@@ -259,6 +264,73 @@ pub trait Extension: Send + Sync {
     ) -> Result<DebugRequest, String> {
         Err("`run_dap_locator` not implemented".to_string())
     }
+
+    /// Returns the task templates that the specified task provider offers for the worktree.
+    ///
+    /// They are listed together with the tasks of the worktree's `.zed/tasks.json`.
+    fn task_templates(
+        &mut self,
+        _provider_name: String,
+        _worktree: &Worktree,
+    ) -> Result<Vec<TaskTemplate>, String> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the custom task variables of the specified task provider.
+    ///
+    /// `variables` are the task variables of the location that tasks are resolved for,
+    /// such as `ZED_FILE` or `ZED_ROW`. A returned variable named `NAME` is available
+    /// to tasks as `ZED_CUSTOM_NAME`.
+    fn task_variables(
+        &mut self,
+        _provider_name: String,
+        _variables: EnvVars,
+        _worktree: &Worktree,
+    ) -> Result<EnvVars, String> {
+        Ok(Vec::new())
+    }
+
+    /// Runs the given editor command on the active buffer.
+    ///
+    /// The returned edits are applied to the buffer as a single undoable transaction.
+    fn run_editor_command(
+        &mut self,
+        _command_name: String,
+        _buffer: EditorCommandBuffer,
+    ) -> Result<EditorCommandOutput, String> {
+        Err("`run_editor_command` not implemented".to_string())
+    }
+
+    /// Returns how the specified formatter formats the buffer: either a command
+    /// to pipe the buffer's text through, or the formatted text itself.
+    fn format_buffer(
+        &mut self,
+        _formatter_name: String,
+        _buffer: BufferContents,
+        _worktree: &Worktree,
+    ) -> Result<FormatterOutput, String> {
+        Err("`format_buffer` not implemented".to_string())
+    }
+
+    /// Returns how the specified linter checks the buffer: either a command
+    /// to pipe the buffer's text through, or the diagnostics themselves.
+    fn lint_buffer(
+        &mut self,
+        _linter_name: String,
+        _buffer: BufferContents,
+        _worktree: &Worktree,
+    ) -> Result<LinterOutput, String> {
+        Err("`lint_buffer` not implemented".to_string())
+    }
+
+    /// Returns the diagnostics that the command of the specified linter reported in its output.
+    fn linter_diagnostics(
+        &mut self,
+        _linter_name: String,
+        _output: LinterCommandOutput,
+    ) -> Result<Vec<LintDiagnostic>, String> {
+        Err("`linter_diagnostics` not implemented".to_string())
+    }
 }
 
 /// Registers the provided type as a Zed extension.
@@ -517,6 +589,51 @@ impl wit::Guest for Component {
     ) -> Result<DebugRequest, String> {
         extension().run_dap_locator(locator_name, build_task)
     }
+
+    fn task_templates(
+        provider_name: String,
+        worktree: &Worktree,
+    ) -> Result<Vec<TaskTemplate>, String> {
+        extension().task_templates(provider_name, worktree)
+    }
+
+    fn task_variables(
+        provider_name: String,
+        variables: EnvVars,
+        worktree: &Worktree,
+    ) -> Result<EnvVars, String> {
+        extension().task_variables(provider_name, variables, worktree)
+    }
+
+    fn run_editor_command(
+        command_name: String,
+        buffer: EditorCommandBuffer,
+    ) -> Result<EditorCommandOutput, String> {
+        extension().run_editor_command(command_name, buffer)
+    }
+
+    fn format_buffer(
+        formatter_name: String,
+        buffer: BufferContents,
+        worktree: &Worktree,
+    ) -> Result<FormatterOutput, String> {
+        extension().format_buffer(formatter_name, buffer, worktree)
+    }
+
+    fn lint_buffer(
+        linter_name: String,
+        buffer: BufferContents,
+        worktree: &Worktree,
+    ) -> Result<LinterOutput, String> {
+        extension().lint_buffer(linter_name, buffer, worktree)
+    }
+
+    fn linter_diagnostics(
+        linter_name: String,
+        output: LinterCommandOutput,
+    ) -> Result<Vec<LintDiagnostic>, String> {
+        extension().linter_diagnostics(linter_name, output)
+    }
 }
 
 /// The ID of a language server.
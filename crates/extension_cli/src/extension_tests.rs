@@ -0,0 +1,1044 @@
+//! Runs the fixtures that an extension declares against its compiled Wasm module, in a headless
+//! extension host, so that extensions can be tested in CI without launching Zed.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsStr;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ::fs::{Fs, RealFs};
+use anyhow::{Context as _, Result, anyhow, bail};
+use async_trait::async_trait;
+use extension::extension_builder::CompileExtensionOptions;
+use extension::{
+    CodeLabel, CodeLabelSpan, Completion, CompletionKind, Extension as _, ExtensionHostProxy,
+    ExtensionManifest, SlashCommand, WorktreeDelegate,
+};
+use extension_host::load_plugin_queries;
+use extension_host::wasm_host::{WasmExtension, WasmHost};
+use futures::future::BoxFuture;
+use gpui::AsyncApp;
+use http_client::{AsyncBody, HttpClient, Request, Response, StatusCode, Url, http::HeaderValue};
+use language::{
+    AnnotatedQuery, Language, LanguageConfig, LanguageName, LanguageServerName,
+    annotate_query_captures, check_query_annotations,
+};
+use node_runtime::NodeRuntime;
+use serde::Deserialize;
+use streaming_iterator::StreamingIterator as _;
+use tree_sitter::{Parser, Query, QueryCursor, WasmStore};
+use util::rel_path::RelPath;
+
+#[derive(clap::Args, Debug)]
+pub struct TestArgs {
+    /// The path to the extension directory
+    #[arg(long)]
+    source_dir: PathBuf,
+    /// The path to a directory where build dependencies are downloaded
+    #[arg(long)]
+    scratch_dir: PathBuf,
+    /// The path to the fixtures file, relative to the extension directory
+    #[arg(long, default_value = "tests/fixtures.toml")]
+    fixtures: PathBuf,
+    /// Write the actual output to the snapshot files instead of comparing against them
+    #[arg(long)]
+    update_snapshots: bool,
+}
+
+/// The fixtures declared in an extension's fixtures file.
+///
+/// Paths in the fixtures are relative to the directory containing the fixtures file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Fixtures {
+    #[serde(default)]
+    worktree: WorktreeFixture,
+    #[serde(default)]
+    http: Vec<HttpFixture>,
+    #[serde(default)]
+    language_server_command: Vec<LanguageServerCommandFixture>,
+    #[serde(default)]
+    completion_labels: Vec<CompletionLabelsFixture>,
+    #[serde(default)]
+    slash_command: Vec<SlashCommandFixture>,
+    #[serde(default)]
+    highlights: Vec<HighlightsFixture>,
+    #[serde(default)]
+    query_annotations: Vec<QueryAnnotationsFixture>,
+}
+
+/// The worktree that the extension sees.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorktreeFixture {
+    /// The directory to use as the worktree's root. Defaults to the fixtures directory.
+    root: Option<PathBuf>,
+    /// The shell environment of the worktree.
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+    /// The paths to report for binaries that the extension looks up on the `PATH`.
+    #[serde(default)]
+    which: BTreeMap<String, String>,
+}
+
+/// A response to serve for HTTP requests to a URL. Requests to other URLs get a 404.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HttpFixture {
+    url: String,
+    #[serde(default = "default_http_status")]
+    status: u16,
+    body: Option<String>,
+    /// A file to serve as the response body, instead of `body`.
+    file: Option<PathBuf>,
+}
+
+fn default_http_status() -> u16 {
+    200
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LanguageServerCommandFixture {
+    language_server: String,
+    language: String,
+    /// The expected command, relative to the extension's work directory if it's inside it.
+    command: Option<String>,
+    args: Option<Vec<String>>,
+    /// Environment variables that the command is expected to set.
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CompletionLabelsFixture {
+    language_server: String,
+    completions: Vec<CompletionFixture>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CompletionFixture {
+    label: String,
+    detail: Option<String>,
+    kind: Option<String>,
+    /// The expected text of the label, or none if the extension shouldn't provide one.
+    expected: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SlashCommandFixture {
+    name: String,
+    #[serde(default)]
+    args: Vec<String>,
+    /// The expected output text.
+    output: Option<String>,
+    /// A snapshot file containing the expected output text, instead of `output`.
+    snapshot: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HighlightsFixture {
+    language: String,
+    /// The sample file to highlight.
+    file: PathBuf,
+    /// The snapshot of the captures.
+    /// Defaults to the sample file's path with `.highlights` appended.
+    snapshot: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct QueryAnnotationsFixture {
+    language: String,
+    /// The sample file, annotated with the query's captures.
+    file: PathBuf,
+    /// One of `highlights`, `outline`, `indents` or `textobjects`.
+    /// Defaults to the query that the sample's file name starts with.
+    query: Option<String>,
+}
+
+pub fn run(args: TestArgs) -> Result<()> {
+    let extension_path = args
+        .source_dir
+        .canonicalize()
+        .context("failed to canonicalize source_dir")?;
+    let scratch_dir = args
+        .scratch_dir
+        .canonicalize()
+        .context("failed to canonicalize scratch_dir")?;
+    let fixtures_path = extension_path.join(&args.fixtures);
+    let fixtures_content = fs::read_to_string(&fixtures_path)
+        .with_context(|| format!("failed to read fixtures from {fixtures_path:?}"))?;
+    let fixtures: Fixtures = toml::from_str(&fixtures_content)
+        .with_context(|| format!("failed to parse fixtures in {fixtures_path:?}"))?;
+    let fixtures_dir = fixtures_path
+        .parent()
+        .context("invalid fixtures path")?
+        .to_path_buf();
+    let runner = FixtureRunner {
+        extension_path,
+        scratch_dir,
+        fixtures_dir,
+        update_snapshots: args.update_snapshots,
+    };
+
+    gpui_platform::headless().run(move |cx| {
+        settings::init(cx);
+        release_channel::init(
+            semver::Version::parse(env!("CARGO_PKG_VERSION")).expect("invalid package version"),
+            cx,
+        );
+        gpui_tokio::init(cx);
+
+        cx.spawn(async move |cx| {
+            if let Err(error) = runner.run(fixtures, cx).await {
+                eprintln!("{error:#}");
+                std::process::exit(1);
+            }
+            cx.update(|cx| cx.quit());
+        })
+        .detach();
+    });
+
+    Ok(())
+}
+
+struct FixtureRunner {
+    extension_path: PathBuf,
+    scratch_dir: PathBuf,
+    fixtures_dir: PathBuf,
+    update_snapshots: bool,
+}
+
+impl FixtureRunner {
+    async fn run(&self, fixtures: Fixtures, cx: &mut AsyncApp) -> Result<()> {
+        let fs: Arc<dyn Fs> = Arc::new(RealFs::new(None, cx.background_executor().clone()));
+
+        log::info!("loading extension manifest");
+        let mut manifest = ExtensionManifest::load(fs.clone(), &self.extension_path).await?;
+
+        log::info!("compiling extension");
+        crate::extension_builder(self.scratch_dir.clone())?
+            .compile_extension(
+                &self.extension_path,
+                &mut manifest,
+                CompileExtensionOptions::dev(),
+                fs.clone(),
+            )
+            .await
+            .context("failed to compile extension")?;
+        let manifest = Arc::new(manifest);
+
+        let http_client = Arc::new(FixtureHttpClient::new(&fixtures.http, &self.fixtures_dir)?);
+        let work_dir = self.scratch_dir.join("test-work");
+        fs::remove_dir_all(&work_dir).ok();
+        fs::create_dir_all(&work_dir).context("failed to create work dir")?;
+        let wasm_host = cx.update(|cx| {
+            WasmHost::new(
+                fs.clone(),
+                http_client,
+                NodeRuntime::unavailable(),
+                Arc::new(ExtensionHostProxy::new()),
+                work_dir,
+                cx,
+            )
+        });
+        let extension = if manifest.lib.kind.is_some() {
+            log::info!("loading extension");
+            let wasm_bytes = fs::read(self.extension_path.join("extension.wasm"))
+                .context("failed to read extension.wasm")?;
+            Some(
+                wasm_host
+                    .load_extension(wasm_bytes, &manifest, cx)
+                    .await
+                    .context("failed to load extension")?,
+            )
+        } else {
+            None
+        };
+
+        let worktree: Arc<dyn WorktreeDelegate> = Arc::new(FixtureWorktree {
+            root: match fixtures.worktree.root {
+                Some(root) => self.fixtures_dir.join(root),
+                None => self.fixtures_dir.clone(),
+            },
+            env: fixtures.worktree.env,
+            which: fixtures.worktree.which,
+        });
+
+        let mut results = Vec::new();
+        for fixture in &fixtures.language_server_command {
+            let result = match &extension {
+                Some(extension) => {
+                    check_language_server_command(extension, fixture, worktree.clone()).await
+                }
+                None => Err(anyhow!("the extension has no Wasm module")),
+            };
+            results.push((
+                format!("language server command `{}`", fixture.language_server),
+                result,
+            ));
+        }
+        for fixture in &fixtures.completion_labels {
+            let result = match &extension {
+                Some(extension) => check_completion_labels(extension, fixture).await,
+                None => Err(anyhow!("the extension has no Wasm module")),
+            };
+            results.push((
+                format!("completion labels `{}`", fixture.language_server),
+                result,
+            ));
+        }
+        for fixture in &fixtures.slash_command {
+            let result = match &extension {
+                Some(extension) => {
+                    self.check_slash_command(extension, &manifest, fixture, worktree.clone())
+                        .await
+                }
+                None => Err(anyhow!("the extension has no Wasm module")),
+            };
+            results.push((format!("slash command `{}`", fixture.name), result));
+        }
+        if !fixtures.highlights.is_empty() {
+            for (fixture, result) in fixtures
+                .highlights
+                .iter()
+                .zip(self.check_highlights(&manifest, &fixtures.highlights))
+            {
+                results.push((format!("highlights `{}`", fixture.file.display()), result));
+            }
+        }
+        if !fixtures.query_annotations.is_empty() {
+            for (fixture, result) in fixtures
+                .query_annotations
+                .iter()
+                .zip(self.check_query_annotations(&manifest, &fixtures.query_annotations))
+            {
+                results.push((
+                    format!("query annotations `{}`", fixture.file.display()),
+                    result,
+                ));
+            }
+        }
+
+        report_results(&results)
+    }
+
+    async fn check_slash_command(
+        &self,
+        extension: &WasmExtension,
+        manifest: &ExtensionManifest,
+        fixture: &SlashCommandFixture,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<()> {
+        let entry = manifest
+            .slash_commands
+            .get(fixture.name.as_str())
+            .context("the extension doesn't provide the slash command")?;
+        let command = SlashCommand {
+            name: fixture.name.clone(),
+            description: entry.description.clone(),
+            tooltip_text: String::new(),
+            requires_argument: entry.requires_argument,
+        };
+        let output = extension
+            .run_slash_command(command, fixture.args.clone(), Some(worktree))
+            .await?;
+        match (&fixture.output, &fixture.snapshot) {
+            (Some(expected), None) => compare_text(expected, &output.text),
+            (None, Some(snapshot)) => self.check_snapshot(snapshot, &output.text),
+            _ => bail!("exactly one of `output` and `snapshot` must be set"),
+        }
+    }
+
+    /// Checks the captures of each language's highlights query against the snapshots.
+    fn check_highlights(
+        &self,
+        manifest: &ExtensionManifest,
+        fixtures: &[HighlightsFixture],
+    ) -> Vec<Result<()>> {
+        let mut highlighter = match self.highlighter(manifest) {
+            Ok(highlighter) => highlighter,
+            Err(error) => {
+                let error = format!("{error:#}");
+                return fixtures.iter().map(|_| Err(anyhow!("{error}"))).collect();
+            }
+        };
+        fixtures
+            .iter()
+            .map(|fixture| {
+                let file = self.fixtures_dir.join(&fixture.file);
+                let text = fs::read_to_string(&file)
+                    .with_context(|| format!("failed to read {file:?}"))?;
+                let captures = highlighter.captures(&fixture.language, &text)?;
+                let snapshot = fixture.snapshot.clone().unwrap_or_else(|| {
+                    let mut snapshot = fixture.file.clone().into_os_string();
+                    snapshot.push(".highlights");
+                    snapshot.into()
+                });
+                self.check_snapshot(&snapshot, &captures)
+            })
+            .collect()
+    }
+
+    fn highlighter(&self, manifest: &ExtensionManifest) -> Result<Highlighter> {
+        let engine = wasmtime::Engine::default();
+        let mut wasm_store = WasmStore::new(&engine)?;
+        let grammars = crate::test_grammars(manifest, &self.extension_path, &mut wasm_store)?;
+
+        let mut languages = HashMap::default();
+        for relative_language_dir in &manifest.languages {
+            let language_dir = self.extension_path.join(relative_language_dir);
+            let config_content = fs::read_to_string(language_dir.join("config.toml"))?;
+            let config: LanguageConfig = toml::from_str(&config_content)?;
+            let Some(grammar) = config
+                .grammar
+                .as_ref()
+                .and_then(|name| grammars.get(name.as_ref()))
+            else {
+                continue;
+            };
+            let highlights_path = language_dir.join("highlights.scm");
+            let query = if highlights_path.exists() {
+                let query_source = fs::read_to_string(&highlights_path)?;
+                Some(Query::new(grammar, &query_source)?)
+            } else {
+                None
+            };
+            languages.insert(config.name, (grammar.clone(), query));
+        }
+
+        let mut parser = Parser::new();
+        parser.set_wasm_store(wasm_store)?;
+        Ok(Highlighter { parser, languages })
+    }
+
+    /// Checks the annotations in each sample file against the captures of the language's query,
+    /// or rewrites the annotations if snapshots are being updated.
+    fn check_query_annotations(
+        &self,
+        manifest: &ExtensionManifest,
+        fixtures: &[QueryAnnotationsFixture],
+    ) -> Vec<Result<()>> {
+        let languages = match self.languages(manifest) {
+            Ok(languages) => languages,
+            Err(error) => {
+                let error = format!("{error:#}");
+                return fixtures.iter().map(|_| Err(anyhow!("{error}"))).collect();
+            }
+        };
+        fixtures
+            .iter()
+            .map(|fixture| {
+                let language = languages
+                    .get(&LanguageName::new(&fixture.language))
+                    .with_context(|| {
+                        format!("the extension has no language `{}`", fixture.language)
+                    })?;
+                let query = match &fixture.query {
+                    Some(name) => AnnotatedQuery::ALL
+                        .into_iter()
+                        .find(|query| query.name() == name)
+                        .with_context(|| format!("unknown query `{name}`"))?,
+                    None => fixture
+                        .file
+                        .file_name()
+                        .and_then(OsStr::to_str)
+                        .and_then(AnnotatedQuery::for_file_name)
+                        .context("the sample's file name doesn't start with a query's name")?,
+                };
+                let file = self.fixtures_dir.join(&fixture.file);
+                let text = fs::read_to_string(&file)
+                    .with_context(|| format!("failed to read {file:?}"))?;
+                if self.update_snapshots {
+                    let annotated_text = annotate_query_captures(language, None, query, &text)?;
+                    fs::write(&file, annotated_text)
+                        .with_context(|| format!("failed to write {file:?}"))
+                } else {
+                    check_query_annotations(language, None, query, &text)
+                }
+            })
+            .collect()
+    }
+
+    /// Loads the extension's languages with their queries, using the grammars compiled
+    /// for the shared parsers.
+    fn languages(
+        &self,
+        manifest: &ExtensionManifest,
+    ) -> Result<HashMap<LanguageName, Arc<Language>>> {
+        let grammars = language::with_parser(|parser| -> Result<_> {
+            let mut wasm_store = parser
+                .take_wasm_store()
+                .context("the parser has no Wasm store")?;
+            let grammars = crate::test_grammars(manifest, &self.extension_path, &mut wasm_store);
+            parser.set_wasm_store(wasm_store)?;
+            grammars
+        })?;
+
+        let mut languages = HashMap::default();
+        for relative_language_dir in &manifest.languages {
+            let language_dir = self.extension_path.join(relative_language_dir);
+            let config_content = fs::read_to_string(language_dir.join("config.toml"))?;
+            let config: LanguageConfig = toml::from_str(&config_content)?;
+            let Some(grammar) = config
+                .grammar
+                .as_ref()
+                .and_then(|name| grammars.get(name.as_ref()))
+                .cloned()
+            else {
+                continue;
+            };
+            let name = config.name.clone();
+            let language = Language::new(config, Some(grammar))
+                .with_queries(load_plugin_queries(&language_dir))
+                .with_context(|| format!("failed to load the queries of `{name}`"))?;
+            languages.insert(name, Arc::new(language));
+        }
+        Ok(languages)
+    }
+
+    /// Compares the actual output against the snapshot, or updates the snapshot if requested.
+    fn check_snapshot(&self, snapshot: &Path, actual: &str) -> Result<()> {
+        let snapshot_path = self.fixtures_dir.join(snapshot);
+        if self.update_snapshots {
+            return fs::write(&snapshot_path, actual)
+                .with_context(|| format!("failed to write snapshot {snapshot_path:?}"));
+        }
+        let expected = fs::read_to_string(&snapshot_path).with_context(|| {
+            format!(
+                "failed to read snapshot {snapshot_path:?}, \
+                 run with --update-snapshots to create it"
+            )
+        })?;
+        compare_text(&expected, actual)
+            .with_context(|| format!("output doesn't match the snapshot {snapshot_path:?}"))
+    }
+}
+
+/// Prints the outcome of each fixture, failing if any of them failed.
+fn report_results(results: &[(String, Result<()>)]) -> Result<()> {
+    let mut failures = Vec::new();
+    for (name, result) in results {
+        match result {
+            Ok(()) => println!("test {name} ... ok"),
+            Err(error) => {
+                println!("test {name} ... FAILED");
+                failures.push(format!("---- {name} ----\n{error:#}"));
+            }
+        }
+    }
+    if !failures.is_empty() {
+        println!("\nfailures:\n\n{}\n", failures.join("\n\n"));
+    }
+    println!(
+        "test result: {}. {} passed; {} failed",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        results.len() - failures.len(),
+        failures.len()
+    );
+
+    if !failures.is_empty() {
+        bail!("{} of {} fixtures failed", failures.len(), results.len());
+    }
+    Ok(())
+}
+
+async fn check_language_server_command(
+    extension: &WasmExtension,
+    fixture: &LanguageServerCommandFixture,
+    worktree: Arc<dyn WorktreeDelegate>,
+) -> Result<()> {
+    let command = extension
+        .language_server_command(
+            LanguageServerName(fixture.language_server.clone().into()),
+            LanguageName::new(&fixture.language),
+            worktree,
+        )
+        .await?;
+
+    if let Some(expected) = &fixture.command {
+        let path = extension.path_from_extension(&command.command);
+        let path = path.strip_prefix(&extension.work_dir).unwrap_or(&path);
+        compare_values("command", expected.as_str(), &*path.to_string_lossy())?;
+    }
+    if let Some(expected) = &fixture.args {
+        compare_values("arguments", expected, &command.args)?;
+    }
+    for (name, expected) in &fixture.env {
+        let value = command
+            .env
+            .iter()
+            .find_map(|(key, value)| (key == name).then_some(value.as_str()));
+        compare_values(
+            &format!("`{name}` variable"),
+            &Some(expected.as_str()),
+            &value,
+        )?;
+    }
+    Ok(())
+}
+
+async fn check_completion_labels(
+    extension: &WasmExtension,
+    fixture: &CompletionLabelsFixture,
+) -> Result<()> {
+    let completions = fixture
+        .completions
+        .iter()
+        .map(|completion| {
+            Ok(Completion {
+                label: completion.label.clone(),
+                label_details: None,
+                detail: completion.detail.clone(),
+                kind: completion
+                    .kind
+                    .as_deref()
+                    .map(completion_kind_from_name)
+                    .transpose()?,
+                insert_text_format: None,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let labels = extension
+        .labels_for_completions(
+            LanguageServerName(fixture.language_server.clone().into()),
+            completions,
+        )
+        .await?;
+
+    for (index, completion) in fixture.completions.iter().enumerate() {
+        let label = labels
+            .get(index)
+            .and_then(Option::as_ref)
+            .map(code_label_text)
+            .transpose()?;
+        compare_values(
+            &format!("label for `{}`", completion.label),
+            &completion.expected.as_deref(),
+            &label.as_deref(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Returns the text that a code label displays.
+fn code_label_text(label: &CodeLabel) -> Result<String> {
+    let mut text = String::new();
+    for span in &label.spans {
+        match span {
+            CodeLabelSpan::CodeRange(range) => text.push_str(
+                label
+                    .code
+                    .get(range.clone())
+                    .with_context(|| format!("invalid code range {range:?} in label"))?,
+            ),
+            CodeLabelSpan::Literal(literal) => text.push_str(&literal.text),
+        }
+    }
+    Ok(text)
+}
+
+fn completion_kind_from_name(name: &str) -> Result<CompletionKind> {
+    Ok(match name {
+        "text" => CompletionKind::Text,
+        "method" => CompletionKind::Method,
+        "function" => CompletionKind::Function,
+        "constructor" => CompletionKind::Constructor,
+        "field" => CompletionKind::Field,
+        "variable" => CompletionKind::Variable,
+        "class" => CompletionKind::Class,
+        "interface" => CompletionKind::Interface,
+        "module" => CompletionKind::Module,
+        "property" => CompletionKind::Property,
+        "unit" => CompletionKind::Unit,
+        "value" => CompletionKind::Value,
+        "enum" => CompletionKind::Enum,
+        "keyword" => CompletionKind::Keyword,
+        "snippet" => CompletionKind::Snippet,
+        "color" => CompletionKind::Color,
+        "file" => CompletionKind::File,
+        "reference" => CompletionKind::Reference,
+        "folder" => CompletionKind::Folder,
+        "enum_member" => CompletionKind::EnumMember,
+        "constant" => CompletionKind::Constant,
+        "struct" => CompletionKind::Struct,
+        "event" => CompletionKind::Event,
+        "operator" => CompletionKind::Operator,
+        "type_parameter" => CompletionKind::TypeParameter,
+        _ => bail!("unknown completion kind `{name}`"),
+    })
+}
+
+fn compare_values<T: std::fmt::Debug + PartialEq + ?Sized>(
+    what: &str,
+    expected: &T,
+    actual: &T,
+) -> Result<()> {
+    if expected != actual {
+        bail!("unexpected {what}\nexpected: {expected:?}\n  actual: {actual:?}");
+    }
+    Ok(())
+}
+
+/// Compares two texts, reporting the first line that differs.
+fn compare_text(expected: &str, actual: &str) -> Result<()> {
+    if expected == actual {
+        return Ok(());
+    }
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut row = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(expected), Some(actual)) if expected == actual => row += 1,
+            (None, None) => bail!("the texts differ in their trailing newlines"),
+            (expected, actual) => bail!(
+                "the texts differ at line {row}\nexpected: {}\n  actual: {}",
+                expected.map_or("<end of text>".into(), |line| format!("{line:?}")),
+                actual.map_or("<end of text>".into(), |line| format!("{line:?}")),
+            ),
+        }
+    }
+}
+
+/// Parses sample files with the extension's grammars and lists their highlight captures.
+struct Highlighter {
+    parser: Parser,
+    languages: HashMap<LanguageName, (tree_sitter::Language, Option<Query>)>,
+}
+
+impl Highlighter {
+    /// Returns a line for each capture, with its 1-based start and end positions,
+    /// its name and the captured text.
+    fn captures(&mut self, language_name: &str, text: &str) -> Result<String> {
+        let (grammar, query) = self
+            .languages
+            .get(&LanguageName::new(language_name))
+            .with_context(|| format!("the extension has no language `{language_name}`"))?;
+        let query = query
+            .as_ref()
+            .with_context(|| format!("the language `{language_name}` has no highlights query"))?;
+        self.parser.set_language(grammar)?;
+        let tree = self
+            .parser
+            .parse(text, None)
+            .context("failed to parse the sample file")?;
+
+        let mut output = String::new();
+        let mut cursor = QueryCursor::new();
+        let mut captures = cursor.captures(query, tree.root_node(), text.as_bytes());
+        while let Some((query_match, capture_index)) = captures.next() {
+            let capture = query_match.captures[*capture_index];
+            let start = capture.node.start_position();
+            let end = capture.node.end_position();
+            writeln!(
+                output,
+                "{}:{}-{}:{} @{} {:?}",
+                start.row + 1,
+                start.column + 1,
+                end.row + 1,
+                end.column + 1,
+                query.capture_names()[capture.index as usize],
+                &text[capture.node.byte_range()],
+            )?;
+        }
+        Ok(output)
+    }
+}
+
+/// A worktree whose files and environment come from the fixtures.
+struct FixtureWorktree {
+    root: PathBuf,
+    env: BTreeMap<String, String>,
+    which: BTreeMap<String, String>,
+}
+
+#[async_trait]
+impl WorktreeDelegate for FixtureWorktree {
+    fn id(&self) -> u64 {
+        0
+    }
+
+    fn root_path(&self) -> String {
+        self.root.to_string_lossy().into_owned()
+    }
+
+    async fn read_text_file(&self, path: &RelPath) -> Result<String> {
+        let path = self.root.join(path.as_std_path());
+        fs::read_to_string(&path).with_context(|| format!("failed to read {path:?}"))
+    }
+
+    async fn which(&self, binary_name: String) -> Option<String> {
+        self.which.get(&binary_name).cloned()
+    }
+
+    async fn shell_env(&self) -> Vec<(String, String)> {
+        self.env.clone().into_iter().collect()
+    }
+}
+
+/// An HTTP client that serves the responses declared in the fixtures, so that tests don't
+/// depend on the network.
+struct FixtureHttpClient {
+    responses: HashMap<String, (StatusCode, Vec<u8>)>,
+}
+
+impl FixtureHttpClient {
+    fn new(fixtures: &[HttpFixture], fixtures_dir: &Path) -> Result<Self> {
+        let mut responses = HashMap::default();
+        for fixture in fixtures {
+            let body = match (&fixture.body, &fixture.file) {
+                (Some(body), None) => body.clone().into_bytes(),
+                (None, Some(file)) => {
+                    let path = fixtures_dir.join(file);
+                    fs::read(&path).with_context(|| format!("failed to read {path:?}"))?
+                }
+                (None, None) => Vec::new(),
+                (Some(_), Some(_)) => {
+                    bail!(
+                        "HTTP fixture for {} has both a body and a file",
+                        fixture.url
+                    )
+                }
+            };
+            let status = StatusCode::from_u16(fixture.status)
+                .with_context(|| format!("invalid status for {}", fixture.url))?;
+            responses.insert(fixture.url.clone(), (status, body));
+        }
+        Ok(Self { responses })
+    }
+}
+
+impl HttpClient for FixtureHttpClient {
+    fn user_agent(&self) -> Option<&HeaderValue> {
+        None
+    }
+
+    fn proxy(&self) -> Option<&Url> {
+        None
+    }
+
+    fn send(&self, request: Request<AsyncBody>) -> BoxFuture<'static, Result<Response<AsyncBody>>> {
+        let url = request.uri().to_string();
+        let response = match self.responses.get(&url) {
+            Some((status, body)) => Response::builder()
+                .status(*status)
+                .body(AsyncBody::from(body.clone())),
+            None => {
+                log::warn!("no HTTP fixture for {url}");
+                Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(AsyncBody::from(format!("no HTTP fixture for {url}")))
+            }
+        };
+        Box::pin(async move { Ok(response?) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{AsyncReadExt as _, executor::block_on};
+
+    #[test]
+    fn test_parse_fixtures() {
+        let fixtures: Fixtures = toml::from_str(
+            r#"
+            [worktree]
+            root = "project"
+            env = { PATH = "/usr/bin" }
+            which = { "my-server" = "/usr/bin/my-server" }
+
+            [[http]]
+            url = "https://example.com/release.json"
+            body = "{}"
+
+            [[http]]
+            url = "https://example.com/missing"
+            status = 500
+
+            [[language_server_command]]
+            language_server = "my-server"
+            language = "My Language"
+            command = "/usr/bin/my-server"
+            args = ["--stdio"]
+
+            [[slash_command]]
+            name = "greet"
+            snapshot = "greet.txt"
+
+            [[query_annotations]]
+            language = "My Language"
+            file = "highlights.my"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(fixtures.worktree.root, Some(PathBuf::from("project")));
+        assert_eq!(fixtures.worktree.env["PATH"], "/usr/bin");
+        assert_eq!(fixtures.worktree.which["my-server"], "/usr/bin/my-server");
+        assert_eq!(fixtures.http.len(), 2);
+        assert_eq!(fixtures.http[0].status, 200);
+        assert_eq!(fixtures.http[0].body.as_deref(), Some("{}"));
+        assert_eq!(fixtures.http[1].status, 500);
+        assert_eq!(
+            fixtures.language_server_command[0].args,
+            Some(vec!["--stdio".to_string()])
+        );
+        assert!(fixtures.language_server_command[0].env.is_empty());
+        assert_eq!(
+            fixtures.slash_command[0].snapshot,
+            Some(PathBuf::from("greet.txt"))
+        );
+        assert!(fixtures.slash_command[0].args.is_empty());
+        assert_eq!(fixtures.query_annotations[0].query, None);
+        assert!(fixtures.completion_labels.is_empty());
+        assert!(fixtures.highlights.is_empty());
+
+        // Typos are reported instead of being silently ignored.
+        assert!(toml::from_str::<Fixtures>("[[slash_commands]]\nname = \"greet\"").is_err());
+        assert!(toml::from_str::<Fixtures>("[worktree]\nenvironment = {}").is_err());
+    }
+
+    #[test]
+    fn test_fixture_worktree() {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("config.json"), "{}").unwrap();
+        let worktree = FixtureWorktree {
+            root: root.path().to_path_buf(),
+            env: BTreeMap::from_iter([("HOME".to_string(), "/home/user".to_string())]),
+            which: BTreeMap::from_iter([("node".to_string(), "/usr/bin/node".to_string())]),
+        };
+
+        assert_eq!(worktree.root_path(), root.path().to_string_lossy());
+        let config = RelPath::unix("config.json").unwrap();
+        assert_eq!(block_on(worktree.read_text_file(config)).unwrap(), "{}");
+        let missing = RelPath::unix("missing.json").unwrap();
+        assert!(block_on(worktree.read_text_file(missing)).is_err());
+        assert_eq!(
+            block_on(worktree.which("node".to_string())).as_deref(),
+            Some("/usr/bin/node")
+        );
+        assert_eq!(block_on(worktree.which("npm".to_string())), None);
+        assert_eq!(
+            block_on(worktree.shell_env()),
+            [("HOME".to_string(), "/home/user".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_fixture_http_client() {
+        let fixtures_dir = tempfile::tempdir().unwrap();
+        fs::write(fixtures_dir.path().join("archive.bin"), [0x1F, 0x8B]).unwrap();
+        let fixtures: Fixtures = toml::from_str(
+            r#"
+            [[http]]
+            url = "https://example.com/release.json"
+            body = "{}"
+
+            [[http]]
+            url = "https://example.com/archive.tar.gz"
+            file = "archive.bin"
+
+            [[http]]
+            url = "https://example.com/error"
+            status = 500
+            "#,
+        )
+        .unwrap();
+        let client = FixtureHttpClient::new(&fixtures.http, fixtures_dir.path()).unwrap();
+
+        let get = |url: &str| {
+            let request = Request::builder()
+                .uri(url)
+                .body(AsyncBody::empty())
+                .unwrap();
+            let mut response = block_on(client.send(request)).unwrap();
+            let mut body = Vec::new();
+            block_on(response.body_mut().read_to_end(&mut body)).unwrap();
+            (response.status(), body)
+        };
+        assert_eq!(
+            get("https://example.com/release.json"),
+            (StatusCode::OK, b"{}".to_vec())
+        );
+        assert_eq!(
+            get("https://example.com/archive.tar.gz"),
+            (StatusCode::OK, vec![0x1F, 0x8B])
+        );
+        assert_eq!(
+            get("https://example.com/error"),
+            (StatusCode::INTERNAL_SERVER_ERROR, Vec::new())
+        );
+        assert_eq!(get("https://example.com/other").0, StatusCode::NOT_FOUND);
+
+        let invalid: Fixtures = toml::from_str(
+            r#"
+            [[http]]
+            url = "https://example.com/both"
+            body = "{}"
+            file = "archive.bin"
+            "#,
+        )
+        .unwrap();
+        assert!(FixtureHttpClient::new(&invalid.http, fixtures_dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let fixtures_dir = tempfile::tempdir().unwrap();
+        let runner = |update_snapshots| FixtureRunner {
+            extension_path: fixtures_dir.path().to_path_buf(),
+            scratch_dir: fixtures_dir.path().to_path_buf(),
+            fixtures_dir: fixtures_dir.path().to_path_buf(),
+            update_snapshots,
+        };
+        let snapshot = Path::new("output.txt");
+
+        // A missing snapshot fails until it's created by updating the snapshots.
+        assert!(
+            runner(false)
+                .check_snapshot(snapshot, "one\ntwo\n")
+                .is_err()
+        );
+        runner(true).check_snapshot(snapshot, "one\ntwo\n").unwrap();
+        assert_eq!(
+            fs::read_to_string(fixtures_dir.path().join(snapshot)).unwrap(),
+            "one\ntwo\n"
+        );
+        runner(false)
+            .check_snapshot(snapshot, "one\ntwo\n")
+            .unwrap();
+
+        let mismatch = runner(false)
+            .check_snapshot(snapshot, "one\nthree\n")
+            .unwrap_err();
+        assert!(format!("{mismatch:#}").contains("the texts differ at line 2"));
+        let trailing_newline = compare_text("one\n", "one").unwrap_err();
+        assert!(trailing_newline.to_string().contains("trailing newlines"));
+
+        // A mismatch fails the run, which makes the command exit with a non-zero status.
+        let results = vec![
+            ("slash command `greet`".to_string(), Ok(())),
+            ("slash command `farewell`".to_string(), Err(mismatch)),
+        ];
+        let error = report_results(&results).unwrap_err();
+        assert_eq!(error.to_string(), "1 of 2 fixtures failed");
+        report_results(&results[..1]).unwrap();
+
+        // Updating the snapshots makes the run pass again.
+        runner(true)
+            .check_snapshot(snapshot, "one\nthree\n")
+            .unwrap();
+        runner(false)
+            .check_snapshot(snapshot, "one\nthree\n")
+            .unwrap();
+    }
+}
@@ -1,3 +1,5 @@
+mod extension_tests;
+
 use std::collections::HashMap;
 use std::env;
 use std::fs;
@@ -6,7 +8,7 @@ use std::sync::Arc;
 
 use ::fs::{CopyOptions, Fs, RealFs, copy_recursive};
 use anyhow::{Context as _, Result, anyhow, bail};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use extension::{ExtensionManifest, ExtensionSnippets};
 use language::LanguageConfig;
@@ -17,8 +19,26 @@ use tokio::process::Command;
 use tree_sitter::{Language, Query, WasmStore};
 
 #[derive(Parser, Debug)]
-#[command(name = "zed-extension")]
+#[command(
+    name = "zed-extension",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Args {
+    #[command(flatten)]
+    package: Option<PackageArgs>,
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Build the extension and run the fixtures that it declares
+    Test(extension_tests::TestArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct PackageArgs {
     /// The path to the extension directory
     #[arg(long)]
     source_dir: PathBuf,
@@ -30,11 +50,20 @@ struct Args {
     scratch_dir: PathBuf,
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
+fn main() -> Result<()> {
     env_logger::init();
 
     let args = Args::parse();
+    match args.command {
+        Some(Commands::Test(test_args)) => extension_tests::run(test_args),
+        None => {
+            let package_args = args.package.context("missing packaging arguments")?;
+            tokio::runtime::Runtime::new()?.block_on(package_extension(package_args))
+        }
+    }
+}
+
+async fn package_extension(args: PackageArgs) -> Result<()> {
     let fs = Arc::new(RealFs::new(None, gpui_platform::background_executor()));
     let engine = wasmtime::Engine::default();
     let mut wasm_store = WasmStore::new(&engine)?;
@@ -58,15 +87,7 @@ async fn main() -> Result<()> {
 
     log::info!("compiling extension");
 
-    let user_agent = format!(
-        "Zed Extension CLI/{} ({}; {})",
-        env!("CARGO_PKG_VERSION"),
-        std::env::consts::OS,
-        std::env::consts::ARCH
-    );
-    let http_client = Arc::new(ReqwestClient::user_agent(&user_agent)?);
-
-    let builder = ExtensionBuilder::new(http_client, scratch_dir);
+    let builder = extension_builder(scratch_dir)?;
     builder
         .compile_extension(
             &extension_path,
@@ -125,6 +146,17 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+fn extension_builder(scratch_dir: PathBuf) -> Result<ExtensionBuilder> {
+    let user_agent = format!(
+        "Zed Extension CLI/{} ({}; {})",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let http_client = Arc::new(ReqwestClient::user_agent(&user_agent)?);
+    Ok(ExtensionBuilder::new(http_client, scratch_dir))
+}
+
 async fn copy_extension_resources(
     manifest: &ExtensionManifest,
     extension_path: &Path,
@@ -148,6 +148,10 @@ fn manifest() -> ExtensionManifest {
         )],
         debug_adapters: Default::default(),
         debug_locators: Default::default(),
+        task_providers: Default::default(),
+        editor_commands: Default::default(),
+        formatters: Default::default(),
+        linters: Default::default(),
         language_model_providers: BTreeMap::default(),
     }
 }
@@ -11,7 +11,7 @@ use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use client::{Client, proto, telemetry::Telemetry};
 use cloud_api_types::{ExtensionMetadata, ExtensionProvides, GetExtensionsResponse};
-use collections::{BTreeMap, BTreeSet, HashSet, btree_map};
+use collections::{BTreeMap, BTreeSet, HashMap, HashSet, btree_map, hash_map};
 pub use extension::ExtensionManifest;
 use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use extension::{
@@ -69,6 +69,9 @@ pub use extension_settings::ExtensionSettings;
 
 pub const RELOAD_DEBOUNCE_DURATION: Duration = Duration::from_millis(200);
 const FS_WATCH_LATENCY: Duration = Duration::from_millis(100);
+/// How long a dev extension's source watcher waits before retrying a rebuild
+/// when another operation on the extension is still in progress.
+const DEV_EXTENSION_REBUILD_RETRY_INTERVAL: Duration = Duration::from_millis(500);
 
 /// The current extension [`SchemaVersion`] supported by Zed.
 const CURRENT_SCHEMA_VERSION: SchemaVersion = SchemaVersion(1);
@@ -125,6 +128,7 @@ pub struct ExtensionStore {
     pub tasks: Vec<Task<()>>,
     pub remote_clients: Vec<WeakEntity<RemoteClient>>,
     pub ssh_registered_tx: UnboundedSender<()>,
+    dev_extension_watchers: HashMap<Arc<str>, Task<()>>,
 }
 
 #[derive(Clone, Copy)]
@@ -141,6 +145,10 @@ pub enum Event {
     ExtensionInstalled(Arc<str>),
     ExtensionUninstalled(Arc<str>),
     ExtensionFailedToLoad(Arc<str>),
+    DevExtensionFailedToBuild {
+        extension_id: Arc<str>,
+        error: Arc<str>,
+    },
 }
 
 impl EventEmitter<Event> for ExtensionStore {}
@@ -276,6 +284,7 @@ impl ExtensionStore {
 
             remote_clients: Default::default(),
             ssh_registered_tx: connection_registered_tx,
+            dev_extension_watchers: HashMap::default(),
         };
 
         // The extensions store maintains an index file, which contains a complete
@@ -1028,12 +1037,26 @@ impl ExtensionStore {
     }
 
     pub fn rebuild_dev_extension(&mut self, extension_id: Arc<str>, cx: &mut Context<Self>) {
+        if let Some(rebuild) = self.start_dev_extension_rebuild(extension_id, cx) {
+            rebuild.detach();
+        }
+    }
+
+    /// Compiles the dev extension and reloads it, emitting [`Event::DevExtensionFailedToBuild`]
+    /// if the compilation fails.
+    ///
+    /// Returns `None` if another operation on the extension is already in progress.
+    fn start_dev_extension_rebuild(
+        &mut self,
+        extension_id: Arc<str>,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<()>> {
         let path = self.installed_dir.join(extension_id.as_ref());
         let builder = self.builder.clone();
         let fs = self.fs.clone();
 
         match self.outstanding_operations.entry(extension_id.clone()) {
-            btree_map::Entry::Occupied(_) => return,
+            btree_map::Entry::Occupied(_) => return None,
             btree_map::Entry::Vacant(e) => e.insert(ExtensionOperation::Upgrade),
         };
 
@@ -1045,22 +1068,91 @@ impl ExtensionStore {
                 .await
         });
 
-        cx.spawn(async move |this, cx| {
+        Some(cx.spawn(async move |this, cx| {
             let result = compile.await;
 
-            this.update(cx, |this, cx| {
+            let reload = this.update(cx, |this, cx| {
                 this.outstanding_operations.remove(&extension_id);
                 cx.notify();
-            })?;
+                match result {
+                    Ok(()) => Some(this.reload(Some(extension_id), cx)),
+                    Err(error) => {
+                        log::error!("failed to rebuild dev extension {extension_id}: {error:#}");
+                        cx.emit(Event::DevExtensionFailedToBuild {
+                            extension_id,
+                            error: format!("{error:#}").into(),
+                        });
+                        None
+                    }
+                }
+            });
+            if let Ok(Some(reload)) = reload {
+                reload.await;
+            }
+        }))
+    }
 
-            if result.is_ok() {
-                this.update(cx, |this, cx| this.reload(Some(extension_id), cx))?
-                    .await;
+    /// Watches the sources of the installed dev extensions, so that they're rebuilt and
+    /// reloaded whenever they change.
+    fn update_dev_extension_watchers(&mut self, cx: &mut Context<Self>) {
+        let dev_extension_ids = self
+            .extension_index
+            .extensions
+            .iter()
+            .filter(|(_, entry)| entry.dev)
+            .map(|(extension_id, _)| extension_id.clone())
+            .collect::<HashSet<_>>();
+        self.dev_extension_watchers
+            .retain(|extension_id, _| dev_extension_ids.contains(extension_id));
+        for extension_id in dev_extension_ids {
+            if let hash_map::Entry::Vacant(entry) =
+                self.dev_extension_watchers.entry(extension_id.clone())
+            {
+                entry.insert(self.watch_dev_extension(extension_id, cx));
             }
+        }
+    }
 
-            result
+    fn watch_dev_extension(&self, extension_id: Arc<str>, cx: &mut Context<Self>) -> Task<()> {
+        let fs = self.fs.clone();
+        let extension_dir = self.installed_dir.join(extension_id.as_ref());
+        cx.spawn(async move |this, cx| {
+            // Dev extensions are symlinked into the installed directory,
+            // so watch the directory that the symlink points to.
+            let Some(source_dir) = fs.canonicalize(&extension_dir).await.log_err() else {
+                return;
+            };
+            let (mut events, _watcher) = fs.watch(&source_dir, FS_WATCH_LATENCY).await;
+            while let Some(events) = events.next().await {
+                if !events
+                    .iter()
+                    .any(|event| is_dev_extension_source_change(&source_dir, &event.path))
+                {
+                    continue;
+                }
+
+                // If the extension is already being rebuilt, wait for that to finish
+                // instead of dropping the change, as the build may predate it.
+                loop {
+                    let Ok(rebuild) = this.update(cx, |this, cx| {
+                        this.start_dev_extension_rebuild(extension_id.clone(), cx)
+                    }) else {
+                        return;
+                    };
+                    match rebuild {
+                        Some(rebuild) => {
+                            rebuild.await;
+                            break;
+                        }
+                        None => {
+                            cx.background_executor()
+                                .timer(DEV_EXTENSION_REBUILD_RETRY_INTERVAL)
+                                .await;
+                        }
+                    }
+                }
+            }
         })
-        .detach_and_log_err(cx)
     }
 
     /// Updates the set of installed extensions.
@@ -1208,6 +1300,19 @@ impl ExtensionStore {
             for locator in extension.manifest.debug_locators.keys() {
                 self.proxy.unregister_debug_locator(locator.clone());
             }
+            for provider in extension.manifest.task_providers.keys() {
+                self.proxy.unregister_task_provider(provider.clone(), cx);
+            }
+            for command_name in extension.manifest.editor_commands.keys() {
+                self.proxy
+                    .unregister_editor_command(command_name.clone(), cx);
+            }
+            for formatter_name in extension.manifest.formatters.keys() {
+                self.proxy.unregister_formatter(formatter_name.clone(), cx);
+            }
+            for linter_name in extension.manifest.linters.keys() {
+                self.proxy.unregister_linter(linter_name.clone(), cx);
+            }
             for command_name in extension.manifest.slash_commands.keys() {
                 self.proxy.unregister_slash_command(command_name.clone());
             }
@@ -1347,6 +1452,7 @@ impl ExtensionStore {
             .filter_map(|name| new_index.extensions.get(name).cloned())
             .collect::<Vec<_>>();
         self.extension_index = new_index;
+        self.update_dev_extension_watchers(cx);
         cx.notify();
         cx.emit(Event::ExtensionsUpdated);
 
@@ -1478,6 +1584,37 @@ impl ExtensionStore {
                         this.proxy
                             .register_debug_locator(extension.clone(), debug_adapter.clone());
                     }
+
+                    for provider in manifest.task_providers.keys() {
+                        this.proxy
+                            .register_task_provider(extension.clone(), provider.clone(), cx);
+                    }
+
+                    for (command_name, command) in &manifest.editor_commands {
+                        this.proxy.register_editor_command(
+                            extension.clone(),
+                            command_name.clone(),
+                            command.description.clone(),
+                            cx,
+                        );
+                    }
+
+                    for formatter_name in manifest.formatters.keys() {
+                        this.proxy.register_formatter(
+                            extension.clone(),
+                            formatter_name.clone(),
+                            cx,
+                        );
+                    }
+
+                    for (linter_name, linter) in &manifest.linters {
+                        this.proxy.register_linter(
+                            extension.clone(),
+                            linter_name.clone(),
+                            linter.languages.clone(),
+                            cx,
+                        );
+                    }
                 }
 
                 this.wasm_extensions.extend(wasm_extensions);
@@ -1882,7 +2019,32 @@ impl ExtensionStore {
     }
 }
 
-fn load_plugin_queries(root_path: &Path) -> LanguageQueries {
+/// Returns whether a change to the given path in a dev extension's directory
+/// should cause the extension to be rebuilt.
+///
+/// Changes to the build's outputs are ignored, as they're written while the
+/// extension is compiled.
+fn is_dev_extension_source_change(source_dir: &Path, path: &Path) -> bool {
+    let Ok(relative_path) = path.strip_prefix(source_dir) else {
+        return false;
+    };
+    if matches!(
+        relative_path.to_str(),
+        Some("" | "extension.wasm" | "Cargo.lock")
+    ) {
+        return false;
+    }
+    relative_path.components().all(|component| match component {
+        path::Component::Normal(name) => {
+            let name = name.to_string_lossy();
+            !name.starts_with('.') && !matches!(name.as_ref(), "target" | "grammars")
+        }
+        _ => true,
+    })
+}
+
+/// Loads the queries in a language's directory, combining files that share a query's prefix.
+pub fn load_plugin_queries(root_path: &Path) -> LanguageQueries {
     let mut result = LanguageQueries::default();
     if let Some(entries) = std::fs::read_dir(root_path).log_err() {
         for entry in entries {
@@ -1,7 +1,7 @@
 use crate::{
     Event, ExtensionIndex, ExtensionIndexEntry, ExtensionIndexLanguageEntry,
     ExtensionIndexThemeEntry, ExtensionManifest, ExtensionStore, GrammarManifestEntry,
-    RELOAD_DEBOUNCE_DURATION, SchemaVersion,
+    RELOAD_DEBOUNCE_DURATION, SchemaVersion, is_dev_extension_source_change,
 };
 use async_compression::futures::bufread::GzipEncoder;
 use collections::{BTreeMap, HashSet};
@@ -165,6 +165,10 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         capabilities: Vec::new(),
                         debug_adapters: Default::default(),
                         debug_locators: Default::default(),
+                        task_providers: Default::default(),
+                        editor_commands: Default::default(),
+                        formatters: Default::default(),
+                        linters: Default::default(),
                         language_model_providers: BTreeMap::default(),
                     }),
                     dev: false,
@@ -197,6 +201,10 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         capabilities: Vec::new(),
                         debug_adapters: Default::default(),
                         debug_locators: Default::default(),
+                        task_providers: Default::default(),
+                        editor_commands: Default::default(),
+                        formatters: Default::default(),
+                        linters: Default::default(),
                         language_model_providers: BTreeMap::default(),
                     }),
                     dev: false,
@@ -378,6 +386,10 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 capabilities: Vec::new(),
                 debug_adapters: Default::default(),
                 debug_locators: Default::default(),
+                task_providers: Default::default(),
+                editor_commands: Default::default(),
+                formatters: Default::default(),
+                linters: Default::default(),
                 language_model_providers: BTreeMap::default(),
             }),
             dev: false,
@@ -999,6 +1011,33 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
     );
 }
 
+#[test]
+fn test_dev_extension_source_changes() {
+    let source_dir = Path::new("/code/my-extension");
+    for (path, is_source_change) in [
+        ("src/lib.rs", true),
+        ("Cargo.toml", true),
+        ("extension.toml", true),
+        ("languages/my-language/highlights.scm", true),
+        ("extension.wasm", false),
+        ("Cargo.lock", false),
+        ("target/wasm32-wasip2/debug/my_extension.wasm", false),
+        ("grammars/my-language.wasm", false),
+        (".git/index", false),
+        ("src/.lib.rs.swp", false),
+    ] {
+        assert_eq!(
+            is_dev_extension_source_change(source_dir, &source_dir.join(path)),
+            is_source_change,
+            "{path}"
+        );
+    }
+    assert!(!is_dev_extension_source_change(
+        source_dir,
+        Path::new("/code/other-extension/src/lib.rs")
+    ));
+}
+
 fn init_test(cx: &mut TestAppContext) {
     cx.update(|cx| {
         let store = SettingsStore::test(cx);
@@ -36,7 +36,10 @@ use std::{
     sync::{Arc, LazyLock, OnceLock},
     time::Duration,
 };
-use task::{DebugScenario, SpawnInTerminal, TaskTemplate, ZedDebugConfig};
+use task::{
+    DebugScenario, SpawnInTerminal, TaskTemplate, TaskTemplates, TaskVariables, VariableName,
+    ZedDebugConfig,
+};
 use util::paths::SanitizedPath;
 use wasmtime::{
     CacheStore, Engine, Store,
@@ -485,6 +488,127 @@ impl extension::Extension for WasmExtension {
         })
         .await?
     }
+
+    async fn task_templates(
+        &self,
+        provider_name: Arc<str>,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<TaskTemplates> {
+        self.call(|extension, store| {
+            async move {
+                let resource = store.data_mut().table().push(worktree)?;
+                let templates = extension
+                    .call_task_templates(store, provider_name, resource)
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+                Ok(TaskTemplates(templates))
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn task_variables(
+        &self,
+        provider_name: Arc<str>,
+        variables: TaskVariables,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<TaskVariables> {
+        self.call(|extension, store| {
+            async move {
+                let resource = store.data_mut().table().push(worktree)?;
+                let variables = variables
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), value))
+                    .collect();
+                let custom_variables = extension
+                    .call_task_variables(store, provider_name, variables, resource)
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+                Ok(TaskVariables::from_iter(custom_variables.into_iter().map(
+                    |(name, value)| (VariableName::Custom(Cow::Owned(name)), value),
+                )))
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn run_editor_command(
+        &self,
+        command_name: Arc<str>,
+        buffer: extension::EditorCommandBuffer,
+    ) -> Result<extension::EditorCommandOutput> {
+        self.call(|extension, store| {
+            async move {
+                let output = extension
+                    .call_run_editor_command(store, command_name, &buffer.into())
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+                Ok(output.into())
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn format_buffer(
+        &self,
+        formatter_name: Arc<str>,
+        buffer: extension::BufferContents,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<extension::FormatterOutput> {
+        self.call(|extension, store| {
+            async move {
+                let resource = store.data_mut().table().push(worktree)?;
+                let output = extension
+                    .call_format_buffer(store, formatter_name, &buffer.into(), resource)
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+                Ok(output.into())
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn lint_buffer(
+        &self,
+        linter_name: Arc<str>,
+        buffer: extension::BufferContents,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<extension::LinterOutput> {
+        self.call(|extension, store| {
+            async move {
+                let resource = store.data_mut().table().push(worktree)?;
+                let output = extension
+                    .call_lint_buffer(store, linter_name, &buffer.into(), resource)
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+                Ok(output.into())
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn linter_diagnostics(
+        &self,
+        linter_name: Arc<str>,
+        output: extension::LinterCommandOutput,
+    ) -> Result<Vec<extension::LintDiagnostic>> {
+        self.call(|extension, store| {
+            async move {
+                let diagnostics = extension
+                    .call_linter_diagnostics(store, linter_name, &output.into())
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+                Ok(diagnostics.into_iter().map(Into::into).collect())
+            }
+            .boxed()
+        })
+        .await?
+    }
 }
 
 pub struct WasmState {
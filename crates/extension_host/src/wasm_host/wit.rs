@@ -33,6 +33,10 @@ pub use latest::CodeLabelSpanLiteral;
 pub use latest::{
     CodeLabel, CodeLabelSpan, Command, DebugAdapterBinary, ExtensionProject, Range, SlashCommand,
     zed::extension::context_server::ContextServerConfiguration,
+    zed::extension::editor_command::{EditorCommandBuffer, EditorCommandOutput},
+    zed::extension::formatting::{
+        BufferContents, FormatterOutput, LintDiagnostic, LinterCommandOutput, LinterOutput,
+    },
     zed::extension::lsp::{
         Completion, CompletionKind, CompletionLabelDetails, InsertTextFormat, Symbol, SymbolKind,
     },
@@ -1212,6 +1216,161 @@ impl Extension {
             }
         }
     }
+
+    pub async fn call_task_templates(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_name: Arc<str>,
+        worktree: Resource<Arc<dyn WorktreeDelegate>>,
+    ) -> Result<Result<Vec<TaskTemplate>, String>> {
+        match self {
+            Extension::V0_8_0(ext) => {
+                let templates = ext
+                    .call_task_templates(store, &provider_name, worktree)
+                    .await?;
+                Ok(templates.map(|templates| templates.into_iter().map(Into::into).collect()))
+            }
+            Extension::V0_6_0(_)
+            | Extension::V0_5_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_1(_) => {
+                anyhow::bail!("`task_templates` not available prior to v0.8.0");
+            }
+        }
+    }
+
+    pub async fn call_task_variables(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_name: Arc<str>,
+        variables: Vec<(String, String)>,
+        worktree: Resource<Arc<dyn WorktreeDelegate>>,
+    ) -> Result<Result<Vec<(String, String)>, String>> {
+        match self {
+            Extension::V0_8_0(ext) => {
+                ext.call_task_variables(store, &provider_name, &variables, worktree)
+                    .await
+            }
+            Extension::V0_6_0(_)
+            | Extension::V0_5_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_1(_) => {
+                anyhow::bail!("`task_variables` not available prior to v0.8.0");
+            }
+        }
+    }
+
+    pub async fn call_run_editor_command(
+        &self,
+        store: &mut Store<WasmState>,
+        command_name: Arc<str>,
+        buffer: &EditorCommandBuffer,
+    ) -> Result<Result<EditorCommandOutput, String>> {
+        match self {
+            Extension::V0_8_0(ext) => {
+                ext.call_run_editor_command(store, &command_name, buffer)
+                    .await
+            }
+            Extension::V0_6_0(_)
+            | Extension::V0_5_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_1(_) => {
+                anyhow::bail!("`run_editor_command` not available prior to v0.8.0");
+            }
+        }
+    }
+
+    pub async fn call_format_buffer(
+        &self,
+        store: &mut Store<WasmState>,
+        formatter_name: Arc<str>,
+        buffer: &BufferContents,
+        worktree: Resource<Arc<dyn WorktreeDelegate>>,
+    ) -> Result<Result<FormatterOutput, String>> {
+        match self {
+            Extension::V0_8_0(ext) => {
+                ext.call_format_buffer(store, &formatter_name, buffer, worktree)
+                    .await
+            }
+            Extension::V0_6_0(_)
+            | Extension::V0_5_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_1(_) => {
+                anyhow::bail!("`format_buffer` not available prior to v0.8.0");
+            }
+        }
+    }
+
+    pub async fn call_lint_buffer(
+        &self,
+        store: &mut Store<WasmState>,
+        linter_name: Arc<str>,
+        buffer: &BufferContents,
+        worktree: Resource<Arc<dyn WorktreeDelegate>>,
+    ) -> Result<Result<LinterOutput, String>> {
+        match self {
+            Extension::V0_8_0(ext) => {
+                ext.call_lint_buffer(store, &linter_name, buffer, worktree)
+                    .await
+            }
+            Extension::V0_6_0(_)
+            | Extension::V0_5_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_1(_) => {
+                anyhow::bail!("`lint_buffer` not available prior to v0.8.0");
+            }
+        }
+    }
+
+    pub async fn call_linter_diagnostics(
+        &self,
+        store: &mut Store<WasmState>,
+        linter_name: Arc<str>,
+        output: &LinterCommandOutput,
+    ) -> Result<Result<Vec<LintDiagnostic>, String>> {
+        match self {
+            Extension::V0_8_0(ext) => {
+                ext.call_linter_diagnostics(store, &linter_name, output)
+                    .await
+            }
+            Extension::V0_6_0(_)
+            | Extension::V0_5_0(_)
+            | Extension::V0_4_0(_)
+            | Extension::V0_3_0(_)
+            | Extension::V0_2_0(_)
+            | Extension::V0_1_0(_)
+            | Extension::V0_0_6(_)
+            | Extension::V0_0_4(_)
+            | Extension::V0_0_1(_) => {
+                anyhow::bail!("`linter_diagnostics` not available prior to v0.8.0");
+            }
+        }
+    }
 }
 
 trait ToWasmtimeResult<T> {
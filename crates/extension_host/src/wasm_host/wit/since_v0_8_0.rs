@@ -76,6 +76,15 @@ impl From<Range> for std::ops::Range<usize> {
     }
 }
 
+impl From<std::ops::Range<usize>> for Range {
+    fn from(range: std::ops::Range<usize>) -> Self {
+        Self {
+            start: range.start as u32,
+            end: range.end as u32,
+        }
+    }
+}
+
 impl From<Command> for extension::Command {
     fn from(value: Command) -> Self {
         Self {
@@ -286,6 +295,7 @@ impl TryFrom<DebugScenario> for extension::DebugScenario {
             label: value.label.into(),
             build: value.build.map(Into::into),
             config: serde_json::Value::from_str(&value.config)?,
+            inputs: Vec::new(),
             tcp_connection: value.tcp_connection.map(Into::into),
         })
     }
@@ -489,6 +499,107 @@ impl From<SlashCommandOutputSection> for extension::SlashCommandOutputSection {
     }
 }
 
+impl From<extension::EditorCommandBuffer> for EditorCommandBuffer {
+    fn from(value: extension::EditorCommandBuffer) -> Self {
+        Self {
+            text: value.text,
+            selections: value.selections.into_iter().map(Into::into).collect(),
+            language: value.language,
+            path: value.path,
+        }
+    }
+}
+
+impl From<EditorCommandOutput> for extension::EditorCommandOutput {
+    fn from(value: EditorCommandOutput) -> Self {
+        Self {
+            edits: value
+                .edits
+                .into_iter()
+                .map(|edit| extension::EditorCommandEdit {
+                    range: edit.range.into(),
+                    new_text: edit.new_text,
+                })
+                .collect(),
+            selections: value
+                .selections
+                .map(|selections| selections.into_iter().map(Into::into).collect()),
+        }
+    }
+}
+
+impl From<extension::BufferContents> for BufferContents {
+    fn from(value: extension::BufferContents) -> Self {
+        Self {
+            text: value.text,
+            path: value.path,
+            language: value.language,
+        }
+    }
+}
+
+impl From<FormatterOutput> for extension::FormatterOutput {
+    fn from(value: FormatterOutput) -> Self {
+        match value {
+            FormatterOutput::Command(command) => Self::Command(command.into()),
+            FormatterOutput::Text(text) => Self::Text(text),
+        }
+    }
+}
+
+impl From<LinterOutput> for extension::LinterOutput {
+    fn from(value: LinterOutput) -> Self {
+        match value {
+            LinterOutput::Command(command) => Self::Command(command.into()),
+            LinterOutput::Diagnostics(diagnostics) => {
+                Self::Diagnostics(diagnostics.into_iter().map(Into::into).collect())
+            }
+        }
+    }
+}
+
+impl From<extension::LinterCommandOutput> for LinterCommandOutput {
+    fn from(value: extension::LinterCommandOutput) -> Self {
+        Self {
+            status: value.status,
+            stdout: value.stdout,
+            stderr: value.stderr,
+        }
+    }
+}
+
+impl From<LintDiagnostic> for extension::LintDiagnostic {
+    fn from(value: LintDiagnostic) -> Self {
+        Self {
+            start: value.start.into(),
+            end: value.end.into(),
+            severity: value.severity.into(),
+            message: value.message,
+            code: value.code,
+        }
+    }
+}
+
+impl From<formatting::LintPosition> for extension::LintPosition {
+    fn from(value: formatting::LintPosition) -> Self {
+        Self {
+            line: value.line,
+            column: value.column,
+        }
+    }
+}
+
+impl From<formatting::LintSeverity> for extension::LintSeverity {
+    fn from(value: formatting::LintSeverity) -> Self {
+        match value {
+            formatting::LintSeverity::Error => Self::Error,
+            formatting::LintSeverity::Warning => Self::Warning,
+            formatting::LintSeverity::Info => Self::Info,
+            formatting::LintSeverity::Hint => Self::Hint,
+        }
+    }
+}
+
 impl From<SlashCommandArgumentCompletion> for extension::SlashCommandArgumentCompletion {
     fn from(value: SlashCommandArgumentCompletion) -> Self {
         Self {
@@ -884,6 +995,12 @@ impl process::Host for WasmState {
 #[async_trait]
 impl slash_command::Host for WasmState {}
 
+#[async_trait]
+impl editor_command::Host for WasmState {}
+
+#[async_trait]
+impl formatting::Host for WasmState {}
+
 #[async_trait]
 impl context_server::Host for WasmState {}
 
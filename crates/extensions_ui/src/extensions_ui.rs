@@ -33,6 +33,7 @@ use vim_mode_setting::VimModeSetting;
 use workspace::{
     Workspace,
     item::{Item, ItemEvent},
+    notifications::{NotificationId, simple_message_notification::MessageNotification},
 };
 use zed_actions::ExtensionCategoryFilter;
 
@@ -171,6 +172,19 @@ pub fn init(cx: &mut App) {
                     .detach();
             });
 
+        if let Some(store) = ExtensionStore::try_global(cx) {
+            cx.subscribe(&store, |workspace, _, event, cx| {
+                if let extension_host::Event::DevExtensionFailedToBuild {
+                    extension_id,
+                    error,
+                } = event
+                {
+                    show_dev_extension_build_error(workspace, extension_id, error, cx);
+                }
+            })
+            .detach();
+        }
+
         cx.subscribe_in(workspace.project(), window, |_, _, event, window, cx| {
             if let project::Event::LanguageNotFound(buffer) = event {
                 extension_suggest::suggest(buffer.clone(), window, cx);
@@ -181,6 +195,25 @@ pub fn init(cx: &mut App) {
     .detach();
 }
 
+struct DevExtensionBuildError;
+
+fn show_dev_extension_build_error(
+    workspace: &mut Workspace,
+    extension_id: &Arc<str>,
+    error: &Arc<str>,
+    cx: &mut Context<Workspace>,
+) {
+    let id = NotificationId::composite::<DevExtensionBuildError>(SharedString::from(
+        extension_id.clone(),
+    ));
+    let title = format!("Failed to build dev extension {extension_id}");
+    let error = error.clone();
+    workspace.dismiss_notification(&id, cx);
+    workspace.show_notification(id, cx, |cx| {
+        cx.new(|cx| MessageNotification::new(error, cx).with_title(title))
+    });
+}
+
 fn extension_provides_label(provides: ExtensionProvides) -> &'static str {
     match provides {
         ExtensionProvides::Themes => "Themes",
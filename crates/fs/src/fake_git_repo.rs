@@ -8,7 +8,7 @@ use git::{
     repository::{
         AskPassDelegate, Branch, CommitDataReader, CommitDetails, CommitOptions, FetchOptions,
         GRAPH_CHUNK_SIZE, GitRepository, GitRepositoryCheckpoint, InitialGraphCommitData, LogOrder,
-        LogSource, PushOptions, Remote, RepoPath, ResetMode, Worktree,
+        LogSource, MergeStages, PushOptions, Remote, RepoPath, ResetMode, Worktree,
     },
     status::{
         DiffTreeType, FileStatus, GitStatus, StatusCode, TrackedStatus, TreeDiff, TreeDiffStatus,
@@ -42,6 +42,7 @@ pub struct FakeGitRepositoryState {
     pub index_contents: HashMap<RepoPath, String>,
     // everything in commit contents is in oids
     pub merge_base_contents: HashMap<RepoPath, Oid>,
+    pub merge_stages: HashMap<RepoPath, MergeStages>,
     pub oids: HashMap<Oid, String>,
     pub blames: HashMap<RepoPath, Blame>,
     pub current_branch_name: Option<String>,
@@ -69,6 +70,7 @@ impl FakeGitRepositoryState {
             simulated_create_worktree_error: Default::default(),
             refs: HashMap::from_iter([("HEAD".into(), "abc".into())]),
             merge_base_contents: Default::default(),
+            merge_stages: Default::default(),
             oids: Default::default(),
             remotes: HashMap::default(),
             graph_commits: Vec::new(),
@@ -126,6 +128,16 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
+    fn load_merge_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<MergeStages>> {
+        self.with_state_async(false, move |state| {
+            state
+                .merge_stages
+                .get(&path)
+                .cloned()
+                .context("path has no merge stages")
+        })
+    }
+
     fn load_commit(
         &self,
         _commit: String,
@@ -625,6 +637,9 @@ impl GitRepository for FakeGitRepository {
             let contents = join_all(contents).await;
             self.with_state_async(true, move |state| {
                 for (path, content) in contents {
+                    // Staging a conflicted path marks it as resolved.
+                    state.unmerged_paths.remove(&path);
+                    state.merge_stages.remove(&path);
                     if let Some(content) = content {
                         state.index_contents.insert(path, content);
                     } else {
@@ -37,6 +37,7 @@ use smol::io::AsyncWriteExt;
 use std::path::Component;
 use std::{
     io::{self, Write},
+    ops::Range,
     path::{Path, PathBuf},
     pin::Pin,
     sync::Arc,
@@ -117,6 +118,8 @@ pub trait Fs: Send + Sync {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+    /// Reads the bytes of the file in `range`, stopping early at the end of the file.
+    async fn load_byte_range(&self, path: &Path, range: Range<u64>) -> Result<Vec<u8>>;
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
     async fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
@@ -770,6 +773,25 @@ impl Fs for RealFs {
         Ok(bytes)
     }
 
+    async fn load_byte_range(&self, path: &Path, range: Range<u64>) -> Result<Vec<u8>> {
+        let path = path.to_path_buf();
+        self.executor
+            .spawn(async move {
+                use std::io::{Read as _, Seek as _};
+
+                let mut file = std::fs::File::open(&path)
+                    .with_context(|| format!("Failed to open file {}", path.display()))?;
+                file.seek(io::SeekFrom::Start(range.start))?;
+                let len = range.end.saturating_sub(range.start);
+                let mut bytes = Vec::with_capacity(len as usize);
+                file.take(len)
+                    .read_to_end(&mut bytes)
+                    .with_context(|| format!("Failed to read file {}", path.display()))?;
+                Ok(bytes)
+            })
+            .await
+    }
+
     #[cfg(not(target_os = "windows"))]
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         smol::unblock(move || {
@@ -1901,6 +1923,22 @@ impl FakeFs {
         .unwrap();
     }
 
+    pub fn set_merge_stages_for_repo(
+        &self,
+        dot_git: &Path,
+        stages_by_path: &[(&str, git::repository::MergeStages)],
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.merge_stages.clear();
+            state.merge_stages.extend(
+                stages_by_path
+                    .iter()
+                    .map(|(path, stages)| (repo_path(path), stages.clone())),
+            );
+        })
+        .unwrap();
+    }
+
     pub fn set_index_for_repo(&self, dot_git: &Path, index_state: &[(&str, String)]) {
         self.with_git_state(dot_git, true, |state| {
             state.index_contents.clear();
@@ -2583,6 +2621,13 @@ impl Fs for FakeFs {
         self.load_internal(path).await
     }
 
+    async fn load_byte_range(&self, path: &Path, range: Range<u64>) -> Result<Vec<u8>> {
+        let content = self.load_internal(path).await?;
+        let end = (range.end as usize).min(content.len());
+        let start = (range.start as usize).min(end);
+        Ok(content[start..end].to_vec())
+    }
+
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path.as_path());
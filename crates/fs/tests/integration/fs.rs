@@ -439,6 +439,18 @@ async fn test_realfs_atomic_write(executor: BackgroundExecutor) {
     assert_eq!(content, "World");
 }
 
+#[gpui::test]
+async fn test_realfs_load_byte_range(executor: BackgroundExecutor) {
+    let fs = RealFs::new(None, executor);
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("file.txt");
+    std::fs::write(&path, "0123456789").unwrap();
+
+    assert_eq!(fs.load_byte_range(&path, 2..5).await.unwrap(), b"234");
+    assert_eq!(fs.load_byte_range(&path, 8..20).await.unwrap(), b"89");
+    assert_eq!(fs.load_byte_range(&path, 12..20).await.unwrap(), b"");
+}
+
 #[gpui::test]
 async fn test_realfs_atomic_write_non_existing_file(executor: BackgroundExecutor) {
     let fs = RealFs::new(None, executor);
@@ -46,6 +46,8 @@ actions!(
         Blame,
         /// Shows the git history for the current file.
         FileHistory,
+        /// Opens the three-way merge editor for the current conflicted file.
+        OpenMergeEditor,
         /// Stages the current file.
         StageFile,
         /// Unstages the current file.
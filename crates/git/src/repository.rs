@@ -430,6 +430,16 @@ pub struct CommitDetails {
     pub author_name: SharedString,
 }
 
+/// The contents of a conflicted file at each stage of the index during a merge.
+///
+/// A stage is `None` when the file doesn't exist on that side of the merge.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MergeStages {
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct FileHistoryEntry {
     pub sha: SharedString,
@@ -699,6 +709,9 @@ pub trait GitRepository: Send + Sync {
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>>;
     fn load_blob_content(&self, oid: Oid) -> BoxFuture<'_, Result<String>>;
 
+    /// Returns the base, ours and theirs versions of a conflicted path, read from index stages 1, 2 and 3.
+    fn load_merge_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<MergeStages>>;
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -1384,6 +1397,39 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn load_merge_stages(&self, path: RepoPath) -> BoxFuture<'_, Result<MergeStages>> {
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                const STAGE_BASE: i32 = 1;
+                const STAGE_OURS: i32 = 2;
+                const STAGE_THEIRS: i32 = 3;
+
+                // git2 unwraps internally on empty paths or `.`
+                if path.is_empty() {
+                    bail!("empty path has no merge stages");
+                }
+                let repo = repo.lock();
+                let mut index = repo.index()?;
+                index.read(false)?;
+                let load_stage = |stage| -> Result<Option<String>> {
+                    let Some(entry) = index.get_path(path.as_std_path(), stage) else {
+                        return Ok(None);
+                    };
+                    let content = repo.find_blob(entry.id)?.content().to_owned();
+                    Ok(Some(
+                        String::from_utf8(content).context("conflicted file is not valid UTF-8")?,
+                    ))
+                };
+                Ok(MergeStages {
+                    base: load_stage(STAGE_BASE)?,
+                    ours: load_stage(STAGE_OURS)?,
+                    theirs: load_stage(STAGE_THEIRS)?,
+                })
+            })
+            .boxed()
+    }
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -7,7 +7,7 @@ use crate::remote_output::{self, RemoteAction, SuccessMessage};
 use crate::{branch_picker, picker_prompt, render_remote_button};
 use crate::{
     file_history_view::FileHistoryView, git_panel_settings::GitPanelSettings, git_status_icon,
-    repository_selector::RepositorySelector,
+    merge_editor::MergeEditor, repository_selector::RepositorySelector,
 };
 use agent_settings::AgentSettings;
 use anyhow::Context as _;
@@ -1332,6 +1332,34 @@ impl GitPanel {
         });
     }
 
+    fn open_merge_editor(
+        &mut self,
+        _: &git::OpenMergeEditor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        maybe!({
+            let entry = self.entries.get(self.selected_entry?)?.status_entry()?;
+            let active_repo = self.active_repository.clone()?;
+
+            MergeEditor::open(
+                active_repo,
+                entry.repo_path.clone(),
+                self.workspace.clone(),
+                window,
+                cx,
+            )
+            .detach_and_prompt_err(
+                "Failed to open merge editor",
+                window,
+                cx,
+                |_, _, _| None,
+            );
+
+            Some(())
+        });
+    }
+
     fn open_file(
         &mut self,
         _: &menu::SecondaryConfirm,
@@ -5026,6 +5054,7 @@ impl GitPanel {
         };
         let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
             let is_created = entry.status.is_created();
+            let is_conflicted = entry.status.is_conflicted();
             context_menu
                 .context(self.focus_handle.clone())
                 .action(stage_title, ToggleStaged.boxed_clone())
@@ -5038,6 +5067,9 @@ impl GitPanel {
                 .separator()
                 .action("Open Diff", menu::Confirm.boxed_clone())
                 .action("Open File", menu::SecondaryConfirm.boxed_clone())
+                .when(is_conflicted, |menu| {
+                    menu.action("Open Merge Editor", git::OpenMergeEditor.boxed_clone())
+                })
                 .separator()
                 .action_disabled_when(is_created, "View File History", Box::new(git::FileHistory))
         });
@@ -5683,6 +5715,7 @@ impl Render for GitPanel {
             .on_action(cx.listener(Self::open_diff))
             .on_action(cx.listener(Self::open_file))
             .on_action(cx.listener(Self::file_history))
+            .on_action(cx.listener(Self::open_merge_editor))
             .on_action(cx.listener(Self::focus_changes_list))
             .on_action(cx.listener(Self::focus_editor))
             .on_action(cx.listener(Self::expand_commit_editor))
@@ -2,7 +2,7 @@ use anyhow::anyhow;
 use commit_modal::CommitModal;
 use editor::{Editor, actions::DiffClipboardWithSelectionData};
 
-use project::ProjectPath;
+use project::{ProjectItem as _, ProjectPath};
 use ui::{
     Headline, HeadlineSize, Icon, IconName, IconSize, IntoElement, ParentElement, Render, Styled,
     StyledExt, div, h_flex, rems, v_flex,
@@ -38,6 +38,7 @@ pub mod file_history_view;
 pub mod git_panel;
 mod git_panel_settings;
 pub mod git_picker;
+pub mod merge_editor;
 pub mod multi_diff_view;
 pub mod picker_prompt;
 pub mod project_diff;
@@ -247,10 +248,42 @@ pub fn init(cx: &mut App) {
                 cx,
             );
         });
+        workspace.register_action(|workspace, _: &git::OpenMergeEditor, window, cx| {
+            open_merge_editor(workspace, window, cx);
+        });
     })
     .detach();
 }
 
+fn open_merge_editor(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let Some(project_path) = buffer.read(cx).project_path(cx) else {
+        return;
+    };
+    let git_store = workspace.project().read(cx).git_store();
+    let Some((repository, repo_path)) = git_store
+        .read(cx)
+        .repository_and_path_for_project_path(&project_path, cx)
+    else {
+        return;
+    };
+    let is_conflicted = repository
+        .read(cx)
+        .status_for_path(&repo_path)
+        .is_some_and(|entry| entry.status.is_conflicted());
+    if !is_conflicted {
+        workspace.show_error(&"The current file has no merge conflicts", cx);
+        return;
+    }
+    merge_editor::MergeEditor::open(repository, repo_path, workspace.weak_handle(), window, cx)
+        .detach_and_prompt_err("Failed to open merge editor", window, cx, |_, _, _| None);
+}
+
 fn open_modified_files(
     workspace: &mut Workspace,
     window: &mut Window,
@@ -0,0 +1,1190 @@
+//! MergeEditor provides a three-way view for resolving the merge conflicts in a single file.
+//!
+//! The base, ours and theirs versions of the file are read from the conflict stages of the
+//! index and shown side by side above the working copy, which is edited in the result pane.
+
+use anyhow::{Context as _, Result};
+use buffer_diff::{BufferDiff, BufferDiffSnapshot};
+use collections::HashMap;
+use editor::{
+    Bias, DisplayPoint, Editor, EditorEvent, MultiBuffer, SelectionEffects, ToPoint as _,
+    display_map::DisplayRow,
+    scroll::{Autoscroll, ScrollOffset},
+};
+use futures::{FutureExt, select_biased};
+use git::repository::RepoPath;
+use gpui::{
+    Action, AnyElement, App, AppContext as _, Context, Entity, EntityId, EventEmitter, FocusHandle,
+    Focusable, IntoElement, PromptLevel, Render, Subscription, Task, WeakEntity, Window, actions,
+    point,
+};
+use language::{Buffer, LanguageRegistry, Point, ToPoint as _};
+use project::{ConflictRegion, ConflictSet, ConflictSetUpdate, Project, git_store::Repository};
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    pin::pin,
+    sync::Arc,
+    time::Duration,
+};
+use ui::{Divider, Tooltip, prelude::*};
+use workspace::{
+    Item, ItemHandle as _, ItemNavHistory, SaveIntent, Workspace,
+    item::{ItemEvent, SaveOptions, TabContentParams},
+    notifications::DetachAndPromptErr,
+    searchable::SearchableItemHandle,
+};
+
+actions!(
+    merge_editor,
+    [
+        /// Resolves the conflict at the cursor by keeping our side.
+        AcceptOurs,
+        /// Resolves the conflict at the cursor by keeping their side.
+        AcceptTheirs,
+        /// Resolves the conflict at the cursor by keeping our side followed by theirs.
+        AcceptBoth,
+        /// Resolves the conflict at the cursor by keeping their side followed by ours.
+        AcceptBothTheirsFirst,
+        /// Moves the cursor to the next conflict in the result pane.
+        NextConflict,
+        /// Moves the cursor to the previous conflict in the result pane.
+        PreviousConflict,
+        /// Saves the result and stages the file, marking it as resolved.
+        MarkResolved,
+    ]
+);
+
+const RECALCULATE_DIFF_DEBOUNCE: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Resolution {
+    Ours,
+    Theirs,
+    OursThenTheirs,
+    TheirsThenOurs,
+}
+
+impl Resolution {
+    fn kept_ranges(self, conflict: &ConflictRegion) -> Vec<Range<language::Anchor>> {
+        match self {
+            Resolution::Ours => vec![conflict.ours.clone()],
+            Resolution::Theirs => vec![conflict.theirs.clone()],
+            Resolution::OursThenTheirs => vec![conflict.ours.clone(), conflict.theirs.clone()],
+            Resolution::TheirsThenOurs => vec![conflict.theirs.clone(), conflict.ours.clone()],
+        }
+    }
+}
+
+pub struct MergeEditor {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    repository: Entity<Repository>,
+    repo_path: RepoPath,
+    buffer: Entity<Buffer>,
+    conflict_set: Entity<ConflictSet>,
+    base_editor: Entity<Editor>,
+    ours_editor: Entity<Editor>,
+    theirs_editor: Entity<Editor>,
+    result_editor: Entity<Editor>,
+    ours_diff: Entity<BufferDiff>,
+    theirs_diff: Entity<BufferDiff>,
+    result_diff: Entity<BufferDiff>,
+    ours_label: SharedString,
+    theirs_label: SharedString,
+    /// Scroll positions we applied to the other panes while following a scroll, so that the
+    /// resulting scroll events aren't synchronized back.
+    synced_scroll_positions: HashMap<EntityId, ScrollOffset>,
+    buffer_changes_tx: watch::Sender<()>,
+    _recalculate_diff_task: Task<Result<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl MergeEditor {
+    pub fn open(
+        repository: Entity<Repository>,
+        repo_path: RepoPath,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        window.spawn(cx, async move |cx| {
+            let existing = workspace.update_in(cx, |workspace, window, cx| {
+                let existing = workspace.items_of_type::<Self>(cx).find(|merge_editor| {
+                    let merge_editor = merge_editor.read(cx);
+                    merge_editor.repository == repository && merge_editor.repo_path == repo_path
+                })?;
+                workspace.activate_item(&existing, true, true, window, cx);
+                Some(existing)
+            })?;
+            if let Some(existing) = existing {
+                return Ok(existing);
+            }
+
+            let project = workspace.read_with(cx, |workspace, _| workspace.project().clone())?;
+            let project_path = repository
+                .read_with(cx, |repository, cx| {
+                    repository.repo_path_to_project_path(&repo_path, cx)
+                })
+                .context("conflicted file is not part of the project")?;
+            let stages = repository
+                .update(cx, |repository, _| {
+                    repository.load_merge_stages(repo_path.clone())
+                })
+                .await??;
+            let buffer = project
+                .update(cx, |project, cx| project.open_buffer(project_path, cx))
+                .await?;
+            let languages = project.read_with(cx, |project, _| project.languages().clone());
+
+            let mut base_text = stages.base.unwrap_or_default();
+            language::LineEnding::normalize(&mut base_text);
+            let base_text: Arc<str> = base_text.into();
+            let base_buffer = build_stage_buffer(&base_text, &buffer, &languages, cx);
+            let ours_buffer =
+                build_stage_buffer(&stages.ours.unwrap_or_default(), &buffer, &languages, cx);
+            let theirs_buffer =
+                build_stage_buffer(&stages.theirs.unwrap_or_default(), &buffer, &languages, cx);
+
+            let ours_diff = build_diff(&base_text, &ours_buffer, cx).await;
+            let theirs_diff = build_diff(&base_text, &theirs_buffer, cx).await;
+            let result_diff = build_diff(&base_text, &buffer, cx).await;
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let merge_editor = cx.new(|cx| {
+                    MergeEditor::new(
+                        workspace.weak_handle(),
+                        project,
+                        repository,
+                        repo_path,
+                        buffer,
+                        [base_buffer, ours_buffer, theirs_buffer],
+                        [ours_diff, theirs_diff, result_diff],
+                        window,
+                        cx,
+                    )
+                });
+                workspace.add_item_to_active_pane(
+                    Box::new(merge_editor.clone()),
+                    None,
+                    true,
+                    window,
+                    cx,
+                );
+                merge_editor
+            })
+        })
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        repository: Entity<Repository>,
+        repo_path: RepoPath,
+        buffer: Entity<Buffer>,
+        [base_buffer, ours_buffer, theirs_buffer]: [Entity<Buffer>; 3],
+        [ours_diff, theirs_diff, result_diff]: [Entity<BufferDiff>; 3],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let base_editor = build_stage_editor(base_buffer, None, window, cx);
+        let ours_editor = build_stage_editor(ours_buffer, Some(ours_diff.clone()), window, cx);
+        let theirs_editor =
+            build_stage_editor(theirs_buffer, Some(theirs_diff.clone()), window, cx);
+        let result_multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::singleton(buffer.clone(), cx);
+            multibuffer.add_diff(result_diff.clone(), cx);
+            multibuffer
+        });
+        let result_editor = cx.new(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(result_multibuffer, Some(project.clone()), window, cx);
+            editor.start_temporary_diff_override();
+            editor
+        });
+
+        let conflict_set = project.update(cx, |project, cx| {
+            project.git_store().update(cx, |git_store, cx| {
+                git_store.open_conflict_set(buffer.clone(), cx)
+            })
+        });
+
+        let mut subscriptions =
+            vec![
+                cx.subscribe(&conflict_set, |this, _, _: &ConflictSetUpdate, cx| {
+                    this.update_labels(cx);
+                    cx.notify();
+                }),
+            ];
+        for editor in [&base_editor, &ours_editor, &theirs_editor, &result_editor] {
+            subscriptions.push(cx.subscribe_in(editor, window, Self::handle_editor_event));
+        }
+
+        let (buffer_changes_tx, mut buffer_changes_rx) = watch::channel(());
+        subscriptions.push(cx.subscribe(&buffer, |this, _, event, _| {
+            if let language::BufferEvent::Edited = event {
+                this.buffer_changes_tx.send(()).ok();
+            }
+        }));
+
+        let mut this = Self {
+            workspace,
+            project,
+            repository,
+            repo_path,
+            buffer,
+            conflict_set,
+            base_editor,
+            ours_editor,
+            theirs_editor,
+            result_editor,
+            ours_diff,
+            theirs_diff,
+            result_diff: result_diff.clone(),
+            ours_label: "Ours".into(),
+            theirs_label: "Theirs".into(),
+            synced_scroll_positions: HashMap::default(),
+            buffer_changes_tx,
+            _recalculate_diff_task: cx.spawn(async move |this, cx| {
+                while buffer_changes_rx.recv().await.is_ok() {
+                    loop {
+                        let mut timer = cx
+                            .background_executor()
+                            .timer(RECALCULATE_DIFF_DEBOUNCE)
+                            .fuse();
+                        let mut recv = pin!(buffer_changes_rx.recv().fuse());
+                        select_biased! {
+                            _ = timer => break,
+                            _ = recv => continue,
+                        }
+                    }
+
+                    let snapshot = this.update(cx, |this, cx| this.buffer.read(cx).snapshot())?;
+                    result_diff
+                        .update(cx, |diff, cx| {
+                            let base_text = diff.base_text_string(cx).map(Arc::from);
+                            diff.set_base_text(
+                                base_text,
+                                snapshot.language().cloned(),
+                                snapshot.text.clone(),
+                                cx,
+                            )
+                        })
+                        .await
+                        .ok();
+                }
+                Ok(())
+            }),
+            _subscriptions: subscriptions,
+        };
+        this.update_labels(cx);
+        this
+    }
+
+    fn update_labels(&mut self, cx: &App) {
+        if let Some(conflict) = self.conflict_set.read(cx).snapshot.conflicts.first() {
+            self.ours_label = conflict.ours_branch_name.clone();
+            self.theirs_label = conflict.theirs_branch_name.clone();
+        }
+    }
+
+    fn conflict_count(&self, cx: &App) -> usize {
+        self.conflict_set.read(cx).snapshot.conflicts.len()
+    }
+
+    /// Returns the conflict containing the cursor in the result pane, or the first one after it.
+    fn conflict_at_cursor(&self, cx: &App) -> Option<ConflictRegion> {
+        let snapshot = self.buffer.read(cx).snapshot();
+        let cursor_row = self.cursor_row(cx);
+        self.conflict_set
+            .read(cx)
+            .snapshot
+            .conflicts
+            .iter()
+            .find(|conflict| conflict.range.end.to_point(&snapshot).row >= cursor_row)
+            .cloned()
+    }
+
+    fn cursor_row(&self, cx: &App) -> u32 {
+        let editor = self.result_editor.read(cx);
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        editor
+            .selections
+            .newest_anchor()
+            .head()
+            .to_point(&snapshot)
+            .row
+    }
+
+    fn resolve(&mut self, resolution: Resolution, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(conflict) = self.conflict_at_cursor(cx) else {
+            return;
+        };
+        conflict.resolve(self.buffer.clone(), &resolution.kept_ranges(&conflict), cx);
+        self.select_conflict(true, window, cx);
+    }
+
+    fn accept_ours(&mut self, _: &AcceptOurs, window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve(Resolution::Ours, window, cx);
+    }
+
+    fn accept_theirs(&mut self, _: &AcceptTheirs, window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve(Resolution::Theirs, window, cx);
+    }
+
+    fn accept_both(&mut self, _: &AcceptBoth, window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve(Resolution::OursThenTheirs, window, cx);
+    }
+
+    fn accept_both_theirs_first(
+        &mut self,
+        _: &AcceptBothTheirsFirst,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.resolve(Resolution::TheirsThenOurs, window, cx);
+    }
+
+    fn next_conflict(&mut self, _: &NextConflict, window: &mut Window, cx: &mut Context<Self>) {
+        self.select_conflict(true, window, cx);
+    }
+
+    fn previous_conflict(
+        &mut self,
+        _: &PreviousConflict,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.select_conflict(false, window, cx);
+    }
+
+    /// Moves the cursor in the result pane to the start of the next or previous conflict,
+    /// wrapping around at either end of the file.
+    fn select_conflict(&mut self, forward: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot();
+        let cursor_row = self.cursor_row(cx);
+        let conflict_rows = self
+            .conflict_set
+            .read(cx)
+            .snapshot
+            .conflicts
+            .iter()
+            .map(|conflict| conflict.range.start.to_point(&snapshot).row)
+            .collect::<Vec<_>>();
+        let target_row = if forward {
+            conflict_rows
+                .iter()
+                .find(|row| **row > cursor_row)
+                .or(conflict_rows.first())
+        } else {
+            conflict_rows
+                .iter()
+                .rev()
+                .find(|row| **row < cursor_row)
+                .or(conflict_rows.last())
+        };
+        let Some(&target_row) = target_row else {
+            return;
+        };
+
+        let target = Point::new(target_row, 0);
+        self.result_editor.update(cx, |editor, cx| {
+            editor.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()),
+                window,
+                cx,
+                |selections| selections.select_ranges([target..target]),
+            );
+        });
+    }
+
+    fn mark_resolved(&mut self, _: &MarkResolved, window: &mut Window, cx: &mut Context<Self>) {
+        let remaining = self.conflict_count(cx);
+        let confirmation = if remaining > 0 {
+            let message = format!(
+                "{remaining} {} still {} conflict markers. Mark the file as resolved anyway?",
+                if remaining == 1 { "region" } else { "regions" },
+                if remaining == 1 { "has" } else { "have" },
+            );
+            Some(window.prompt(
+                PromptLevel::Warning,
+                &message,
+                None,
+                &["Mark as Resolved", "Cancel"],
+                cx,
+            ))
+        } else {
+            None
+        };
+
+        let buffer = self.buffer.clone();
+        let project = self.project.clone();
+        let repository = self.repository.clone();
+        let repo_path = self.repo_path.clone();
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            if let Some(confirmation) = confirmation
+                && !matches!(confirmation.await, Ok(0))
+            {
+                return Ok(());
+            }
+
+            project
+                .update(cx, |project, cx| project.save_buffer(buffer, cx))
+                .await?;
+            repository
+                .update(cx, |repository, cx| {
+                    repository.stage_entries(vec![repo_path], cx)
+                })
+                .await?;
+
+            let close = workspace.update_in(cx, |workspace, window, cx| {
+                let this = this.upgrade()?;
+                let pane = workspace.pane_for(&this)?;
+                Some(pane.update(cx, |pane, cx| {
+                    pane.close_item_by_id(this.entity_id(), SaveIntent::Skip, window, cx)
+                }))
+            })?;
+            if let Some(close) = close {
+                close.await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err(
+            "Failed to mark file as resolved",
+            window,
+            cx,
+            |_, _, _| None,
+        );
+    }
+
+    fn handle_editor_event(
+        &mut self,
+        editor: &Entity<Editor>,
+        event: &EditorEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let EditorEvent::ScrollPositionChanged { .. } = event {
+            self.sync_scroll_positions(editor, window, cx);
+        }
+        if editor == &self.result_editor {
+            cx.emit(event.clone());
+        }
+    }
+
+    fn pane_diff(&self, editor: &Entity<Editor>) -> Option<&Entity<BufferDiff>> {
+        if editor == &self.ours_editor {
+            Some(&self.ours_diff)
+        } else if editor == &self.theirs_editor {
+            Some(&self.theirs_diff)
+        } else if editor == &self.result_editor {
+            Some(&self.result_diff)
+        } else {
+            None
+        }
+    }
+
+    /// Scrolls every other pane so that it shows the lines corresponding to the top of `source`,
+    /// matching lines up through each pane's diff against the merge base.
+    fn sync_scroll_positions(
+        &mut self,
+        source: &Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let source_position = source.update(cx, |editor, cx| editor.scroll_position(cx).y);
+        if self.synced_scroll_positions.remove(&source.entity_id()) == Some(source_position) {
+            return;
+        }
+
+        let (source_row, row_fraction) = top_buffer_row(source, cx);
+        let base_row = match self.pane_diff(source) {
+            Some(diff) => {
+                let diff = diff.read(cx).snapshot(cx);
+                let buffer = buffer_snapshot(source, cx);
+                buffer_row_to_base_row(&diff, &buffer, source_row)
+            }
+            None => source_row,
+        };
+
+        let targets = [
+            &self.base_editor,
+            &self.ours_editor,
+            &self.theirs_editor,
+            &self.result_editor,
+        ]
+        .into_iter()
+        .filter(|editor| *editor != source)
+        .cloned()
+        .collect::<Vec<_>>();
+        for target in targets {
+            let target_row = match self.pane_diff(&target) {
+                Some(diff) => {
+                    let diff = diff.read(cx).snapshot(cx);
+                    let buffer = buffer_snapshot(&target, cx);
+                    base_row_to_buffer_row(&diff, &buffer, base_row)
+                }
+                None => base_row,
+            };
+            let position = target.update(cx, |editor, cx| {
+                let snapshot = editor.display_snapshot(cx);
+                let display_row = snapshot
+                    .point_to_display_point(Point::new(target_row, 0), Bias::Left)
+                    .row();
+                let current = editor.scroll_position(cx);
+                let position = point(current.x, display_row.0 as ScrollOffset + row_fraction);
+                (position != current).then(|| {
+                    editor.set_scroll_position(position, window, cx);
+                    position.y
+                })
+            });
+            if let Some(position) = position {
+                self.synced_scroll_positions
+                    .insert(target.entity_id(), position);
+            }
+        }
+    }
+
+    fn render_pane_header(
+        &self,
+        title: SharedString,
+        description: &'static str,
+        cx: &App,
+    ) -> impl IntoElement {
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new(title).size(LabelSize::Small))
+            .child(
+                Label::new(description)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let remaining = self.conflict_count(cx);
+        let has_conflicts = remaining > 0;
+        let status = if has_conflicts {
+            format!(
+                "{remaining} {} remaining",
+                if remaining == 1 {
+                    "conflict"
+                } else {
+                    "conflicts"
+                }
+            )
+        } else {
+            "No conflicts remaining".to_string()
+        };
+        let focus_handle = self.result_editor.focus_handle(cx);
+
+        h_flex()
+            .justify_between()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Label::new(status)
+                    .size(LabelSize::Small)
+                    .color(if has_conflicts {
+                        Color::Warning
+                    } else {
+                        Color::Success
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new("previous-conflict", IconName::ArrowUp)
+                            .icon_size(IconSize::Small)
+                            .disabled(!has_conflicts)
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Previous Conflict",
+                                &PreviousConflict,
+                                &focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.select_conflict(false, window, cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("next-conflict", IconName::ArrowDown)
+                            .icon_size(IconSize::Small)
+                            .disabled(!has_conflicts)
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Next Conflict",
+                                &NextConflict,
+                                &focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.select_conflict(true, window, cx)
+                            })),
+                    )
+                    .child(Divider::vertical())
+                    .child(self.render_resolution_button(
+                        "accept-ours",
+                        format!("Use {}", self.ours_label),
+                        Resolution::Ours,
+                        &AcceptOurs,
+                        has_conflicts,
+                        cx,
+                    ))
+                    .child(self.render_resolution_button(
+                        "accept-theirs",
+                        format!("Use {}", self.theirs_label),
+                        Resolution::Theirs,
+                        &AcceptTheirs,
+                        has_conflicts,
+                        cx,
+                    ))
+                    .child(self.render_resolution_button(
+                        "accept-both",
+                        "Use Both".to_string(),
+                        Resolution::OursThenTheirs,
+                        &AcceptBoth,
+                        has_conflicts,
+                        cx,
+                    ))
+                    .child(self.render_resolution_button(
+                        "accept-both-theirs-first",
+                        "Use Both (Theirs First)".to_string(),
+                        Resolution::TheirsThenOurs,
+                        &AcceptBothTheirsFirst,
+                        has_conflicts,
+                        cx,
+                    ))
+                    .child(Divider::vertical())
+                    .child(
+                        Button::new("mark-resolved", "Mark as Resolved")
+                            .label_size(LabelSize::Small)
+                            .style(ButtonStyle::Filled)
+                            .tooltip(Tooltip::for_action_title_in(
+                                "Save and Stage the File",
+                                &MarkResolved,
+                                &focus_handle,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.mark_resolved(&MarkResolved, window, cx)
+                            })),
+                    ),
+            )
+    }
+
+    fn render_resolution_button(
+        &self,
+        id: &'static str,
+        label: String,
+        resolution: Resolution,
+        action: &dyn Action,
+        enabled: bool,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let focus_handle = self.result_editor.focus_handle(cx);
+        let tooltip_title = format!("{label} for the Conflict at the Cursor");
+        let action = action.boxed_clone();
+        Button::new(id, label)
+            .label_size(LabelSize::Small)
+            .disabled(!enabled)
+            .tooltip(move |_window, cx| {
+                Tooltip::for_action_in(tooltip_title.clone(), &*action, &focus_handle, cx)
+            })
+            .on_click(cx.listener(move |this, _, window, cx| this.resolve(resolution, window, cx)))
+    }
+}
+
+fn build_stage_buffer(
+    text: &str,
+    buffer: &Entity<Buffer>,
+    languages: &Arc<LanguageRegistry>,
+    cx: &mut gpui::AsyncApp,
+) -> Entity<Buffer> {
+    let language = buffer.read_with(cx, |buffer, _| buffer.language().cloned());
+    cx.new(|cx| {
+        let mut buffer = Buffer::local(text, cx);
+        buffer.set_language_registry(languages.clone());
+        buffer.set_language(language, cx);
+        buffer.set_capability(language::Capability::ReadOnly, cx);
+        buffer
+    })
+}
+
+async fn build_diff(
+    base_text: &Arc<str>,
+    buffer: &Entity<Buffer>,
+    cx: &mut gpui::AsyncApp,
+) -> Entity<BufferDiff> {
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    let diff = cx.new(|cx| BufferDiff::new(&snapshot.text, cx));
+    diff.update(cx, |diff, cx| {
+        diff.set_base_text(
+            Some(base_text.clone()),
+            snapshot.language().cloned(),
+            snapshot.text.clone(),
+            cx,
+        )
+    })
+    .await
+    .ok();
+    diff
+}
+
+fn build_stage_editor(
+    buffer: Entity<Buffer>,
+    diff: Option<Entity<BufferDiff>>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Entity<Editor> {
+    let multibuffer = cx.new(|cx| {
+        let mut multibuffer = MultiBuffer::singleton(buffer, cx);
+        if let Some(diff) = diff {
+            multibuffer.add_diff(diff, cx);
+        }
+        multibuffer
+    });
+    cx.new(|cx| {
+        let mut editor = Editor::for_multibuffer(multibuffer, None, window, cx);
+        editor.set_read_only(true);
+        editor.disable_diagnostics(cx);
+        editor
+    })
+}
+
+fn buffer_snapshot(editor: &Entity<Editor>, cx: &App) -> language::TextBufferSnapshot {
+    editor
+        .read(cx)
+        .buffer()
+        .read(cx)
+        .as_singleton()
+        .expect("merge editor panes are singletons")
+        .read(cx)
+        .text_snapshot()
+}
+
+/// Returns the buffer row at the top of the editor's viewport, along with how far the viewport
+/// is scrolled into that row.
+fn top_buffer_row(editor: &Entity<Editor>, cx: &mut App) -> (u32, ScrollOffset) {
+    editor.update(cx, |editor, cx| {
+        let scroll_top = editor.scroll_position(cx).y;
+        let display_row = scroll_top.floor();
+        let snapshot = editor.display_snapshot(cx);
+        let point = snapshot.display_point_to_point(
+            DisplayPoint::new(DisplayRow(display_row as u32), 0),
+            Bias::Left,
+        );
+        (point.row, scroll_top - display_row)
+    })
+}
+
+/// Maps a row of a buffer to the row of the diff's base text it corresponds to. Rows inside a
+/// hunk map to the same offset into the hunk's base text, clamped to its length.
+fn buffer_row_to_base_row(
+    diff: &BufferDiffSnapshot,
+    buffer: &language::TextBufferSnapshot,
+    row: u32,
+) -> u32 {
+    let base_text = diff.base_text();
+    let mut delta = 0i64;
+    for hunk in diff.hunks(buffer) {
+        if row < hunk.range.start.row {
+            break;
+        }
+        let base_start = base_text
+            .offset_to_point(hunk.diff_base_byte_range.start)
+            .row;
+        let base_end = base_text.offset_to_point(hunk.diff_base_byte_range.end).row;
+        if row < hunk.range.end.row {
+            return (base_start + (row - hunk.range.start.row)).min(base_end.max(base_start));
+        }
+        delta = base_end as i64 - hunk.range.end.row as i64;
+    }
+    (row as i64 + delta).max(0) as u32
+}
+
+/// The inverse of [`buffer_row_to_base_row`].
+fn base_row_to_buffer_row(
+    diff: &BufferDiffSnapshot,
+    buffer: &language::TextBufferSnapshot,
+    base_row: u32,
+) -> u32 {
+    let base_text = diff.base_text();
+    let mut delta = 0i64;
+    for hunk in diff.hunks(buffer) {
+        let base_start = base_text
+            .offset_to_point(hunk.diff_base_byte_range.start)
+            .row;
+        if base_row < base_start {
+            break;
+        }
+        let base_end = base_text.offset_to_point(hunk.diff_base_byte_range.end).row;
+        if base_row < base_end {
+            return (hunk.range.start.row + (base_row - base_start))
+                .min(hunk.range.end.row.max(hunk.range.start.row));
+        }
+        delta = hunk.range.end.row as i64 - base_end as i64;
+    }
+    (base_row as i64 + delta).max(0) as u32
+}
+
+impl EventEmitter<EditorEvent> for MergeEditor {}
+
+impl Focusable for MergeEditor {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.result_editor.focus_handle(cx)
+    }
+}
+
+impl Item for MergeEditor {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranchAlt).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _: &App) -> SharedString {
+        let file_name = self
+            .repo_path
+            .file_name()
+            .unwrap_or(self.repo_path.as_unix_str());
+        format!("Merge {file_name}").into()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(format!("Merge {}", self.repo_path.as_unix_str()).into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: &mut dyn FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Merge Editor Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.result_editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.result_editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.result_editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.result_editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Arc<dyn Any + Send>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.result_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.result_editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.result_editor
+            .update(cx, |editor, cx| editor.save(options, project, window, cx))
+    }
+}
+
+impl Render for MergeEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let border_color = cx.theme().colors().border;
+        v_flex()
+            .key_context("MergeEditor")
+            .on_action(cx.listener(Self::accept_ours))
+            .on_action(cx.listener(Self::accept_theirs))
+            .on_action(cx.listener(Self::accept_both))
+            .on_action(cx.listener(Self::accept_both_theirs_first))
+            .on_action(cx.listener(Self::next_conflict))
+            .on_action(cx.listener(Self::previous_conflict))
+            .on_action(cx.listener(Self::mark_resolved))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_toolbar(cx))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .border_b_1()
+                    .border_color(border_color)
+                    .child(
+                        v_flex()
+                            .flex_1()
+                            .min_w_0()
+                            .h_full()
+                            .child(self.render_pane_header("Base".into(), "Common Ancestor", cx))
+                            .child(self.base_editor.clone()),
+                    )
+                    .child(
+                        v_flex()
+                            .flex_1()
+                            .min_w_0()
+                            .h_full()
+                            .border_l_1()
+                            .border_color(border_color)
+                            .child(self.render_pane_header(self.ours_label.clone(), "Ours", cx))
+                            .child(self.ours_editor.clone()),
+                    )
+                    .child(
+                        v_flex()
+                            .flex_1()
+                            .min_w_0()
+                            .h_full()
+                            .border_l_1()
+                            .border_color(border_color)
+                            .child(self.render_pane_header(self.theirs_label.clone(), "Theirs", cx))
+                            .child(self.theirs_editor.clone()),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .child(self.render_pane_header("Result".into(), "Working Copy", cx))
+                    .child(self.result_editor.clone()),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::{
+        repository::MergeStages,
+        status::{UnmergedStatus, UnmergedStatusCode},
+    };
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::{path, rel_path::rel_path};
+    use workspace::MultiWorkspace;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    async fn open_merge_editor(
+        cx: &mut TestAppContext,
+    ) -> (Entity<MergeEditor>, Arc<FakeFs>, &mut VisualTestContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "foo.txt": "one\n<<<<<<< HEAD\ntwo ours\n=======\ntwo theirs\n>>>>>>> feature\nthree\n",
+            }),
+        )
+        .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        fs.set_head_and_index_for_repo(dot_git, &[("foo.txt", "one\ntwo\nthree\n".into())]);
+        fs.set_status_for_repo(
+            dot_git,
+            &[(
+                "foo.txt",
+                UnmergedStatus {
+                    first_head: UnmergedStatusCode::Updated,
+                    second_head: UnmergedStatusCode::Updated,
+                }
+                .into(),
+            )],
+        );
+        fs.set_merge_stages_for_repo(
+            dot_git,
+            &[(
+                "foo.txt",
+                MergeStages {
+                    base: Some("one\ntwo\nthree\n".into()),
+                    ours: Some("one\ntwo ours\nthree\n".into()),
+                    theirs: Some("one\ntwo theirs\nthree\n".into()),
+                },
+            )],
+        );
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+        cx.run_until_parked();
+
+        let repository = project.read_with(cx, |project, cx| {
+            project.active_repository(cx).expect("no active repository")
+        });
+        let merge_editor = cx
+            .update(|window, cx| {
+                MergeEditor::open(
+                    repository,
+                    RepoPath::from_rel_path(rel_path("foo.txt")),
+                    workspace.downgrade(),
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        (merge_editor, fs, cx)
+    }
+
+    #[gpui::test]
+    async fn test_merge_editor_shows_stages(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (merge_editor, _fs, cx) = open_merge_editor(cx).await;
+
+        merge_editor.read_with(cx, |merge_editor, cx| {
+            assert_eq!(
+                merge_editor.base_editor.read(cx).text(cx),
+                "one\ntwo\nthree\n"
+            );
+            assert_eq!(
+                merge_editor.ours_editor.read(cx).text(cx),
+                "one\ntwo ours\nthree\n"
+            );
+            assert_eq!(
+                merge_editor.theirs_editor.read(cx).text(cx),
+                "one\ntwo theirs\nthree\n"
+            );
+            assert_eq!(merge_editor.conflict_count(cx), 1);
+            assert_eq!(merge_editor.ours_label.as_ref(), "HEAD");
+            assert_eq!(merge_editor.theirs_label.as_ref(), "feature");
+        });
+    }
+
+    #[gpui::test]
+    async fn test_merge_editor_resolve_and_stage(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (merge_editor, fs, cx) = open_merge_editor(cx).await;
+
+        cx.focus(&merge_editor);
+        cx.dispatch_action(AcceptBothTheirsFirst);
+        cx.run_until_parked();
+        merge_editor.read_with(cx, |merge_editor, cx| {
+            assert_eq!(
+                merge_editor.buffer.read(cx).text(),
+                "one\ntwo theirs\ntwo ours\nthree\n"
+            );
+            assert_eq!(merge_editor.conflict_count(cx), 0);
+        });
+
+        cx.dispatch_action(MarkResolved);
+        cx.run_until_parked();
+
+        let contents =
+            String::from_utf8(fs.read_file_sync(path!("/project/foo.txt")).unwrap()).unwrap();
+        assert_eq!(contents, "one\ntwo theirs\ntwo ours\nthree\n");
+        fs.with_git_state(Path::new(path!("/project/.git")), false, |state| {
+            let repo_path = RepoPath::from_rel_path(rel_path("foo.txt"));
+            assert!(!state.unmerged_paths.contains_key(&repo_path));
+            assert_eq!(
+                state.index_contents.get(&repo_path).map(String::as_str),
+                Some("one\ntwo theirs\ntwo ours\nthree\n")
+            );
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_row_mapping(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("a\nB1\nB2\nB3\nc\ne\n", cx));
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.text_snapshot());
+        let diff = cx.new(|cx| BufferDiff::new_with_base_text("a\nb\nc\nd\ne\n", &snapshot, cx));
+        cx.run_until_parked();
+        let diff = diff.read_with(cx, |diff, cx| diff.snapshot(cx));
+
+        assert_eq!(buffer_row_to_base_row(&diff, &snapshot, 0), 0);
+        assert_eq!(buffer_row_to_base_row(&diff, &snapshot, 1), 1);
+        assert_eq!(buffer_row_to_base_row(&diff, &snapshot, 3), 2);
+        assert_eq!(buffer_row_to_base_row(&diff, &snapshot, 4), 2);
+        assert_eq!(buffer_row_to_base_row(&diff, &snapshot, 5), 4);
+
+        assert_eq!(base_row_to_buffer_row(&diff, &snapshot, 1), 1);
+        assert_eq!(base_row_to_buffer_row(&diff, &snapshot, 2), 4);
+        assert_eq!(base_row_to_buffer_row(&diff, &snapshot, 4), 5);
+    }
+}
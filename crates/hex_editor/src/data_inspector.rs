@@ -0,0 +1,98 @@
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endianness {
+    pub fn label(self) -> &'static str {
+        match self {
+            Endianness::Little => "LE",
+            Endianness::Big => "BE",
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            Endianness::Little => Endianness::Big,
+            Endianness::Big => Endianness::Little,
+        }
+    }
+}
+
+/// Interprets the bytes at the start of `bytes` as each of the supported numeric types.
+///
+/// Types that need more bytes than are available are omitted.
+pub fn inspect_bytes(bytes: &[u8], endianness: Endianness) -> Vec<(&'static str, String)> {
+    macro_rules! decode {
+        ($values:ident, $label:literal, $ty:ty) => {
+            if let Some(chunk) = bytes.first_chunk::<{ size_of::<$ty>() }>() {
+                let value = match endianness {
+                    Endianness::Little => <$ty>::from_le_bytes(*chunk),
+                    Endianness::Big => <$ty>::from_be_bytes(*chunk),
+                };
+                $values.push(($label, value.to_string()));
+            }
+        };
+    }
+
+    let mut values = Vec::new();
+    decode!(values, "u8", u8);
+    decode!(values, "i8", i8);
+    decode!(values, "u16", u16);
+    decode!(values, "i16", i16);
+    decode!(values, "u32", u32);
+    decode!(values, "i32", i32);
+    decode!(values, "u64", u64);
+    decode!(values, "i64", i64);
+    decode!(values, "f32", f32);
+    decode!(values, "f64", f64);
+    if let Some(&byte) = bytes.first() {
+        values.push(("binary", format!("{byte:08b}")));
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value<'a>(values: &'a [(&'static str, String)], label: &str) -> Option<&'a str> {
+        values
+            .iter()
+            .find(|(l, _)| *l == label)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn test_inspect_bytes() {
+        let bytes = [0xFE, 0xFF, 0x00, 0x00, 0x00, 0x00, 0xF0, 0x3F];
+
+        let values = inspect_bytes(&bytes, Endianness::Little);
+        assert_eq!(value(&values, "u8"), Some("254"));
+        assert_eq!(value(&values, "i8"), Some("-2"));
+        assert_eq!(value(&values, "u16"), Some("65534"));
+        assert_eq!(value(&values, "i16"), Some("-2"));
+        assert_eq!(value(&values, "u32"), Some("65534"));
+        assert_eq!(value(&values, "binary"), Some("11111110"));
+
+        let values = inspect_bytes(&bytes, Endianness::Big);
+        assert_eq!(value(&values, "u16"), Some("65279"));
+        assert_eq!(value(&values, "u32"), Some("4278124544"));
+
+        let values = inspect_bytes(&1.5f64.to_le_bytes(), Endianness::Little);
+        assert_eq!(value(&values, "f64"), Some("1.5"));
+        let values = inspect_bytes(&(-0.25f32).to_be_bytes(), Endianness::Big);
+        assert_eq!(value(&values, "f32"), Some("-0.25"));
+    }
+
+    #[test]
+    fn test_inspect_bytes_omits_types_that_do_not_fit() {
+        let values = inspect_bytes(&[0x01, 0x02, 0x03], Endianness::Little);
+        let labels = values.iter().map(|(label, _)| *label).collect::<Vec<_>>();
+        assert_eq!(labels, ["u8", "i8", "u16", "i16", "binary"]);
+
+        assert!(inspect_bytes(&[], Endianness::Little).is_empty());
+    }
+}
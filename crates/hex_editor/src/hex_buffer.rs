@@ -0,0 +1,492 @@
+use std::{ops::Range, path::PathBuf};
+
+use anyhow::Result;
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Task};
+use project::{Project, ProjectEntryId, ProjectPath, Worktree};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HexBufferEvent {
+    Edited,
+    Saved,
+    Reloaded,
+}
+
+/// The bytes of a file opened in the hex editor, along with their edit history.
+///
+/// The file is read and written through its worktree, bypassing the text buffer machinery.
+pub struct HexBuffer {
+    abs_path: PathBuf,
+    project_path: ProjectPath,
+    entry_id: Option<ProjectEntryId>,
+    worktree: Entity<Worktree>,
+    contents: ByteContents,
+}
+
+impl EventEmitter<HexBufferEvent> for HexBuffer {}
+
+impl HexBuffer {
+    pub fn open(
+        project: &Entity<Project>,
+        project_path: ProjectPath,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let project = project.read(cx);
+        let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx) else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "no worktree found for {project_path:?}"
+            )));
+        };
+        let abs_path = worktree.read(cx).absolutize(&project_path.path);
+        let entry_id = project
+            .entry_for_path(&project_path, cx)
+            .map(|entry| entry.id);
+        let load = worktree.update(cx, |worktree, cx| {
+            worktree.load_bytes(&project_path.path, None, cx)
+        });
+
+        cx.spawn(async move |cx| {
+            let bytes = load.await?;
+            Ok(cx.new(|_| Self {
+                abs_path,
+                project_path,
+                entry_id,
+                worktree,
+                contents: ByteContents::new(bytes),
+            }))
+        })
+    }
+
+    pub fn abs_path(&self) -> &PathBuf {
+        &self.abs_path
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        self.contents.bytes()
+    }
+
+    pub fn len(&self) -> usize {
+        self.contents.bytes().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contents.bytes().is_empty()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.contents.is_dirty()
+    }
+
+    /// Replaces the bytes starting at `offset` with `new_bytes`, growing the file if needed.
+    pub fn overwrite(&mut self, offset: usize, new_bytes: &[u8], cx: &mut Context<Self>) {
+        let end = (offset + new_bytes.len()).min(self.len());
+        self.edit(offset..end, new_bytes, cx);
+    }
+
+    pub fn insert(&mut self, offset: usize, new_bytes: &[u8], cx: &mut Context<Self>) {
+        self.edit(offset..offset, new_bytes, cx);
+    }
+
+    pub fn delete(&mut self, range: Range<usize>, cx: &mut Context<Self>) {
+        self.edit(range, &[], cx);
+    }
+
+    fn edit(&mut self, range: Range<usize>, new_bytes: &[u8], cx: &mut Context<Self>) {
+        if self.contents.edit(range, new_bytes) {
+            cx.emit(HexBufferEvent::Edited);
+            cx.notify();
+        }
+    }
+
+    /// Groups the following edits into one undo step, until [`Self::end_transaction`] is called.
+    pub fn start_transaction(&mut self) {
+        self.contents.start_transaction();
+    }
+
+    pub fn end_transaction(&mut self) {
+        self.contents.end_transaction();
+    }
+
+    /// Undoes the last edit, returning the range of bytes it restored.
+    pub fn undo(&mut self, cx: &mut Context<Self>) -> Option<Range<usize>> {
+        let range = self.contents.undo()?;
+        cx.emit(HexBufferEvent::Edited);
+        cx.notify();
+        Some(range)
+    }
+
+    /// Redoes the last undone edit, returning the range of bytes it wrote.
+    pub fn redo(&mut self, cx: &mut Context<Self>) -> Option<Range<usize>> {
+        let range = self.contents.redo()?;
+        cx.emit(HexBufferEvent::Edited);
+        cx.notify();
+        Some(range)
+    }
+
+    pub fn find(&self, pattern: &[u8], from: usize, reverse: bool) -> Option<usize> {
+        find_bytes(self.bytes(), pattern, from, reverse)
+    }
+
+    pub fn save(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let path = self.project_path.path.clone();
+        let bytes = self.bytes().to_vec();
+        let write = self
+            .worktree
+            .update(cx, |worktree, cx| worktree.write_bytes(path, bytes, cx));
+        cx.spawn(async move |this, cx| {
+            write.await?;
+            this.update(cx, |this, cx| {
+                this.contents.mark_saved();
+                cx.emit(HexBufferEvent::Saved);
+                cx.notify();
+            })
+        })
+    }
+
+    pub fn save_as(
+        &mut self,
+        project_path: ProjectPath,
+        project: &Entity<Project>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(worktree) = project
+            .read(cx)
+            .worktree_for_id(project_path.worktree_id, cx)
+        else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "no worktree found for {project_path:?}"
+            )));
+        };
+        self.abs_path = worktree.read(cx).absolutize(&project_path.path);
+        self.project_path = project_path;
+        self.entry_id = None;
+        self.worktree = worktree;
+        self.save(cx)
+    }
+
+    pub fn reload(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let load = self.worktree.update(cx, |worktree, cx| {
+            worktree.load_bytes(&self.project_path.path, None, cx)
+        });
+        cx.spawn(async move |this, cx| {
+            let bytes = load.await?;
+            this.update(cx, |this, cx| {
+                this.contents = ByteContents::new(bytes);
+                cx.emit(HexBufferEvent::Reloaded);
+                cx.notify();
+            })
+        })
+    }
+}
+
+impl project::ProjectItem for HexBuffer {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        // The hex editor is registered before the text editor, so it's only asked to open
+        // the files that the text editor declined as binary.
+        project
+            .read(cx)
+            .entry_for_path(path, cx)
+            .is_some_and(|entry| entry.is_file())
+            .then(|| Self::open(project, path.clone(), cx))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.contents.is_dirty()
+    }
+}
+
+/// A single splice of the byte contents, recorded so it can be undone.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ByteEdit {
+    offset: usize,
+    old_bytes: Vec<u8>,
+    new_bytes: Vec<u8>,
+}
+
+/// The edits that are undone and redone together, in the order they were made.
+type Transaction = Vec<ByteEdit>;
+
+/// Byte contents with linear undo and redo history.
+#[derive(Debug, Default)]
+struct ByteContents {
+    bytes: Vec<u8>,
+    undo_stack: Vec<Transaction>,
+    redo_stack: Vec<Transaction>,
+    /// The depth of the undo stack when the contents were last saved, or `None` if
+    /// that state can no longer be reached through undo and redo.
+    saved_depth: Option<usize>,
+    /// The depth of the undo stack when the open transaction was started. Edits are added to
+    /// the transaction right above that depth.
+    transaction_depth: Option<usize>,
+}
+
+impl ByteContents {
+    fn new(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            saved_depth: Some(0),
+            ..Default::default()
+        }
+    }
+
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.saved_depth != Some(self.undo_stack.len())
+    }
+
+    fn mark_saved(&mut self) {
+        self.saved_depth = Some(self.undo_stack.len());
+    }
+
+    /// Replaces `range` with `new_bytes`, returning whether anything changed.
+    fn edit(&mut self, range: Range<usize>, new_bytes: &[u8]) -> bool {
+        let start = range.start.min(self.bytes.len());
+        let end = range.end.clamp(start, self.bytes.len());
+        if self.bytes[start..end] == *new_bytes {
+            return false;
+        }
+
+        let old_bytes = self
+            .bytes
+            .splice(start..end, new_bytes.iter().copied())
+            .collect();
+        let edit = ByteEdit {
+            offset: start,
+            old_bytes,
+            new_bytes: new_bytes.to_vec(),
+        };
+        self.redo_stack.clear();
+
+        // Changing the last undo step makes the state saved at its end unreachable too.
+        let kept_depth = match self.transaction_depth {
+            Some(depth) if depth + 1 == self.undo_stack.len() => depth,
+            _ => self.undo_stack.len(),
+        };
+        if self.saved_depth.is_some_and(|depth| depth > kept_depth) {
+            self.saved_depth = None;
+        }
+        if kept_depth < self.undo_stack.len() {
+            self.undo_stack.last_mut().unwrap().push(edit);
+        } else {
+            self.undo_stack.push(vec![edit]);
+        }
+        true
+    }
+
+    fn start_transaction(&mut self) {
+        self.transaction_depth = Some(self.undo_stack.len());
+    }
+
+    fn end_transaction(&mut self) {
+        self.transaction_depth = None;
+    }
+
+    /// Undoes the last transaction, returning the range of bytes restored by its first edit.
+    fn undo(&mut self) -> Option<Range<usize>> {
+        self.end_transaction();
+        let transaction = self.undo_stack.pop()?;
+        for edit in transaction.iter().rev() {
+            self.bytes.splice(
+                edit.offset..edit.offset + edit.new_bytes.len(),
+                edit.old_bytes.iter().copied(),
+            );
+        }
+        let first_edit = transaction.first()?;
+        let range = first_edit.offset..first_edit.offset + first_edit.old_bytes.len();
+        self.redo_stack.push(transaction);
+        Some(range)
+    }
+
+    /// Redoes the last undone transaction, returning the range of bytes written by its last edit.
+    fn redo(&mut self) -> Option<Range<usize>> {
+        self.end_transaction();
+        let transaction = self.redo_stack.pop()?;
+        for edit in &transaction {
+            self.bytes.splice(
+                edit.offset..edit.offset + edit.old_bytes.len(),
+                edit.new_bytes.iter().copied(),
+            );
+        }
+        let last_edit = transaction.last()?;
+        let range = last_edit.offset..last_edit.offset + last_edit.new_bytes.len();
+        self.undo_stack.push(transaction);
+        Some(range)
+    }
+}
+
+/// Finds the next occurrence of `pattern` starting at `from` (or, when `reverse`
+/// is set, the closest one before it), wrapping around the end of `bytes`.
+pub fn find_bytes(bytes: &[u8], pattern: &[u8], from: usize, reverse: bool) -> Option<usize> {
+    if pattern.is_empty() || pattern.len() > bytes.len() {
+        return None;
+    }
+    let last_start = bytes.len() - pattern.len();
+    let matches_at = |offset: usize| bytes[offset..offset + pattern.len()] == *pattern;
+    let from = from.min(last_start + 1);
+
+    if reverse {
+        (0..from)
+            .rev()
+            .chain((from..=last_start).rev())
+            .find(|&offset| matches_at(offset))
+    } else {
+        (from..=last_start)
+            .chain(0..from)
+            .find(|&offset| matches_at(offset))
+    }
+}
+
+/// Parses a search query into the bytes to search for.
+///
+/// Hex queries are pairs of hex digits, optionally separated by whitespace
+/// (e.g. `DE AD be ef`); text queries are searched for as UTF-8.
+pub fn parse_byte_pattern(query: &str, hex: bool) -> Option<Vec<u8>> {
+    if !hex {
+        return (!query.is_empty()).then(|| query.as_bytes().to_vec());
+    }
+
+    let digits = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_digit(16).map(|digit| digit as u8))
+        .collect::<Option<Vec<_>>>()?;
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        return None;
+    }
+    Some(
+        digits
+            .chunks(2)
+            .map(|pair| (pair[0] << 4) | pair[1])
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_undo_redo() {
+        let mut contents = ByteContents::new(vec![0x00, 0x11, 0x22, 0x33]);
+        assert!(!contents.is_dirty());
+
+        // Overwrite
+        assert!(contents.edit(1..2, &[0xFF]));
+        assert_eq!(contents.bytes(), &[0x00, 0xFF, 0x22, 0x33]);
+        // Insert
+        assert!(contents.edit(4..4, &[0x44, 0x55]));
+        assert_eq!(contents.bytes(), &[0x00, 0xFF, 0x22, 0x33, 0x44, 0x55]);
+        // Delete
+        assert!(contents.edit(0..2, &[]));
+        assert_eq!(contents.bytes(), &[0x22, 0x33, 0x44, 0x55]);
+        // No-op edits aren't recorded
+        assert!(!contents.edit(0..1, &[0x22]));
+        assert!(contents.is_dirty());
+
+        assert_eq!(contents.undo(), Some(0..2));
+        assert_eq!(contents.bytes(), &[0x00, 0xFF, 0x22, 0x33, 0x44, 0x55]);
+        assert_eq!(contents.undo(), Some(4..4));
+        assert_eq!(contents.undo(), Some(1..2));
+        assert_eq!(contents.bytes(), &[0x00, 0x11, 0x22, 0x33]);
+        assert!(!contents.is_dirty());
+        assert_eq!(contents.undo(), None);
+
+        assert_eq!(contents.redo(), Some(1..2));
+        assert_eq!(contents.bytes(), &[0x00, 0xFF, 0x22, 0x33]);
+        assert!(contents.is_dirty());
+    }
+
+    #[test]
+    fn test_dirty_tracking_after_save() {
+        let mut contents = ByteContents::new(vec![0x00; 4]);
+        contents.edit(0..1, &[0x01]);
+        contents.mark_saved();
+        assert!(!contents.is_dirty());
+
+        contents.undo();
+        assert!(contents.is_dirty());
+        contents.redo();
+        assert!(!contents.is_dirty());
+
+        // Once the saved state is discarded from the redo stack, it can't be reached again.
+        contents.undo();
+        contents.edit(1..2, &[0x02]);
+        contents.undo();
+        assert!(contents.is_dirty());
+    }
+
+    #[test]
+    fn test_transactions() {
+        let mut contents = ByteContents::new(vec![0x00, 0x11, 0x22, 0x33]);
+        contents.start_transaction();
+        contents.edit(1..3, &[]);
+        contents.edit(1..1, &[0xA0]);
+        contents.edit(1..2, &[0xAB]);
+        contents.end_transaction();
+        contents.edit(0..1, &[0xFF]);
+        assert_eq!(contents.bytes(), &[0xFF, 0xAB, 0x33]);
+
+        assert_eq!(contents.undo(), Some(0..1));
+        assert_eq!(contents.undo(), Some(1..3));
+        assert_eq!(contents.bytes(), &[0x00, 0x11, 0x22, 0x33]);
+        assert!(!contents.is_dirty());
+        assert_eq!(contents.redo(), Some(1..2));
+        assert_eq!(contents.bytes(), &[0x00, 0xAB, 0x33]);
+
+        // Saving in the middle of a transaction and then extending it leaves the contents dirty.
+        contents.start_transaction();
+        contents.edit(2..3, &[0x30]);
+        contents.mark_saved();
+        contents.edit(2..3, &[0x34]);
+        contents.end_transaction();
+        assert!(contents.is_dirty());
+        contents.undo();
+        assert_eq!(contents.bytes(), &[0x00, 0xAB, 0x33]);
+        assert!(contents.is_dirty());
+    }
+
+    #[test]
+    fn test_find_bytes() {
+        let bytes = [0xDE, 0xAD, 0x00, 0xDE, 0xAD, 0xBE, 0xEF];
+        assert_eq!(find_bytes(&bytes, &[0xDE, 0xAD], 0, false), Some(0));
+        assert_eq!(find_bytes(&bytes, &[0xDE, 0xAD], 1, false), Some(3));
+        // Wraps around the end
+        assert_eq!(find_bytes(&bytes, &[0xDE, 0xAD], 4, false), Some(0));
+        assert_eq!(find_bytes(&bytes, &[0xDE, 0xAD], 3, true), Some(0));
+        assert_eq!(find_bytes(&bytes, &[0xDE, 0xAD], 0, true), Some(3));
+        assert_eq!(find_bytes(&bytes, &[0xBE, 0xEF], 0, false), Some(5));
+        assert_eq!(find_bytes(&bytes, &[0x12], 0, false), None);
+        assert_eq!(find_bytes(&bytes, &[], 0, false), None);
+    }
+
+    #[test]
+    fn test_parse_byte_pattern() {
+        assert_eq!(
+            parse_byte_pattern("DE AD be ef", true),
+            Some(vec![0xDE, 0xAD, 0xBE, 0xEF])
+        );
+        assert_eq!(
+            parse_byte_pattern("deadbeef", true),
+            Some(vec![0xDE, 0xAD, 0xBE, 0xEF])
+        );
+        assert_eq!(parse_byte_pattern("ABC", true), None);
+        assert_eq!(parse_byte_pattern("XY", true), None);
+        assert_eq!(parse_byte_pattern("", true), None);
+        assert_eq!(parse_byte_pattern("ELF", false), Some(b"ELF".to_vec()));
+        assert_eq!(parse_byte_pattern("", false), None);
+    }
+}
@@ -0,0 +1,1157 @@
+mod data_inspector;
+mod hex_buffer;
+
+use std::{mem, ops::Range, path::Path};
+
+use anyhow::Result;
+use editor::{Editor, EditorSettings, actions::SelectAll};
+use file_icons::FileIcons;
+use gpui::{
+    App, Context, ElementId, Entity, EventEmitter, FocusHandle, Focusable, KeyDownEvent,
+    MouseButton, MouseDownEvent, ScrollStrategy, Subscription, Task, UniformListScrollHandle,
+    Window, actions, uniform_list,
+};
+use project::{Project, ProjectPath};
+use settings::Settings;
+use theme::ThemeSettings;
+use ui::{Tooltip, prelude::*};
+use util::paths::PathExt;
+use workspace::{
+    ItemSettings, Pane, ToolbarItemLocation, Workspace, WorkspaceId,
+    invalid_item_view::InvalidItemView,
+    item::{BreadcrumbText, Item, ItemBufferKind, ItemEvent, ProjectItem, SaveOptions},
+};
+use zed_actions::hex_editor::OpenInHexEditor;
+
+pub use crate::data_inspector::*;
+pub use crate::hex_buffer::*;
+
+actions!(
+    hex_editor,
+    [
+        /// Moves the cursor to the previous byte.
+        MoveLeft,
+        /// Moves the cursor to the next byte.
+        MoveRight,
+        /// Moves the cursor up one row.
+        MoveUp,
+        /// Moves the cursor down one row.
+        MoveDown,
+        /// Extends the selection to the previous byte.
+        SelectLeft,
+        /// Extends the selection to the next byte.
+        SelectRight,
+        /// Extends the selection up one row.
+        SelectUp,
+        /// Extends the selection down one row.
+        SelectDown,
+        /// Moves the cursor to the start of the row.
+        MoveToRowStart,
+        /// Moves the cursor to the end of the row.
+        MoveToRowEnd,
+        /// Moves the cursor to the first byte of the file.
+        MoveToStart,
+        /// Moves the cursor past the last byte of the file.
+        MoveToEnd,
+        /// Moves the cursor up one page.
+        PageUp,
+        /// Moves the cursor down one page.
+        PageDown,
+        /// Deletes the selection, or the byte before the cursor in insert mode.
+        Backspace,
+        /// Deletes the selection, or the byte under the cursor.
+        Delete,
+        /// Switches between overwriting and inserting bytes when typing.
+        ToggleInsertMode,
+        /// Switches typing between the hex and text columns.
+        ToggleColumn,
+        /// Undoes the last edit.
+        Undo,
+        /// Redoes the last undone edit.
+        Redo,
+        /// Focuses the byte pattern search input.
+        FocusSearch,
+        /// Selects the next match of the search pattern.
+        FindNext,
+        /// Selects the previous match of the search pattern.
+        FindPrevious,
+        /// Switches the search pattern between hex bytes and text.
+        ToggleSearchMode,
+        /// Switches the data inspector between little and big endian.
+        ToggleEndianness,
+    ]
+);
+
+const BYTES_PER_ROW: usize = 16;
+const INSPECTOR_BYTE_COUNT: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Column {
+    Hex,
+    Text,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EditMode {
+    Overwrite,
+    Insert,
+}
+
+/// An editor for the raw bytes of a file, showing offset, hex, and text columns.
+pub struct HexEditor {
+    buffer: Entity<HexBuffer>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    /// The offset of the byte under the cursor. May be equal to the buffer's length,
+    /// so bytes can be appended.
+    cursor: usize,
+    selection_anchor: Option<usize>,
+    column: Column,
+    mode: EditMode,
+    /// Set after the first hex digit of a byte is typed, so the next digit completes it.
+    pending_nibble: bool,
+    endianness: Endianness,
+    search_editor: Entity<Editor>,
+    search_is_hex: bool,
+    search_status: Option<SharedString>,
+    visible_rows: usize,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl HexEditor {
+    pub fn new(
+        buffer: Entity<HexBuffer>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let search_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Search bytes (e.g. 7F 45 4C 46)…", window, cx);
+            editor
+        });
+        let subscriptions = vec![cx.subscribe(&buffer, Self::on_buffer_event)];
+
+        Self {
+            buffer,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            cursor: 0,
+            selection_anchor: None,
+            column: Column::Hex,
+            mode: EditMode::Overwrite,
+            pending_nibble: false,
+            endianness: Endianness::default(),
+            search_editor,
+            search_is_hex: true,
+            search_status: None,
+            visible_rows: 0,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    pub fn buffer(&self) -> &Entity<HexBuffer> {
+        &self.buffer
+    }
+
+    fn on_buffer_event(
+        &mut self,
+        _: Entity<HexBuffer>,
+        event: &HexBufferEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let len = self.buffer.read(cx).len();
+        self.cursor = self.cursor.min(len);
+        self.selection_anchor = self.selection_anchor.map(|anchor| anchor.min(len));
+        if *event == HexBufferEvent::Reloaded {
+            self.pending_nibble = false;
+        }
+        cx.emit(HexEditorEvent::from(event.clone()));
+        cx.notify();
+    }
+
+    /// The range of bytes that edits and the data inspector apply to.
+    fn selection(&self, cx: &App) -> Range<usize> {
+        let len = self.buffer.read(cx).len();
+        let (start, end) = match self.selection_anchor {
+            Some(anchor) => (anchor.min(self.cursor), anchor.max(self.cursor) + 1),
+            None => (self.cursor, self.cursor + 1),
+        };
+        start.min(len)..end.min(len)
+    }
+
+    fn has_selection(&self) -> bool {
+        self.selection_anchor
+            .is_some_and(|anchor| anchor != self.cursor)
+    }
+
+    fn move_cursor_to(&mut self, offset: usize, select: bool, cx: &mut Context<Self>) {
+        if select {
+            self.selection_anchor.get_or_insert(self.cursor);
+        } else {
+            self.selection_anchor = None;
+        }
+        self.cursor = offset.min(self.buffer.read(cx).len());
+        self.finish_pending_nibble(cx);
+        self.scroll_handle
+            .scroll_to_item(self.cursor / BYTES_PER_ROW, ScrollStrategy::Nearest);
+        cx.notify();
+    }
+
+    /// Stops waiting for the second hex digit of the byte under the cursor, closing the
+    /// transaction its first digit was typed in.
+    fn finish_pending_nibble(&mut self, cx: &mut Context<Self>) {
+        if mem::take(&mut self.pending_nibble) {
+            self.buffer.update(cx, |buffer, _| buffer.end_transaction());
+        }
+    }
+
+    fn move_cursor_by(&mut self, delta: isize, select: bool, cx: &mut Context<Self>) {
+        let offset = self.cursor.saturating_add_signed(delta);
+        self.move_cursor_to(offset, select, cx);
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(-1, false, cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(1, false, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(-(BYTES_PER_ROW as isize), false, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(BYTES_PER_ROW as isize, false, cx);
+    }
+
+    fn select_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(-1, true, cx);
+    }
+
+    fn select_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(1, true, cx);
+    }
+
+    fn select_up(&mut self, _: &SelectUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(-(BYTES_PER_ROW as isize), true, cx);
+    }
+
+    fn select_down(&mut self, _: &SelectDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_by(BYTES_PER_ROW as isize, true, cx);
+    }
+
+    fn move_to_row_start(&mut self, _: &MoveToRowStart, _: &mut Window, cx: &mut Context<Self>) {
+        let row_start = self.cursor - self.cursor % BYTES_PER_ROW;
+        self.move_cursor_to(row_start, false, cx);
+    }
+
+    fn move_to_row_end(&mut self, _: &MoveToRowEnd, _: &mut Window, cx: &mut Context<Self>) {
+        let row_end = self.cursor - self.cursor % BYTES_PER_ROW + BYTES_PER_ROW - 1;
+        self.move_cursor_to(row_end, false, cx);
+    }
+
+    fn move_to_start(&mut self, _: &MoveToStart, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_to(0, false, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor_to(usize::MAX, false, cx);
+    }
+
+    fn page_up(&mut self, _: &PageUp, _: &mut Window, cx: &mut Context<Self>) {
+        let rows = self.visible_rows.max(1) as isize;
+        self.move_cursor_by(-rows * BYTES_PER_ROW as isize, false, cx);
+    }
+
+    fn page_down(&mut self, _: &PageDown, _: &mut Window, cx: &mut Context<Self>) {
+        let rows = self.visible_rows.max(1) as isize;
+        self.move_cursor_by(rows * BYTES_PER_ROW as isize, false, cx);
+    }
+
+    fn delete_selection(&mut self, cx: &mut Context<Self>) -> bool {
+        if !self.has_selection() {
+            return false;
+        }
+        let selection = self.selection(cx);
+        self.buffer
+            .update(cx, |buffer, cx| buffer.delete(selection.clone(), cx));
+        self.move_cursor_to(selection.start, false, cx);
+        true
+    }
+
+    fn backspace(&mut self, _: &Backspace, _: &mut Window, cx: &mut Context<Self>) {
+        self.finish_pending_nibble(cx);
+        if self.delete_selection(cx) || self.cursor == 0 {
+            return;
+        }
+        let offset = self.cursor - 1;
+        if self.mode == EditMode::Insert {
+            self.buffer
+                .update(cx, |buffer, cx| buffer.delete(offset..offset + 1, cx));
+        }
+        self.move_cursor_to(offset, false, cx);
+    }
+
+    fn delete(&mut self, _: &Delete, _: &mut Window, cx: &mut Context<Self>) {
+        self.finish_pending_nibble(cx);
+        if self.delete_selection(cx) {
+            return;
+        }
+        let offset = self.cursor;
+        self.buffer
+            .update(cx, |buffer, cx| buffer.delete(offset..offset + 1, cx));
+        self.move_cursor_to(offset, false, cx);
+    }
+
+    fn toggle_insert_mode(&mut self, _: &ToggleInsertMode, _: &mut Window, cx: &mut Context<Self>) {
+        self.mode = match self.mode {
+            EditMode::Overwrite => EditMode::Insert,
+            EditMode::Insert => EditMode::Overwrite,
+        };
+        self.finish_pending_nibble(cx);
+        cx.notify();
+    }
+
+    fn toggle_column(&mut self, _: &ToggleColumn, _: &mut Window, cx: &mut Context<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Text,
+            Column::Text => Column::Hex,
+        };
+        self.finish_pending_nibble(cx);
+        cx.notify();
+    }
+
+    fn undo(&mut self, _: &Undo, _: &mut Window, cx: &mut Context<Self>) {
+        self.finish_pending_nibble(cx);
+        if let Some(range) = self.buffer.update(cx, |buffer, cx| buffer.undo(cx)) {
+            self.move_cursor_to(range.start, false, cx);
+        }
+    }
+
+    fn redo(&mut self, _: &Redo, _: &mut Window, cx: &mut Context<Self>) {
+        self.finish_pending_nibble(cx);
+        if let Some(range) = self.buffer.update(cx, |buffer, cx| buffer.redo(cx)) {
+            self.move_cursor_to(range.start, false, cx);
+        }
+    }
+
+    fn toggle_endianness(&mut self, _: &ToggleEndianness, _: &mut Window, cx: &mut Context<Self>) {
+        self.endianness = self.endianness.toggle();
+        cx.notify();
+    }
+
+    fn handle_key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let modifiers = &event.keystroke.modifiers;
+        if modifiers.control || modifiers.platform || modifiers.alt || modifiers.function {
+            return;
+        }
+        let Some(key_char) = event.keystroke.key_char.as_deref() else {
+            return;
+        };
+        let mut chars = key_char.chars();
+        let (Some(char), None) = (chars.next(), chars.next()) else {
+            return;
+        };
+
+        match self.column {
+            Column::Hex => {
+                if let Some(digit) = char.to_digit(16) {
+                    self.type_hex_digit(digit as u8, cx);
+                    cx.stop_propagation();
+                }
+            }
+            Column::Text => {
+                if char.is_ascii() && !char.is_ascii_control() {
+                    self.type_byte(char as u8, cx);
+                    cx.stop_propagation();
+                }
+            }
+        }
+    }
+
+    fn type_hex_digit(&mut self, digit: u8, cx: &mut Context<Self>) {
+        if self.pending_nibble {
+            let offset = self.cursor;
+            let current = self.buffer.read(cx).bytes().get(offset).copied();
+            let byte = (current.unwrap_or(0) & 0xF0) | digit;
+            self.buffer
+                .update(cx, |buffer, cx| buffer.overwrite(offset, &[byte], cx));
+            // Moving past the completed byte closes its transaction.
+            self.move_cursor_to(offset + 1, false, cx);
+            return;
+        }
+
+        // Both digits of a byte, and the selection they replace, are undone together.
+        self.buffer
+            .update(cx, |buffer, _| buffer.start_transaction());
+        self.delete_selection(cx);
+        let offset = self.cursor;
+        let current = self.buffer.read(cx).bytes().get(offset).copied();
+        match (self.mode, current) {
+            (EditMode::Overwrite, Some(current)) => {
+                let byte = (digit << 4) | (current & 0x0F);
+                self.buffer
+                    .update(cx, |buffer, cx| buffer.overwrite(offset, &[byte], cx));
+            }
+            (EditMode::Insert, _) | (EditMode::Overwrite, None) => {
+                self.buffer
+                    .update(cx, |buffer, cx| buffer.insert(offset, &[digit << 4], cx));
+            }
+        }
+        self.pending_nibble = true;
+        cx.notify();
+    }
+
+    fn type_byte(&mut self, byte: u8, cx: &mut Context<Self>) {
+        self.buffer
+            .update(cx, |buffer, _| buffer.start_transaction());
+        self.delete_selection(cx);
+        let offset = self.cursor;
+        let mode = self.mode;
+        self.buffer.update(cx, |buffer, cx| {
+            match mode {
+                EditMode::Overwrite => buffer.overwrite(offset, &[byte], cx),
+                EditMode::Insert => buffer.insert(offset, &[byte], cx),
+            }
+            buffer.end_transaction();
+        });
+        self.move_cursor_to(offset + 1, false, cx);
+    }
+
+    fn handle_byte_mouse_down(
+        &mut self,
+        offset: usize,
+        column: Column,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.column = column;
+        self.move_cursor_to(offset, event.modifiers.shift, cx);
+        window.focus(&self.focus_handle, cx);
+    }
+
+    fn focus_search(&mut self, _: &FocusSearch, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_editor.update(cx, |editor, cx| {
+            editor.select_all(&SelectAll, window, cx);
+        });
+        window.focus(&self.search_editor.focus_handle(cx), cx);
+    }
+
+    fn dismiss_search(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_status = None;
+        window.focus(&self.focus_handle, cx);
+        cx.notify();
+    }
+
+    fn toggle_search_mode(&mut self, _: &ToggleSearchMode, _: &mut Window, cx: &mut Context<Self>) {
+        self.search_is_hex = !self.search_is_hex;
+        self.search_status = None;
+        cx.notify();
+    }
+
+    fn find_next(&mut self, _: &FindNext, _: &mut Window, cx: &mut Context<Self>) {
+        self.find(false, cx);
+    }
+
+    fn find_previous(&mut self, _: &FindPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        self.find(true, cx);
+    }
+
+    fn find(&mut self, reverse: bool, cx: &mut Context<Self>) {
+        let query = self.search_editor.read(cx).text(cx);
+        let Some(pattern) = parse_byte_pattern(&query, self.search_is_hex) else {
+            self.search_status = (!query.is_empty()).then(|| "Invalid hex pattern".into());
+            cx.notify();
+            return;
+        };
+
+        let selection_start = self.selection(cx).start;
+        let from = if reverse {
+            selection_start
+        } else {
+            selection_start + 1
+        };
+        match self.buffer.read(cx).find(&pattern, from, reverse) {
+            Some(offset) => {
+                self.search_status = None;
+                self.move_cursor_to(offset, false, cx);
+                self.move_cursor_to(offset + pattern.len() - 1, true, cx);
+            }
+            None => {
+                self.search_status = Some("No matches".into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn render_row(&self, row: usize, cx: &Context<Self>) -> impl IntoElement {
+        let buffer = self.buffer.read(cx);
+        let bytes = buffer.bytes();
+        let row_start = row * BYTES_PER_ROW;
+        let selection = self.selection(cx);
+        let colors = cx.theme().colors();
+
+        let cell = |offset: usize, column: Column, text: String| {
+            let is_cursor = offset == self.cursor && !self.has_selection();
+            let is_selected = self.has_selection() && selection.contains(&offset);
+            div()
+                .id(ElementId::NamedInteger(
+                    match column {
+                        Column::Hex => "hex-byte".into(),
+                        Column::Text => "text-byte".into(),
+                    },
+                    offset as u64,
+                ))
+                .when(column == Column::Hex, |this| this.px_0p5())
+                .when(is_selected, |this| this.bg(colors.element_selected))
+                .when(is_cursor, |this| {
+                    if column == self.column {
+                        this.bg(colors.element_active)
+                            .text_color(colors.text_accent)
+                    } else {
+                        this.bg(colors.element_hover)
+                    }
+                })
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, event, window, cx| {
+                        this.handle_byte_mouse_down(offset, column, event, window, cx)
+                    }),
+                )
+                .child(text)
+        };
+
+        let offsets = (row_start..row_start + BYTES_PER_ROW).filter(|&offset| {
+            offset < bytes.len() || (offset == bytes.len() && offset == self.cursor)
+        });
+
+        h_flex()
+            .gap_4()
+            .child(
+                div()
+                    .text_color(colors.editor_line_number)
+                    .child(format!("{row_start:08X}")),
+            )
+            .child(h_flex().children(offsets.clone().map(|offset| {
+                let text = match bytes.get(offset) {
+                    Some(byte) => format!("{byte:02X}"),
+                    None => "  ".to_string(),
+                };
+                let element = cell(offset, Column::Hex, text);
+                if offset % BYTES_PER_ROW == BYTES_PER_ROW / 2 {
+                    element.ml_2()
+                } else {
+                    element
+                }
+            })))
+            .child(h_flex().children(offsets.map(|offset| {
+                let text = match bytes.get(offset) {
+                    Some(byte) if byte.is_ascii_graphic() || *byte == b' ' => {
+                        (*byte as char).to_string()
+                    }
+                    Some(_) => ".".to_string(),
+                    None => " ".to_string(),
+                };
+                cell(offset, Column::Text, text)
+            })))
+    }
+
+    fn render_search_bar(&self, cx: &Context<Self>) -> impl IntoElement {
+        h_flex()
+            .key_context("HexEditorSearch")
+            .on_action(cx.listener(Self::find_next))
+            .on_action(cx.listener(Self::find_previous))
+            .on_action(cx.listener(Self::dismiss_search))
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                div()
+                    .flex_1()
+                    .px_2()
+                    .py_0p5()
+                    .rounded_sm()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.search_editor.clone()),
+            )
+            .child(
+                Button::new(
+                    "search-mode",
+                    if self.search_is_hex { "Hex" } else { "Text" },
+                )
+                .label_size(LabelSize::Small)
+                .tooltip(|_window, cx| {
+                    Tooltip::for_action("Toggle Hex/Text Search", &ToggleSearchMode, cx)
+                })
+                .on_click(cx.listener(|this, _, window, cx| {
+                    this.toggle_search_mode(&ToggleSearchMode, window, cx)
+                })),
+            )
+            .child(
+                IconButton::new("find-previous", IconName::ChevronLeft)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|_window, cx| Tooltip::for_action("Find Previous", &FindPrevious, cx))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.find_previous(&FindPrevious, window, cx)
+                    })),
+            )
+            .child(
+                IconButton::new("find-next", IconName::ChevronRight)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|_window, cx| Tooltip::for_action("Find Next", &FindNext, cx))
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.find_next(&FindNext, window, cx)),
+                    ),
+            )
+            .when_some(self.search_status.clone(), |this, status| {
+                this.child(
+                    Label::new(status)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+    }
+
+    fn render_inspector(&self, cx: &Context<Self>) -> impl IntoElement {
+        let buffer = self.buffer.read(cx);
+        let start = self.selection(cx).start;
+        let end = (start + INSPECTOR_BYTE_COUNT).min(buffer.len());
+        let values = inspect_bytes(&buffer.bytes()[start..end], self.endianness);
+
+        v_flex()
+            .w(px(240.))
+            .h_full()
+            .p_2()
+            .gap_1()
+            .border_l_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(Label::new("Data Inspector").size(LabelSize::Small))
+                    .child(
+                        Button::new("endianness", self.endianness.label())
+                            .label_size(LabelSize::Small)
+                            .tooltip(|_window, cx| {
+                                Tooltip::for_action("Toggle Endianness", &ToggleEndianness, cx)
+                            })
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.toggle_endianness(&ToggleEndianness, window, cx)
+                            })),
+                    ),
+            )
+            .children(values.into_iter().map(|(label, value)| {
+                h_flex()
+                    .justify_between()
+                    .gap_2()
+                    .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+                    .child(Label::new(value).size(LabelSize::Small).truncate())
+            }))
+    }
+
+    fn render_status(&self, cx: &Context<Self>) -> impl IntoElement {
+        let len = self.buffer.read(cx).len();
+        let selection = self.selection(cx);
+        let selection_label = if self.has_selection() {
+            format!(
+                "{} bytes selected",
+                selection.end.saturating_sub(selection.start)
+            )
+        } else {
+            format!("Offset 0x{:X} ({})", self.cursor, self.cursor)
+        };
+        let mode_label = match self.mode {
+            EditMode::Overwrite => "OVR",
+            EditMode::Insert => "INS",
+        };
+
+        h_flex()
+            .px_2()
+            .py_0p5()
+            .gap_4()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(selection_label).size(LabelSize::Small))
+            .child(
+                Label::new(format!("{len} bytes"))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(div().flex_1())
+            .child(
+                Button::new("edit-mode", mode_label)
+                    .label_size(LabelSize::Small)
+                    .tooltip(|_window, cx| {
+                        Tooltip::for_action("Toggle Insert Mode", &ToggleInsertMode, cx)
+                    })
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.toggle_insert_mode(&ToggleInsertMode, window, cx)
+                    })),
+            )
+    }
+}
+
+pub enum HexEditorEvent {
+    Edited,
+    Saved,
+    Reloaded,
+}
+
+impl From<HexBufferEvent> for HexEditorEvent {
+    fn from(event: HexBufferEvent) -> Self {
+        match event {
+            HexBufferEvent::Edited => HexEditorEvent::Edited,
+            HexBufferEvent::Saved => HexEditorEvent::Saved,
+            HexBufferEvent::Reloaded => HexEditorEvent::Reloaded,
+        }
+    }
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+
+impl Focusable for HexEditor {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let row_count = self.buffer.read(cx).len() / BYTES_PER_ROW + 1;
+        let settings = ThemeSettings::get_global(cx);
+
+        v_flex()
+            .key_context("HexEditor")
+            .on_action(cx.listener(Self::focus_search))
+            .on_action(cx.listener(Self::find_next))
+            .on_action(cx.listener(Self::find_previous))
+            .on_action(cx.listener(Self::toggle_search_mode))
+            .on_action(cx.listener(Self::toggle_endianness))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_search_bar(cx))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .child(
+                        div()
+                            .id("hex-editor-bytes")
+                            .track_focus(&self.focus_handle)
+                            .on_action(cx.listener(Self::move_left))
+                            .on_action(cx.listener(Self::move_right))
+                            .on_action(cx.listener(Self::move_up))
+                            .on_action(cx.listener(Self::move_down))
+                            .on_action(cx.listener(Self::select_left))
+                            .on_action(cx.listener(Self::select_right))
+                            .on_action(cx.listener(Self::select_up))
+                            .on_action(cx.listener(Self::select_down))
+                            .on_action(cx.listener(Self::move_to_row_start))
+                            .on_action(cx.listener(Self::move_to_row_end))
+                            .on_action(cx.listener(Self::move_to_start))
+                            .on_action(cx.listener(Self::move_to_end))
+                            .on_action(cx.listener(Self::page_up))
+                            .on_action(cx.listener(Self::page_down))
+                            .on_action(cx.listener(Self::backspace))
+                            .on_action(cx.listener(Self::delete))
+                            .on_action(cx.listener(Self::toggle_insert_mode))
+                            .on_action(cx.listener(Self::toggle_column))
+                            .on_action(cx.listener(Self::undo))
+                            .on_action(cx.listener(Self::redo))
+                            .on_key_down(cx.listener(Self::handle_key_down))
+                            .flex_1()
+                            .h_full()
+                            .p_2()
+                            .font(settings.buffer_font.clone())
+                            .text_size(settings.buffer_font_size(cx))
+                            .child(
+                                uniform_list(
+                                    "hex-editor-rows",
+                                    row_count,
+                                    cx.processor(|this, range: Range<usize>, _window, cx| {
+                                        this.visible_rows = range.len();
+                                        range
+                                            .map(|row| this.render_row(row, cx))
+                                            .collect::<Vec<_>>()
+                                    }),
+                                )
+                                .size_full()
+                                .track_scroll(&self.scroll_handle),
+                            ),
+                    )
+                    .child(self.render_inspector(cx)),
+            )
+            .child(self.render_status(cx))
+    }
+}
+
+impl Item for HexEditor {
+    type Event = HexEditorEvent;
+
+    fn to_item_events(event: &Self::Event, f: &mut dyn FnMut(ItemEvent)) {
+        match event {
+            HexEditorEvent::Edited => {
+                f(ItemEvent::Edit);
+                f(ItemEvent::UpdateTab);
+            }
+            HexEditorEvent::Saved | HexEditorEvent::Reloaded => f(ItemEvent::UpdateTab),
+        }
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.buffer.entity_id(), self.buffer.read(cx))
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.buffer.read(cx).abs_path();
+        Some(abs_path.compact().to_string_lossy().into_owned().into())
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.buffer
+            .read(cx)
+            .abs_path()
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned().into())
+            .unwrap_or_default()
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.buffer.read(cx).abs_path();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Hex Editor Opened")
+    }
+
+    fn breadcrumb_location(&self, cx: &App) -> ToolbarItemLocation {
+        if EditorSettings::get_global(cx).toolbar.breadcrumbs {
+            ToolbarItemLocation::PrimaryLeft
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+
+    fn breadcrumbs(&self, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        let project = self.project.read(cx);
+        let project_path = project::ProjectItem::project_path(self.buffer.read(cx), cx)?;
+        let mut path = project_path.path.clone();
+        if project.visible_worktrees(cx).count() > 1
+            && let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx)
+        {
+            path = worktree.read(cx).root_name().join(&path);
+        }
+
+        Some(vec![BreadcrumbText {
+            text: path.display(project.path_style(cx)).to_string(),
+            highlights: None,
+            font: Some(ThemeSettings::get_global(cx).buffer_font.clone()),
+        }])
+    }
+
+    fn can_split(&self) -> bool {
+        true
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Entity<Self>>>
+    where
+        Self: Sized,
+    {
+        let buffer = self.buffer.clone();
+        let project = self.project.clone();
+        Task::ready(Some(cx.new(|cx| Self::new(buffer, project, window, cx))))
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.buffer.read(cx).is_dirty()
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn can_save_as(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _: SaveOptions,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.buffer.update(cx, |buffer, cx| buffer.save(cx))
+    }
+
+    fn save_as(
+        &mut self,
+        project: Entity<Project>,
+        path: ProjectPath,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.buffer
+            .update(cx, |buffer, cx| buffer.save_as(path, &project, cx))
+    }
+
+    fn reload(
+        &mut self,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.buffer.update(cx, |buffer, cx| buffer.reload(cx))
+    }
+
+    fn buffer_kind(&self, _: &App) -> ItemBufferKind {
+        ItemBufferKind::Singleton
+    }
+}
+
+impl ProjectItem for HexEditor {
+    type Item = HexBuffer;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, project, window, cx)
+    }
+
+    fn for_broken_project_item(
+        abs_path: &Path,
+        is_local: bool,
+        e: &anyhow::Error,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<InvalidItemView>
+    where
+        Self: Sized,
+    {
+        Some(InvalidItemView::new(abs_path, is_local, e, window, cx))
+    }
+}
+
+/// Opens the file of the active item in the hex editor. When the active item is a
+/// file that failed to open, e.g. because it is binary, that file is opened instead.
+fn open_in_hex_editor(
+    workspace: &mut Workspace,
+    _: &OpenInHexEditor,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(active_item) = workspace.active_item(cx) else {
+        return;
+    };
+    let project = workspace.project().clone();
+    let project_path = active_item.project_path(cx).or_else(|| {
+        let invalid_item = active_item.downcast::<InvalidItemView>()?;
+        let abs_path = invalid_item.read(cx).abs_path.clone();
+        project.read(cx).find_project_path(&abs_path, cx)
+    });
+    let Some(project_path) = project_path else {
+        return;
+    };
+
+    let open_buffer = HexBuffer::open(&project, project_path, cx);
+    let pane = workspace.active_pane().clone();
+    cx.spawn_in(window, async move |workspace, cx| {
+        let buffer = open_buffer.await?;
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace
+                .open_project_item::<HexEditor>(pane, buffer, true, true, false, true, window, cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<HexEditor>(cx);
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(open_in_hex_editor);
+    })
+    .detach();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Fs as _};
+    use serde_json::json;
+    use std::sync::Arc;
+    use util::{path, rel_path::rel_path};
+    use workspace::{AppState, MultiWorkspace};
+
+    #[gpui::test]
+    async fn test_only_binary_files_open_in_hex_editor(cx: &mut TestAppContext) {
+        let (_, workspace, cx) = init_test(cx).await;
+
+        let text_item = open_path(&workspace, "text.txt", cx).await;
+        assert!(text_item.downcast::<Editor>().is_some());
+        let binary_item = open_path(&workspace, "app.bin", cx).await;
+        assert!(binary_item.downcast::<HexEditor>().is_some());
+    }
+
+    #[gpui::test]
+    async fn test_nibble_entry_and_undo(cx: &mut TestAppContext) {
+        let (_, workspace, cx) = init_test(cx).await;
+        let hex_editor = open_hex_editor(&workspace, cx).await;
+
+        // The first digit sets the high nibble, and the second one completes the byte.
+        cx.simulate_input("a");
+        assert_bytes(&hex_editor, &[0xAF, 0x45, 0x4C, 0x46, 0x02], cx);
+        hex_editor.read_with(cx, |editor, _| {
+            assert_eq!(editor.cursor, 0);
+            assert!(editor.pending_nibble);
+        });
+        cx.simulate_input("b");
+        assert_bytes(&hex_editor, &[0xAB, 0x45, 0x4C, 0x46, 0x02], cx);
+        hex_editor.read_with(cx, |editor, _| {
+            assert_eq!(editor.cursor, 1);
+            assert!(!editor.pending_nibble);
+        });
+        cx.simulate_input("0g1");
+        assert_bytes(&hex_editor, &[0xAB, 0x01, 0x4C, 0x46, 0x02], cx);
+
+        // Each byte is undone and redone as a whole.
+        cx.dispatch_action(Undo);
+        assert_bytes(&hex_editor, &[0xAB, 0x45, 0x4C, 0x46, 0x02], cx);
+        hex_editor.read_with(cx, |editor, _| assert_eq!(editor.cursor, 1));
+        cx.dispatch_action(Undo);
+        assert_bytes(&hex_editor, &[0x7F, 0x45, 0x4C, 0x46, 0x02], cx);
+        hex_editor.read_with(cx, |editor, cx| {
+            assert_eq!(editor.cursor, 0);
+            assert!(!editor.is_dirty(cx));
+        });
+        cx.dispatch_action(Redo);
+        assert_bytes(&hex_editor, &[0xAB, 0x45, 0x4C, 0x46, 0x02], cx);
+
+        // A byte that is left half-typed is undone on its own.
+        cx.dispatch_action(MoveToEnd);
+        cx.dispatch_action(ToggleInsertMode);
+        cx.simulate_input("c");
+        cx.dispatch_action(Undo);
+        assert_bytes(&hex_editor, &[0xAB, 0x45, 0x4C, 0x46, 0x02], cx);
+        cx.dispatch_action(Undo);
+        assert_bytes(&hex_editor, &[0x7F, 0x45, 0x4C, 0x46, 0x02], cx);
+    }
+
+    #[gpui::test]
+    async fn test_save(cx: &mut TestAppContext) {
+        let (fs, workspace, cx) = init_test(cx).await;
+        let hex_editor = open_hex_editor(&workspace, cx).await;
+
+        cx.simulate_input("00");
+        cx.dispatch_action(ToggleColumn);
+        cx.simulate_input("e");
+        assert_bytes(&hex_editor, &[0x00, b'e', 0x4C, 0x46, 0x02], cx);
+        hex_editor.read_with(cx, |editor, cx| assert!(editor.is_dirty(cx)));
+
+        let project = workspace.read_with(cx, |workspace, _| workspace.project().clone());
+        hex_editor
+            .update_in(cx, |editor, window, cx| {
+                editor.save(SaveOptions::default(), project, window, cx)
+            })
+            .await
+            .unwrap();
+        hex_editor.read_with(cx, |editor, cx| assert!(!editor.is_dirty(cx)));
+        assert_eq!(
+            fs.load_bytes(Path::new(path!("/dir/app.bin")))
+                .await
+                .unwrap(),
+            [0x00, b'e', 0x4C, 0x46, 0x02]
+        );
+
+        cx.dispatch_action(Undo);
+        hex_editor.read_with(cx, |editor, cx| assert!(editor.is_dirty(cx)));
+    }
+
+    async fn init_test(
+        cx: &mut TestAppContext,
+    ) -> (Arc<FakeFs>, Entity<Workspace>, &mut VisualTestContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            crate::init(cx);
+            editor::init(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "text.txt": "hello" }))
+            .await;
+        fs.insert_file(path!("/dir/app.bin"), vec![0x7F, 0x45, 0x4C, 0x46, 0x02])
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project, window, cx));
+        let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+        (fs, workspace, cx)
+    }
+
+    async fn open_path(
+        workspace: &Entity<Workspace>,
+        path: &str,
+        cx: &mut VisualTestContext,
+    ) -> Box<dyn workspace::item::ItemHandle> {
+        let worktree_id = workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        });
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path(path)), None, true, window, cx)
+            })
+            .await
+            .unwrap()
+    }
+
+    async fn open_hex_editor(
+        workspace: &Entity<Workspace>,
+        cx: &mut VisualTestContext,
+    ) -> Entity<HexEditor> {
+        let hex_editor = open_path(workspace, "app.bin", cx)
+            .await
+            .downcast::<HexEditor>()
+            .unwrap();
+        cx.run_until_parked();
+        hex_editor
+    }
+
+    #[track_caller]
+    fn assert_bytes(hex_editor: &Entity<HexEditor>, expected: &[u8], cx: &mut VisualTestContext) {
+        hex_editor.read_with(cx, |editor, cx| {
+            assert_eq!(editor.buffer().read(cx).bytes(), expected)
+        });
+    }
+}
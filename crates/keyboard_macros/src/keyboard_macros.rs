@@ -0,0 +1,647 @@
+//! Keyboard macros record the actions and text input of a session and replay them later.
+//!
+//! Recording happens at the action-dispatch level: every action triggered by a key binding is
+//! recorded, along with text typed into full editors. Macros can be replayed a number of times,
+//! once for every cursor, or saved under a name in the `keyboard_macros` setting and bound in the keymap.
+mod recording_indicator;
+mod save_macro_modal;
+
+pub use recording_indicator::RecordingIndicator;
+pub use save_macro_modal::SaveMacroModal;
+
+use anyhow::{Context as _, Result};
+use collections::{HashMap, VecDeque};
+use editor::{Anchor, Editor, EditorEvent, SelectionEffects};
+use gpui::{Action, App, Entity, Global, KeystrokeEvent, Window, actions};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use settings::{KeyboardMacroStepContent, RegisterSetting, Settings};
+use std::{cell::RefCell, ops::Range, rc::Rc, sync::Arc};
+use text::Selection;
+use util::ResultExt;
+use workspace::Workspace;
+
+actions!(
+    keyboard_macros,
+    [
+        /// Starts recording a keyboard macro, or stops the recording in progress.
+        ToggleRecording,
+        /// Stops recording the keyboard macro.
+        StopRecording,
+        /// Replays the last recorded keyboard macro once for every cursor.
+        ReplayOnEachCursor,
+        /// Saves the last recorded keyboard macro to your settings under a name.
+        SaveLast
+    ]
+);
+
+/// Replays the last recorded keyboard macro.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+#[action(namespace = keyboard_macros)]
+#[serde(deny_unknown_fields)]
+pub struct Replay {
+    /// How many times to replay the macro. Defaults to once.
+    #[serde(default)]
+    pub times: Option<usize>,
+}
+
+/// Runs a keyboard macro saved in the `keyboard_macros` setting.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+#[action(namespace = keyboard_macros)]
+#[serde(deny_unknown_fields)]
+pub struct Run {
+    /// The name of the macro in the `keyboard_macros` setting.
+    pub name: String,
+    /// How many times to run the macro. Defaults to once.
+    #[serde(default)]
+    pub times: Option<usize>,
+    /// Whether to run the macro once for every cursor.
+    #[serde(default)]
+    pub on_each_cursor: bool,
+}
+
+/// Replays are aborted after this many steps, to guard against macros that invoke themselves.
+const MAX_REPLAY_STEPS: usize = 10_000;
+
+#[derive(Debug, RegisterSetting)]
+pub struct KeyboardMacrosSettings {
+    pub macros: HashMap<String, Vec<KeyboardMacroStepContent>>,
+}
+
+impl Settings for KeyboardMacrosSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        Self {
+            macros: content.keyboard_macros.clone().unwrap(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MacroStep {
+    Action {
+        action: Box<dyn Action>,
+        /// How to write the action to settings, if it can be rebuilt from its name and input.
+        content: Option<KeyboardMacroStepContent>,
+    },
+    Insertion {
+        text: Arc<str>,
+        utf16_range_to_replace: Option<Range<isize>>,
+    },
+}
+
+impl Clone for MacroStep {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Action { action, content } => Self::Action {
+                action: action.boxed_clone(),
+                content: content.clone(),
+            },
+            Self::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => Self::Insertion {
+                text: text.clone(),
+                utf16_range_to_replace: utf16_range_to_replace.clone(),
+            },
+        }
+    }
+}
+
+impl MacroStep {
+    fn from_content(content: &KeyboardMacroStepContent, cx: &App) -> Result<Self> {
+        match content {
+            KeyboardMacroStepContent::Action { action, input } => {
+                let built = cx
+                    .build_action(action, input.clone())
+                    .with_context(|| format!("invalid action {action:?} in keyboard macro"))?;
+                Ok(Self::Action {
+                    action: built,
+                    content: Some(content.clone()),
+                })
+            }
+            KeyboardMacroStepContent::Text { text } => Ok(Self::Insertion {
+                text: text.as_str().into(),
+                utf16_range_to_replace: None,
+            }),
+        }
+    }
+
+    fn to_content(&self) -> Option<KeyboardMacroStepContent> {
+        match self {
+            Self::Action { content, .. } => content.clone(),
+            Self::Insertion { text, .. } => Some(KeyboardMacroStepContent::Text {
+                text: text.to_string(),
+            }),
+        }
+    }
+}
+
+/// Converts a recorded macro into the form stored in settings, or returns the names of the
+/// actions that can't be stored.
+pub fn macro_to_content(
+    steps: &[MacroStep],
+) -> std::result::Result<Vec<KeyboardMacroStepContent>, Vec<&'static str>> {
+    let mut content = Vec::with_capacity(steps.len());
+    let mut unsupported = Vec::new();
+    for step in steps {
+        match step.to_content() {
+            Some(step_content) => content.push(step_content),
+            None => {
+                if let MacroStep::Action { action, .. } = step {
+                    unsupported.push(action.name());
+                }
+            }
+        }
+    }
+    if unsupported.is_empty() {
+        Ok(content)
+    } else {
+        Err(unsupported)
+    }
+}
+
+#[derive(Default)]
+pub struct KeyboardMacros {
+    recording: Option<Vec<MacroStep>>,
+    last_recorded: Option<Vec<MacroStep>>,
+    replayer: Option<Replayer>,
+}
+
+impl Global for KeyboardMacros {}
+
+impl KeyboardMacros {
+    pub fn is_recording(cx: &App) -> bool {
+        cx.try_global::<Self>()
+            .is_some_and(|macros| macros.recording.is_some())
+    }
+
+    pub fn last_recorded(cx: &App) -> Option<&[MacroStep]> {
+        cx.try_global::<Self>()
+            .and_then(|macros| macros.last_recorded.as_deref())
+    }
+
+    fn start_recording(cx: &mut App) {
+        cx.global_mut::<Self>().recording = Some(Vec::new());
+    }
+
+    fn stop_recording(cx: &mut App) {
+        if !Self::is_recording(cx) {
+            return;
+        }
+        let macros = cx.global_mut::<Self>();
+        if let Some(steps) = macros.recording.take()
+            && !steps.is_empty()
+        {
+            macros.last_recorded = Some(steps);
+        }
+    }
+
+    fn should_record(cx: &App) -> bool {
+        cx.try_global::<Self>()
+            .is_some_and(|macros| macros.recording.is_some() && macros.replayer.is_none())
+    }
+
+    fn record(step: MacroStep, cx: &mut App) {
+        if let Some(recording) = cx.global_mut::<Self>().recording.as_mut() {
+            recording.push(step);
+        }
+    }
+
+    fn observe_keystroke(event: &KeystrokeEvent, window: &mut Window, cx: &mut App) {
+        let Some(action) = event.action.as_ref() else {
+            return;
+        };
+        if action.name().starts_with("keyboard_macros::") || !Self::should_record(cx) {
+            return;
+        }
+
+        let content = action_content(action.as_ref(), window, cx);
+        Self::record(
+            MacroStep::Action {
+                action: action.boxed_clone(),
+                content,
+            },
+            cx,
+        );
+    }
+
+    fn observe_editor_event(editor: Entity<Editor>, event: &EditorEvent, cx: &mut App) {
+        let EditorEvent::InputHandled {
+            text,
+            utf16_range_to_replace,
+        } = event
+        else {
+            return;
+        };
+        if !editor.read(cx).mode().is_full() || !Self::should_record(cx) {
+            return;
+        }
+        Self::record(
+            MacroStep::Insertion {
+                text: text.clone(),
+                utf16_range_to_replace: utf16_range_to_replace.clone(),
+            },
+            cx,
+        );
+    }
+}
+
+/// Returns the settings representation of a recorded action, using the input of the key binding
+/// that triggered it. Returns `None` when the action can't be rebuilt from that input.
+fn action_content(
+    action: &dyn Action,
+    window: &Window,
+    cx: &App,
+) -> Option<KeyboardMacroStepContent> {
+    let input = window
+        .highest_precedence_binding_for_action(action)
+        .and_then(|binding| binding.action_input())
+        .and_then(|input| serde_json::from_str::<serde_json::Value>(&input).log_err());
+    let rebuilt = cx.build_action(action.name(), input.clone()).ok()?;
+    rebuilt
+        .partial_eq(action)
+        .then(|| KeyboardMacroStepContent::Action {
+            action: action.name().to_string(),
+            input,
+        })
+}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(KeyboardMacros::default());
+
+    cx.observe_keystrokes(KeyboardMacros::observe_keystroke)
+        .detach();
+
+    cx.observe_new(|_: &mut Editor, _, cx| {
+        let editor = cx.entity();
+        App::subscribe(cx, &editor, KeyboardMacros::observe_editor_event).detach();
+    })
+    .detach();
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|_, _: &ToggleRecording, _, cx| {
+            if KeyboardMacros::is_recording(cx) {
+                KeyboardMacros::stop_recording(cx);
+            } else {
+                KeyboardMacros::start_recording(cx);
+            }
+        });
+        workspace.register_action(|_, _: &StopRecording, _, cx| {
+            KeyboardMacros::stop_recording(cx);
+        });
+        workspace.register_action(|workspace, action: &Replay, window, cx| {
+            let Some(steps) = KeyboardMacros::last_recorded(cx).map(<[_]>::to_vec) else {
+                return;
+            };
+            replay(
+                workspace,
+                steps,
+                action.times.unwrap_or(1),
+                false,
+                window,
+                cx,
+            );
+        });
+        workspace.register_action(|workspace, _: &ReplayOnEachCursor, window, cx| {
+            let Some(steps) = KeyboardMacros::last_recorded(cx).map(<[_]>::to_vec) else {
+                return;
+            };
+            replay(workspace, steps, 1, true, window, cx);
+        });
+        workspace.register_action(|workspace, action: &Run, window, cx| {
+            let steps = KeyboardMacrosSettings::get_global(cx)
+                .macros
+                .get(&action.name)
+                .with_context(|| format!("no keyboard macro named {:?}", action.name))
+                .and_then(|steps| {
+                    steps
+                        .iter()
+                        .map(|step| MacroStep::from_content(step, cx))
+                        .collect::<Result<Vec<_>>>()
+                });
+            match steps {
+                Ok(steps) => replay(
+                    workspace,
+                    steps,
+                    action.times.unwrap_or(1),
+                    action.on_each_cursor,
+                    window,
+                    cx,
+                ),
+                Err(error) => workspace.show_error(&error, cx),
+            }
+        });
+        workspace.register_action(|workspace, _: &SaveLast, window, cx| {
+            SaveMacroModal::toggle(workspace, window, cx);
+        });
+    })
+    .detach();
+}
+
+fn replay(
+    workspace: &mut Workspace,
+    steps: Vec<MacroStep>,
+    times: usize,
+    on_each_cursor: bool,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    // Replaying a macro while recording makes the replayed steps part of the new macro.
+    if KeyboardMacros::should_record(cx) {
+        for _ in 0..times {
+            for step in &steps {
+                KeyboardMacros::record(step.clone(), cx);
+            }
+        }
+    }
+
+    let mut replay_steps = VecDeque::new();
+    if on_each_cursor {
+        let Some(editor) = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+        else {
+            return;
+        };
+        for selection in editor.read(cx).selections.disjoint_anchors_arc().iter() {
+            replay_steps.push_back(ReplayStep::SelectCursor(selection.clone()));
+            for _ in 0..times {
+                replay_steps.extend(steps.iter().cloned().map(ReplayStep::Macro));
+            }
+            replay_steps.push_back(ReplayStep::SaveCursor);
+        }
+        replay_steps.push_back(ReplayStep::RestoreCursors);
+    } else {
+        for _ in 0..times {
+            replay_steps.extend(steps.iter().cloned().map(ReplayStep::Macro));
+        }
+    }
+
+    let replayer = cx
+        .global_mut::<KeyboardMacros>()
+        .replayer
+        .get_or_insert_with(Replayer::new)
+        .clone();
+    replayer.replay(replay_steps, window, cx);
+}
+
+enum ReplayStep {
+    Macro(MacroStep),
+    /// Replaces the selections of the active editor with a single one, before replaying the macro on it.
+    SelectCursor(Selection<Anchor>),
+    /// Remembers the selections left behind by the macro on the current cursor.
+    SaveCursor,
+    /// Selects everything remembered by `SaveCursor`.
+    RestoreCursors,
+}
+
+struct ReplayerState {
+    steps: VecDeque<ReplayStep>,
+    steps_replayed: usize,
+    running: bool,
+    saved_selections: Vec<Selection<Anchor>>,
+}
+
+#[derive(Clone)]
+struct Replayer(Rc<RefCell<ReplayerState>>);
+
+impl Replayer {
+    fn new() -> Self {
+        Self(Rc::new(RefCell::new(ReplayerState {
+            steps: VecDeque::new(),
+            steps_replayed: 0,
+            running: false,
+            saved_selections: Vec::new(),
+        })))
+    }
+
+    /// Queues `steps` to run before anything already queued, so that macros can run other macros.
+    fn replay(&self, steps: VecDeque<ReplayStep>, window: &mut Window, cx: &mut App) {
+        let mut state = self.0.borrow_mut();
+        for step in steps.into_iter().rev() {
+            state.steps.push_front(step);
+        }
+        if state.running {
+            return;
+        }
+        state.running = true;
+        let this = self.clone();
+        window.defer(cx, move |window, cx| this.next(window, cx));
+    }
+
+    fn next(self, window: &mut Window, cx: &mut App) {
+        let mut state = self.0.borrow_mut();
+        let step = if state.steps_replayed < MAX_REPLAY_STEPS {
+            state.steps.pop_front()
+        } else {
+            log::error!("Aborting keyboard macro after {MAX_REPLAY_STEPS} steps");
+            None
+        };
+        state.steps_replayed += 1;
+        drop(state);
+
+        let Some(step) = step else {
+            cx.global_mut::<KeyboardMacros>().replayer.take();
+            return;
+        };
+
+        match step {
+            ReplayStep::Macro(MacroStep::Action { action, .. }) => {
+                window.dispatch_action(action, cx);
+            }
+            ReplayStep::Macro(MacroStep::Insertion {
+                text,
+                utf16_range_to_replace,
+            }) => {
+                if let Some(editor) = active_editor(window, cx) {
+                    editor.update(cx, |editor, cx| {
+                        editor.replay_insert_event(&text, utf16_range_to_replace, window, cx)
+                    });
+                }
+            }
+            ReplayStep::SelectCursor(selection) => {
+                if let Some(editor) = active_editor(window, cx) {
+                    editor.update(cx, |editor, cx| {
+                        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                            s.select_anchors(vec![selection])
+                        });
+                    });
+                }
+            }
+            ReplayStep::SaveCursor => {
+                if let Some(editor) = active_editor(window, cx) {
+                    let selections = editor.read(cx).selections.disjoint_anchors_arc();
+                    self.0
+                        .borrow_mut()
+                        .saved_selections
+                        .extend(selections.iter().cloned());
+                }
+            }
+            ReplayStep::RestoreCursors => {
+                let selections = std::mem::take(&mut self.0.borrow_mut().saved_selections);
+                if let Some(editor) = active_editor(window, cx)
+                    && !selections.is_empty()
+                {
+                    editor.update(cx, |editor, cx| {
+                        editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+                            s.select_anchors(selections)
+                        });
+                    });
+                }
+            }
+        }
+        window.defer(cx, move |window, cx| self.next(window, cx));
+    }
+}
+
+fn active_editor(window: &mut Window, cx: &mut App) -> Option<Entity<Editor>> {
+    Workspace::for_window(window, cx)?
+        .read(cx)
+        .active_item(cx)?
+        .act_as::<Editor>(cx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::actions::{MoveDown, MoveToBeginningOfLine};
+    use gpui::{KeyBinding, TestAppContext, VisualTestContext};
+    use indoc::indoc;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use util::{path, rel_path::rel_path};
+    use workspace::{AppState, MultiWorkspace};
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            editor::init(cx);
+            crate::init(cx);
+            cx.bind_keys([
+                KeyBinding::new("down", MoveDown, Some("Editor")),
+                KeyBinding::new(
+                    "home",
+                    MoveToBeginningOfLine {
+                        stop_at_soft_wraps: true,
+                        stop_at_indent: false,
+                    },
+                    Some("Editor"),
+                ),
+            ]);
+            state
+        })
+    }
+
+    async fn open_editor(
+        text: &str,
+        cx: &mut TestAppContext,
+    ) -> (Entity<Editor>, Entity<Workspace>, &mut VisualTestContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.txt": text }))
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+        let worktree_id = workspace.update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        });
+        let editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("a.txt")), None, true, window, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+        (editor, workspace, cx)
+    }
+
+    #[gpui::test]
+    async fn test_record_and_replay(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (editor, _workspace, cx) = open_editor("one\ntwo\nthree\nfour\n", cx).await;
+
+        cx.dispatch_action(ToggleRecording);
+        cx.simulate_keystrokes("- space home down");
+        cx.dispatch_action(ToggleRecording);
+        cx.run_until_parked();
+        assert_eq!(
+            editor.read_with(cx, |editor, cx| editor.text(cx)),
+            "- one\ntwo\nthree\nfour\n"
+        );
+
+        cx.dispatch_action(Replay { times: Some(2) });
+        cx.run_until_parked();
+        assert_eq!(
+            editor.read_with(cx, |editor, cx| editor.text(cx)),
+            "- one\n- two\n- three\nfour\n"
+        );
+
+        let content = cx
+            .update(|_, cx| macro_to_content(KeyboardMacros::last_recorded(cx).unwrap()).unwrap());
+        assert_eq!(
+            content,
+            [
+                KeyboardMacroStepContent::Text { text: "-".into() },
+                KeyboardMacroStepContent::Text { text: " ".into() },
+                KeyboardMacroStepContent::Action {
+                    action: "editor::MoveToBeginningOfLine".into(),
+                    input: None,
+                },
+                KeyboardMacroStepContent::Action {
+                    action: "editor::MoveDown".into(),
+                    input: None,
+                },
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_replay_on_each_cursor(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (editor, _workspace, cx) = open_editor(
+            indoc! {"
+            a
+            b
+            c
+        "},
+            cx,
+        )
+        .await;
+
+        cx.dispatch_action(ToggleRecording);
+        cx.simulate_keystrokes("> space");
+        cx.dispatch_action(StopRecording);
+        cx.run_until_parked();
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                s.select_ranges([
+                    text::Point::new(1, 0)..text::Point::new(1, 0),
+                    text::Point::new(2, 0)..text::Point::new(2, 0),
+                ])
+            });
+        });
+        cx.dispatch_action(ReplayOnEachCursor);
+        cx.run_until_parked();
+
+        assert_eq!(
+            editor.read_with(cx, |editor, cx| editor.text(cx)),
+            indoc! {"
+                > a
+                > b
+                > c
+            "}
+        );
+        assert_eq!(
+            editor.update(cx, |editor, cx| editor
+                .selections
+                .newest::<text::Point>(&editor.display_snapshot(cx))
+                .head()),
+            text::Point::new(2, 2)
+        );
+    }
+}
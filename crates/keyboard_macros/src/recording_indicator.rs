@@ -0,0 +1,47 @@
+use gpui::{Context, Render, Window};
+use ui::{Tooltip, prelude::*};
+use workspace::{StatusItemView, item::ItemHandle};
+
+use crate::{KeyboardMacros, ToggleRecording};
+
+/// Shows in the status bar while a keyboard macro is being recorded.
+pub struct RecordingIndicator;
+
+impl RecordingIndicator {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        cx.observe_global::<KeyboardMacros>(|_, cx| cx.notify())
+            .detach();
+        Self
+    }
+}
+
+impl Render for RecordingIndicator {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if !KeyboardMacros::is_recording(cx) {
+            return div().hidden().into_any_element();
+        }
+
+        Button::new("keyboard-macro-recording", "Recording Macro")
+            .label_size(LabelSize::Small)
+            .icon(IconName::Circle)
+            .icon_size(IconSize::XSmall)
+            .icon_color(Color::Error)
+            .icon_position(IconPosition::Start)
+            .tooltip(Tooltip::for_action_title(
+                "Stop Recording",
+                &ToggleRecording,
+            ))
+            .on_click(|_, window, cx| window.dispatch_action(Box::new(ToggleRecording), cx))
+            .into_any_element()
+    }
+}
+
+impl StatusItemView for RecordingIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        _active_pane_item: Option<&dyn ItemHandle>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) {
+    }
+}
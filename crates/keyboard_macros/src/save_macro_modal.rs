@@ -0,0 +1,127 @@
+use editor::Editor;
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Styled, div,
+    prelude::*,
+};
+use project::Fs;
+use settings::KeyboardMacroStepContent;
+use std::sync::Arc;
+use theme::ActiveTheme;
+use ui::prelude::*;
+use workspace::{ModalView, Workspace};
+
+use crate::{KeyboardMacros, macro_to_content};
+
+/// A modal for saving the last recorded keyboard macro to the `keyboard_macros` setting.
+pub struct SaveMacroModal {
+    name_editor: Entity<Editor>,
+    steps: Vec<KeyboardMacroStepContent>,
+    fs: Arc<dyn Fs>,
+}
+
+impl ModalView for SaveMacroModal {}
+
+impl Focusable for SaveMacroModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for SaveMacroModal {}
+
+impl SaveMacroModal {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(steps) = KeyboardMacros::last_recorded(cx) else {
+            workspace.show_error(&"No keyboard macro has been recorded yet", cx);
+            return;
+        };
+        let steps = match macro_to_content(steps) {
+            Ok(steps) => steps,
+            Err(unsupported) => {
+                let error = format!(
+                    "The last keyboard macro can't be saved because these actions can't be rebuilt from your keymap: {}",
+                    unsupported.join(", ")
+                );
+                workspace.show_error(&error, cx);
+                return;
+            }
+        };
+
+        let fs = workspace.app_state().fs.clone();
+        workspace.toggle_modal(window, cx, move |window, cx| {
+            SaveMacroModal::new(steps, fs, window, cx)
+        });
+    }
+
+    fn new(
+        steps: Vec<KeyboardMacroStepContent>,
+        fs: Arc<dyn Fs>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let name_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Macro name", window, cx);
+            editor
+        });
+
+        Self {
+            name_editor,
+            steps,
+            fs,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_editor.read(cx).text(cx).trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+
+        let steps = self.steps.clone();
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings
+                .keyboard_macros
+                .get_or_insert_default()
+                .insert(name, steps);
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for SaveMacroModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let step_count = self.steps.len();
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("SaveMacroModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.name_editor.clone()),
+            )
+            .child(
+                h_flex().px_2().py_1().gap_1().child(
+                    Label::new(format!(
+                        "Save the last macro ({step_count} {}) to your settings",
+                        if step_count == 1 { "step" } else { "steps" }
+                    ))
+                    .color(Color::Muted),
+                ),
+            )
+    }
+}
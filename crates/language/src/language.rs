@@ -14,6 +14,7 @@ pub mod language_settings;
 mod manifest;
 mod outline;
 pub mod proto;
+mod query_annotations;
 mod syntax_map;
 mod task_context;
 mod text_diff;
@@ -88,6 +89,7 @@ pub use language_registry::{
 };
 pub use lsp::{LanguageServerId, LanguageServerName};
 pub use outline::*;
+pub use query_annotations::{AnnotatedQuery, annotate_query_captures, check_query_annotations};
 pub use syntax_map::{
     OwnedSyntaxLayer, SyntaxLayer, SyntaxMapMatches, ToTreeSitterPoint, TreeSitterOptions,
 };
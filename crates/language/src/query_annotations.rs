@@ -0,0 +1,337 @@
+//! Annotates sample files with the captures of a language's queries, so that query changes can be
+//! checked against samples whose expected captures have been written down.
+//!
+//! Annotations are line comments placed below the line they refer to, in the style of
+//! tree-sitter's query tests. Carets mark the captured columns, unless the capture starts before
+//! the comment's text could reach it, in which case `<-` points at the capture's first column:
+//!
+//! ```text
+//! fn main() {}
+//! // <- keyword
+//! // ^^^^ function
+//! ```
+//!
+//! Captures that span multiple lines are marked from their start to the end of their first line
+//! (followed by `...`), and from the indentation of their last line to their end (preceded by
+//! `...`).
+
+use std::{collections::BTreeMap, fmt::Write as _, sync::Arc};
+
+use anyhow::{Context as _, Result, bail};
+use text::{Buffer, BufferId, Point, ReplicaId};
+use tree_sitter::Query;
+
+use crate::{Grammar, Language, LanguageRegistry, syntax_map::SyntaxSnapshot, unified_diff};
+
+/// A query whose captures can be annotated in sample files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnnotatedQuery {
+    Highlights,
+    Outline,
+    Indents,
+    TextObjects,
+}
+
+impl AnnotatedQuery {
+    pub const ALL: [Self; 4] = [
+        Self::Highlights,
+        Self::Outline,
+        Self::Indents,
+        Self::TextObjects,
+    ];
+
+    /// The name of the query's file, without the `.scm` extension.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Highlights => "highlights",
+            Self::Outline => "outline",
+            Self::Indents => "indents",
+            Self::TextObjects => "textobjects",
+        }
+    }
+
+    /// Returns the query whose name starts the given file name, the same way query files are
+    /// matched (e.g. `highlights-strings.rs` is a sample for the highlights query).
+    pub fn for_file_name(file_name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|query| file_name.starts_with(query.name()))
+    }
+
+    fn query(self) -> fn(&Grammar) -> Option<&Query> {
+        match self {
+            Self::Highlights => highlights_query,
+            Self::Outline => outline_query,
+            Self::Indents => indents_query,
+            Self::TextObjects => text_objects_query,
+        }
+    }
+}
+
+fn highlights_query(grammar: &Grammar) -> Option<&Query> {
+    grammar
+        .highlights_config
+        .as_ref()
+        .map(|config| &config.query)
+}
+
+fn outline_query(grammar: &Grammar) -> Option<&Query> {
+    grammar.outline_config.as_ref().map(|config| &config.query)
+}
+
+fn indents_query(grammar: &Grammar) -> Option<&Query> {
+    grammar.indents_config.as_ref().map(|config| &config.query)
+}
+
+fn text_objects_query(grammar: &Grammar) -> Option<&Query> {
+    grammar
+        .text_object_config
+        .as_ref()
+        .map(|config| &config.query)
+}
+
+/// Returns the given text with an annotation below each line for every capture of the query on
+/// that line. Annotations already present in the text are replaced.
+///
+/// Captures whose names start with an underscore are omitted, as are captures of injected
+/// languages when no language registry is given.
+pub fn annotate_query_captures(
+    language: &Arc<Language>,
+    language_registry: Option<Arc<LanguageRegistry>>,
+    query: AnnotatedQuery,
+    text: &str,
+) -> Result<String> {
+    let comment_prefix = language
+        .config()
+        .line_comments
+        .first()
+        .map(|prefix| prefix.trim_end())
+        .filter(|prefix| !prefix.is_empty())
+        .with_context(|| {
+            format!(
+                "{} has no line comments to write annotations with",
+                language.name()
+            )
+        })?;
+
+    let text = text.replace("\r\n", "\n");
+    let lines = text
+        .split('\n')
+        .filter(|line| !is_annotation(comment_prefix, line))
+        .collect::<Vec<_>>();
+    let buffer = Buffer::new(
+        ReplicaId::LOCAL,
+        BufferId::new(1).unwrap(),
+        lines.join("\n"),
+    );
+    let mut syntax_snapshot = SyntaxSnapshot::new(&buffer);
+    syntax_snapshot.reparse(&buffer, language_registry, language.clone());
+
+    let query = query.query();
+    let captures = syntax_snapshot.captures(0..buffer.len(), &buffer, query);
+    let queries = captures
+        .grammars()
+        .iter()
+        .map(|&grammar| query(grammar))
+        .collect::<Vec<_>>();
+    let mut names_by_range = BTreeMap::<(Point, Point), Vec<&str>>::new();
+    for capture in captures {
+        let Some(query) = queries[capture.grammar_index] else {
+            continue;
+        };
+        let name = query.capture_names()[capture.index as usize];
+        if name.starts_with('_') {
+            continue;
+        }
+        let range = capture.node.byte_range();
+        let names = names_by_range
+            .entry((
+                buffer.offset_to_point(range.start),
+                buffer.offset_to_point(range.end),
+            ))
+            .or_default();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    let mut annotations = Vec::new();
+    for ((start, end), names) in names_by_range {
+        let names = names.join(" ");
+        if start.row == end.row {
+            annotations.push((start.row, start.column, end.column, names));
+        } else {
+            let first_line = lines[start.row as usize];
+            annotations.push((
+                start.row,
+                start.column,
+                first_line.len() as u32,
+                format!("{names} ..."),
+            ));
+            let last_line = lines[end.row as usize];
+            let indent = (last_line.len() - last_line.trim_start().len()) as u32;
+            annotations.push((
+                end.row,
+                indent.min(end.column),
+                end.column,
+                format!("... {names}"),
+            ));
+        }
+    }
+    annotations.sort_by_key(|&(row, start, end, _)| (row, start, end));
+
+    let prefix_len = comment_prefix.chars().count();
+    let mut annotations = annotations.into_iter().peekable();
+    let mut annotated_text = String::with_capacity(text.len());
+    for (row, line) in lines.iter().enumerate() {
+        if row > 0 {
+            annotated_text.push('\n');
+        }
+        annotated_text.push_str(line);
+        while let Some((_, start, end, names)) =
+            annotations.next_if(|annotation| annotation.0 as usize == row)
+        {
+            let start = char_column(line, start);
+            let end = char_column(line, end).max(start + 1);
+            annotated_text.push('\n');
+            if start >= prefix_len {
+                write!(
+                    annotated_text,
+                    "{comment_prefix}{}{} {names}",
+                    " ".repeat(start - prefix_len),
+                    "^".repeat(end - start)
+                )
+                .unwrap();
+            } else {
+                write!(
+                    annotated_text,
+                    "{}{comment_prefix} <- {names}",
+                    " ".repeat(start)
+                )
+                .unwrap();
+            }
+        }
+    }
+    Ok(annotated_text)
+}
+
+/// Checks that the annotations in the given text match the captures of the query, returning a
+/// diff between the two otherwise.
+pub fn check_query_annotations(
+    language: &Arc<Language>,
+    language_registry: Option<Arc<LanguageRegistry>>,
+    query: AnnotatedQuery,
+    annotated_text: &str,
+) -> Result<()> {
+    let annotated_text = annotated_text.replace("\r\n", "\n");
+    let expected_text =
+        annotate_query_captures(language, language_registry, query, &annotated_text)?;
+    if annotated_text != expected_text {
+        bail!(
+            "{} captures don't match the annotations:\n{}",
+            query.name(),
+            unified_diff(&annotated_text, &expected_text)
+        );
+    }
+    Ok(())
+}
+
+fn is_annotation(comment_prefix: &str, line: &str) -> bool {
+    line.trim_start()
+        .strip_prefix(comment_prefix)
+        .is_some_and(|rest| {
+            let rest = rest.trim_start();
+            rest.starts_with('^') || rest.starts_with("<-")
+        })
+}
+
+fn char_column(line: &str, column: u32) -> usize {
+    let column = (column as usize).min(line.len());
+    line.get(..column)
+        .map_or(column, |prefix| prefix.chars().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LanguageConfig, LanguageMatcher, LanguageQueries};
+    use std::borrow::Cow;
+
+    fn rust_with_highlights(highlights: &str) -> Arc<Language> {
+        Arc::new(
+            Language::new(
+                LanguageConfig {
+                    name: "Rust".into(),
+                    matcher: LanguageMatcher {
+                        path_suffixes: vec!["rs".to_string()],
+                        ..Default::default()
+                    },
+                    line_comments: vec!["// ".into()],
+                    ..Default::default()
+                },
+                Some(tree_sitter_rust::LANGUAGE.into()),
+            )
+            .with_queries(LanguageQueries {
+                highlights: Some(Cow::from(highlights.to_string())),
+                ..Default::default()
+            })
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_annotate_query_captures() {
+        let language = rust_with_highlights(
+            r#"
+            "fn" @keyword
+            (function_item name: (identifier) @function)
+            (identifier) @variable
+            (block) @_block
+            (string_literal) @string
+            "#,
+        );
+
+        let annotated = annotate_query_captures(
+            &language,
+            None,
+            AnnotatedQuery::Highlights,
+            "fn main() {\n    let s = \"a\nb\";\n}\n",
+        )
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            annotated,
+            concat!(
+                "fn main() {\n",
+                "// <- keyword\n",
+                "// ^^^^ function variable\n",
+                "    let s = \"a\n",
+                "//      ^ variable\n",
+                "//          ^^ string ...\n",
+                "b\";\n",
+                "// <- ... string\n",
+                "}\n",
+            )
+        );
+
+        check_query_annotations(&language, None, AnnotatedQuery::Highlights, &annotated).unwrap();
+
+        let stale = annotated.replace("^^^^ function variable", "^^^^ function");
+        let error = check_query_annotations(&language, None, AnnotatedQuery::Highlights, &stale)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("+// ^^^^ function variable"), "{error}");
+    }
+
+    #[test]
+    fn test_query_for_file_name() {
+        assert_eq!(
+            AnnotatedQuery::for_file_name("highlights-strings.rs"),
+            Some(AnnotatedQuery::Highlights)
+        );
+        assert_eq!(
+            AnnotatedQuery::for_file_name("textobjects.rs"),
+            Some(AnnotatedQuery::TextObjects)
+        );
+        assert_eq!(AnnotatedQuery::for_file_name("brackets.rs"), None);
+    }
+}
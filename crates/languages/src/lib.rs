@@ -449,3 +449,43 @@ fn load_queries(name: &str) -> LanguageQueries {
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// Checks the samples in `test_data/queries/<language>` against the captures of the query
+    /// their file name starts with. Run with `UPDATE_GOLDEN=true` to rewrite their annotations.
+    #[test]
+    fn test_query_annotations() {
+        let update = std::env::var("UPDATE_GOLDEN")
+            .map(|val| val.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        let samples_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_data/queries");
+        for language_dir in std::fs::read_dir(&samples_dir).unwrap() {
+            let language_dir = language_dir.unwrap().path();
+            let name = language_dir.file_name().unwrap().to_str().unwrap();
+            let grammar = match name {
+                "json" => tree_sitter_json::LANGUAGE,
+                "rust" => tree_sitter_rust::LANGUAGE,
+                _ => panic!("no grammar for the query samples in {language_dir:?}"),
+            };
+            let language = language(name, grammar.into());
+
+            for sample in std::fs::read_dir(&language_dir).unwrap() {
+                let sample = sample.unwrap().path();
+                let file_name = sample.file_name().unwrap().to_str().unwrap();
+                let query = AnnotatedQuery::for_file_name(file_name)
+                    .unwrap_or_else(|| panic!("{sample:?} doesn't start with the name of a query"));
+                let text = std::fs::read_to_string(&sample).unwrap();
+                if update {
+                    let annotated = annotate_query_captures(&language, None, query, &text).unwrap();
+                    std::fs::write(&sample, annotated).expect("could not write out golden data");
+                } else if let Err(error) = check_query_annotations(&language, None, query, &text) {
+                    panic!("{sample:?}: {error}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+// <- keyword
+//  ^^^ variable
+//     ^^ punctuation.delimiter
+//       ^^^^^^^^^^^ variable
+//                  ^^ punctuation.delimiter
+//                    ^^^^^^^ variable type
+//                           ^ punctuation.delimiter
+
+const MAX_DEPTH: usize = 8;
+// <- keyword
+//    ^^^^^^^^^ variable type constant
+//             ^ operator
+//               ^^^^^ type.builtin
+//                     ^ operator
+//                       ^ number
+//                        ^ punctuation.delimiter
+
+struct Point<T> {
+// <- keyword
+//     ^^^^^ type
+//          ^ punctuation.bracket operator
+//           ^ type
+//            ^ punctuation.bracket operator
+//              ^ punctuation.bracket
+    x: T,
+//  ^ property
+//   ^ operator
+//     ^ type
+//      ^ punctuation.delimiter
+    y: T,
+//  ^ property
+//   ^ operator
+//     ^ type
+//      ^ punctuation.delimiter
+}
+// <- punctuation.bracket
+
+enum Shape {
+// <- keyword
+//   ^^^^^ type
+//         ^ punctuation.bracket
+    Circle { radius: f64 },
+//  ^^^^^^ variable type
+//         ^ punctuation.bracket
+//           ^^^^^^ property
+//                 ^ operator
+//                   ^^^ type.builtin
+//                       ^ punctuation.bracket
+//                        ^ punctuation.delimiter
+    Square(f64),
+//  ^^^^^^ variable type
+//        ^ punctuation.bracket
+//         ^^^ type.builtin
+//            ^ punctuation.bracket
+//             ^ punctuation.delimiter
+}
+// <- punctuation.bracket
+
+trait Area {
+// <- keyword
+//    ^^^^ type type.interface
+//         ^ punctuation.bracket
+    fn area(&self) -> f64;
+//  ^^ keyword
+//     ^^^^ variable function.definition
+//         ^ punctuation.bracket
+//          ^ operator
+//           ^^^^ variable.special
+//               ^ punctuation.bracket
+//                 ^^ operator
+//                    ^^^ type.builtin
+//                       ^ punctuation.delimiter
+}
+// <- punctuation.bracket
+
+impl Area for Shape {
+// <- keyword
+//   ^^^^ type type.interface
+//        ^^^ keyword
+//            ^^^^^ type
+//                  ^ punctuation.bracket
+    fn area(&self) -> f64 {
+//  ^^ keyword
+//     ^^^^ variable function.definition
+//         ^ punctuation.bracket
+//          ^ operator
+//           ^^^^ variable.special
+//               ^ punctuation.bracket
+//                 ^^ operator
+//                    ^^^ type.builtin
+//                        ^ punctuation.bracket
+        match self {
+//      ^^^^^ keyword.control
+//            ^^^^ variable.special
+//                 ^ punctuation.bracket
+            Shape::Circle { radius } => 3.14 * radius * radius,
+//          ^^^^^ variable type
+//               ^^ punctuation.delimiter
+//                 ^^^^^^ type
+//                        ^ punctuation.bracket
+//                          ^^^^^^ property
+//                                 ^ punctuation.bracket
+//                                   ^^ operator
+//                                      ^^^^ number
+//                                           ^ operator
+//                                             ^^^^^^ variable
+//                                                    ^ operator
+//                                                      ^^^^^^ variable
+//                                                            ^ punctuation.delimiter
+            Shape::Square(side) => side * side,
+//          ^^^^^ variable type
+//               ^^ punctuation.delimiter
+//                 ^^^^^^ variable type
+//                       ^ punctuation.bracket
+//                        ^^^^ variable
+//                            ^ punctuation.bracket
+//                              ^^ operator
+//                                 ^^^^ variable
+//                                      ^ operator
+//                                        ^^^^ variable
+//                                            ^ punctuation.delimiter
+        }
+//      ^ punctuation.bracket
+    }
+//  ^ punctuation.bracket
+}
+// <- punctuation.bracket
+
+// Counts the words of a text.
+// <- comment
+fn count<'a>(text: &'a str, counts: &mut HashMap<&'a str, usize>) -> bool {
+// <- keyword
+// ^^^^^ variable function.definition
+//      ^ punctuation.bracket operator
+//       ^ lifetime
+//        ^ variable lifetime
+//         ^ punctuation.bracket operator
+//          ^ punctuation.bracket
+//           ^^^^ variable variable.parameter
+//               ^ operator
+//                 ^ operator
+//                  ^ lifetime
+//                   ^ variable lifetime
+//                     ^^^ type.builtin
+//                        ^ punctuation.delimiter
+//                          ^^^^^^ variable variable.parameter
+//                                ^ operator
+//                                  ^ operator
+//                                   ^^^ keyword
+//                                       ^^^^^^^ type
+//                                              ^ punctuation.bracket operator
+//                                               ^ operator
+//                                                ^ lifetime
+//                                                 ^ variable lifetime
+//                                                   ^^^ type.builtin
+//                                                      ^ punctuation.delimiter
+//                                                        ^^^^^ type.builtin
+//                                                             ^ punctuation.bracket operator
+//                                                              ^ punctuation.bracket
+//                                                                ^^ operator
+//                                                                   ^^^^ type.builtin
+//                                                                        ^ punctuation.bracket
+    for word in text.split_whitespace() {
+//  ^^^ keyword keyword.control
+//      ^^^^ variable
+//           ^^ keyword.control
+//              ^^^^ variable
+//                  ^ punctuation.delimiter
+//                   ^^^^^^^^^^^^^^^^ property function.method
+//                                   ^ punctuation.bracket
+//                                    ^ punctuation.bracket
+//                                      ^ punctuation.bracket
+        *counts.entry(word).or_insert(0) += 1;
+//      ^ operator
+//       ^^^^^^ variable
+//             ^ punctuation.delimiter
+//              ^^^^^ property function.method
+//                   ^ punctuation.bracket
+//                    ^^^^ variable
+//                        ^ punctuation.bracket
+//                         ^ punctuation.delimiter
+//                          ^^^^^^^^^ property function.method
+//                                   ^ punctuation.bracket
+//                                    ^ number
+//                                     ^ punctuation.bracket
+//                                       ^^ operator
+//                                          ^ number
+//                                           ^ punctuation.delimiter
+    }
+//  ^ punctuation.bracket
+    let point = Point { x: 1, y: 2 };
+//  ^^^ keyword
+//      ^^^^^ variable
+//            ^ operator
+//              ^^^^^ type
+//                    ^ punctuation.bracket
+//                      ^ property
+//                       ^ operator
+//                         ^ number
+//                          ^ punctuation.delimiter
+//                            ^ property
+//                             ^ operator
+//                               ^ number
+//                                 ^ punctuation.bracket
+//                                  ^ punctuation.delimiter
+    point.x < MAX_DEPTH as i32 && !text.is_empty() && 'c' != '\n'
+//  ^^^^^ variable
+//       ^ punctuation.delimiter
+//        ^ property
+//          ^ operator
+//            ^^^^^^^^^ variable type constant
+//                      ^^ keyword
+//                         ^^^ type.builtin
+//                             ^^ operator
+//                                ^ operator
+//                                 ^^^^ variable
+//                                     ^ punctuation.delimiter
+//                                      ^^^^^^^^ property function.method
+//                                              ^ punctuation.bracket
+//                                               ^ punctuation.bracket
+//                                                 ^^ operator
+//                                                    ^^^ string
+//                                                        ^^ operator
+//                                                           ^^^^ string
+}
+// <- punctuation.bracket
@@ -0,0 +1,62 @@
+fn main() {
+//     ^^ indent
+//      ^ end
+//        ^ indent ...
+    let numbers = [
+//  ^^^^^^^^^^^^^^^ indent ...
+//                ^ indent ...
+        1,
+        2,
+    ];
+//  ^ ... indent
+//  ^ end
+//  ^^ ... indent
+    let total = numbers
+//  ^^^^^^^^^^^^^^^^^^^ indent ...
+//              ^^^^^^^ indent ...
+//              ^^^^^^^ indent ...
+//              ^^^^^^^ indent ...
+//              ^^^^^^^ indent ...
+        .iter()
+//      ^^^^^ ... indent
+//      ^^^^^^^ ... indent
+//           ^^ indent
+//            ^ end
+        .sum::<i32>();
+//      ^^^^ ... indent
+//      ^^^^^^^^^^^^^ ... indent
+//      ^^^^^^^^^^^^^^ ... indent
+//            ^^^^^ indent
+//                ^ end
+//                 ^^ indent
+//                  ^ end
+    if total > 0 {
+//               ^ indent ...
+        print(
+//      ^^^^^^ indent ...
+//           ^ indent ...
+            total,
+        );
+//      ^ ... indent
+//      ^ ... indent
+//      ^ end
+    }
+//  ^ ... indent
+//  ^ end
+}
+// <- ... indent
+// <- end
+
+struct Wrapper<T>
+//            ^^^ indent
+//              ^ end
+where
+// <- indent ...
+    T: Clone,
+//  ^^^^^^^^^ ... indent
+{
+// <- end indent ...
+    value: T,
+}
+// <- ... end indent
+// <- end
@@ -0,0 +1,58 @@
+// Shapes that can be drawn.
+// <- comment.around
+// They are all measured in pixels.
+// <- comment.around
+struct Point {
+// <- class.around ...
+    x: i32,
+//  ^^^^^^ class.inside
+//        ^ class.inside
+    y: i32,
+//  ^^^^^^ class.inside
+//        ^ class.inside
+}
+// <- ... class.around
+
+struct Size(u32, u32);
+// <- class.around
+//          ^^^ class.inside
+//             ^ class.inside
+//               ^^^ class.inside
+
+enum Shape {
+// <- class.around ...
+    Dot(Point),
+//  ^^^^^^^^^^ class.inside
+//            ^ class.inside
+    Rectangle { origin: Point, size: Size },
+//  ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ class.inside
+//                                         ^ class.inside
+}
+// <- ... class.around
+
+trait Draw {
+// <- class.around ...
+    fn draw(&self);
+//  ^^^^^^^^^^^^^^^ function.around class.inside
+}
+// <- ... class.around
+
+impl Draw for Shape {
+// <- class.around ...
+    fn draw(&self) {
+//  ^^^^^^^^^^^^^^^^ function.around class.inside ...
+        let _ = self;
+//      ^^^^^^^^^^^^^ function.inside
+    }
+//  ^ ... function.around class.inside
+}
+// <- ... class.around
+
+mod tests {
+// <- class.around ...
+    /* A block comment. */
+//  ^^^^^^^^^^^^^^^^^^^^^^ class.inside comment.around
+    fn check() {}
+//  ^^^^^^^^^^^^^ function.around class.inside
+}
+// <- ... class.around
@@ -0,0 +1,567 @@
+use std::{
+    collections::VecDeque,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use fs::Fs;
+use futures::StreamExt as _;
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Task};
+use project::{Project, ProjectEntryId, ProjectPath};
+use settings::Settings as _;
+
+use crate::LargeFileViewerSettings;
+
+/// The size of the chunks the file is paged in from disk for display.
+const PAGE_SIZE: u64 = 256 * 1024;
+/// The number of pages kept in memory. Older pages are evicted first.
+const MAX_CACHED_PAGES: usize = 64;
+/// The size of the chunks scanned when indexing lines and searching.
+const SCAN_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+/// Lines longer than this are truncated for display.
+pub const MAX_DISPLAYED_LINE_LEN: u64 = 16 * 1024;
+const WATCH_LATENCY: Duration = Duration::from_millis(100);
+
+pub fn is_large_file(project: &Entity<Project>, path: &ProjectPath, cx: &App) -> bool {
+    let settings = LargeFileViewerSettings::get_global(cx);
+    let project = project.read(cx);
+    settings.enabled
+        && project.is_local()
+        && project
+            .entry_for_path(path, cx)
+            .is_some_and(|entry| entry.is_file() && entry.size >= settings.threshold_bytes())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LargeFileEvent {
+    /// More of the file was indexed, so more lines can be displayed.
+    LinesIndexed,
+    /// Pages that were requested for display finished loading.
+    PagesLoaded,
+    /// The file grew on disk.
+    Grew,
+    /// The file shrank on disk, e.g. because it was truncated or rotated, so it was reloaded.
+    Reset,
+}
+
+/// A file too large to load into a text buffer.
+///
+/// The file is paged in from disk through [`Fs`] as it is displayed, while line
+/// offsets are indexed in the background. Content is never parsed or sent to
+/// language servers.
+pub struct LargeFile {
+    abs_path: PathBuf,
+    project_path: ProjectPath,
+    entry_id: Option<ProjectEntryId>,
+    fs: Arc<dyn Fs>,
+    len: u64,
+    line_index: LineIndex,
+    pages: HashMap<u64, Arc<[u8]>>,
+    page_order: VecDeque<u64>,
+    loading_pages: HashSet<u64>,
+    /// Incremented whenever the file is reloaded, so that stale indexing and page
+    /// loads can be discarded.
+    generation: usize,
+    index_task: Option<Task<()>>,
+    _watch_task: Task<()>,
+}
+
+impl EventEmitter<LargeFileEvent> for LargeFile {}
+
+impl LargeFile {
+    pub fn open(
+        project: &Entity<Project>,
+        project_path: ProjectPath,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let project = project.read(cx);
+        let fs = project.fs().clone();
+        let abs_path = project.absolute_path(&project_path, cx);
+        let entry_id = project
+            .entry_for_path(&project_path, cx)
+            .map(|entry| entry.id);
+
+        cx.spawn(async move |cx| {
+            let abs_path = abs_path
+                .with_context(|| format!("finding the absolute path of {project_path:?}"))?;
+            let metadata = fs
+                .metadata(&abs_path)
+                .await?
+                .with_context(|| format!("{abs_path:?} does not exist"))?;
+
+            Ok(cx.new(|cx| {
+                let mut this = Self {
+                    _watch_task: Self::watch(fs.clone(), abs_path.clone(), cx),
+                    abs_path,
+                    project_path,
+                    entry_id,
+                    fs,
+                    len: metadata.len,
+                    line_index: LineIndex::default(),
+                    pages: HashMap::default(),
+                    page_order: VecDeque::new(),
+                    loading_pages: HashSet::default(),
+                    generation: 0,
+                    index_task: None,
+                };
+                this.index_lines(cx);
+                this
+            }))
+        })
+    }
+
+    fn watch(fs: Arc<dyn Fs>, abs_path: PathBuf, cx: &mut Context<Self>) -> Task<()> {
+        cx.spawn(async move |this, cx| {
+            let (mut events, _watcher) = fs.watch(&abs_path, WATCH_LATENCY).await;
+            while events.next().await.is_some() {
+                let Ok(Some(metadata)) = fs.metadata(&abs_path).await else {
+                    continue;
+                };
+                if this
+                    .update(cx, |this, cx| this.set_len(metadata.len, cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        })
+    }
+
+    pub fn abs_path(&self) -> &PathBuf {
+        &self.abs_path
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of lines indexed so far.
+    pub fn line_count(&self) -> usize {
+        self.line_index.line_count()
+    }
+
+    pub fn is_indexing(&self) -> bool {
+        self.index_task.is_some()
+    }
+
+    /// The number of bytes that have been scanned for line breaks.
+    pub fn indexed_len(&self) -> u64 {
+        self.line_index.indexed_len
+    }
+
+    pub fn row_for_offset(&self, offset: u64) -> Option<usize> {
+        self.line_index.row_for_offset(offset)
+    }
+
+    /// The byte range of the given line, excluding its line ending.
+    pub fn line_range(&self, row: usize) -> Option<Range<u64>> {
+        self.line_index.line_range(row)
+    }
+
+    /// Returns the bytes of the given line, truncated to [`MAX_DISPLAYED_LINE_LEN`],
+    /// or `None` if they still need to be loaded from disk.
+    ///
+    /// Missing pages are loaded in the background, emitting [`LargeFileEvent::PagesLoaded`]
+    /// once they are available.
+    pub fn line_bytes(&mut self, row: usize, cx: &mut Context<Self>) -> Option<Vec<u8>> {
+        let range = self.line_range(row)?;
+        let range = range.start..range.end.min(range.start + MAX_DISPLAYED_LINE_LEN);
+        self.read(range, cx)
+    }
+
+    fn read(&mut self, range: Range<u64>, cx: &mut Context<Self>) -> Option<Vec<u8>> {
+        if range.is_empty() {
+            return Some(Vec::new());
+        }
+        let first_page = range.start / PAGE_SIZE;
+        let last_page = (range.end - 1) / PAGE_SIZE;
+        let missing_pages = (first_page..=last_page)
+            .filter(|page| !self.pages.contains_key(page))
+            .collect::<Vec<_>>();
+        if !missing_pages.is_empty() {
+            for page in missing_pages {
+                self.load_page(page, cx);
+            }
+            return None;
+        }
+
+        let mut bytes = Vec::with_capacity((range.end - range.start) as usize);
+        for page in first_page..=last_page {
+            let page_start = page * PAGE_SIZE;
+            let contents = &self.pages[&page];
+            let start = (range.start.max(page_start) - page_start) as usize;
+            let end = ((range.end - page_start) as usize).min(contents.len());
+            if start < end {
+                bytes.extend_from_slice(&contents[start..end]);
+            }
+        }
+        Some(bytes)
+    }
+
+    fn load_page(&mut self, page: u64, cx: &mut Context<Self>) {
+        if !self.loading_pages.insert(page) {
+            return;
+        }
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        let generation = self.generation;
+        let page_range = page * PAGE_SIZE..(page + 1) * PAGE_SIZE;
+        cx.spawn(async move |this, cx| {
+            let contents = fs.load_byte_range(&abs_path, page_range).await;
+            this.update(cx, |this, cx| {
+                if this.generation != generation {
+                    return;
+                }
+                this.loading_pages.remove(&page);
+                match contents {
+                    Ok(contents) => {
+                        if this.page_order.len() >= MAX_CACHED_PAGES
+                            && let Some(evicted) = this.page_order.pop_front()
+                        {
+                            this.pages.remove(&evicted);
+                        }
+                        this.pages.insert(page, contents.into());
+                        this.page_order.push_back(page);
+                        cx.emit(LargeFileEvent::PagesLoaded);
+                        cx.notify();
+                    }
+                    Err(error) => log::error!("failed to load page of {abs_path:?}: {error:#}"),
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn set_len(&mut self, len: u64, cx: &mut Context<Self>) {
+        if len == self.len {
+            return;
+        }
+
+        if len < self.len {
+            self.generation += 1;
+            self.len = len;
+            self.line_index = LineIndex::default();
+            self.pages.clear();
+            self.page_order.clear();
+            self.loading_pages.clear();
+            self.index_task = None;
+            cx.emit(LargeFileEvent::Reset);
+        } else {
+            // The last page may have been only partially filled before the file grew.
+            let last_page = self.len / PAGE_SIZE;
+            self.pages.remove(&last_page);
+            self.page_order.retain(|page| *page != last_page);
+            self.len = len;
+            cx.emit(LargeFileEvent::Grew);
+        }
+        self.index_lines(cx);
+        cx.notify();
+    }
+
+    /// Scans the rest of the file for line breaks in the background, unless it is already
+    /// being scanned.
+    fn index_lines(&mut self, cx: &mut Context<Self>) {
+        if self.index_task.is_some() || self.line_index.indexed_len >= self.len {
+            return;
+        }
+
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        let generation = self.generation;
+        self.index_task = Some(cx.spawn(async move |this, cx| {
+            loop {
+                let Ok(Some(range)) = this.read_with(cx, |this, _| {
+                    (this.generation == generation).then(|| {
+                        let start = this.line_index.indexed_len;
+                        start..this.len.min(start + SCAN_CHUNK_SIZE)
+                    })
+                }) else {
+                    return;
+                };
+                if range.is_empty() {
+                    break;
+                }
+
+                let chunk = match fs.load_byte_range(&abs_path, range.clone()).await {
+                    Ok(chunk) if !chunk.is_empty() => chunk,
+                    Ok(_) => break,
+                    Err(error) => {
+                        log::error!("failed to index lines of {abs_path:?}: {error:#}");
+                        break;
+                    }
+                };
+                let start = range.start;
+                let line_starts = cx
+                    .background_spawn(async move {
+                        let line_starts = line_starts_in_chunk(start, &chunk);
+                        (line_starts, chunk.len() as u64)
+                    })
+                    .await;
+
+                let updated = this.update(cx, |this, cx| {
+                    if this.generation != generation {
+                        return false;
+                    }
+                    this.line_index.extend(line_starts.0, line_starts.1);
+                    cx.emit(LargeFileEvent::LinesIndexed);
+                    cx.notify();
+                    true
+                });
+                if !matches!(updated, Ok(true)) {
+                    return;
+                }
+            }
+
+            this.update(cx, |this, cx| {
+                if this.generation == generation {
+                    this.index_task = None;
+                    cx.emit(LargeFileEvent::LinesIndexed);
+                    cx.notify();
+                }
+            })
+            .ok();
+        }));
+    }
+
+    /// Searches the file for `query`, starting at `from` and wrapping around the end of the
+    /// file, returning the byte range of the match.
+    ///
+    /// The search reads the file in chunks in the background, independently of line indexing.
+    pub fn search(
+        &self,
+        query: String,
+        from: u64,
+        reverse: bool,
+        case_sensitive: bool,
+        cx: &App,
+    ) -> Task<Result<Option<Range<u64>>>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        let len = self.len;
+        cx.background_spawn(async move {
+            let mut pattern = query.into_bytes();
+            if pattern.is_empty() {
+                return Ok(None);
+            }
+            if !case_sensitive {
+                pattern.make_ascii_lowercase();
+            }
+
+            let from = from.min(len);
+            let ranges = if reverse {
+                [0..from, from..len]
+            } else {
+                [from..len, 0..from]
+            };
+            for range in ranges {
+                if let Some(offset) = search_range(
+                    fs.as_ref(),
+                    &abs_path,
+                    len,
+                    range,
+                    &pattern,
+                    reverse,
+                    case_sensitive,
+                )
+                .await?
+                {
+                    return Ok(Some(offset..offset + pattern.len() as u64));
+                }
+            }
+            Ok(None)
+        })
+    }
+}
+
+impl project::ProjectItem for LargeFile {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        is_large_file(project, path, cx).then(|| Self::open(project, path.clone(), cx))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        false
+    }
+}
+
+/// Finds the first (or, when `reverse` is set, the last) match of `pattern` that starts
+/// within `range`.
+async fn search_range(
+    fs: &dyn Fs,
+    abs_path: &Path,
+    len: u64,
+    range: Range<u64>,
+    pattern: &[u8],
+    reverse: bool,
+    case_sensitive: bool,
+) -> Result<Option<u64>> {
+    let overlap = pattern.len() as u64 - 1;
+    let mut remaining = range;
+    while !remaining.is_empty() {
+        let chunk_starts = if reverse {
+            remaining
+                .end
+                .saturating_sub(SCAN_CHUNK_SIZE)
+                .max(remaining.start)..remaining.end
+        } else {
+            remaining.start..remaining.end.min(remaining.start + SCAN_CHUNK_SIZE)
+        };
+        let mut chunk = fs
+            .load_byte_range(
+                abs_path,
+                chunk_starts.start..(chunk_starts.end + overlap).min(len),
+            )
+            .await?;
+        if !case_sensitive {
+            chunk.make_ascii_lowercase();
+        }
+
+        let start_limit = (chunk_starts.end - chunk_starts.start) as usize;
+        if let Some(ix) = find_in_chunk(&chunk, pattern, start_limit, reverse) {
+            return Ok(Some(chunk_starts.start + ix as u64));
+        }
+
+        if reverse {
+            remaining.end = chunk_starts.start;
+        } else {
+            remaining.start = chunk_starts.end;
+        }
+    }
+    Ok(None)
+}
+
+/// Finds the first (or last) occurrence of `pattern` in `chunk` that starts before `start_limit`.
+fn find_in_chunk(chunk: &[u8], pattern: &[u8], start_limit: usize, reverse: bool) -> Option<usize> {
+    let mut windows = chunk
+        .windows(pattern.len())
+        .take(start_limit)
+        .enumerate()
+        .filter(|(_, window)| *window == pattern)
+        .map(|(ix, _)| ix);
+    if reverse {
+        windows.last()
+    } else {
+        windows.next()
+    }
+}
+
+fn line_starts_in_chunk(chunk_start: u64, chunk: &[u8]) -> Vec<u64> {
+    chunk
+        .iter()
+        .enumerate()
+        .filter(|(_, byte)| **byte == b'\n')
+        .map(|(ix, _)| chunk_start + ix as u64 + 1)
+        .collect()
+}
+
+/// The offsets at which each line of the file starts, as far as the file has been scanned.
+#[derive(Debug)]
+struct LineIndex {
+    line_starts: Vec<u64>,
+    indexed_len: u64,
+}
+
+impl Default for LineIndex {
+    fn default() -> Self {
+        Self {
+            line_starts: vec![0],
+            indexed_len: 0,
+        }
+    }
+}
+
+impl LineIndex {
+    fn extend(&mut self, line_starts: Vec<u64>, scanned_len: u64) {
+        self.line_starts.extend(line_starts);
+        self.indexed_len += scanned_len;
+    }
+
+    fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    fn line_range(&self, row: usize) -> Option<Range<u64>> {
+        let start = *self.line_starts.get(row)?;
+        let end = match self.line_starts.get(row + 1) {
+            Some(next_start) => next_start - 1,
+            None => self.indexed_len,
+        };
+        Some(start..end)
+    }
+
+    fn row_for_offset(&self, offset: u64) -> Option<usize> {
+        if offset > self.indexed_len {
+            return None;
+        }
+        Some(
+            self.line_starts
+                .partition_point(|start| *start <= offset)
+                .saturating_sub(1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_index() {
+        let mut index = LineIndex::default();
+        assert_eq!(index.line_count(), 1);
+        assert_eq!(index.line_range(0), Some(0..0));
+
+        let chunk = b"one\ntwo\nthr";
+        index.extend(line_starts_in_chunk(0, chunk), chunk.len() as u64);
+        assert_eq!(index.line_count(), 3);
+        assert_eq!(index.line_range(0), Some(0..3));
+        assert_eq!(index.line_range(1), Some(4..7));
+        // The last line extends as far as the file has been indexed.
+        assert_eq!(index.line_range(2), Some(8..11));
+        assert_eq!(index.line_range(3), None);
+
+        let chunk = b"ee\n";
+        index.extend(line_starts_in_chunk(11, chunk), chunk.len() as u64);
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_range(2), Some(8..13));
+        assert_eq!(index.line_range(3), Some(14..14));
+
+        assert_eq!(index.row_for_offset(0), Some(0));
+        assert_eq!(index.row_for_offset(3), Some(0));
+        assert_eq!(index.row_for_offset(4), Some(1));
+        assert_eq!(index.row_for_offset(12), Some(2));
+        assert_eq!(index.row_for_offset(14), Some(3));
+        assert_eq!(index.row_for_offset(15), None);
+    }
+
+    #[test]
+    fn test_find_in_chunk() {
+        let chunk = b"abcabcab";
+        assert_eq!(find_in_chunk(chunk, b"abc", chunk.len(), false), Some(0));
+        assert_eq!(find_in_chunk(chunk, b"abc", chunk.len(), true), Some(3));
+        // Matches must start before the limit, but may extend past it.
+        assert_eq!(find_in_chunk(chunk, b"cab", 3, false), Some(2));
+        assert_eq!(find_in_chunk(chunk, b"cab", 2, false), None);
+        assert_eq!(find_in_chunk(chunk, b"xyz", chunk.len(), false), None);
+    }
+}
@@ -0,0 +1,639 @@
+mod large_file;
+mod large_file_viewer_settings;
+
+use std::{borrow::Cow, ops::Range, path::Path};
+
+use editor::{Editor, EditorEvent, EditorSettings, actions::SelectAll};
+use file_icons::FileIcons;
+use gpui::{
+    App, Context, Entity, EventEmitter, FocusHandle, Focusable, HighlightStyle, ScrollStrategy,
+    StyledText, Subscription, Task, UniformListScrollHandle, Window, actions, uniform_list,
+};
+use project::{Project, ProjectItem as _};
+use settings::Settings;
+use theme::ThemeSettings;
+use ui::{Tooltip, prelude::*};
+use util::{paths::PathExt, size::format_file_size};
+use workspace::{
+    ItemSettings, Pane, ToolbarItemLocation, WorkspaceId,
+    invalid_item_view::InvalidItemView,
+    item::{BreadcrumbText, Item, ItemBufferKind, ItemEvent, ProjectItem},
+};
+
+pub use crate::large_file::*;
+pub use crate::large_file_viewer_settings::*;
+
+actions!(
+    large_file_viewer,
+    [
+        /// Focuses the search input.
+        FocusSearch,
+        /// Scrolls to the next match of the search query.
+        FindNext,
+        /// Scrolls to the previous match of the search query.
+        FindPrevious,
+        /// Toggles whether searches are case sensitive.
+        ToggleCaseSensitive,
+        /// Focuses the go to line input.
+        GoToLine,
+        /// Toggles following the end of the file as it grows.
+        ToggleFollow,
+        /// Scrolls to the first line.
+        ScrollToTop,
+        /// Scrolls to the last indexed line.
+        ScrollToBottom,
+    ]
+);
+
+/// A read-only view of a file that is too large to open in an editor.
+pub struct LargeFileViewer {
+    file: Entity<LargeFile>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    search_editor: Entity<Editor>,
+    go_to_line_editor: Entity<Editor>,
+    case_sensitive: bool,
+    /// Whether to keep the end of the file in view as it grows.
+    following: bool,
+    active_match: Option<Range<u64>>,
+    highlighted_row: Option<usize>,
+    /// An offset to scroll to once the line containing it has been indexed.
+    pending_reveal: Option<u64>,
+    status: Option<SharedString>,
+    search_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl LargeFileViewer {
+    pub fn new(
+        file: Entity<LargeFile>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let search_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Search…", window, cx);
+            editor
+        });
+        let go_to_line_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Go to line…", window, cx);
+            editor
+        });
+        let subscriptions = vec![
+            cx.subscribe(&file, Self::on_file_event),
+            cx.subscribe(&search_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::Edited { .. } = event {
+                    this.active_match = None;
+                    this.status = None;
+                    cx.notify();
+                }
+            }),
+        ];
+
+        Self {
+            file,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            search_editor,
+            go_to_line_editor,
+            case_sensitive: false,
+            following: false,
+            active_match: None,
+            highlighted_row: None,
+            pending_reveal: None,
+            status: None,
+            search_task: None,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    pub fn file(&self) -> &Entity<LargeFile> {
+        &self.file
+    }
+
+    fn on_file_event(
+        &mut self,
+        file: Entity<LargeFile>,
+        event: &LargeFileEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            LargeFileEvent::Reset => {
+                self.active_match = None;
+                self.highlighted_row = None;
+                self.pending_reveal = None;
+            }
+            LargeFileEvent::LinesIndexed | LargeFileEvent::Grew => {
+                if let Some(offset) = self.pending_reveal
+                    && let Some(row) = file.read(cx).row_for_offset(offset)
+                {
+                    self.pending_reveal = None;
+                    self.status = None;
+                    self.highlighted_row = Some(row);
+                    self.scroll_handle
+                        .scroll_to_item(row, ScrollStrategy::Center);
+                }
+            }
+            LargeFileEvent::PagesLoaded => {}
+        }
+        if self.following && *event != LargeFileEvent::PagesLoaded {
+            self.scroll_handle.scroll_to_bottom();
+        }
+        cx.emit(LargeFileViewerEvent::Changed);
+        cx.notify();
+    }
+
+    fn reveal_offset(&mut self, offset: u64, cx: &mut Context<Self>) {
+        self.following = false;
+        match self.file.read(cx).row_for_offset(offset) {
+            Some(row) => {
+                self.pending_reveal = None;
+                self.highlighted_row = Some(row);
+                self.scroll_handle
+                    .scroll_to_item(row, ScrollStrategy::Center);
+            }
+            None => {
+                self.pending_reveal = Some(offset);
+                self.status = Some("Waiting for lines to be indexed…".into());
+            }
+        }
+        cx.notify();
+    }
+
+    fn focus_search(&mut self, _: &FocusSearch, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_editor.update(cx, |editor, cx| {
+            editor.select_all(&SelectAll, window, cx);
+        });
+        window.focus(&self.search_editor.focus_handle(cx), cx);
+    }
+
+    fn go_to_line(&mut self, _: &GoToLine, window: &mut Window, cx: &mut Context<Self>) {
+        self.go_to_line_editor.update(cx, |editor, cx| {
+            editor.select_all(&SelectAll, window, cx);
+        });
+        window.focus(&self.go_to_line_editor.focus_handle(cx), cx);
+    }
+
+    fn dismiss(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        self.status = None;
+        window.focus(&self.focus_handle, cx);
+        cx.notify();
+    }
+
+    fn confirm_go_to_line(
+        &mut self,
+        _: &menu::Confirm,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let text = self.go_to_line_editor.read(cx).text(cx);
+        let Some(line) = text
+            .trim()
+            .split(':')
+            .next()
+            .and_then(|line| line.parse::<usize>().ok())
+        else {
+            self.status = Some("Enter a line number".into());
+            cx.notify();
+            return;
+        };
+
+        let file = self.file.read(cx);
+        let row = line.saturating_sub(1);
+        if row < file.line_count() {
+            self.following = false;
+            self.status = None;
+            self.highlighted_row = Some(row);
+            self.scroll_handle
+                .scroll_to_item(row, ScrollStrategy::Center);
+            window.focus(&self.focus_handle, cx);
+        } else if file.is_indexing() {
+            self.status = Some(format!("Line {line} has not been indexed yet").into());
+        } else {
+            self.status = Some(format!("The file has {} lines", file.line_count()).into());
+        }
+        cx.notify();
+    }
+
+    fn toggle_case_sensitive(
+        &mut self,
+        _: &ToggleCaseSensitive,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.case_sensitive = !self.case_sensitive;
+        self.active_match = None;
+        cx.notify();
+    }
+
+    fn toggle_follow(&mut self, _: &ToggleFollow, _: &mut Window, cx: &mut Context<Self>) {
+        self.following = !self.following;
+        if self.following {
+            self.scroll_handle.scroll_to_bottom();
+        }
+        cx.notify();
+    }
+
+    fn scroll_to_top(&mut self, _: &ScrollToTop, _: &mut Window, cx: &mut Context<Self>) {
+        self.following = false;
+        self.scroll_handle.scroll_to_item(0, ScrollStrategy::Top);
+        cx.notify();
+    }
+
+    fn scroll_to_bottom(&mut self, _: &ScrollToBottom, _: &mut Window, cx: &mut Context<Self>) {
+        self.scroll_handle.scroll_to_bottom();
+        cx.notify();
+    }
+
+    fn find_next(&mut self, _: &FindNext, _: &mut Window, cx: &mut Context<Self>) {
+        self.find(false, cx);
+    }
+
+    fn find_previous(&mut self, _: &FindPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        self.find(true, cx);
+    }
+
+    fn find(&mut self, reverse: bool, cx: &mut Context<Self>) {
+        let query = self.search_editor.read(cx).text(cx);
+        if query.is_empty() {
+            return;
+        }
+
+        let file = self.file.read(cx);
+        let from = match &self.active_match {
+            Some(active_match) if reverse => active_match.start,
+            Some(active_match) => active_match.start + 1,
+            None => file
+                .line_range(self.scroll_handle.logical_scroll_top_index())
+                .map_or(0, |range| range.start),
+        };
+        let search = file.search(query, from, reverse, self.case_sensitive, cx);
+        self.status = Some("Searching…".into());
+        self.search_task = Some(cx.spawn(async move |this, cx| {
+            let result = search.await;
+            this.update(cx, |this, cx| {
+                this.search_task = None;
+                match result {
+                    Ok(Some(range)) => {
+                        this.status = None;
+                        let offset = range.start;
+                        this.active_match = Some(range);
+                        this.reveal_offset(offset, cx);
+                    }
+                    Ok(None) => this.status = Some("No matches".into()),
+                    Err(error) => this.status = Some(format!("Search failed: {error}").into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn render_line(&mut self, row: usize, cx: &mut Context<Self>) -> impl IntoElement {
+        let line_range = self.file.read(cx).line_range(row);
+        let bytes = self.file.update(cx, |file, cx| file.line_bytes(row, cx));
+        let colors = cx.theme().colors();
+
+        let content = match (bytes, line_range) {
+            (Some(bytes), Some(line_range)) => {
+                let mut text = String::from_utf8_lossy(&bytes);
+                if text.ends_with('\r') {
+                    text.to_mut().pop();
+                }
+                let highlight = self
+                    .active_match
+                    .as_ref()
+                    .filter(|_| matches!(text, Cow::Borrowed(_)))
+                    .and_then(|active_match| {
+                        let start = active_match.start.max(line_range.start) - line_range.start;
+                        let end = active_match.end.min(line_range.end) - line_range.start;
+                        let range = start as usize..(end as usize).min(text.len());
+                        (start < end
+                            && text.is_char_boundary(range.start)
+                            && text.is_char_boundary(range.end))
+                        .then_some(range)
+                    });
+                let highlight_style = HighlightStyle {
+                    background_color: Some(colors.search_match_background),
+                    ..Default::default()
+                };
+                StyledText::new(text.into_owned())
+                    .with_highlights(highlight.map(|range| (range, highlight_style)))
+                    .into_any_element()
+            }
+            _ => Label::new("…").color(Color::Muted).into_any_element(),
+        };
+
+        h_flex()
+            .w_full()
+            .gap_4()
+            .when(self.highlighted_row == Some(row), |this| {
+                this.bg(colors.editor_active_line_background)
+            })
+            .child(
+                div()
+                    .min_w_16()
+                    .text_color(colors.editor_line_number)
+                    .child((row + 1).to_string()),
+            )
+            .child(div().whitespace_nowrap().overflow_hidden().child(content))
+    }
+
+    fn render_toolbar(&self, cx: &Context<Self>) -> impl IntoElement {
+        let input = |editor: &Entity<Editor>| {
+            div()
+                .px_2()
+                .py_0p5()
+                .rounded_sm()
+                .border_1()
+                .border_color(cx.theme().colors().border)
+                .child(editor.clone())
+        };
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .key_context("LargeFileViewerSearch")
+                    .on_action(cx.listener(Self::find_next))
+                    .on_action(cx.listener(Self::find_previous))
+                    .on_action(cx.listener(Self::dismiss))
+                    .flex_1()
+                    .gap_1()
+                    .child(input(&self.search_editor).flex_1())
+                    .child(
+                        IconButton::new("case-sensitive", IconName::CaseSensitive)
+                            .icon_size(IconSize::Small)
+                            .toggle_state(self.case_sensitive)
+                            .tooltip(|_window, cx| {
+                                Tooltip::for_action(
+                                    "Toggle Case Sensitive",
+                                    &ToggleCaseSensitive,
+                                    cx,
+                                )
+                            })
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.toggle_case_sensitive(&ToggleCaseSensitive, window, cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("find-previous", IconName::ChevronLeft)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|_window, cx| {
+                                Tooltip::for_action("Find Previous", &FindPrevious, cx)
+                            })
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.find_previous(&FindPrevious, window, cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("find-next", IconName::ChevronRight)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|_window, cx| Tooltip::for_action("Find Next", &FindNext, cx))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.find_next(&FindNext, window, cx)
+                            })),
+                    ),
+            )
+            .child(
+                div()
+                    .key_context("LargeFileViewerGoToLine")
+                    .on_action(cx.listener(Self::confirm_go_to_line))
+                    .on_action(cx.listener(Self::dismiss))
+                    .w_32()
+                    .child(input(&self.go_to_line_editor)),
+            )
+    }
+
+    fn render_status(&self, cx: &Context<Self>) -> impl IntoElement {
+        let file = self.file.read(cx);
+        let lines_label = if file.is_indexing() && !file.is_empty() {
+            let progress = file.indexed_len() * 100 / file.len();
+            format!("{} lines (indexing, {progress}%)", file.line_count())
+        } else {
+            format!("{} lines", file.line_count())
+        };
+
+        h_flex()
+            .px_2()
+            .py_0p5()
+            .gap_4()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(lines_label).size(LabelSize::Small))
+            .child(
+                Label::new(format_file_size(file.len(), false))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .when_some(self.status.clone(), |this, status| {
+                this.child(
+                    Label::new(status)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .child(div().flex_1())
+            .child(
+                Button::new("follow", "Follow")
+                    .label_size(LabelSize::Small)
+                    .toggle_state(self.following)
+                    .tooltip(|_window, cx| {
+                        Tooltip::for_action("Follow the End of the File", &ToggleFollow, cx)
+                    })
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.toggle_follow(&ToggleFollow, window, cx)
+                    })),
+            )
+    }
+}
+
+pub enum LargeFileViewerEvent {
+    Changed,
+}
+
+impl EventEmitter<LargeFileViewerEvent> for LargeFileViewer {}
+
+impl Focusable for LargeFileViewer {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for LargeFileViewer {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let line_count = self.file.read(cx).line_count();
+        let settings = ThemeSettings::get_global(cx);
+
+        v_flex()
+            .key_context("LargeFileViewer")
+            .on_action(cx.listener(Self::focus_search))
+            .on_action(cx.listener(Self::go_to_line))
+            .on_action(cx.listener(Self::toggle_case_sensitive))
+            .on_action(cx.listener(Self::toggle_follow))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_toolbar(cx))
+            .child(
+                div()
+                    .id("large-file-viewer-lines")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::scroll_to_top))
+                    .on_action(cx.listener(Self::scroll_to_bottom))
+                    .flex_1()
+                    .min_h_0()
+                    .p_2()
+                    .font(settings.buffer_font.clone())
+                    .text_size(settings.buffer_font_size(cx))
+                    .child(
+                        uniform_list(
+                            "large-file-viewer-rows",
+                            line_count,
+                            cx.processor(|this, range: Range<usize>, _window, cx| {
+                                range
+                                    .map(|row| this.render_line(row, cx))
+                                    .collect::<Vec<_>>()
+                            }),
+                        )
+                        .size_full()
+                        .track_scroll(&self.scroll_handle),
+                    ),
+            )
+            .child(self.render_status(cx))
+    }
+}
+
+impl Item for LargeFileViewer {
+    type Event = LargeFileViewerEvent;
+
+    fn to_item_events(event: &Self::Event, f: &mut dyn FnMut(ItemEvent)) {
+        match event {
+            LargeFileViewerEvent::Changed => f(ItemEvent::UpdateTab),
+        }
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.file.entity_id(), self.file.read(cx))
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.file.read(cx).abs_path();
+        Some(abs_path.compact().to_string_lossy().into_owned().into())
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.file
+            .read(cx)
+            .abs_path()
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned().into())
+            .unwrap_or_default()
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.file.read(cx).abs_path();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Large File Viewer Opened")
+    }
+
+    fn breadcrumb_location(&self, cx: &App) -> ToolbarItemLocation {
+        if EditorSettings::get_global(cx).toolbar.breadcrumbs {
+            ToolbarItemLocation::PrimaryLeft
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+
+    fn breadcrumbs(&self, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        let project = self.project.read(cx);
+        let project_path = self.file.read(cx).project_path(cx)?;
+        let mut path = project_path.path.clone();
+        if project.visible_worktrees(cx).count() > 1
+            && let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx)
+        {
+            path = worktree.read(cx).root_name().join(&path);
+        }
+
+        Some(vec![BreadcrumbText {
+            text: path.display(project.path_style(cx)).to_string(),
+            highlights: None,
+            font: Some(ThemeSettings::get_global(cx).buffer_font.clone()),
+        }])
+    }
+
+    fn can_split(&self) -> bool {
+        true
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Entity<Self>>>
+    where
+        Self: Sized,
+    {
+        let file = self.file.clone();
+        let project = self.project.clone();
+        Task::ready(Some(cx.new(|cx| Self::new(file, project, window, cx))))
+    }
+
+    fn buffer_kind(&self, _: &App) -> ItemBufferKind {
+        ItemBufferKind::Singleton
+    }
+}
+
+impl ProjectItem for LargeFileViewer {
+    type Item = LargeFile;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, project, window, cx)
+    }
+
+    fn for_broken_project_item(
+        abs_path: &Path,
+        is_local: bool,
+        e: &anyhow::Error,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<InvalidItemView>
+    where
+        Self: Sized,
+    {
+        Some(InvalidItemView::new(abs_path, is_local, e, window, cx))
+    }
+}
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<LargeFileViewer>(cx);
+}
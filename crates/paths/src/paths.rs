@@ -431,6 +431,12 @@ pub fn remote_servers_dir() -> &'static PathBuf {
     REMOTE_SERVERS_DIR.get_or_init(|| data_dir().join("remote_servers"))
 }
 
+/// Returns the path to the directory where edits made to remote projects while offline are journaled.
+pub fn offline_journal_dir() -> &'static PathBuf {
+    static OFFLINE_JOURNAL_DIR: OnceLock<PathBuf> = OnceLock::new();
+    OFFLINE_JOURNAL_DIR.get_or_init(|| data_dir().join("offline_journal"))
+}
+
 /// Returns the path to the directory where the devcontainer CLI is installed.
 pub fn devcontainer_dir() -> &'static PathBuf {
     static DEVCONTAINER_DIR: OnceLock<PathBuf> = OnceLock::new();
@@ -0,0 +1,356 @@
+//! Module for managing bookmarks in a project.
+//!
+//! Bookmarks are anchored to buffer positions, so they follow the text they point at while the buffer is edited.
+//! The store keeps buffers with bookmarks open, which lets bookmarks follow their file when it's renamed on disk (e.g. by git).
+use anyhow::Result;
+use collections::BTreeMap;
+use gpui::{App, AppContext, Context, Entity, EventEmitter, SharedString, Subscription, Task};
+use language::{Buffer, BufferEvent, BufferSnapshot};
+use std::{ops::Range, path::Path, sync::Arc};
+use text::Point;
+
+use crate::{ProjectPath, buffer_store::BufferStore, worktree_store::WorktreeStore};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bookmark {
+    pub position: text::Anchor,
+    pub label: Option<SharedString>,
+}
+
+/// A bookmark in a form that can be persisted across restarts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerializedBookmark {
+    pub path: Arc<Path>,
+    pub row: u32,
+    pub label: Option<Arc<str>>,
+}
+
+/// A bookmark resolved against the current contents of its buffer.
+#[derive(Clone, Debug)]
+pub struct BookmarkEntry {
+    pub path: Arc<Path>,
+    pub buffer: Entity<Buffer>,
+    pub bookmark: Bookmark,
+    pub row: u32,
+    /// The trimmed text of the bookmarked line.
+    pub line: SharedString,
+}
+
+struct BookmarksInFile {
+    buffer: Entity<Buffer>,
+    bookmarks: Vec<Bookmark>,
+    _subscription: Subscription,
+}
+
+impl BookmarksInFile {
+    fn new(buffer: Entity<Buffer>, cx: &mut Context<BookmarkStore>) -> Self {
+        let subscription = cx.subscribe(&buffer, |bookmark_store, buffer, event, cx| {
+            if let BufferEvent::FileHandleChanged = event {
+                bookmark_store.handle_file_changed(buffer, cx);
+            }
+        });
+
+        BookmarksInFile {
+            buffer,
+            bookmarks: Vec::new(),
+            _subscription: subscription,
+        }
+    }
+
+    fn row_of(bookmark: &Bookmark, snapshot: &BufferSnapshot) -> u32 {
+        bookmark.position.summary::<Point>(snapshot).row
+    }
+}
+
+pub struct BookmarkStore {
+    buffer_store: Entity<BufferStore>,
+    worktree_store: Entity<WorktreeStore>,
+    bookmarks: BTreeMap<Arc<Path>, BookmarksInFile>,
+}
+
+impl BookmarkStore {
+    pub fn new(worktree_store: Entity<WorktreeStore>, buffer_store: Entity<BufferStore>) -> Self {
+        BookmarkStore {
+            buffer_store,
+            worktree_store,
+            bookmarks: BTreeMap::new(),
+        }
+    }
+
+    pub fn abs_path_from_buffer(buffer: &Entity<Buffer>, cx: &App) -> Option<Arc<Path>> {
+        worktree::File::from_dyn(buffer.read(cx).file())
+            .map(|file| file.worktree.read(cx).absolutize(&file.path))
+            .map(Arc::<Path>::from)
+    }
+
+    fn handle_file_changed(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let entity_id = buffer.entity_id();
+        let Some(old_path) = self
+            .bookmarks
+            .iter()
+            .find(|(_, in_file)| in_file.buffer.entity_id() == entity_id)
+            .map(|(path, _)| path.clone())
+        else {
+            return;
+        };
+
+        if buffer
+            .read(cx)
+            .file()
+            .is_none_or(|f| f.disk_state().is_deleted())
+        {
+            self.bookmarks.remove(&old_path);
+            cx.emit(BookmarkStoreEvent::BookmarksCleared(vec![old_path]));
+            cx.notify();
+            return;
+        }
+
+        let Some(new_path) = Self::abs_path_from_buffer(&buffer, cx) else {
+            return;
+        };
+        if new_path == old_path || self.bookmarks.contains_key(&new_path) {
+            return;
+        }
+        if let Some(bookmarks_in_file) = self.bookmarks.remove(&old_path) {
+            self.bookmarks.insert(new_path.clone(), bookmarks_in_file);
+            cx.emit(BookmarkStoreEvent::BookmarksCleared(vec![old_path]));
+            cx.emit(BookmarkStoreEvent::BookmarksUpdated(new_path));
+            cx.notify();
+        }
+    }
+
+    /// Adds a bookmark on the row containing `position`, or removes the bookmarks on that row if there are any.
+    pub fn toggle_bookmark(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.summary::<Point>(&snapshot).row;
+        self.update_bookmarks_in_file(buffer, cx, |bookmarks| {
+            let len = bookmarks.len();
+            bookmarks.retain(|bookmark| BookmarksInFile::row_of(bookmark, &snapshot) != row);
+            if bookmarks.len() == len {
+                bookmarks.push(Bookmark {
+                    position: snapshot.anchor_after(Point::new(row, 0)),
+                    label: None,
+                });
+            }
+            bookmarks.sort_by(|a, b| a.position.cmp(&b.position, &snapshot));
+        });
+    }
+
+    /// Sets the label of the bookmark on the row containing `position`, creating the bookmark if needed.
+    pub fn set_label(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        label: Option<SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.summary::<Point>(&snapshot).row;
+        let label = label.filter(|label| !label.trim().is_empty());
+        self.update_bookmarks_in_file(buffer, cx, |bookmarks| {
+            if let Some(bookmark) = bookmarks
+                .iter_mut()
+                .find(|bookmark| BookmarksInFile::row_of(bookmark, &snapshot) == row)
+            {
+                bookmark.label = label;
+            } else {
+                bookmarks.push(Bookmark {
+                    position: snapshot.anchor_after(Point::new(row, 0)),
+                    label,
+                });
+                bookmarks.sort_by(|a, b| a.position.cmp(&b.position, &snapshot));
+            }
+        });
+    }
+
+    pub fn remove_bookmark(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_bookmarks_in_file(buffer, cx, |bookmarks| {
+            bookmarks.retain(|bookmark| bookmark.position != position);
+        });
+    }
+
+    fn update_bookmarks_in_file(
+        &mut self,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+        update: impl FnOnce(&mut Vec<Bookmark>),
+    ) {
+        let Some(abs_path) = Self::abs_path_from_buffer(&buffer, cx) else {
+            return;
+        };
+
+        let bookmarks_in_file = self
+            .bookmarks
+            .entry(abs_path.clone())
+            .or_insert_with(|| BookmarksInFile::new(buffer, cx));
+        update(&mut bookmarks_in_file.bookmarks);
+        if bookmarks_in_file.bookmarks.is_empty() {
+            self.bookmarks.remove(&abs_path);
+        }
+
+        cx.emit(BookmarkStoreEvent::BookmarksUpdated(abs_path));
+        cx.notify();
+    }
+
+    pub fn clear_bookmarks(&mut self, cx: &mut Context<Self>) {
+        let bookmark_paths = self.bookmarks.keys().cloned().collect();
+        self.bookmarks.clear();
+        cx.emit(BookmarkStoreEvent::BookmarksCleared(bookmark_paths));
+        cx.notify();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bookmarks.is_empty()
+    }
+
+    /// Returns the bookmarks in `buffer`, ordered by position and optionally limited to `range`.
+    pub fn bookmarks<'a>(
+        &'a self,
+        buffer: &'a Entity<Buffer>,
+        range: Option<Range<text::Anchor>>,
+        buffer_snapshot: &'a BufferSnapshot,
+        cx: &App,
+    ) -> impl Iterator<Item = &'a Bookmark> + 'a {
+        let abs_path = Self::abs_path_from_buffer(buffer, cx);
+        abs_path
+            .and_then(|path| self.bookmarks.get(&path))
+            .into_iter()
+            .flat_map(move |bookmarks_in_file| {
+                let range = range.clone();
+                bookmarks_in_file.bookmarks.iter().filter(move |bookmark| {
+                    if !buffer_snapshot.can_resolve(&bookmark.position) {
+                        return false;
+                    }
+                    range.as_ref().is_none_or(|range| {
+                        bookmark.position.cmp(&range.start, buffer_snapshot).is_ge()
+                            && bookmark.position.cmp(&range.end, buffer_snapshot).is_le()
+                    })
+                })
+            })
+    }
+
+    /// Returns every bookmark in the project, ordered by path and row.
+    pub fn all_bookmarks(&self, cx: &App) -> Vec<BookmarkEntry> {
+        self.bookmarks
+            .iter()
+            .flat_map(|(path, bookmarks_in_file)| {
+                let snapshot = bookmarks_in_file.buffer.read(cx).snapshot();
+                bookmarks_in_file
+                    .bookmarks
+                    .iter()
+                    .map(move |bookmark| {
+                        let row = BookmarksInFile::row_of(bookmark, &snapshot);
+                        let line = snapshot
+                            .text_for_range(
+                                Point::new(row, 0)..Point::new(row, snapshot.line_len(row)),
+                            )
+                            .collect::<String>();
+                        BookmarkEntry {
+                            path: path.clone(),
+                            buffer: bookmarks_in_file.buffer.clone(),
+                            bookmark: bookmark.clone(),
+                            row,
+                            line: line.trim().to_string().into(),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    pub fn all_serialized_bookmarks(
+        &self,
+        cx: &App,
+    ) -> BTreeMap<Arc<Path>, Vec<SerializedBookmark>> {
+        self.bookmarks
+            .iter()
+            .map(|(path, bookmarks_in_file)| {
+                let snapshot = bookmarks_in_file.buffer.read(cx).snapshot();
+                (
+                    path.clone(),
+                    bookmarks_in_file
+                        .bookmarks
+                        .iter()
+                        .map(|bookmark| SerializedBookmark {
+                            path: path.clone(),
+                            row: BookmarksInFile::row_of(bookmark, &snapshot),
+                            label: bookmark.label.clone().map(Arc::from),
+                        })
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn with_serialized_bookmarks(
+        &self,
+        bookmarks: BTreeMap<Arc<Path>, Vec<SerializedBookmark>>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let worktree_store = self.worktree_store.downgrade();
+        let buffer_store = self.buffer_store.downgrade();
+        cx.spawn(async move |this, cx| {
+            let mut new_bookmarks = BTreeMap::default();
+            for (path, serialized) in bookmarks {
+                if serialized.is_empty() {
+                    continue;
+                }
+                let (worktree, relative_path) = worktree_store
+                    .update(cx, |this, cx| {
+                        this.find_or_create_worktree(&path, false, cx)
+                    })?
+                    .await?;
+                let buffer = buffer_store
+                    .update(cx, |this, cx| {
+                        let path = ProjectPath {
+                            worktree_id: worktree.read(cx).id(),
+                            path: relative_path,
+                        };
+                        this.open_buffer(path, cx)
+                    })?
+                    .await;
+                let Ok(buffer) = buffer else {
+                    log::debug!("Skipping bookmarks for a file that could not be opened: {path:?}");
+                    continue;
+                };
+                let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+
+                let mut bookmarks_in_file =
+                    this.update(cx, |_, cx| BookmarksInFile::new(buffer, cx))?;
+                for bookmark in serialized {
+                    if bookmark.row > snapshot.max_point().row {
+                        log::debug!("Skipping a deserialized bookmark that's out of range");
+                        continue;
+                    }
+                    bookmarks_in_file.bookmarks.push(Bookmark {
+                        position: snapshot.anchor_after(Point::new(bookmark.row, 0)),
+                        label: bookmark.label.map(SharedString::from),
+                    });
+                }
+                new_bookmarks.insert(path, bookmarks_in_file);
+            }
+
+            this.update(cx, |this, cx| {
+                this.bookmarks = new_bookmarks;
+                cx.notify();
+            })
+        })
+    }
+}
+
+pub enum BookmarkStoreEvent {
+    BookmarksUpdated(Arc<Path>),
+    BookmarksCleared(Vec<Arc<Path>>),
+}
+
+impl EventEmitter<BookmarkStoreEvent> for BookmarkStore {}
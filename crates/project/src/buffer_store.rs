@@ -1073,17 +1073,23 @@ impl BufferStore {
     }
 
     pub fn disconnected_from_host(&mut self, cx: &mut App) {
-        for open_buffer in self.opened_buffers.values_mut() {
-            if let Some(buffer) = open_buffer.upgrade() {
-                buffer.update(cx, |buffer, _| buffer.give_up_waiting());
-            }
-        }
+        self.disconnected_from_remote_server(cx);
 
         for buffer in self.buffers() {
             buffer.update(cx, |buffer, cx| {
                 buffer.set_capability(Capability::ReadOnly, cx)
             });
         }
+    }
+
+    /// Like [`Self::disconnected_from_host`], but leaves buffers editable, so that
+    /// edits can be journaled until the project is reconnected.
+    pub fn disconnected_from_remote_server(&mut self, cx: &mut App) {
+        for open_buffer in self.opened_buffers.values_mut() {
+            if let Some(buffer) = open_buffer.upgrade() {
+                buffer.update(cx, |buffer, _| buffer.give_up_waiting());
+            }
+        }
 
         if let Some(remote) = self.as_remote_mut() {
             // Wake up all futures currently waiting on a buffer to get opened,
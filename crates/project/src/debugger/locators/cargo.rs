@@ -109,6 +109,7 @@ impl DapLocator for CargoLocator {
                 locator_name: Some(self.name()),
             }),
             config,
+            inputs: Vec::new(),
             tcp_connection: None,
         })
     }
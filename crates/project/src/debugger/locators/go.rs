@@ -186,6 +186,7 @@ impl DapLocator for GoLocator {
                     adapter: adapter.0.clone(),
                     build: None,
                     config,
+                    inputs: Vec::new(),
                     tcp_connection: None,
                 })
             }
@@ -230,6 +231,7 @@ impl DapLocator for GoLocator {
                     adapter: adapter.0.clone(),
                     build: None,
                     config,
+                    inputs: Vec::new(),
                     tcp_connection: None,
                 })
             }
@@ -52,6 +52,7 @@ impl DapLocator for NodeLocator {
             label: resolved_label.to_string().into(),
             build: None,
             config,
+            inputs: Vec::new(),
             tcp_connection: None,
         })
     }
@@ -86,6 +86,7 @@ impl DapLocator for PythonLocator {
             label: resolved_label.to_string().into(),
             build: None,
             config,
+            inputs: Vec::new(),
             tcp_connection: None,
         })
     }
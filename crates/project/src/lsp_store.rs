@@ -13,6 +13,7 @@ pub mod clangd_ext;
 mod code_lens;
 mod document_colors;
 mod document_symbols;
+pub mod extension_formatting;
 mod folding_ranges;
 mod inlay_hints;
 pub mod json_language_server_ext;
@@ -20,12 +21,17 @@ pub mod log_store;
 pub mod lsp_ext_command;
 pub mod rust_analyzer_ext;
 mod semantic_tokens;
+mod task_diagnostics;
 pub mod vue_language_server_ext;
 
 use self::code_lens::CodeLensData;
 use self::document_colors::DocumentColorData;
 use self::document_symbols::DocumentSymbolsData;
+pub use self::extension_formatting::EXTENSION_LINTER_SERVER_ID;
+use self::extension_formatting::LinterDiagnostics;
 use self::inlay_hints::BufferInlayHints;
+pub use self::task_diagnostics::TASK_DIAGNOSTICS_SERVER_ID;
+use self::task_diagnostics::TaskProblems;
 use crate::{
     CodeAction, Completion, CompletionDisplayOptions, CompletionResponse, CompletionSource,
     CoreCompletion, Hover, InlayHint, InlayId, LocationLink, LspAction, LspPullDiagnostics,
@@ -305,6 +311,10 @@ pub struct LocalLspStore {
         HashMap<LanguageServerId, (LanguageServerName, Arc<LanguageServer>)>,
     prettier_store: Entity<PrettierStore>,
     next_diagnostic_group_id: usize,
+    task_problems: TaskProblems,
+    linter_diagnostics: LinterDiagnostics,
+    /// The running lint of each file, replaced when the file is linted again.
+    linter_tasks: HashMap<PathBuf, Task<()>>,
     diagnostics: HashMap<
         WorktreeId,
         HashMap<
@@ -1758,6 +1768,31 @@ impl LocalLspStore {
                         },
                     )?;
                 }
+                Formatter::Extension(formatter_name) => {
+                    let logger = zlog::scoped!(logger => "extension");
+                    zlog::trace!(logger => "formatting");
+                    let _timer = zlog::time!(logger => "Formatting buffer via extension");
+
+                    let diff = Self::format_via_extension(
+                        &lsp_store,
+                        &buffer.handle,
+                        formatter_name,
+                        cx,
+                    )
+                    .await
+                    .with_context(|| {
+                        format!("Failed to format buffer via extension formatter: {formatter_name}")
+                    })?;
+
+                    extend_formatting_transaction(
+                        buffer,
+                        formatting_transaction_id,
+                        cx,
+                        |buffer, cx| {
+                            buffer.apply_diff(diff, cx);
+                        },
+                    )?;
+                }
                 Formatter::LanguageServer(specifier) => {
                     let logger = zlog::scoped!(logger => "language-server");
                     zlog::trace!(logger => "formatting");
@@ -4172,6 +4207,7 @@ impl LspStore {
             .detach();
         cx.observe_global::<SettingsStore>(Self::on_settings_changed)
             .detach();
+        Self::observe_extension_linters(cx).detach();
         subscribe_to_binary_statuses(&languages, cx).detach();
 
         let _maintain_workspace_config = {
@@ -4203,6 +4239,9 @@ impl LspStore {
                 fs,
                 yarn,
                 next_diagnostic_group_id: Default::default(),
+                task_problems: Default::default(),
+                linter_diagnostics: Default::default(),
+                linter_tasks: Default::default(),
                 diagnostics: Default::default(),
                 _subscription: cx.on_app_quit(|this, _| {
                     this.as_local_mut()
@@ -4425,9 +4464,20 @@ impl LspStore {
             }
 
             language::BufferEvent::Saved => {
+                self.lint_buffer(&buffer, cx);
                 self.on_buffer_saved(buffer, cx);
             }
 
+            language::BufferEvent::LanguageChanged(_) => {
+                let buffer_id = buffer.read(cx).remote_id();
+                if self
+                    .as_local()
+                    .is_some_and(|local| local.registered_buffers.contains_key(&buffer_id))
+                {
+                    self.lint_buffer(&buffer, cx);
+                }
+            }
+
             _ => {}
         }
     }
@@ -4520,6 +4570,9 @@ impl LspStore {
             if ignore_refcounts || *refcount == 1 {
                 local.register_buffer_with_language_servers(buffer, only_register_servers, cx);
             }
+            if !ignore_refcounts && *refcount == 1 {
+                self.lint_buffer(buffer, cx);
+            }
             if !ignore_refcounts {
                 cx.observe_release(&handle.0, move |lsp_store, buffer, cx| {
                     let refcount = {
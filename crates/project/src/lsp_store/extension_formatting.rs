@@ -0,0 +1,379 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use collections::{BTreeMap, HashMap};
+use extension::{
+    BufferContents, Command, Extension, ExtensionFormatterProxy, ExtensionHostProxy,
+    FormatterOutput, LintDiagnostic, LintSeverity, LinterCommandOutput, LinterOutput,
+    WorktreeDelegate,
+};
+use futures::{AsyncWriteExt as _, future};
+use gpui::{App, AsyncApp, Context, Entity, Global, Subscription, WeakEntity};
+use language::{
+    Buffer, Diagnostic, DiagnosticEntry, DiagnosticSourceKind, Diff, LanguageName, PointUtf16,
+    Unclipped,
+};
+use lsp::{DiagnosticSeverity, LanguageServerId, NumberOrString};
+use util::{ResultExt as _, post_inc};
+use worktree::File;
+
+use crate::{LspStore, task_store::extension::extension_worktree};
+
+/// Diagnostics reported by extension linters are stored as if they were reported by a language
+/// server with this id.
+pub const EXTENSION_LINTER_SERVER_ID: LanguageServerId = LanguageServerId(usize::MAX - 2);
+
+/// Diagnostics reported by extension linters, by absolute path and linter name.
+pub(super) type LinterDiagnostics = HashMap<PathBuf, BTreeMap<Arc<str>, Vec<LintDiagnostic>>>;
+
+pub fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_formatter_proxy(ExtensionFormattersProxy);
+}
+
+/// The formatters and linters of the installed extensions, by name.
+#[derive(Default)]
+struct ExtensionFormatters {
+    formatters: BTreeMap<Arc<str>, Arc<dyn Extension>>,
+    linters: BTreeMap<Arc<str>, ExtensionLinter>,
+}
+
+impl Global for ExtensionFormatters {}
+
+struct ExtensionLinter {
+    extension: Arc<dyn Extension>,
+    languages: Vec<LanguageName>,
+}
+
+impl ExtensionFormatters {
+    fn linters_for_language(&self, language: &LanguageName) -> Vec<(Arc<str>, Arc<dyn Extension>)> {
+        self.linters
+            .iter()
+            .filter(|(_, linter)| linter.languages.contains(language))
+            .map(|(name, linter)| (name.clone(), linter.extension.clone()))
+            .collect()
+    }
+}
+
+struct ExtensionFormattersProxy;
+
+impl ExtensionFormatterProxy for ExtensionFormattersProxy {
+    fn register_formatter(
+        &self,
+        extension: Arc<dyn Extension>,
+        formatter_name: Arc<str>,
+        cx: &mut App,
+    ) {
+        cx.default_global::<ExtensionFormatters>()
+            .formatters
+            .insert(formatter_name, extension);
+    }
+
+    fn unregister_formatter(&self, formatter_name: Arc<str>, cx: &mut App) {
+        cx.default_global::<ExtensionFormatters>()
+            .formatters
+            .remove(&formatter_name);
+    }
+
+    fn register_linter(
+        &self,
+        extension: Arc<dyn Extension>,
+        linter_name: Arc<str>,
+        languages: Vec<LanguageName>,
+        cx: &mut App,
+    ) {
+        cx.default_global::<ExtensionFormatters>().linters.insert(
+            linter_name,
+            ExtensionLinter {
+                extension,
+                languages,
+            },
+        );
+    }
+
+    fn unregister_linter(&self, linter_name: Arc<str>, cx: &mut App) {
+        cx.default_global::<ExtensionFormatters>()
+            .linters
+            .remove(&linter_name);
+    }
+}
+
+impl LspStore {
+    /// Formats the buffer with the extension formatter with the given name,
+    /// returning the diff from the buffer's text to the formatted text.
+    pub(super) async fn format_via_extension(
+        lsp_store: &WeakEntity<Self>,
+        buffer: &Entity<Buffer>,
+        formatter_name: &str,
+        cx: &mut AsyncApp,
+    ) -> Result<Diff> {
+        let (extension, contents, worktree) = lsp_store.update(cx, |lsp_store, cx| {
+            let extension = cx
+                .try_global::<ExtensionFormatters>()
+                .and_then(|formatters| formatters.formatters.get(formatter_name))
+                .cloned()
+                .with_context(|| {
+                    format!("No extension provides the formatter `{formatter_name}`")
+                })?;
+            let local = lsp_store.as_local().context("Not a local project")?;
+            let (contents, worktree) = buffer_contents(buffer, cx)
+                .context("Extension formatters only format files in local worktrees")?;
+            let worktree = extension_worktree(&worktree, local.fs.clone(), &local.environment, cx);
+            anyhow::Ok((extension, contents, worktree))
+        })??;
+
+        let text = contents.text.clone();
+        let formatted_text = match extension
+            .format_buffer(formatter_name.into(), contents, worktree.clone())
+            .await?
+        {
+            FormatterOutput::Text(formatted_text) => formatted_text,
+            FormatterOutput::Command(command) => {
+                let output = run_command(command, text, worktree, cx).await?;
+                anyhow::ensure!(
+                    output.status == Some(0),
+                    "command failed with exit code {:?}:\nstderr: {}",
+                    output.status,
+                    output.stderr,
+                );
+                output.stdout
+            }
+        };
+        Ok(buffer
+            .update(cx, |buffer, cx| buffer.diff(formatted_text, cx))
+            .await)
+    }
+
+    /// Runs the extension linters for the buffer's language on its text,
+    /// replacing the diagnostics they reported for the buffer before.
+    pub(super) fn lint_buffer(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        let Some(local) = self.as_local() else {
+            return;
+        };
+        let Some(language) = buffer.read(cx).language().map(|language| language.name()) else {
+            return;
+        };
+        let linters = cx
+            .try_global::<ExtensionFormatters>()
+            .map(|formatters| formatters.linters_for_language(&language))
+            .unwrap_or_default();
+        if linters.is_empty() {
+            return;
+        }
+        let Some((contents, worktree)) = buffer_contents(buffer, cx) else {
+            return;
+        };
+        let worktree = extension_worktree(&worktree, local.fs.clone(), &local.environment, cx);
+        let abs_path = PathBuf::from(&contents.path);
+
+        let lint = cx.spawn({
+            let abs_path = abs_path.clone();
+            async move |lsp_store, cx| {
+                let mut linter_diagnostics = Vec::new();
+                for (linter_name, extension) in linters {
+                    match run_linter(&extension, linter_name.clone(), &contents, &worktree, cx)
+                        .await
+                    {
+                        Ok(diagnostics) => linter_diagnostics.push((linter_name, diagnostics)),
+                        Err(e) => log::error!(
+                            "Failed to lint {abs_path:?} with linter `{linter_name}`: {e:#}"
+                        ),
+                    }
+                }
+                lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.update_linter_diagnostics(abs_path, linter_diagnostics, cx)
+                    })
+                    .ok();
+            }
+        });
+        // Dropping the previous lint of the file cancels it, so that its results can't
+        // overwrite those of the newer text.
+        if let Some(local) = self.as_local_mut() {
+            local.linter_tasks.insert(abs_path, lint);
+        }
+    }
+
+    /// Drops the diagnostics of linters that were unregistered, e.g. when their extension
+    /// got uninstalled.
+    pub(super) fn observe_extension_linters(cx: &mut Context<Self>) -> Subscription {
+        cx.observe_global::<ExtensionFormatters>(|lsp_store, cx| {
+            let Some(local) = lsp_store.as_local() else {
+                return;
+            };
+            let linted_paths = local.linter_diagnostics.keys().cloned().collect::<Vec<_>>();
+            for abs_path in linted_paths {
+                lsp_store.update_linter_diagnostics(abs_path, Vec::new(), cx);
+            }
+        })
+    }
+
+    /// Replaces the diagnostics of the given linters for the file,
+    /// and drops those of linters that are no longer installed.
+    fn update_linter_diagnostics(
+        &mut self,
+        abs_path: PathBuf,
+        linter_diagnostics: Vec<(Arc<str>, Vec<LintDiagnostic>)>,
+        cx: &mut Context<Self>,
+    ) {
+        let installed_linters = cx
+            .try_global::<ExtensionFormatters>()
+            .map(|formatters| formatters.linters.keys().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let old_diagnostics = local
+            .linter_diagnostics
+            .get(&abs_path)
+            .cloned()
+            .unwrap_or_default();
+        let mut new_diagnostics = old_diagnostics.clone();
+        new_diagnostics.extend(linter_diagnostics);
+        new_diagnostics.retain(|linter_name, diagnostics| {
+            installed_linters.contains(linter_name) && !diagnostics.is_empty()
+        });
+        if new_diagnostics == old_diagnostics {
+            return;
+        }
+
+        let entries = new_diagnostics
+            .values()
+            .flatten()
+            .map(|diagnostic| {
+                let group_id = post_inc(&mut local.next_diagnostic_group_id);
+                lint_diagnostic_to_entry(diagnostic, group_id)
+            })
+            .collect();
+        if new_diagnostics.is_empty() {
+            local.linter_diagnostics.remove(&abs_path);
+        } else {
+            local
+                .linter_diagnostics
+                .insert(abs_path.clone(), new_diagnostics);
+        }
+        self.update_diagnostic_entries(
+            EXTENSION_LINTER_SERVER_ID,
+            abs_path,
+            None,
+            None,
+            entries,
+            cx,
+        )
+        .log_err();
+    }
+}
+
+/// Returns what formatters and linters get to see of the buffer, along with its worktree,
+/// if the buffer is a file in a local worktree.
+fn buffer_contents(
+    buffer: &Entity<Buffer>,
+    cx: &App,
+) -> Option<(BufferContents, Entity<worktree::Worktree>)> {
+    let buffer = buffer.read(cx);
+    let file = File::from_dyn(buffer.file())?;
+    let abs_path = file.as_local()?.abs_path(cx);
+    let contents = BufferContents {
+        text: buffer.text(),
+        path: abs_path.to_string_lossy().into_owned(),
+        language: buffer
+            .language()
+            .map(|language| language.name().to_string()),
+    };
+    Some((contents, file.worktree.clone()))
+}
+
+async fn run_linter(
+    extension: &Arc<dyn Extension>,
+    linter_name: Arc<str>,
+    contents: &BufferContents,
+    worktree: &Arc<dyn WorktreeDelegate>,
+    cx: &AsyncApp,
+) -> Result<Vec<LintDiagnostic>> {
+    match extension
+        .lint_buffer(linter_name.clone(), contents.clone(), worktree.clone())
+        .await?
+    {
+        LinterOutput::Diagnostics(diagnostics) => Ok(diagnostics),
+        LinterOutput::Command(command) => {
+            let output = run_command(command, contents.text.clone(), worktree.clone(), cx).await?;
+            extension.linter_diagnostics(linter_name, output).await
+        }
+    }
+}
+
+/// Runs the command in the worktree's root directory, with the text on its stdin.
+async fn run_command(
+    command: Command,
+    text: String,
+    worktree: Arc<dyn WorktreeDelegate>,
+    cx: &AsyncApp,
+) -> Result<LinterCommandOutput> {
+    let Command {
+        command,
+        args,
+        env: command_env,
+    } = command;
+    let mut env = worktree.shell_env().await;
+    env.extend(command_env);
+    let root_path = PathBuf::from(worktree.root_path());
+    cx.background_spawn(async move {
+        use util::command::Stdio;
+        let mut child = util::command::new_command(&command);
+        child.args(args).envs(env);
+        if root_path.is_dir() {
+            child.current_dir(&root_path);
+        } else if let Some(parent) = root_path.parent() {
+            child.current_dir(parent);
+        }
+        let mut child = child
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to run {command:?}"))?;
+
+        let mut stdin = child.stdin.take().context("Failed to acquire stdin")?;
+        let write_stdin = async move {
+            stdin.write_all(text.as_bytes()).await?;
+            stdin.close().await
+        };
+        // Read the output while writing the text, so that neither side blocks on a full pipe.
+        let (written, output) = future::join(write_stdin, child.output()).await;
+        let output = output?;
+        written.context("Failed to write the buffer's text to the command")?;
+        Ok(LinterCommandOutput {
+            status: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        })
+    })
+    .await
+}
+
+fn lint_diagnostic_to_entry(
+    diagnostic: &LintDiagnostic,
+    group_id: usize,
+) -> DiagnosticEntry<Unclipped<PointUtf16>> {
+    let start = PointUtf16::new(diagnostic.start.line, diagnostic.start.column);
+    let end = PointUtf16::new(diagnostic.end.line, diagnostic.end.column);
+    DiagnosticEntry {
+        range: Unclipped(start)..Unclipped(end.max(start)),
+        diagnostic: Diagnostic {
+            code: diagnostic.code.clone().map(NumberOrString::String),
+            severity: match diagnostic.severity {
+                LintSeverity::Error => DiagnosticSeverity::ERROR,
+                LintSeverity::Warning => DiagnosticSeverity::WARNING,
+                LintSeverity::Info => DiagnosticSeverity::INFORMATION,
+                LintSeverity::Hint => DiagnosticSeverity::HINT,
+            },
+            message: diagnostic.message.clone(),
+            group_id,
+            is_primary: true,
+            // Buffers are linted when opened and saved, so the diagnostics refer to the saved text.
+            is_disk_based: true,
+            source_kind: DiagnosticSourceKind::Other,
+            ..Diagnostic::default()
+        },
+    }
+}
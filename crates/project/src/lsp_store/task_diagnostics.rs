@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+
+use collections::{HashMap, HashSet};
+use gpui::Context;
+use language::{Diagnostic, DiagnosticEntry, DiagnosticSourceKind, PointUtf16, Unclipped};
+use lsp::{DiagnosticSeverity, LanguageServerId, NumberOrString};
+use task::{Problem, ProblemSeverity};
+use util::{ResultExt as _, post_inc};
+
+use crate::LspStore;
+
+/// Diagnostics produced by the problem matchers of tasks are stored as if they were reported by a language server with this id.
+pub const TASK_DIAGNOSTICS_SERVER_ID: LanguageServerId = LanguageServerId(usize::MAX - 1);
+
+/// Problems found in the output of the tasks, by task label and absolute path.
+pub(super) type TaskProblems = HashMap<String, HashMap<PathBuf, Vec<Problem>>>;
+
+impl LspStore {
+    /// Replaces the diagnostics of the task with the given label with the problems found in its output.
+    /// Problems with relative paths, or paths outside of the project's worktrees, are ignored.
+    pub fn update_task_diagnostics(
+        &mut self,
+        task_label: &str,
+        problems: Vec<Problem>,
+        cx: &mut Context<Self>,
+    ) {
+        let worktree_store = self.worktree_store.read(cx);
+        let mut problems_by_path = HashMap::<PathBuf, Vec<Problem>>::default();
+        for problem in problems {
+            if problem.path.is_absolute()
+                && worktree_store.find_worktree(&problem.path, cx).is_some()
+            {
+                problems_by_path
+                    .entry(problem.path.clone())
+                    .or_default()
+                    .push(problem);
+            }
+        }
+
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        if local.task_problems.get(task_label) == Some(&problems_by_path)
+            || (problems_by_path.is_empty() && !local.task_problems.contains_key(task_label))
+        {
+            return;
+        }
+        let mut changed_paths = problems_by_path.keys().cloned().collect::<HashSet<_>>();
+        let old_problems = if problems_by_path.is_empty() {
+            local.task_problems.remove(task_label)
+        } else {
+            local
+                .task_problems
+                .insert(task_label.to_string(), problems_by_path)
+        };
+        changed_paths.extend(old_problems.into_iter().flat_map(|old| old.into_keys()));
+
+        for path in changed_paths {
+            let Some(local) = self.as_local_mut() else {
+                return;
+            };
+            let problems = local
+                .task_problems
+                .values()
+                .filter_map(|problems_by_path| problems_by_path.get(&path))
+                .flatten()
+                .cloned()
+                .collect::<Vec<_>>();
+            let diagnostics = problems
+                .into_iter()
+                .map(|problem| {
+                    let group_id = post_inc(&mut local.next_diagnostic_group_id);
+                    problem_to_diagnostic(problem, group_id)
+                })
+                .collect();
+            self.update_diagnostic_entries(
+                TASK_DIAGNOSTICS_SERVER_ID,
+                path,
+                None,
+                None,
+                diagnostics,
+                cx,
+            )
+            .log_err();
+        }
+    }
+}
+
+fn problem_to_diagnostic(
+    problem: Problem,
+    group_id: usize,
+) -> DiagnosticEntry<Unclipped<PointUtf16>> {
+    let start = PointUtf16::new(problem.line, problem.column);
+    let end = PointUtf16::new(
+        problem.end_line.unwrap_or(problem.line),
+        problem.end_column.unwrap_or(problem.column),
+    );
+    DiagnosticEntry {
+        range: Unclipped(start)..Unclipped(end.max(start)),
+        diagnostic: Diagnostic {
+            source: problem.source,
+            code: problem.code.map(NumberOrString::String),
+            severity: match problem.severity {
+                ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+            },
+            message: problem.message,
+            group_id,
+            is_primary: true,
+            // Task output refers to the files on disk, so unsaved edits shift the diagnostics.
+            is_disk_based: true,
+            source_kind: DiagnosticSourceKind::Other,
+            ..Diagnostic::default()
+        },
+    }
+}
@@ -0,0 +1,644 @@
+//! Module for journaling edits made to a remote project while its connection is down.
+//!
+//! While the remote client is offline, dirty buffers are periodically written to a journal file on the local machine,
+//! and saves are queued instead of failing. Queued saves are replayed once the connection comes back, or when the
+//! project is reopened after the connection was lost. A save is only replayed if the remote file is unchanged since
+//! the buffer was last saved; otherwise the journaled text is kept in the buffer and the buffer is marked as conflicted.
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use collections::HashMap;
+use fs::{Fs, MTime, RemoveOptions};
+use gpui::{App, AppContext as _, Context, Entity, Subscription, Task, WeakEntity};
+use language::{Buffer, BufferEvent};
+use remote::{ConnectionState, RemoteClient};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use text::{BufferId, LineEnding};
+use util::{ResultExt as _, rel_path::RelPath};
+use worktree::Worktree;
+
+use crate::{
+    ProjectPath,
+    buffer_store::BufferStore,
+    worktree_store::{WorktreeStore, WorktreeStoreEvent},
+};
+
+/// How often dirty buffers are journaled while offline.
+const JOURNAL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OfflineBufferStatus {
+    /// The buffer's unsaved edits are journaled locally.
+    Journaled,
+    /// The buffer was saved while offline, and will be written to the remote once the connection is restored.
+    SaveQueued,
+    /// The file changed on the remote while offline, so the queued save wasn't replayed.
+    Conflict,
+}
+
+/// The journaled state of a single file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// The absolute path of the worktree's root on the remote.
+    pub worktree_root: String,
+    /// The path of the file, relative to the worktree's root.
+    pub path: String,
+    /// The mtime of the remote file when the buffer was last saved or reloaded.
+    pub base_mtime: Option<MTime>,
+    /// A digest of the buffer's text when it was last saved or reloaded.
+    pub base_digest: String,
+    pub text: String,
+    pub save_requested: bool,
+}
+
+type JournalKey = (String, String);
+
+impl JournalEntry {
+    fn key(&self) -> JournalKey {
+        (self.worktree_root.clone(), self.path.clone())
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct JournalFile {
+    entries: Vec<JournalEntry>,
+}
+
+/// What to do with a journal entry, given the current state of the remote file.
+#[derive(Debug, PartialEq, Eq)]
+enum Reconciliation {
+    /// The remote file is unchanged, so the journaled text can be applied.
+    Apply,
+    /// The remote file already has the journaled text.
+    AlreadyApplied,
+    /// The remote file changed since the entry was journaled.
+    Conflict,
+}
+
+fn reconcile(
+    entry: &JournalEntry,
+    remote_mtime: Option<MTime>,
+    remote_text: &str,
+) -> Reconciliation {
+    if remote_text == entry.text {
+        Reconciliation::AlreadyApplied
+    } else if (entry.base_mtime.is_some() && remote_mtime == entry.base_mtime)
+        || digest(remote_text) == entry.base_digest
+    {
+        Reconciliation::Apply
+    } else {
+        Reconciliation::Conflict
+    }
+}
+
+/// Decodes a file read from the remote the way buffers see it, so that it can be compared with journaled text.
+fn remote_text(content: Vec<u8>) -> String {
+    let mut text = match String::from_utf8(content) {
+        Ok(text) => text,
+        Err(error) => String::from_utf8_lossy(error.as_bytes()).into_owned(),
+    };
+    if text.starts_with('\u{feff}') {
+        text.remove(0);
+    }
+    LineEnding::normalize(&mut text);
+    text
+}
+
+fn digest(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+fn is_offline(state: ConnectionState) -> bool {
+    matches!(
+        state,
+        ConnectionState::HeartbeatMissed
+            | ConnectionState::Reconnecting
+            | ConnectionState::Disconnected
+    )
+}
+
+/// The name of the journal file for a remote host.
+fn journal_file_name(host: &str) -> String {
+    let name = host
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    format!("{name}-{}.json", &digest(host)[..8])
+}
+
+struct TrackedBuffer {
+    key: JournalKey,
+    status: OfflineBufferStatus,
+    buffer: WeakEntity<Buffer>,
+    _subscription: Subscription,
+}
+
+pub struct OfflineJournal {
+    fs: Arc<dyn Fs>,
+    journal_path: PathBuf,
+    buffer_store: Entity<BufferStore>,
+    worktree_store: Entity<WorktreeStore>,
+    entries: HashMap<JournalKey, JournalEntry>,
+    tracked_buffers: HashMap<BufferId, TrackedBuffer>,
+    offline: bool,
+    journal_task: Option<Task<()>>,
+    persist_task: Option<Task<()>>,
+    _load_journal: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl OfflineJournal {
+    pub fn new(
+        fs: Arc<dyn Fs>,
+        journal_dir: PathBuf,
+        remote_client: Entity<RemoteClient>,
+        buffer_store: Entity<BufferStore>,
+        worktree_store: Entity<WorktreeStore>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let host = remote_client.read(cx).connection_options().display_name();
+        let journal_path = journal_dir.join(journal_file_name(&host));
+        let subscriptions = vec![
+            cx.observe(&remote_client, |this, remote_client, cx| {
+                let offline = is_offline(remote_client.read(cx).connection_state());
+                this.set_offline(offline, cx);
+            }),
+            cx.subscribe(&worktree_store, |this, _, event, cx| {
+                if let WorktreeStoreEvent::WorktreeAdded(worktree) = event {
+                    this.replay_journal(worktree, cx);
+                }
+            }),
+        ];
+
+        let load_journal = cx.spawn({
+            let fs = fs.clone();
+            let journal_path = journal_path.clone();
+            async move |this, cx| {
+                let Ok(content) = fs.load(&journal_path).await else {
+                    return;
+                };
+                let Some(journal) = serde_json::from_str::<JournalFile>(&content).log_err() else {
+                    return;
+                };
+                this.update(cx, |this, cx| {
+                    for entry in journal.entries {
+                        this.entries.entry(entry.key()).or_insert(entry);
+                    }
+                    let worktrees = this.worktree_store.read(cx).worktrees().collect::<Vec<_>>();
+                    for worktree in &worktrees {
+                        this.replay_journal(worktree, cx);
+                    }
+                })
+                .ok();
+            }
+        });
+
+        Self {
+            fs,
+            journal_path,
+            buffer_store,
+            worktree_store,
+            entries: HashMap::default(),
+            tracked_buffers: HashMap::default(),
+            offline: false,
+            journal_task: None,
+            persist_task: None,
+            _load_journal: load_journal,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Whether the remote connection is currently down.
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    pub fn status(&self, buffer_id: BufferId) -> Option<OfflineBufferStatus> {
+        self.tracked_buffers
+            .get(&buffer_id)
+            .map(|tracked| tracked.status)
+    }
+
+    pub fn queued_save_count(&self) -> usize {
+        self.tracked_buffers
+            .values()
+            .filter(|tracked| tracked.status == OfflineBufferStatus::SaveQueued)
+            .count()
+    }
+
+    /// Queues a save of the buffer, to be replayed once the connection is restored. Returns false if the buffer
+    /// can't be journaled, e.g. because it isn't backed by a file in the project.
+    pub fn queue_save(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) -> bool {
+        let Some(entry) = self.journal_entry(buffer, true, cx) else {
+            return false;
+        };
+        let key = entry.key();
+        self.entries.insert(key.clone(), entry);
+        self.track_buffer(buffer, key, OfflineBufferStatus::SaveQueued, cx);
+        self.persist(cx);
+        true
+    }
+
+    fn set_offline(&mut self, offline: bool, cx: &mut Context<Self>) {
+        if offline == self.offline {
+            return;
+        }
+        self.offline = offline;
+        if offline {
+            self.journal_task = Some(cx.spawn(async move |this, cx| {
+                loop {
+                    if this
+                        .update(cx, |this, cx| this.journal_dirty_buffers(cx))
+                        .is_err()
+                    {
+                        break;
+                    }
+                    cx.background_executor().timer(JOURNAL_INTERVAL).await;
+                }
+            }));
+        } else {
+            self.journal_task = None;
+            self.replay_queued_saves(cx);
+        }
+        cx.notify();
+    }
+
+    fn journal_dirty_buffers(&mut self, cx: &mut Context<Self>) {
+        let dirty_buffers = self
+            .buffer_store
+            .read(cx)
+            .buffers()
+            .filter(|buffer| buffer.read(cx).is_dirty())
+            .collect::<Vec<_>>();
+
+        let mut changed = false;
+        for buffer in dirty_buffers {
+            let buffer_id = buffer.read(cx).remote_id();
+            let save_requested = self
+                .status(buffer_id)
+                .is_some_and(|status| status != OfflineBufferStatus::Journaled);
+            let Some(entry) = self.journal_entry(&buffer, save_requested, cx) else {
+                continue;
+            };
+            let key = entry.key();
+            if self.entries.get(&key) == Some(&entry) {
+                continue;
+            }
+            self.entries.insert(key.clone(), entry);
+            if self.status(buffer_id).is_none() {
+                self.track_buffer(&buffer, key, OfflineBufferStatus::Journaled, cx);
+            }
+            changed = true;
+        }
+
+        if changed {
+            self.persist(cx);
+        }
+    }
+
+    /// Builds a journal entry for the buffer's current text, keeping the base of any existing entry for its file.
+    fn journal_entry(
+        &self,
+        buffer: &Entity<Buffer>,
+        save_requested: bool,
+        cx: &App,
+    ) -> Option<JournalEntry> {
+        let buffer = buffer.read(cx);
+        let file = worktree::File::from_dyn(buffer.file())?;
+        let worktree_root = file
+            .worktree
+            .read(cx)
+            .abs_path()
+            .to_string_lossy()
+            .into_owned();
+        let path = file.path.as_unix_str().to_string();
+        let text = buffer.text();
+
+        if let Some(existing) = self.entries.get(&(worktree_root.clone(), path.clone())) {
+            return Some(JournalEntry {
+                text,
+                save_requested: save_requested || existing.save_requested,
+                ..existing.clone()
+            });
+        }
+
+        let saved_text = buffer.rope_for_version(buffer.saved_version()).to_string();
+        Some(JournalEntry {
+            worktree_root,
+            path,
+            base_mtime: buffer.saved_mtime(),
+            base_digest: digest(&saved_text),
+            text,
+            save_requested,
+        })
+    }
+
+    fn track_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        key: JournalKey,
+        status: OfflineBufferStatus,
+        cx: &mut Context<Self>,
+    ) {
+        let buffer_id = buffer.read(cx).remote_id();
+        let subscription = cx.subscribe(buffer, move |this, _, event, cx| {
+            if matches!(event, BufferEvent::Saved | BufferEvent::Reloaded) {
+                this.forget_buffer(buffer_id, cx);
+            }
+        });
+        self.tracked_buffers.insert(
+            buffer_id,
+            TrackedBuffer {
+                key,
+                status,
+                buffer: buffer.downgrade(),
+                _subscription: subscription,
+            },
+        );
+        cx.notify();
+    }
+
+    fn forget_buffer(&mut self, buffer_id: BufferId, cx: &mut Context<Self>) {
+        if let Some(tracked) = self.tracked_buffers.remove(&buffer_id) {
+            self.entries.remove(&tracked.key);
+            self.persist(cx);
+            cx.notify();
+        }
+    }
+
+    /// Replays the saves queued during this session, now that the connection is back.
+    fn replay_queued_saves(&mut self, cx: &mut Context<Self>) {
+        if self.offline {
+            return;
+        }
+
+        let tracked_buffers = self
+            .tracked_buffers
+            .iter()
+            .map(|(buffer_id, tracked)| (*buffer_id, tracked.status, tracked.buffer.clone()))
+            .collect::<Vec<_>>();
+        for (buffer_id, status, buffer) in tracked_buffers {
+            let Some(buffer) = buffer.upgrade() else {
+                self.forget_buffer(buffer_id, cx);
+                continue;
+            };
+            match status {
+                // The edits are still in the buffer, so there's nothing left to journal.
+                OfflineBufferStatus::Journaled => self.forget_buffer(buffer_id, cx),
+                OfflineBufferStatus::SaveQueued => {
+                    let Some(entry) = self
+                        .tracked_buffers
+                        .get(&buffer_id)
+                        .and_then(|tracked| self.entries.get(&tracked.key))
+                    else {
+                        continue;
+                    };
+                    let entry = entry.clone();
+                    let Some((worktree, path)) = worktree::File::from_dyn(buffer.read(cx).file())
+                        .map(|file| (file.worktree.clone(), file.path.clone()))
+                    else {
+                        self.mark_conflicted(&buffer, cx);
+                        continue;
+                    };
+                    // The worktree may not have caught up with the changes made on the remote while offline yet,
+                    // so the file is read again instead of trusting the buffer's last known mtime.
+                    let load_remote_file =
+                        worktree.update(cx, |worktree, cx| worktree.load_bytes(&path, None, cx));
+                    cx.spawn(async move |this, cx| {
+                        let remote_file = load_remote_file.await;
+                        this.update(cx, |this, cx| {
+                            this.replay_queued_save(&entry, buffer, remote_file, cx)
+                        })
+                    })
+                    .detach_and_log_err(cx);
+                }
+                OfflineBufferStatus::Conflict => {}
+            }
+        }
+    }
+
+    fn replay_queued_save(
+        &mut self,
+        entry: &JournalEntry,
+        buffer: Entity<Buffer>,
+        remote_file: Result<Vec<u8>>,
+        cx: &mut Context<Self>,
+    ) {
+        // The connection may have dropped again, or the buffer been saved, while the file was being read.
+        let buffer_id = buffer.read(cx).remote_id();
+        if self.offline || self.status(buffer_id) != Some(OfflineBufferStatus::SaveQueued) {
+            return;
+        }
+        let reconciliation = match remote_file {
+            Ok(content) => reconcile(entry, None, &remote_text(content)),
+            Err(error) => {
+                log::error!("failed to read {} from the remote: {error:#}", entry.path);
+                Reconciliation::Conflict
+            }
+        };
+        match reconciliation {
+            Reconciliation::Apply | Reconciliation::AlreadyApplied => self.save_buffer(buffer, cx),
+            Reconciliation::Conflict => self.mark_conflicted(&buffer, cx),
+        }
+    }
+
+    /// Applies the journal entries under the worktree to a project that was reopened after losing its connection.
+    fn replay_journal(&mut self, worktree: &Entity<Worktree>, cx: &mut Context<Self>) {
+        let (worktree_id, worktree_root) = {
+            let worktree = worktree.read(cx);
+            (
+                worktree.id(),
+                worktree.abs_path().to_string_lossy().into_owned(),
+            )
+        };
+        let entries = self
+            .entries
+            .values()
+            .filter(|entry| {
+                entry.worktree_root == worktree_root
+                    && !self
+                        .tracked_buffers
+                        .values()
+                        .any(|tracked| tracked.key == entry.key())
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for entry in entries {
+            let Some(path) = RelPath::unix(&entry.path).log_err() else {
+                continue;
+            };
+            let project_path = ProjectPath {
+                worktree_id,
+                path: path.into_arc(),
+            };
+            let open_buffer = self.buffer_store.update(cx, |buffer_store, cx| {
+                buffer_store.open_buffer(project_path, cx)
+            });
+            cx.spawn(async move |this, cx| {
+                let buffer = open_buffer.await?;
+                this.update(cx, |this, cx| this.replay_entry(entry, buffer, cx))
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
+    fn replay_entry(
+        &mut self,
+        entry: JournalEntry,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) {
+        let key = entry.key();
+        let reconciliation = {
+            let buffer = buffer.read(cx);
+            let remote_text = buffer.rope_for_version(buffer.saved_version()).to_string();
+            reconcile(&entry, buffer.saved_mtime(), &remote_text)
+        };
+        if reconciliation == Reconciliation::AlreadyApplied {
+            self.entries.remove(&key);
+            self.persist(cx);
+            return;
+        }
+
+        buffer.update(cx, |buffer, cx| {
+            if buffer.text() != entry.text {
+                buffer.set_text(entry.text.as_str(), cx);
+            }
+        });
+        match reconciliation {
+            Reconciliation::Apply if entry.save_requested => {
+                self.track_buffer(&buffer, key, OfflineBufferStatus::SaveQueued, cx);
+                self.save_buffer(buffer, cx);
+            }
+            // The buffer now holds the journaled edits, so they no longer need to be journaled.
+            Reconciliation::Apply => {
+                self.entries.remove(&key);
+                self.persist(cx);
+            }
+            Reconciliation::Conflict => {
+                self.track_buffer(&buffer, key, OfflineBufferStatus::Conflict, cx);
+                self.mark_conflicted(&buffer, cx);
+            }
+            Reconciliation::AlreadyApplied => {}
+        }
+    }
+
+    fn save_buffer(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let save = self
+            .buffer_store
+            .update(cx, |buffer_store, cx| buffer_store.save_buffer(buffer, cx));
+        // A successful save is picked up through the buffer's `Saved` event, and a failed one stays queued.
+        save.detach_and_log_err(cx);
+    }
+
+    fn mark_conflicted(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_conflict();
+            cx.notify();
+        });
+        let buffer_id = buffer.read(cx).remote_id();
+        if let Some(tracked) = self.tracked_buffers.get_mut(&buffer_id) {
+            tracked.status = OfflineBufferStatus::Conflict;
+        }
+        cx.notify();
+    }
+
+    fn persist(&mut self, cx: &mut Context<Self>) {
+        let fs = self.fs.clone();
+        let journal_path = self.journal_path.clone();
+        let mut entries = self.entries.values().cloned().collect::<Vec<_>>();
+        entries.sort_by_key(|entry| entry.key());
+        // Replacing the task cancels any write that is still in flight, so the latest journal always wins.
+        self.persist_task = Some(cx.background_spawn(async move {
+            if entries.is_empty() {
+                fs.remove_file(
+                    &journal_path,
+                    RemoveOptions {
+                        ignore_if_not_exists: true,
+                        ..Default::default()
+                    },
+                )
+                .await
+                .log_err();
+                return;
+            }
+            let Some(content) = serde_json::to_string_pretty(&JournalFile { entries }).log_err()
+            else {
+                return;
+            };
+            if let Some(journal_dir) = journal_path.parent() {
+                fs.create_dir(journal_dir).await.log_err();
+            }
+            fs.atomic_write(journal_path, content).await.log_err();
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(base_mtime: Option<MTime>, base_text: &str, text: &str) -> JournalEntry {
+        JournalEntry {
+            worktree_root: "/code/project".into(),
+            path: "src/main.rs".into(),
+            base_mtime,
+            base_digest: digest(base_text),
+            text: text.into(),
+            save_requested: true,
+        }
+    }
+
+    #[test]
+    fn test_reconcile() {
+        let base_mtime = MTime::from_seconds_and_nanos(100, 0);
+        let later_mtime = MTime::from_seconds_and_nanos(200, 0);
+
+        // The remote file is untouched.
+        assert_eq!(
+            reconcile(
+                &entry(Some(base_mtime), "one", "two"),
+                Some(base_mtime),
+                "one"
+            ),
+            Reconciliation::Apply
+        );
+        // The remote file was touched, but its content didn't change.
+        assert_eq!(
+            reconcile(
+                &entry(Some(base_mtime), "one", "two"),
+                Some(later_mtime),
+                "one"
+            ),
+            Reconciliation::Apply
+        );
+        // The journaled text already made it to the remote.
+        assert_eq!(
+            reconcile(
+                &entry(Some(base_mtime), "one", "two"),
+                Some(later_mtime),
+                "two"
+            ),
+            Reconciliation::AlreadyApplied
+        );
+        // The remote file changed while offline.
+        assert_eq!(
+            reconcile(
+                &entry(Some(base_mtime), "one", "two"),
+                Some(later_mtime),
+                "three"
+            ),
+            Reconciliation::Conflict
+        );
+    }
+
+    #[test]
+    fn test_journal_file_name() {
+        let name = journal_file_name("user@example.com");
+        assert!(name.starts_with("user_example_com-"));
+        assert!(name.ends_with(".json"));
+        assert_ne!(name, journal_file_name("user@example_com"));
+    }
+}
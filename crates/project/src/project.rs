@@ -1,5 +1,6 @@
 pub mod agent_registry_store;
 pub mod agent_server_store;
+pub mod bookmark_store;
 pub mod buffer_store;
 pub mod color_extractor;
 pub mod connection_manager;
@@ -11,6 +12,7 @@ pub mod image_store;
 pub mod lsp_command;
 pub mod lsp_store;
 pub mod manifest_tree;
+pub mod offline_journal;
 pub mod prettier_store;
 pub mod project_search;
 pub mod project_settings;
@@ -54,11 +56,13 @@ pub use manifest_tree::ManifestTree;
 pub use project_search::{Search, SearchResults};
 
 use anyhow::{Context as _, Result, anyhow};
+use bookmark_store::BookmarkStore;
 use buffer_store::{BufferStore, BufferStoreEvent};
 use client::{
     Client, Collaborator, PendingEntitySubscription, ProjectId, TypedEnvelope, UserStore, proto,
 };
 use clock::ReplicaId;
+use offline_journal::OfflineJournal;
 
 use dap::client::DebugAdapterClient;
 
@@ -138,7 +142,7 @@ use util::{
     paths::{PathStyle, SanitizedPath, is_absolute},
     rel_path::RelPath,
 };
-use worktree::{CreatedEntry, Snapshot, Traversal};
+use worktree::{BINARY_FILE_ERROR, CreatedEntry, Snapshot, Traversal};
 pub use worktree::{
     Entry, EntryKind, FS_WATCH_LATENCY, File, LocalWorktree, PathChange, ProjectEntryId,
     UpdatedEntriesSet, UpdatedGitRepositoriesSet, Worktree, WorktreeId, WorktreeSettings,
@@ -179,6 +183,10 @@ impl Default for LocalProjectFlags {
 }
 
 pub trait ProjectItem: 'static {
+    /// Starts opening the item at `path`, or returns `None` if this kind of item can't open it.
+    ///
+    /// When the path can only be ruled out after reading it, the task can fail with
+    /// [`DeclinedProjectItem`] to let the other kinds of items try to open it.
     fn try_open(
         project: &Entity<Project>,
         path: &ProjectPath,
@@ -191,6 +199,18 @@ pub trait ProjectItem: 'static {
     fn is_dirty(&self) -> bool;
 }
 
+/// Returned by a [`ProjectItem::try_open`] task that turned out not to be able to open its path.
+#[derive(Debug)]
+pub struct DeclinedProjectItem;
+
+impl std::fmt::Display for DeclinedProjectItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "project item declined to open the path")
+    }
+}
+
+impl std::error::Error for DeclinedProjectItem {}
+
 #[derive(Clone)]
 pub enum OpenedBufferEvent {
     Disconnected,
@@ -211,6 +231,7 @@ pub struct Project {
     agent_server_store: Entity<AgentServerStore>,
 
     breakpoint_store: Entity<BreakpointStore>,
+    bookmark_store: Entity<BookmarkStore>,
     collab_client: Arc<client::Client>,
     join_project_response_message_id: u32,
     task_store: Entity<TaskStore>,
@@ -242,6 +263,7 @@ pub struct Project {
     toolchain_store: Option<Entity<ToolchainStore>>,
     agent_location: Option<AgentLocation>,
     downloading_files: Arc<Mutex<HashMap<(WorktreeId, String), DownloadingFile>>>,
+    offline_journal: Option<Entity<OfflineJournal>>,
 }
 
 struct DownloadingFile {
@@ -1134,6 +1156,8 @@ impl Project {
         DapStore::init(&client, cx);
         BreakpointStore::init(&client);
         context_server_store::init(cx);
+        task_store::extension::init(cx);
+        lsp_store::extension_formatting::init(cx);
     }
 
     pub fn local(
@@ -1196,6 +1220,8 @@ impl Project {
 
             let breakpoint_store =
                 cx.new(|_| BreakpointStore::local(worktree_store.clone(), buffer_store.clone()));
+            let bookmark_store =
+                cx.new(|_| BookmarkStore::new(worktree_store.clone(), buffer_store.clone()));
 
             let dap_store = cx.new(|cx| {
                 DapStore::new_local(
@@ -1312,6 +1338,7 @@ impl Project {
                 fs,
                 remote_client: None,
                 breakpoint_store,
+                bookmark_store,
                 dap_store,
                 agent_server_store,
 
@@ -1332,6 +1359,7 @@ impl Project {
 
                 agent_location: None,
                 downloading_files: Default::default(),
+                offline_journal: None,
             }
         })
     }
@@ -1475,6 +1503,8 @@ impl Project {
                     worktree_store.clone(),
                 )
             });
+            let bookmark_store =
+                cx.new(|_| BookmarkStore::new(worktree_store.clone(), buffer_store.clone()));
 
             let dap_store = cx.new(|cx| {
                 DapStore::new_remote(
@@ -1502,6 +1532,17 @@ impl Project {
             let agent_server_store =
                 cx.new(|_| AgentServerStore::remote(REMOTE_SERVER_PROJECT_ID, remote.clone()));
 
+            let offline_journal = cx.new(|cx| {
+                OfflineJournal::new(
+                    fs.clone(),
+                    paths::offline_journal_dir().clone(),
+                    remote.clone(),
+                    buffer_store.clone(),
+                    worktree_store.clone(),
+                    cx,
+                )
+            });
+
             cx.subscribe(&remote, Self::on_remote_client_event).detach();
 
             let this = Self {
@@ -1513,6 +1554,7 @@ impl Project {
                 lsp_store,
                 context_server_store,
                 breakpoint_store,
+                bookmark_store,
                 dap_store,
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
@@ -1563,6 +1605,7 @@ impl Project {
                 toolchain_store: Some(toolchain_store),
                 agent_location: None,
                 downloading_files: Default::default(),
+                offline_journal: Some(offline_journal),
             };
 
             // remote server -> local machine handlers
@@ -1697,6 +1740,8 @@ impl Project {
                 worktree_store.clone(),
             )
         });
+        let bookmark_store =
+            cx.new(|_| BookmarkStore::new(worktree_store.clone(), buffer_store.clone()));
         let dap_store = cx.new(|cx| {
             DapStore::new_collab(
                 remote_id,
@@ -1822,6 +1867,7 @@ impl Project {
                     replica_id,
                 },
                 breakpoint_store: breakpoint_store.clone(),
+                bookmark_store,
                 dap_store: dap_store.clone(),
                 git_store: git_store.clone(),
                 agent_server_store,
@@ -1839,6 +1885,7 @@ impl Project {
                 toolchain_store: None,
                 agent_location: None,
                 downloading_files: Default::default(),
+                offline_journal: None,
             };
             project.set_role(role, cx);
             for worktree in worktrees {
@@ -2054,6 +2101,16 @@ impl Project {
         self.breakpoint_store.clone()
     }
 
+    #[inline]
+    /// The journal of edits made while the connection to the remote server is down, for remote server projects.
+    pub fn offline_journal(&self) -> Option<Entity<OfflineJournal>> {
+        self.offline_journal.clone()
+    }
+
+    pub fn bookmark_store(&self) -> Entity<BookmarkStore> {
+        self.bookmark_store.clone()
+    }
+
     pub fn active_debug_session(&self, cx: &App) -> Option<(Entity<Session>, ActiveStackFrame)> {
         let active_position = self.breakpoint_store.read(cx).active_position()?;
         let session = self
@@ -3103,6 +3160,12 @@ impl Project {
     }
 
     pub fn save_buffer(&self, buffer: Entity<Buffer>, cx: &mut Context<Self>) -> Task<Result<()>> {
+        if let Some(offline_journal) = &self.offline_journal
+            && offline_journal.read(cx).is_offline()
+            && offline_journal.update(cx, |journal, cx| journal.queue_save(&buffer, cx))
+        {
+            return Task::ready(Ok(()));
+        }
         self.buffer_store
             .update(cx, |buffer_store, cx| buffer_store.save_buffer(buffer, cx))
     }
@@ -3506,7 +3569,7 @@ impl Project {
                     store.disconnected_from_host(cx);
                 });
                 self.buffer_store.update(cx, |buffer_store, cx| {
-                    buffer_store.disconnected_from_host(cx)
+                    buffer_store.disconnected_from_remote_server(cx)
                 });
                 self.lsp_store.update(cx, |lsp_store, _cx| {
                     lsp_store.disconnected_from_ssh_remote()
@@ -6100,7 +6163,18 @@ impl ProjectItem for Buffer {
         path: &ProjectPath,
         cx: &mut App,
     ) -> Option<Task<Result<Entity<Self>>>> {
-        Some(project.update(cx, |project, cx| project.open_buffer(path.clone(), cx)))
+        let open_buffer = project.update(cx, |project, cx| project.open_buffer(path.clone(), cx));
+        Some(cx.background_spawn(async move {
+            // Binary files are left to the kinds of items that can show them. Their error only
+            // survives remote requests as a message, so that's what is matched.
+            open_buffer.await.map_err(|error| {
+                if format!("{error:#}").contains(BINARY_FILE_ERROR) {
+                    DeclinedProjectItem.into()
+                } else {
+                    error
+                }
+            })
+        }))
     }
 
     fn entry_id(&self, _cx: &App) -> Option<ProjectEntryId> {
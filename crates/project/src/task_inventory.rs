@@ -43,6 +43,10 @@ pub struct Inventory {
     last_scheduled_scenarios: VecDeque<(DebugScenario, DebugScenarioContext)>,
     templates_from_settings: InventoryFor<TaskTemplate>,
     scenarios_from_settings: InventoryFor<DebugScenario>,
+    /// Task templates that extension task providers returned for each worktree, by provider name.
+    templates_from_extensions: HashMap<WorktreeId, Vec<(Arc<str>, TaskTemplate)>>,
+    /// Input values entered the last time a task or debug scenario was run, by its label.
+    last_input_values: HashMap<String, HashMap<String, String>>,
 }
 
 impl std::fmt::Debug for Inventory {
@@ -52,6 +56,8 @@ impl std::fmt::Debug for Inventory {
             .field("last_scheduled_scenarios", &self.last_scheduled_scenarios)
             .field("templates_from_settings", &self.templates_from_settings)
             .field("scenarios_from_settings", &self.scenarios_from_settings)
+            .field("templates_from_extensions", &self.templates_from_extensions)
+            .field("last_input_values", &self.last_input_values)
             .finish()
     }
 }
@@ -135,7 +141,7 @@ impl<T> Default for InventoryFor<T> {
 pub enum TaskSourceKind {
     /// bash-like commands spawned by users, not associated with any path
     UserInput,
-    /// Tasks from the worktree's .zed/task.json
+    /// Tasks from the worktree's .zed/task.json, or provided for the worktree by extensions
     Worktree {
         id: WorktreeId,
         directory_in_worktree: Arc<RelPath>,
@@ -246,6 +252,8 @@ impl Inventory {
             last_scheduled_scenarios: VecDeque::default(),
             templates_from_settings: InventoryFor::default(),
             scenarios_from_settings: InventoryFor::default(),
+            templates_from_extensions: HashMap::default(),
+            last_input_values: HashMap::default(),
         })
     }
 
@@ -386,7 +394,10 @@ impl Inventory {
         let global_tasks = self.global_templates_from_settings().collect::<Vec<_>>();
         let mut worktree_tasks = worktree
             .into_iter()
-            .flat_map(|worktree| self.worktree_templates_from_settings(worktree))
+            .flat_map(|worktree| {
+                self.worktree_templates_from_settings(worktree)
+                    .chain(self.worktree_templates_from_extensions(worktree))
+            })
             .collect::<Vec<_>>();
 
         let task_source_kind = language.as_ref().map(|language| TaskSourceKind::Language {
@@ -489,7 +500,10 @@ impl Inventory {
             });
         let worktree_tasks = worktree
             .into_iter()
-            .flat_map(|worktree| self.worktree_templates_from_settings(worktree))
+            .flat_map(|worktree| {
+                self.worktree_templates_from_settings(worktree)
+                    .chain(self.worktree_templates_from_extensions(worktree))
+            })
             .collect::<Vec<_>>();
         let task_contexts = task_contexts.clone();
         cx.background_spawn(async move {
@@ -604,6 +618,17 @@ impl Inventory {
         }
     }
 
+    /// Remembers the input values entered for the task or debug scenario with the given label,
+    /// to suggest them the next time it runs.
+    pub fn inputs_entered(&mut self, label: String, values: HashMap<String, String>) {
+        self.last_input_values.insert(label, values);
+    }
+
+    /// Input values entered the last time the task or debug scenario with the given label was run.
+    pub fn last_input_values(&self, label: &str) -> Option<&HashMap<String, String>> {
+        self.last_input_values.get(label)
+    }
+
     /// Deletes a resolved task from history, using its id.
     /// A similar may still resurface in `used_and_current_resolved_tasks` when its [`TaskTemplate`] is resolved again.
     pub fn delete_previously_used(&mut self, id: &TaskId) {
@@ -636,6 +661,39 @@ impl Inventory {
         self.templates_from_settings.worktree_scenarios(worktree)
     }
 
+    fn worktree_templates_from_extensions(
+        &self,
+        worktree: WorktreeId,
+    ) -> impl '_ + Iterator<Item = (TaskSourceKind, TaskTemplate)> {
+        self.templates_from_extensions
+            .get(&worktree)
+            .into_iter()
+            .flatten()
+            .map(move |(provider_name, template)| {
+                (
+                    TaskSourceKind::Worktree {
+                        id: worktree,
+                        directory_in_worktree: RelPath::empty().into_arc(),
+                        id_base: format!("extension {provider_name} tasks").into(),
+                    },
+                    template.clone(),
+                )
+            })
+    }
+
+    /// Replaces the task templates that extension task providers returned for the worktree given.
+    pub fn update_extension_tasks(
+        &mut self,
+        worktree: WorktreeId,
+        templates: Vec<(Arc<str>, TaskTemplate)>,
+    ) {
+        if templates.is_empty() {
+            self.templates_from_extensions.remove(&worktree);
+        } else {
+            self.templates_from_extensions.insert(worktree, templates);
+        }
+    }
+
     /// Updates in-memory task metadata from the JSON string given.
     /// Will fail if the JSON is not a valid array of objects, but will continue if any object will not parse into a [`TaskTemplate`].
     ///
@@ -1,12 +1,15 @@
+pub mod extension;
+
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::Context as _;
 use collections::HashMap;
 use fs::Fs;
-use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Task, WeakEntity};
+use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity};
 use language::{
     ContextLocation, ContextProvider as _, LanguageToolchainStore, Location,
     proto::{deserialize_anchor, serialize_anchor},
@@ -16,12 +19,21 @@ use settings::{InvalidSettingsError, SettingsLocation};
 use task::{TaskContext, TaskVariables, VariableName};
 use text::{BufferId, OffsetRangeExt};
 use util::ResultExt;
+use worktree::WorktreeId;
 
 use crate::{
-    BasicContextProvider, Inventory, ProjectEnvironment, buffer_store::BufferStore,
-    worktree_store::WorktreeStore,
+    BasicContextProvider, Inventory, ProjectEnvironment,
+    buffer_store::BufferStore,
+    worktree_store::{WorktreeStore, WorktreeStoreEvent},
+};
+
+use self::extension::{
+    TaskProviderRegistry, extension_task_templates, extension_task_variables, extension_worktree,
 };
 
+/// How long to wait after a worktree change before asking extensions for its tasks again.
+const EXTENSION_TASKS_DEBOUNCE: Duration = Duration::from_millis(500);
+
 // platform-dependent warning
 pub enum TaskStore {
     Functional(StoreState),
@@ -40,6 +52,9 @@ enum StoreMode {
     Local {
         downstream_client: Option<(AnyProtoClient, u64)>,
         environment: Entity<ProjectEnvironment>,
+        /// Pending requests for the task templates that extensions provide, by worktree.
+        extension_task_refreshes: HashMap<WorktreeId, Task<()>>,
+        _extension_subscriptions: Vec<Subscription>,
     },
     Remote {
         upstream_client: AnyProtoClient,
@@ -165,10 +180,30 @@ impl TaskStore {
         environment: Entity<ProjectEnvironment>,
         cx: &mut Context<Self>,
     ) -> Self {
+        let task_provider_registry = TaskProviderRegistry::default_global(cx);
+        let extension_subscriptions = vec![
+            cx.observe(&task_provider_registry, |task_store, _, cx| {
+                task_store.refresh_extension_tasks(None, cx)
+            }),
+            cx.subscribe(&worktree_store, |task_store, _, event, cx| match event {
+                WorktreeStoreEvent::WorktreeAdded(worktree) => {
+                    task_store.refresh_extension_tasks(Some(worktree.read(cx).id()), cx)
+                }
+                WorktreeStoreEvent::WorktreeUpdatedEntries(worktree_id, _) => {
+                    task_store.refresh_extension_tasks(Some(*worktree_id), cx)
+                }
+                WorktreeStoreEvent::WorktreeRemoved(_, worktree_id) => {
+                    task_store.remove_extension_tasks(*worktree_id, cx)
+                }
+                _ => {}
+            }),
+        ];
         Self::Functional(StoreState {
             mode: StoreMode::Local {
                 downstream_client: None,
                 environment,
+                extension_task_refreshes: HashMap::default(),
+                _extension_subscriptions: extension_subscriptions,
             },
             task_inventory: Inventory::new(cx),
             buffer_store,
@@ -261,6 +296,82 @@ impl TaskStore {
         }
     }
 
+    /// Asks the extension task providers for the task templates of the worktree given, or of all
+    /// visible worktrees, and stores them in the inventory.
+    fn refresh_extension_tasks(&mut self, worktree_id: Option<WorktreeId>, cx: &mut Context<Self>) {
+        let TaskStore::Functional(StoreState {
+            mode:
+                StoreMode::Local {
+                    environment,
+                    extension_task_refreshes,
+                    ..
+                },
+            task_inventory,
+            worktree_store,
+            ..
+        }) = self
+        else {
+            return;
+        };
+        let Some(fs) = worktree_store.read(cx).fs() else {
+            return;
+        };
+        let providers = TaskProviderRegistry::default_global(cx)
+            .read(cx)
+            .providers();
+        let worktrees = worktree_store
+            .read(cx)
+            .visible_worktrees(cx)
+            .filter(|worktree| worktree_id.is_none_or(|id| worktree.read(cx).id() == id))
+            .collect::<Vec<_>>();
+        for worktree in worktrees {
+            let worktree_id = worktree.read(cx).id();
+            if providers.is_empty() {
+                extension_task_refreshes.remove(&worktree_id);
+                task_inventory.update(cx, |inventory, cx| {
+                    inventory.update_extension_tasks(worktree_id, Vec::new());
+                    cx.notify();
+                });
+                continue;
+            }
+            let extension_worktree = extension_worktree(&worktree, fs.clone(), environment, cx);
+            let providers = providers.clone();
+            let task_inventory = task_inventory.downgrade();
+            let refresh = cx.spawn(async move |_, cx| {
+                cx.background_executor()
+                    .timer(EXTENSION_TASKS_DEBOUNCE)
+                    .await;
+                let templates = extension_task_templates(providers, extension_worktree).await;
+                task_inventory
+                    .update(cx, |inventory, cx| {
+                        inventory.update_extension_tasks(worktree_id, templates);
+                        cx.notify();
+                    })
+                    .ok();
+            });
+            extension_task_refreshes.insert(worktree_id, refresh);
+        }
+    }
+
+    fn remove_extension_tasks(&mut self, worktree_id: WorktreeId, cx: &mut Context<Self>) {
+        if let TaskStore::Functional(StoreState {
+            mode:
+                StoreMode::Local {
+                    extension_task_refreshes,
+                    ..
+                },
+            task_inventory,
+            ..
+        }) = self
+        {
+            extension_task_refreshes.remove(&worktree_id);
+            task_inventory.update(cx, |inventory, cx| {
+                inventory.update_extension_tasks(worktree_id, Vec::new());
+                cx.notify();
+            });
+        }
+    }
+
     pub(super) fn update_user_tasks(
         &self,
         location: TaskSettingsLocation<'_>,
@@ -275,8 +386,10 @@ impl TaskStore {
             .map(|json| json.trim())
             .filter(|json| !json.is_empty());
 
-        task_inventory.update(cx, |inventory, _| {
-            inventory.update_file_based_tasks(location, raw_tasks_json)
+        task_inventory.update(cx, |inventory, cx| {
+            let result = inventory.update_file_based_tasks(location, raw_tasks_json);
+            cx.notify();
+            result
         })
     }
 
@@ -306,13 +419,22 @@ fn local_task_context_for_location(
     environment: Entity<ProjectEnvironment>,
     captured_variables: TaskVariables,
     location: Location,
-    cx: &App,
+    cx: &mut App,
 ) -> Task<Option<TaskContext>> {
     let worktree_id = location.buffer.read(cx).file().map(|f| f.worktree_id(cx));
-    let worktree_abs_path = worktree_id
-        .and_then(|worktree_id| worktree_store.read(cx).worktree_for_id(worktree_id, cx))
+    let worktree = worktree_id
+        .and_then(|worktree_id| worktree_store.read(cx).worktree_for_id(worktree_id, cx));
+    let worktree_abs_path = worktree
+        .as_ref()
         .and_then(|worktree| worktree.read(cx).root_dir());
     let fs = worktree_store.read(cx).fs();
+    let task_providers = TaskProviderRegistry::default_global(cx)
+        .read(cx)
+        .providers();
+    let extension_worktree = worktree
+        .zip(fs.clone())
+        .filter(|_| !task_providers.is_empty())
+        .map(|(worktree, fs)| extension_worktree(&worktree, fs, &environment, cx));
 
     cx.spawn(async move |cx| {
         let project_env = environment
@@ -336,6 +458,11 @@ fn local_task_context_for_location(
             })
             .await
             .log_err()?;
+        if let Some(extension_worktree) = extension_worktree {
+            let extension_variables =
+                extension_task_variables(task_providers, &task_variables, extension_worktree).await;
+            task_variables.extend(extension_variables);
+        }
         // Remove all custom entries starting with _, as they're not intended for use by the end user.
         task_variables.sweep();
 
@@ -0,0 +1,181 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use collections::{BTreeMap, HashMap};
+use extension::{Extension, ExtensionHostProxy, ExtensionTaskProviderProxy, WorktreeDelegate};
+use fs::Fs;
+use futures::future::Shared;
+use gpui::{App, AppContext as _, Entity, Global, Task};
+use task::{TaskTemplate, TaskVariables};
+use util::rel_path::RelPath;
+use worktree::{Worktree, WorktreeId};
+
+use crate::ProjectEnvironment;
+
+pub fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_task_provider_proxy(TaskProviderRegistryProxy {
+        task_provider_registry: TaskProviderRegistry::default_global(cx),
+    });
+}
+
+struct GlobalTaskProviderRegistry(Entity<TaskProviderRegistry>);
+
+impl Global for GlobalTaskProviderRegistry {}
+
+/// The task providers of the installed extensions, by provider name.
+#[derive(Default)]
+pub struct TaskProviderRegistry {
+    providers: BTreeMap<Arc<str>, Arc<dyn Extension>>,
+}
+
+impl TaskProviderRegistry {
+    /// Returns the global [`TaskProviderRegistry`].
+    ///
+    /// Inserts a default [`TaskProviderRegistry`] if one does not yet exist.
+    pub fn default_global(cx: &mut App) -> Entity<Self> {
+        if !cx.has_global::<GlobalTaskProviderRegistry>() {
+            let registry = cx.new(|_| Self::default());
+            cx.set_global(GlobalTaskProviderRegistry(registry));
+        }
+        cx.global::<GlobalTaskProviderRegistry>().0.clone()
+    }
+
+    pub fn providers(&self) -> Vec<(Arc<str>, Arc<dyn Extension>)> {
+        self.providers
+            .iter()
+            .map(|(name, extension)| (name.clone(), extension.clone()))
+            .collect()
+    }
+}
+
+struct TaskProviderRegistryProxy {
+    task_provider_registry: Entity<TaskProviderRegistry>,
+}
+
+impl ExtensionTaskProviderProxy for TaskProviderRegistryProxy {
+    fn register_task_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_name: Arc<str>,
+        cx: &mut App,
+    ) {
+        self.task_provider_registry.update(cx, |registry, cx| {
+            registry.providers.insert(provider_name, extension);
+            cx.notify();
+        })
+    }
+
+    fn unregister_task_provider(&self, provider_name: Arc<str>, cx: &mut App) {
+        self.task_provider_registry.update(cx, |registry, cx| {
+            if registry.providers.remove(&provider_name).is_some() {
+                cx.notify();
+            }
+        })
+    }
+}
+
+/// A local worktree, as extensions see it.
+struct ExtensionWorktree {
+    id: WorktreeId,
+    root_path: Arc<Path>,
+    fs: Arc<dyn Fs>,
+    shell_env: Shared<Task<Option<HashMap<String, String>>>>,
+}
+
+pub(crate) fn extension_worktree(
+    worktree: &Entity<Worktree>,
+    fs: Arc<dyn Fs>,
+    environment: &Entity<ProjectEnvironment>,
+    cx: &mut App,
+) -> Arc<dyn WorktreeDelegate> {
+    let shell_env = environment.update(cx, |environment, cx| {
+        environment.worktree_environment(worktree.clone(), cx)
+    });
+    let worktree = worktree.read(cx);
+    Arc::new(ExtensionWorktree {
+        id: worktree.id(),
+        root_path: worktree.abs_path(),
+        fs,
+        shell_env,
+    })
+}
+
+#[async_trait]
+impl WorktreeDelegate for ExtensionWorktree {
+    fn id(&self) -> u64 {
+        self.id.to_proto()
+    }
+
+    fn root_path(&self) -> String {
+        self.root_path.to_string_lossy().into_owned()
+    }
+
+    async fn read_text_file(&self, path: &RelPath) -> Result<String> {
+        self.fs.load(&self.root_path.join(path.as_std_path())).await
+    }
+
+    async fn which(&self, binary_name: String) -> Option<String> {
+        let env = self.shell_env.clone().await.unwrap_or_default();
+        which::which_in(binary_name, env.get("PATH"), &self.root_path)
+            .ok()
+            .map(|path| path.to_string_lossy().into_owned())
+    }
+
+    async fn shell_env(&self) -> Vec<(String, String)> {
+        self.shell_env
+            .clone()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Collects the task templates that the extension task providers return for the worktree.
+pub(super) async fn extension_task_templates(
+    providers: Vec<(Arc<str>, Arc<dyn Extension>)>,
+    worktree: Arc<dyn WorktreeDelegate>,
+) -> Vec<(Arc<str>, TaskTemplate)> {
+    let mut templates = Vec::new();
+    for (provider_name, extension) in providers {
+        match extension
+            .task_templates(provider_name.clone(), worktree.clone())
+            .await
+        {
+            Ok(provider_templates) => templates.extend(
+                provider_templates
+                    .0
+                    .into_iter()
+                    .map(|template| (provider_name.clone(), template)),
+            ),
+            Err(e) => {
+                log::error!("Failed to list the tasks of task provider `{provider_name}`: {e:#}")
+            }
+        }
+    }
+    templates
+}
+
+/// Collects the custom task variables that the extension task providers return for the worktree,
+/// given the variables of the task context built so far.
+pub(super) async fn extension_task_variables(
+    providers: Vec<(Arc<str>, Arc<dyn Extension>)>,
+    variables: &TaskVariables,
+    worktree: Arc<dyn WorktreeDelegate>,
+) -> TaskVariables {
+    let mut custom_variables = TaskVariables::default();
+    for (provider_name, extension) in providers {
+        match extension
+            .task_variables(provider_name.clone(), variables.clone(), worktree.clone())
+            .await
+        {
+            Ok(provider_variables) => custom_variables.extend(provider_variables),
+            Err(e) => log::error!(
+                "Failed to get the task variables of task provider `{provider_name}`: {e:#}"
+            ),
+        }
+    }
+    custom_variables
+}
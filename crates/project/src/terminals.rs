@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use collections::HashMap;
 use gpui::{App, AppContext as _, Context, Entity, Task, WeakEntity};
 
@@ -11,13 +11,19 @@ use smol::channel::bounded;
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
+};
+use task::{
+    CompiledProblemMatcher, Problem, ProblemMatchState, ProblemMatcher, Shell, ShellBuilder,
+    ShellKind, SpawnInTerminal,
 };
-use task::{Shell, ShellBuilder, ShellKind, SpawnInTerminal};
 use terminal::{
-    TaskState, TaskStatus, Terminal, TerminalBuilder, insert_zed_terminal_env,
+    OutputPosition, TaskState, TaskStatus, Terminal, TerminalBuilder, insert_zed_terminal_env,
     terminal_settings::TerminalSettings,
 };
-use util::{command::new_std_command, get_default_system_shell, maybe, rel_path::RelPath};
+use util::{
+    ResultExt as _, command::new_std_command, get_default_system_shell, maybe, rel_path::RelPath,
+};
 
 use crate::{Project, ProjectPath};
 
@@ -90,8 +96,11 @@ impl Project {
         let detect_venv = settings.detect_venv.as_option().is_some();
 
         let (completion_tx, completion_rx) = bounded(1);
+        let task_label = spawn_task.full_label.clone();
+        let problem_matchers = spawn_task.problem_matchers.clone();
 
         let local_path = if is_via_remote { None } else { path.clone() };
+        let local_cwd = local_path.as_deref().map(Path::to_path_buf);
         let task_state = Some(TaskState {
             spawned_task: spawn_task.clone(),
             status: TaskStatus::Running,
@@ -266,6 +275,17 @@ impl Project {
                     .local_handles
                     .push(terminal_handle.downgrade());
 
+                // Task diagnostics are only supported for local projects.
+                if !problem_matchers.is_empty() && !is_via_remote {
+                    this.match_task_problems(
+                        &terminal_handle,
+                        task_label,
+                        problem_matchers,
+                        local_cwd,
+                        cx,
+                    );
+                }
+
                 let id = terminal_handle.entity_id();
                 cx.observe_release(&terminal_handle, move |project, _terminal, cx| {
                     let handles = &mut project.terminals.local_handles;
@@ -285,6 +305,86 @@ impl Project {
         })
     }
 
+    /// Runs the problem matchers of a task over its output while the task is running,
+    /// reporting the problems found as diagnostics.
+    fn match_task_problems(
+        &mut self,
+        terminal: &Entity<Terminal>,
+        task_label: String,
+        problem_matchers: Vec<ProblemMatcher>,
+        cwd: Option<PathBuf>,
+        cx: &mut Context<Self>,
+    ) {
+        const MATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+        let mut matchers = problem_matchers
+            .into_iter()
+            .filter_map(|matcher| {
+                let base_dir = match matcher.base_dir.as_deref() {
+                    Some(base_dir) => match &cwd {
+                        Some(cwd) => Some(cwd.join(base_dir)),
+                        None => Some(PathBuf::from(base_dir)),
+                    },
+                    None => cwd.clone(),
+                };
+                let matcher = matcher
+                    .compile()
+                    .with_context(|| format!("compiling problem matcher of task `{task_label}`"))
+                    .log_err()?;
+                Some((matcher, base_dir, ProblemMatchState::default()))
+            })
+            .collect::<Vec<_>>();
+        if matchers.is_empty() {
+            return;
+        }
+
+        // Problems from the previous run of the task go away once it is rerun.
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.update_task_diagnostics(&task_label, Vec::new(), cx)
+        });
+
+        let terminal = terminal.downgrade();
+        cx.spawn(async move |project, cx| {
+            let mut position = OutputPosition::default();
+            loop {
+                cx.background_executor().timer(MATCH_INTERVAL).await;
+                // Only the lines printed since the last check are matched, the matchers keep
+                // the problems found in the earlier ones, even after they leave the scrollback.
+                let Ok((lines, running)) = terminal.read_with(cx, |terminal, _| {
+                    let running = terminal
+                        .task()
+                        .is_some_and(|task| matches!(task.status, TaskStatus::Running));
+                    (terminal.read_output_lines(&mut position, !running), running)
+                }) else {
+                    break;
+                };
+
+                if !lines.is_empty() || !running {
+                    let problems;
+                    (matchers, problems) = cx
+                        .background_spawn(async move {
+                            for (matcher, _, state) in &mut matchers {
+                                matcher.match_lines(state, lines.iter().cloned(), !running);
+                            }
+                            let problems = match_problems(&matchers);
+                            (matchers, problems)
+                        })
+                        .await;
+                    project.update(cx, |project, cx| {
+                        project.lsp_store.update(cx, |lsp_store, cx| {
+                            lsp_store.update_task_diagnostics(&task_label, problems, cx)
+                        })
+                    })?;
+                }
+                if !running {
+                    break;
+                }
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn create_terminal_shell(
         &mut self,
         cwd: Option<PathBuf>,
@@ -638,3 +738,20 @@ fn create_remote_shell(
         command.env,
     ))
 }
+
+fn match_problems(
+    matchers: &[(CompiledProblemMatcher, Option<PathBuf>, ProblemMatchState)],
+) -> Vec<Problem> {
+    matchers
+        .iter()
+        .flat_map(|(_, base_dir, state)| {
+            state.problems().into_iter().filter_map(move |mut problem| {
+                if problem.path.is_relative() {
+                    problem.path = base_dir.as_ref()?.join(&problem.path);
+                }
+                problem.path = util::paths::normalize_lexically(&problem.path).ok()?;
+                Some(problem)
+            })
+        })
+        .collect()
+}
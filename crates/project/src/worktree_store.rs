@@ -99,6 +99,8 @@ impl WorktreeStore {
         client.add_entity_request_handler(Self::handle_delete_project_entry);
         client.add_entity_request_handler(Self::handle_expand_project_entry);
         client.add_entity_request_handler(Self::handle_expand_all_for_project_entry);
+        client.add_entity_request_handler(Self::handle_load_binary_file);
+        client.add_entity_request_handler(Self::handle_write_binary_file);
     }
 
     pub fn init_remote(client: &AnyProtoClient) {
@@ -1020,6 +1022,59 @@ impl WorktreeStore {
         Worktree::handle_create_entry(worktree, envelope.payload, cx).await
     }
 
+    pub async fn handle_load_binary_file(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::LoadBinaryFile>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::LoadBinaryFileResponse> {
+        let worktree = this.update(&mut cx, |this, cx| {
+            this.worktree_for_downstream_file(
+                envelope.payload.worktree_id,
+                &envelope.payload.path,
+                cx,
+            )
+        })?;
+        Worktree::handle_load_binary_file(worktree, envelope.payload, cx).await
+    }
+
+    pub async fn handle_write_binary_file(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::WriteBinaryFile>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let worktree = this.update(&mut cx, |this, cx| {
+            this.worktree_for_downstream_file(
+                envelope.payload.worktree_id,
+                &envelope.payload.path,
+                cx,
+            )
+        })?;
+        Worktree::handle_write_binary_file(worktree, envelope.payload, cx).await
+    }
+
+    /// Returns the worktree containing a file requested by the downstream client, refusing
+    /// private files unless the client is the one owning this remote server.
+    fn worktree_for_downstream_file(
+        &self,
+        worktree_id: u64,
+        path: &str,
+        cx: &App,
+    ) -> Result<Entity<Worktree>> {
+        let Some((_, project_id)) = &self.downstream_client else {
+            bail!("no downstream client")
+        };
+        let worktree = self
+            .worktree_for_id(WorktreeId::from_proto(worktree_id), cx)
+            .context("worktree not found")?;
+        if *project_id != REMOTE_SERVER_PROJECT_ID
+            && let Some(local_worktree) = worktree.read(cx).as_local()
+            && local_worktree.is_path_private(&RelPath::from_proto(path)?)
+        {
+            bail!("file is private")
+        }
+        Ok(worktree)
+    }
+
     pub async fn handle_copy_project_entry(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::CopyProjectEntry>,
@@ -0,0 +1,128 @@
+use fs::FakeFs;
+use gpui::TestAppContext;
+use project::Project;
+use project::bookmark_store::*;
+use serde_json::json;
+use settings::SettingsStore;
+use std::path::Path;
+use text::Point;
+use util::path;
+
+pub fn init_test(cx: &mut TestAppContext) {
+    zlog::init_test();
+
+    cx.update(|cx| {
+        let settings_store = SettingsStore::test(cx);
+        cx.set_global(settings_store);
+    });
+}
+
+fn bookmark_rows(
+    project: &gpui::Entity<Project>,
+    cx: &TestAppContext,
+) -> Vec<(String, u32, Option<String>)> {
+    project.read_with(cx, |project, cx| {
+        project
+            .bookmark_store()
+            .read(cx)
+            .all_bookmarks(cx)
+            .into_iter()
+            .map(|entry| {
+                (
+                    entry.path.to_string_lossy().into_owned(),
+                    entry.row,
+                    entry.bookmark.label.map(|label| label.to_string()),
+                )
+            })
+            .collect()
+    })
+}
+
+#[gpui::test]
+async fn test_bookmarks_follow_edits_and_renames(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.txt": "one\ntwo\nthree\nfour\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/a.txt"), cx)
+        })
+        .await
+        .unwrap();
+    let bookmark_store = project.read_with(cx, |project, _| project.bookmark_store());
+
+    bookmark_store.update(cx, |store, cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        store.toggle_bookmark(buffer.clone(), snapshot.anchor_before(Point::new(1, 2)), cx);
+        store.set_label(
+            buffer.clone(),
+            snapshot.anchor_before(Point::new(3, 0)),
+            Some("last".into()),
+            cx,
+        );
+    });
+    assert_eq!(
+        bookmark_rows(&project, cx),
+        [
+            (path!("/dir/a.txt").to_string(), 1, None),
+            (path!("/dir/a.txt").to_string(), 3, Some("last".to_string())),
+        ]
+    );
+
+    // Bookmarks move with the text they point at.
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+    assert_eq!(
+        bookmark_rows(&project, cx),
+        [
+            (path!("/dir/a.txt").to_string(), 2, None),
+            (path!("/dir/a.txt").to_string(), 4, Some("last".to_string())),
+        ]
+    );
+
+    // Toggling a bookmarked row removes the bookmark.
+    bookmark_store.update(cx, |store, cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        store.toggle_bookmark(buffer.clone(), snapshot.anchor_before(Point::new(2, 0)), cx);
+    });
+    assert_eq!(
+        bookmark_rows(&project, cx),
+        [(path!("/dir/a.txt").to_string(), 4, Some("last".to_string()))]
+    );
+
+    // Bookmarks follow their file when it's renamed on disk.
+    fs.rename(
+        Path::new(path!("/dir/a.txt")),
+        Path::new(path!("/dir/b.txt")),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(
+        bookmark_rows(&project, cx),
+        [(path!("/dir/b.txt").to_string(), 4, Some("last".to_string()))]
+    );
+
+    let serialized = bookmark_store.read_with(cx, |store, cx| store.all_serialized_bookmarks(cx));
+    bookmark_store.update(cx, |store, cx| store.clear_bookmarks(cx));
+    assert!(bookmark_rows(&project, cx).is_empty());
+
+    bookmark_store
+        .update(cx, |store, cx| {
+            store.with_serialized_bookmarks(serialized, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        bookmark_rows(&project, cx),
+        [(path!("/dir/b.txt").to_string(), 4, Some("last".to_string()))]
+    );
+}
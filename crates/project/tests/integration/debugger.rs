@@ -22,6 +22,11 @@ mod go_locator {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matchers: vec![],
+            inputs: vec![],
+            triggers: Default::default(),
         };
 
         let scenario = locator
@@ -49,6 +54,11 @@ mod go_locator {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matchers: vec![],
+            inputs: vec![],
+            triggers: Default::default(),
         };
 
         let scenario = locator
@@ -187,6 +197,11 @@ mod go_locator {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matchers: vec![],
+            inputs: vec![],
+            triggers: Default::default(),
         };
 
         let scenario = locator
@@ -221,6 +236,11 @@ mod python_locator {
             shell: task::Shell::System,
             show_summary: false,
             show_command: false,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matchers: vec![],
+            inputs: vec![],
+            triggers: Default::default(),
         };
 
         let expected_scenario = DebugScenario {
@@ -234,6 +254,7 @@ mod python_locator {
                 "cwd": "$ZED_WORKTREE_ROOT",
                 "module": "$ZED_CUSTOM_PYTHON_MODULE_NAME",
             }),
+            inputs: Vec::new(),
             tcp_connection: None,
         };
 
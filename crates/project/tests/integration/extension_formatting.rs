@@ -0,0 +1,519 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use collections::HashSet;
+use extension::{
+    BufferContents, CodeLabel, Command, Completion, ContextServerConfiguration, DebugAdapterBinary,
+    DebugRequest, DebugScenario, DebugTaskDefinition, EditorCommandBuffer, EditorCommandOutput,
+    Extension, ExtensionFormatterProxy as _, ExtensionHostProxy, ExtensionManifest,
+    FormatterOutput, KeyValueStoreDelegate, LintDiagnostic, LintPosition, LintSeverity,
+    LinterCommandOutput, LinterOutput, ProjectDelegate, SlashCommand,
+    SlashCommandArgumentCompletion, SlashCommandOutput, StartDebuggingRequestArgumentsRequest,
+    Symbol, WorktreeDelegate,
+};
+use fs::FakeFs;
+use gpui::{Entity, TestAppContext};
+use language::{
+    Buffer, DiagnosticSourceKind, LanguageName, PointUtf16,
+    language_settings::{Formatter, FormatterList, LanguageSettingsContent},
+};
+use lsp::{DiagnosticSeverity, LanguageServerName, NumberOrString};
+use project::{
+    Project,
+    lsp_store::{EXTENSION_LINTER_SERVER_ID, FormatTrigger, LspFormatTarget},
+};
+use serde_json::json;
+use settings::SettingsStore;
+use task::{BuildTaskTemplate, SpawnInTerminal, TaskTemplates, TaskVariables, ZedDebugConfig};
+use util::path;
+
+use super::js_lang;
+
+/// An extension that only provides a formatter and a linter.
+struct FakeExtension {
+    format: fn(BufferContents) -> FormatterOutput,
+    lint: fn(BufferContents) -> Vec<LintDiagnostic>,
+}
+
+#[gpui::test]
+async fn test_extension_formatter(cx: &mut TestAppContext) {
+    let (project, buffer) = init_test("let a = 1;\nlet b = 2;\n", cx).await;
+    register_formatter(
+        FakeExtension {
+            format: |buffer| FormatterOutput::Text(buffer.text.replace("let", "const")),
+            lint: |_| Vec::new(),
+        },
+        cx,
+    );
+
+    let transaction = format(&project, &buffer, cx).await.unwrap();
+    assert!(transaction.0.contains_key(&buffer));
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "const a = 1;\nconst b = 2;\n");
+    });
+
+    // The formatting is undone as a whole.
+    buffer.update(cx, |buffer, cx| buffer.undo(cx));
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "let a = 1;\nlet b = 2;\n");
+    });
+}
+
+#[cfg(not(windows))]
+#[gpui::test]
+async fn test_extension_formatter_command_failure(cx: &mut TestAppContext) {
+    cx.executor().allow_parking();
+    let (project, buffer) = init_test("let a = 1;\n", cx).await;
+    register_formatter(
+        FakeExtension {
+            format: |_| {
+                FormatterOutput::Command(Command {
+                    command: PathBuf::from("/bin/sh"),
+                    args: vec!["-c".into(), "cat; echo 'bad syntax' >&2; exit 3".into()],
+                    env: Vec::new(),
+                })
+            },
+            lint: |_| Vec::new(),
+        },
+        cx,
+    );
+
+    let error = format(&project, &buffer, cx).await.unwrap_err();
+    let error = format!("{error:#}");
+    assert!(error.contains("exit code Some(3)"), "{error}");
+    assert!(error.contains("bad syntax"), "{error}");
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(
+            buffer.text(),
+            "let a = 1;\n",
+            "the output of a failed command must not replace the buffer's text"
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_extension_linter_diagnostics(cx: &mut TestAppContext) {
+    let (project, _) = init_test("let a = 1;\nlet b = 2; // TODO\n", cx).await;
+    register_linter(
+        FakeExtension {
+            format: |_| unreachable!(),
+            lint: |buffer| {
+                buffer
+                    .text
+                    .lines()
+                    .enumerate()
+                    .filter_map(|(line, text)| {
+                        let column = text.find("TODO")? as u32;
+                        Some(LintDiagnostic {
+                            start: LintPosition {
+                                line: line as u32,
+                                column,
+                            },
+                            end: LintPosition {
+                                line: line as u32,
+                                column: column + 4,
+                            },
+                            severity: LintSeverity::Warning,
+                            message: "Unresolved TODO".to_string(),
+                            code: Some("todo".to_string()),
+                        })
+                    })
+                    .collect()
+            },
+        },
+        cx,
+    );
+
+    // Buffers are linted once they're registered with language servers.
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.js"), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    assert_eq!(
+        linter_diagnostics(&project, cx),
+        [(EXTENSION_LINTER_SERVER_ID, 0, 1)]
+    );
+    buffer.read_with(cx, |buffer, _| {
+        let snapshot = buffer.snapshot();
+        let diagnostics = snapshot
+            .diagnostics_in_range::<_, PointUtf16>(0..buffer.len(), false)
+            .map(|entry| {
+                let diagnostic = entry.diagnostic;
+                (
+                    entry.range,
+                    diagnostic.severity,
+                    diagnostic.message.clone(),
+                    diagnostic.code.clone(),
+                    diagnostic.source_kind,
+                    diagnostic.is_disk_based,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            [(
+                PointUtf16::new(1, 14)..PointUtf16::new(1, 18),
+                DiagnosticSeverity::WARNING,
+                "Unresolved TODO".to_string(),
+                Some(NumberOrString::String("todo".to_string())),
+                DiagnosticSourceKind::Other,
+                true,
+            )]
+        );
+    });
+
+    // Saving the fixed file lints it again, and clears the diagnostics.
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..buffer.len(), "let a = 1;\nlet b = 2;\n")], None, cx)
+    });
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    assert!(linter_diagnostics(&project, cx).is_empty());
+}
+
+#[gpui::test]
+async fn test_unregistered_linter_diagnostics_are_cleared(cx: &mut TestAppContext) {
+    let (project, _) = init_test("", cx).await;
+    register_linter(
+        FakeExtension {
+            format: |_| unreachable!(),
+            lint: |_| {
+                vec![LintDiagnostic {
+                    start: LintPosition { line: 0, column: 0 },
+                    end: LintPosition { line: 0, column: 3 },
+                    severity: LintSeverity::Error,
+                    message: "Always wrong".to_string(),
+                    code: None,
+                }]
+            },
+        },
+        cx,
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.js"), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    assert_eq!(
+        linter_diagnostics(&project, cx),
+        [(EXTENSION_LINTER_SERVER_ID, 1, 0)]
+    );
+
+    cx.update(|cx| ExtensionHostProxy::global(cx).unregister_linter("always-wrong".into(), cx));
+    cx.run_until_parked();
+    assert!(linter_diagnostics(&project, cx).is_empty());
+    buffer.read_with(cx, |buffer, _| {
+        let snapshot = buffer.snapshot();
+        assert_eq!(
+            snapshot
+                .diagnostics_in_range::<_, usize>(0..buffer.len(), false)
+                .count(),
+            0
+        );
+    });
+}
+
+/// Creates a project with a JavaScript file, and opens it.
+async fn init_test(text: &str, cx: &mut TestAppContext) -> (Entity<Project>, Entity<Buffer>) {
+    zlog::init_test();
+    cx.update(|cx| {
+        let settings_store = SettingsStore::test(cx);
+        cx.set_global(settings_store);
+        project::lsp_store::extension_formatting::init(cx);
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings(cx, |settings| {
+                settings.languages_mut().insert(
+                    "JavaScript".into(),
+                    LanguageSettingsContent {
+                        formatter: Some(FormatterList::Single(Formatter::Extension(
+                            "fake-formatter".to_string(),
+                        ))),
+                        ..Default::default()
+                    },
+                );
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({ "a.js": text })).await;
+    let project = Project::test(fs, [Path::new(path!("/dir"))], cx).await;
+    project.read_with(cx, |project, _| project.languages().add(js_lang()));
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/a.js"), cx)
+        })
+        .await
+        .unwrap();
+    (project, buffer)
+}
+
+fn register_formatter(extension: FakeExtension, cx: &mut TestAppContext) {
+    cx.update(|cx| {
+        ExtensionHostProxy::global(cx).register_formatter(
+            Arc::new(extension),
+            "fake-formatter".into(),
+            cx,
+        )
+    });
+}
+
+fn register_linter(extension: FakeExtension, cx: &mut TestAppContext) {
+    cx.update(|cx| {
+        ExtensionHostProxy::global(cx).register_linter(
+            Arc::new(extension),
+            "always-wrong".into(),
+            vec![LanguageName::new_static("JavaScript")],
+            cx,
+        )
+    });
+}
+
+async fn format(
+    project: &Entity<Project>,
+    buffer: &Entity<Buffer>,
+    cx: &mut TestAppContext,
+) -> Result<project::ProjectTransaction> {
+    project
+        .update(cx, |project, cx| {
+            project.format(
+                HashSet::from_iter([buffer.clone()]),
+                LspFormatTarget::Buffers,
+                true,
+                FormatTrigger::Manual,
+                cx,
+            )
+        })
+        .await
+}
+
+/// Returns the server id, error count and warning count of the project's diagnostics.
+fn linter_diagnostics(
+    project: &Entity<Project>,
+    cx: &mut TestAppContext,
+) -> Vec<(lsp::LanguageServerId, usize, usize)> {
+    project.read_with(cx, |project, cx| {
+        project
+            .diagnostic_summaries(false, cx)
+            .map(|(_, server_id, summary)| (server_id, summary.error_count, summary.warning_count))
+            .collect()
+    })
+}
+
+#[async_trait]
+impl Extension for FakeExtension {
+    fn manifest(&self) -> Arc<ExtensionManifest> {
+        unimplemented!()
+    }
+
+    fn work_dir(&self) -> Arc<Path> {
+        unimplemented!()
+    }
+
+    async fn language_server_command(
+        &self,
+        _: LanguageServerName,
+        _: LanguageName,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Command> {
+        unimplemented!()
+    }
+
+    async fn language_server_initialization_options(
+        &self,
+        _: LanguageServerName,
+        _: LanguageName,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Option<String>> {
+        unimplemented!()
+    }
+
+    async fn language_server_workspace_configuration(
+        &self,
+        _: LanguageServerName,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Option<String>> {
+        unimplemented!()
+    }
+
+    async fn language_server_additional_initialization_options(
+        &self,
+        _: LanguageServerName,
+        _: LanguageServerName,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Option<String>> {
+        unimplemented!()
+    }
+
+    async fn language_server_additional_workspace_configuration(
+        &self,
+        _: LanguageServerName,
+        _: LanguageServerName,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Option<String>> {
+        unimplemented!()
+    }
+
+    async fn labels_for_completions(
+        &self,
+        _: LanguageServerName,
+        _: Vec<Completion>,
+    ) -> Result<Vec<Option<CodeLabel>>> {
+        unimplemented!()
+    }
+
+    async fn labels_for_symbols(
+        &self,
+        _: LanguageServerName,
+        _: Vec<Symbol>,
+    ) -> Result<Vec<Option<CodeLabel>>> {
+        unimplemented!()
+    }
+
+    async fn complete_slash_command_argument(
+        &self,
+        _: SlashCommand,
+        _: Vec<String>,
+    ) -> Result<Vec<SlashCommandArgumentCompletion>> {
+        unimplemented!()
+    }
+
+    async fn run_slash_command(
+        &self,
+        _: SlashCommand,
+        _: Vec<String>,
+        _: Option<Arc<dyn WorktreeDelegate>>,
+    ) -> Result<SlashCommandOutput> {
+        unimplemented!()
+    }
+
+    async fn context_server_command(
+        &self,
+        _: Arc<str>,
+        _: Arc<dyn ProjectDelegate>,
+    ) -> Result<Command> {
+        unimplemented!()
+    }
+
+    async fn context_server_configuration(
+        &self,
+        _: Arc<str>,
+        _: Arc<dyn ProjectDelegate>,
+    ) -> Result<Option<ContextServerConfiguration>> {
+        unimplemented!()
+    }
+
+    async fn suggest_docs_packages(&self, _: Arc<str>) -> Result<Vec<String>> {
+        unimplemented!()
+    }
+
+    async fn index_docs(
+        &self,
+        _: Arc<str>,
+        _: Arc<str>,
+        _: Arc<dyn KeyValueStoreDelegate>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    async fn get_dap_binary(
+        &self,
+        _: Arc<str>,
+        _: DebugTaskDefinition,
+        _: Option<PathBuf>,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<DebugAdapterBinary> {
+        unimplemented!()
+    }
+
+    async fn dap_request_kind(
+        &self,
+        _: Arc<str>,
+        _: serde_json::Value,
+    ) -> Result<StartDebuggingRequestArgumentsRequest> {
+        unimplemented!()
+    }
+
+    async fn dap_config_to_scenario(&self, _: ZedDebugConfig) -> Result<DebugScenario> {
+        unimplemented!()
+    }
+
+    async fn dap_locator_create_scenario(
+        &self,
+        _: String,
+        _: BuildTaskTemplate,
+        _: String,
+        _: String,
+    ) -> Result<Option<DebugScenario>> {
+        unimplemented!()
+    }
+
+    async fn run_dap_locator(&self, _: String, _: SpawnInTerminal) -> Result<DebugRequest> {
+        unimplemented!()
+    }
+
+    async fn task_templates(
+        &self,
+        _: Arc<str>,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<TaskTemplates> {
+        unimplemented!()
+    }
+
+    async fn task_variables(
+        &self,
+        _: Arc<str>,
+        _: TaskVariables,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<TaskVariables> {
+        unimplemented!()
+    }
+
+    async fn run_editor_command(
+        &self,
+        _: Arc<str>,
+        _: EditorCommandBuffer,
+    ) -> Result<EditorCommandOutput> {
+        unimplemented!()
+    }
+
+    async fn format_buffer(
+        &self,
+        _: Arc<str>,
+        buffer: BufferContents,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<FormatterOutput> {
+        Ok((self.format)(buffer))
+    }
+
+    async fn lint_buffer(
+        &self,
+        _: Arc<str>,
+        buffer: BufferContents,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<LinterOutput> {
+        Ok(LinterOutput::Diagnostics((self.lint)(buffer)))
+    }
+
+    async fn linter_diagnostics(
+        &self,
+        _: Arc<str>,
+        _: LinterCommandOutput,
+    ) -> Result<Vec<LintDiagnostic>> {
+        unimplemented!()
+    }
+}
@@ -1,10 +1,12 @@
 #![allow(clippy::format_collect)]
 
+mod bookmark_store;
 mod color_extractor;
 mod context_server_store;
 mod debugger;
 mod ext_agent_tests;
 mod extension_agent_tests;
+mod extension_formatting;
 mod git_store;
 mod image_store;
 mod lsp_command;
@@ -3610,6 +3612,89 @@ async fn test_diagnostics_from_multiple_language_servers(cx: &mut gpui::TestAppC
     });
 }
 
+#[gpui::test]
+async fn test_task_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({ "a.rs": "one two three", "b.rs": "four five" }),
+    )
+    .await;
+
+    let project = Project::test(fs, [Path::new(path!("/dir"))], cx).await;
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+    let problem = |path: &str, message: &str, severity| task::Problem {
+        path: PathBuf::from(path),
+        line: 0,
+        column: 4,
+        end_line: None,
+        end_column: None,
+        severity,
+        code: None,
+        message: message.to_string(),
+        source: Some("rustc".to_string()),
+    };
+
+    lsp_store.update(cx, |lsp_store, cx| {
+        lsp_store.update_task_diagnostics(
+            "build",
+            vec![
+                problem(path!("/dir/a.rs"), "first", task::ProblemSeverity::Error),
+                problem(path!("/dir/b.rs"), "second", task::ProblemSeverity::Warning),
+                problem(
+                    path!("/outside/c.rs"),
+                    "ignored",
+                    task::ProblemSeverity::Error,
+                ),
+            ],
+            cx,
+        );
+        lsp_store.update_task_diagnostics(
+            "lint",
+            vec![problem(
+                path!("/dir/a.rs"),
+                "third",
+                task::ProblemSeverity::Warning,
+            )],
+            cx,
+        );
+        assert_eq!(
+            lsp_store.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 1,
+                warning_count: 2,
+            }
+        );
+
+        // Rerunning a task replaces its own problems only.
+        lsp_store.update_task_diagnostics(
+            "build",
+            vec![problem(
+                path!("/dir/b.rs"),
+                "second",
+                task::ProblemSeverity::Warning,
+            )],
+            cx,
+        );
+        assert_eq!(
+            lsp_store.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 0,
+                warning_count: 2,
+            }
+        );
+
+        lsp_store.update_task_diagnostics("build", Vec::new(), cx);
+        lsp_store.update_task_diagnostics("lint", Vec::new(), cx);
+        assert_eq!(
+            lsp_store.diagnostic_summary(false, cx),
+            DiagnosticSummary::default()
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_edits_from_lsp2_with_past_version(cx: &mut gpui::TestAppContext) {
     init_test(cx);
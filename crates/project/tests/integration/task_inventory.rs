@@ -560,6 +560,65 @@ async fn test_inventory_static_task_filters(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_extension_tasks(cx: &mut TestAppContext) {
+    init_test(cx);
+    let inventory = cx.update(|cx| Inventory::new(cx));
+    let worktree_1 = WorktreeId::from_usize(1);
+    let worktree_2 = WorktreeId::from_usize(2);
+    let extension_task = |label: &str| {
+        (
+            Arc::<str>::from("deno"),
+            task::TaskTemplate {
+                label: label.to_string(),
+                command: "deno".to_string(),
+                ..task::TaskTemplate::default()
+            },
+        )
+    };
+
+    inventory.update(cx, |inventory, _| {
+        inventory.update_extension_tasks(
+            worktree_1,
+            vec![
+                extension_task("deno task dev"),
+                extension_task("deno task test"),
+            ],
+        );
+    });
+    assert_eq!(
+        list_tasks(&inventory, Some(worktree_1), cx).await,
+        ["deno task dev", "deno task test"]
+            .into_iter()
+            .map(|label| (
+                TaskSourceKind::Worktree {
+                    id: worktree_1,
+                    directory_in_worktree: rel_path("").into(),
+                    id_base: "extension deno tasks".into(),
+                },
+                label.to_string(),
+            ))
+            .collect::<Vec<_>>(),
+    );
+    assert_eq!(
+        list_tasks(&inventory, Some(worktree_2), cx).await,
+        Vec::new(),
+        "Extension tasks should only be listed for their worktree"
+    );
+    assert_eq!(
+        resolved_task_names(&inventory, Some(worktree_1), cx).await,
+        vec!["deno task dev", "deno task test"],
+    );
+
+    inventory.update(cx, |inventory, _| {
+        inventory.update_extension_tasks(worktree_1, Vec::new());
+    });
+    assert_eq!(
+        list_tasks(&inventory, Some(worktree_1), cx).await,
+        Vec::new()
+    );
+}
+
 fn init_test(_cx: &mut TestAppContext) {
     zlog::init_test();
     TaskStore::init(None);
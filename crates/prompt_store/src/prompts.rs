@@ -75,6 +75,17 @@ pub struct WorktreeContext {
     pub root_name: String,
     pub abs_path: Arc<Path>,
     pub rules_file: Option<RulesFileContext>,
+    /// Rules files found in subdirectories of the worktree. These aren't part of the system
+    /// prompt, and are instead provided when the agent reads or edits files beneath them.
+    #[serde(skip)]
+    pub nested_rules_files: Vec<NestedRulesFileContext>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NestedRulesFileContext {
+    /// The directory whose files the rules apply to.
+    pub directory: Arc<RelPath>,
+    pub rules_file: RulesFileContext,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize)]
@@ -324,6 +324,11 @@ messages!(
     (GitDiffResponse, Background),
     (GitDiffStat, Background),
     (GitDiffStatResponse, Background),
+    (GitLoadMergeStages, Background),
+    (GitMergeStages, Background),
+    (LoadBinaryFile, Background),
+    (LoadBinaryFileResponse, Background),
+    (WriteBinaryFile, Background),
     (GitInit, Background),
     (GetDebugAdapterBinary, Background),
     (DebugAdapterBinary, Background),
@@ -542,6 +547,9 @@ request_messages!(
     (CheckForPushedCommits, CheckForPushedCommitsResponse),
     (GitDiff, GitDiffResponse),
     (GitDiffStat, GitDiffStatResponse),
+    (GitLoadMergeStages, GitMergeStages),
+    (LoadBinaryFile, LoadBinaryFileResponse),
+    (WriteBinaryFile, Ack),
     (GitInit, Ack),
     (ToggleBreakpoint, Ack),
     (GetDebugAdapterBinary, DebugAdapterBinary),
@@ -731,6 +739,9 @@ entity_messages!(
     CheckForPushedCommits,
     GitDiff,
     GitDiffStat,
+    GitLoadMergeStages,
+    LoadBinaryFile,
+    WriteBinaryFile,
     GitInit,
     BreakpointsForFile,
     ToggleBreakpoint,
@@ -169,7 +169,8 @@ impl Render for DisconnectedOverlay {
                     "not responding"
                 };
                 format!(
-                    "Your connection to {} has been lost due to the server {reason}.{autosave}",
+                    "Your connection to {} has been lost due to the server {reason}.{autosave}\n\
+                     Saves made while disconnected are queued and replayed when you reconnect.",
                     options.display_name(),
                 )
             }
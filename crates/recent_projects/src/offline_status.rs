@@ -0,0 +1,113 @@
+use editor::Editor;
+use gpui::{Entity, Subscription};
+use language::BufferId;
+use project::offline_journal::{OfflineBufferStatus, OfflineJournal};
+use ui::{Tooltip, prelude::*};
+use workspace::{StatusItemView, Workspace, item::ItemHandle};
+
+/// Shows whether the active buffer has edits or saves journaled while the remote connection is down.
+pub struct OfflineStatusIndicator {
+    offline_journal: Option<Entity<OfflineJournal>>,
+    active_buffer_id: Option<BufferId>,
+    _observe_offline_journal: Option<Subscription>,
+    _observe_active_editor: Option<Subscription>,
+}
+
+impl OfflineStatusIndicator {
+    pub fn new(workspace: &Workspace, cx: &mut Context<Self>) -> Self {
+        let offline_journal = workspace.project().read(cx).offline_journal();
+        let observe_offline_journal = offline_journal
+            .as_ref()
+            .map(|offline_journal| cx.observe(offline_journal, |_, _, cx| cx.notify()));
+        Self {
+            offline_journal,
+            active_buffer_id: None,
+            _observe_offline_journal: observe_offline_journal,
+            _observe_active_editor: None,
+        }
+    }
+
+    fn update(&mut self, editor: Entity<Editor>, _: &mut Window, cx: &mut Context<Self>) {
+        self.active_buffer_id = editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .map(|buffer| buffer.read(cx).remote_id());
+        cx.notify();
+    }
+}
+
+impl Render for OfflineStatusIndicator {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(offline_journal) = self.offline_journal.as_ref() else {
+            return div();
+        };
+        let offline_journal = offline_journal.read(cx);
+        let status = self
+            .active_buffer_id
+            .and_then(|buffer_id| offline_journal.status(buffer_id));
+
+        let (icon, color, label, tooltip): (_, _, SharedString, _) = match status {
+            Some(OfflineBufferStatus::Conflict) => (
+                IconName::Warning,
+                Color::Warning,
+                "Remote Conflict".into(),
+                "This file changed on the remote while you were offline. Saving asks before overwriting it.",
+            ),
+            Some(OfflineBufferStatus::SaveQueued) => (
+                IconName::Disconnected,
+                Color::Muted,
+                "Save Queued".into(),
+                "Saved locally. The file is written to the remote once the connection is restored.",
+            ),
+            Some(OfflineBufferStatus::Journaled) => (
+                IconName::Disconnected,
+                Color::Muted,
+                "Edits Journaled".into(),
+                "Unsaved edits are journaled locally while the connection is down.",
+            ),
+            None if offline_journal.is_offline() => {
+                let label = match offline_journal.queued_save_count() {
+                    0 => "Offline".into(),
+                    1 => "Offline · 1 save queued".into(),
+                    count => format!("Offline · {count} saves queued").into(),
+                };
+                (
+                    IconName::Disconnected,
+                    Color::Muted,
+                    label,
+                    "The connection to the remote is down. Edits and saves are journaled locally.",
+                )
+            }
+            None => return div(),
+        };
+
+        div().child(
+            h_flex()
+                .id("offline-status")
+                .gap_1()
+                .child(Icon::new(icon).size(IconSize::Small).color(color))
+                .child(Label::new(label).size(LabelSize::Small).color(color))
+                .tooltip(Tooltip::text(tooltip)),
+        )
+    }
+}
+
+impl StatusItemView for OfflineStatusIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.downcast::<Editor>()) {
+            self._observe_active_editor = Some(cx.observe_in(&editor, window, Self::update));
+            self.update(editor, window, cx);
+        } else {
+            self.active_buffer_id = None;
+            self._observe_active_editor = None;
+        }
+        cx.notify();
+    }
+}
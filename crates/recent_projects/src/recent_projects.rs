@@ -1,6 +1,8 @@
 mod dev_container_suggest;
 pub mod disconnected_overlay;
+mod offline_status;
 mod remote_connections;
+mod remote_ports;
 mod remote_servers;
 mod ssh_config;
 
@@ -27,6 +29,7 @@ use gpui::{
     Subscription, Task, WeakEntity, Window, actions, px,
 };
 
+pub use offline_status::OfflineStatusIndicator;
 use picker::{
     Picker, PickerDelegate,
     highlighted_match_with_paths::{HighlightedMatch, HighlightedMatchWithPaths},
@@ -368,6 +371,7 @@ pub fn init(cx: &mut App) {
     });
 
     cx.observe_new(DisconnectedOverlay::register).detach();
+    cx.observe_new(remote_ports::register).detach();
 
     cx.on_action(|_: &OpenDevContainer, cx| {
         with_active_or_new_workspace(cx, move |workspace, window, cx| {
@@ -1394,6 +1398,7 @@ fn icon_for_remote_connection(options: Option<&RemoteConnectionOptions>) -> Icon
             RemoteConnectionOptions::Ssh(_) => IconName::Server,
             RemoteConnectionOptions::Wsl(_) => IconName::Linux,
             RemoteConnectionOptions::Docker(_) => IconName::Box,
+            RemoteConnectionOptions::Command(_) => IconName::Terminal,
             #[cfg(any(test, feature = "test-support"))]
             RemoteConnectionOptions::Mock(_) => IconName::Server,
         },
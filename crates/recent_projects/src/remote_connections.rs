@@ -17,7 +17,10 @@ use remote::{
     SshConnectionOptions,
 };
 pub use settings::SshConnection;
-use settings::{DevContainerConnection, ExtendingVec, RegisterSetting, Settings, WslConnection};
+use settings::{
+    CommandConnection, DevContainerConnection, ExtendingVec, RegisterSetting, Settings,
+    WslConnection,
+};
 use util::paths::PathWithPosition;
 use workspace::{
     AppState, MultiWorkspace, OpenOptions, SerializedWorkspaceLocation, Workspace,
@@ -33,6 +36,7 @@ pub use remote_connection::{
 pub struct RemoteSettings {
     pub ssh_connections: ExtendingVec<SshConnection>,
     pub wsl_connections: ExtendingVec<WslConnection>,
+    pub command_connections: ExtendingVec<CommandConnection>,
     /// Whether to read ~/.ssh/config for ssh connection sources.
     pub read_ssh_config: bool,
 }
@@ -46,6 +50,10 @@ impl RemoteSettings {
         self.wsl_connections.clone().0.into_iter()
     }
 
+    pub fn command_connections(&self) -> impl Iterator<Item = CommandConnection> + use<> {
+        self.command_connections.clone().0.into_iter()
+    }
+
     pub fn fill_connection_options_from_settings(&self, options: &mut SshConnectionOptions) {
         for conn in self.ssh_connections() {
             if conn.host == options.host.to_string()
@@ -83,6 +91,7 @@ pub enum Connection {
     Ssh(SshConnection),
     Wsl(WslConnection),
     DevContainer(DevContainerConnection),
+    Command(CommandConnection),
 }
 
 impl From<Connection> for RemoteConnectionOptions {
@@ -97,8 +106,10 @@ impl From<Connection> for RemoteConnectionOptions {
                     container_id: conn.container_id,
                     upload_binary_over_docker_exec: false,
                     use_podman: conn.use_podman,
+                    port_forwards: conn.port_forwards,
                 })
             }
+            Connection::Command(conn) => RemoteConnectionOptions::Command(conn.into()),
         }
     }
 }
@@ -115,12 +126,23 @@ impl From<WslConnection> for Connection {
     }
 }
 
+impl From<CommandConnection> for Connection {
+    fn from(val: CommandConnection) -> Self {
+        Connection::Command(val)
+    }
+}
+
 impl Settings for RemoteSettings {
     fn from_settings(content: &settings::SettingsContent) -> Self {
         let remote = &content.remote;
         Self {
             ssh_connections: remote.ssh_connections.clone().unwrap_or_default().into(),
             wsl_connections: remote.wsl_connections.clone().unwrap_or_default().into(),
+            command_connections: remote
+                .command_connections
+                .clone()
+                .unwrap_or_default()
+                .into(),
             read_ssh_config: remote.read_ssh_config.unwrap(),
         }
     }
@@ -320,6 +342,9 @@ pub async fn open_remote_project(
                                 RemoteConnectionOptions::Docker(_) => {
                                     "Failed to connect to Dev Container"
                                 }
+                                RemoteConnectionOptions::Command(_) => {
+                                    "Failed to connect with command"
+                                }
                                 #[cfg(any(test, feature = "test-support"))]
                                 RemoteConnectionOptions::Mock(_) => {
                                     "Failed to connect to mock server"
@@ -381,6 +406,9 @@ pub async fn open_remote_project(
                                 RemoteConnectionOptions::Docker(_) => {
                                     "Failed to connect to Dev Container"
                                 }
+                                RemoteConnectionOptions::Command(_) => {
+                                    "Failed to connect with command"
+                                }
                                 #[cfg(any(test, feature = "test-support"))]
                                 RemoteConnectionOptions::Mock(_) => {
                                     "Failed to connect to mock server"
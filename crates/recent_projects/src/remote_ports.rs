@@ -0,0 +1,447 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use gpui::{
+    Action, AnyElement, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    Render, Task, WeakEntity, Window, actions,
+};
+use picker::{Picker, PickerDelegate};
+use remote::{ForwardedPort, RemoteClient};
+use ui::{KeyBinding, ListItem, ListItemSpacing, prelude::*};
+use workspace::{ModalView, Workspace};
+
+actions!(
+    remote_ports,
+    [
+        /// Shows the ports forwarded from the remote machine.
+        Toggle
+    ]
+);
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+pub(crate) fn register(
+    workspace: &mut Workspace,
+    _: Option<&mut Window>,
+    _: &mut Context<Workspace>,
+) {
+    workspace.register_action(|workspace, _: &Toggle, window, cx| {
+        let Some(remote_client) = workspace.project().read(cx).remote_client() else {
+            return;
+        };
+        if !remote_client.read(cx).supports_port_forwarding() {
+            let error = anyhow::anyhow!("This remote connection does not support forwarding ports");
+            workspace.show_error(&error, cx);
+            return;
+        }
+        let weak_workspace = cx.entity().downgrade();
+        workspace.toggle_modal(window, cx, |window, cx| {
+            RemotePorts::new(remote_client, weak_workspace, window, cx)
+        });
+    });
+}
+
+pub(crate) struct RemotePorts {
+    picker: Entity<Picker<RemotePortsDelegate>>,
+    _refresh: Task<()>,
+}
+
+impl RemotePorts {
+    fn new(
+        remote_client: Entity<RemoteClient>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = RemotePortsDelegate {
+            remote_client,
+            workspace,
+            entries: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+
+        // Ports start and stop listening on their own, so keep the list current while it's open.
+        let _refresh = cx.spawn_in(window, {
+            let picker = picker.downgrade();
+            async move |_, cx| {
+                loop {
+                    cx.background_executor().timer(REFRESH_INTERVAL).await;
+                    if picker
+                        .update_in(cx, |picker, window, cx| picker.refresh(window, cx))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Self { picker, _refresh }
+    }
+}
+
+impl ModalView for RemotePorts {}
+
+impl EventEmitter<DismissEvent> for RemotePorts {}
+
+impl Focusable for RemotePorts {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for RemotePorts {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("RemotePorts")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum PortEntry {
+    Port {
+        remote_port: u16,
+        forwarded: Option<ForwardedPort>,
+        listening: bool,
+    },
+    /// A port typed into the query that isn't listed yet.
+    ForwardNew(u16),
+}
+
+impl PortEntry {
+    fn remote_port(&self) -> u16 {
+        match self {
+            PortEntry::Port { remote_port, .. } | PortEntry::ForwardNew(remote_port) => {
+                *remote_port
+            }
+        }
+    }
+}
+
+pub(crate) struct RemotePortsDelegate {
+    remote_client: Entity<RemoteClient>,
+    workspace: WeakEntity<Workspace>,
+    entries: Vec<PortEntry>,
+    matches: Vec<PortEntry>,
+    selected_index: usize,
+}
+
+impl RemotePortsDelegate {
+    fn load_entries(&mut self, cx: &App) {
+        let remote_client = self.remote_client.read(cx);
+        let listening_ports = remote_client.listening_ports();
+        let forwarded_ports = remote_client.forwarded_ports();
+
+        self.entries = port_entries(&listening_ports, forwarded_ports);
+    }
+
+    /// Forwards `remote_port` to the same local port, or to an unused one if it's taken.
+    fn forward(&self, remote_port: u16, cx: &App) -> Result<ForwardedPort> {
+        let remote_client = self.remote_client.read(cx);
+        remote_client
+            .forward_port(remote_port, "localhost".to_string(), remote_port)
+            .or_else(|_| remote_client.forward_port(0, "localhost".to_string(), remote_port))
+    }
+
+    fn show_error(&self, error: anyhow::Error, cx: &mut App) {
+        self.workspace
+            .update(cx, |workspace, cx| workspace.show_error(&error, cx))
+            .ok();
+    }
+}
+
+fn port_entries(listening_ports: &[u16], forwarded_ports: Vec<ForwardedPort>) -> Vec<PortEntry> {
+    let mut entries = forwarded_ports
+        .into_iter()
+        .map(|forwarded_port| PortEntry::Port {
+            remote_port: forwarded_port.remote_port,
+            listening: listening_ports.contains(&forwarded_port.remote_port),
+            forwarded: Some(forwarded_port),
+        })
+        .collect::<Vec<_>>();
+    for &remote_port in listening_ports {
+        if !entries
+            .iter()
+            .any(|entry| entry.remote_port() == remote_port)
+        {
+            entries.push(PortEntry::Port {
+                remote_port,
+                forwarded: None,
+                listening: true,
+            });
+        }
+    }
+    entries.sort_by_key(PortEntry::remote_port);
+    entries
+}
+
+fn filter_entries(entries: &[PortEntry], query: &str) -> Vec<PortEntry> {
+    let query = query.trim();
+    let mut matches = entries
+        .iter()
+        .filter(|entry| match entry {
+            PortEntry::Port {
+                remote_port,
+                forwarded,
+                ..
+            } => {
+                remote_port.to_string().contains(query)
+                    || forwarded
+                        .as_ref()
+                        .is_some_and(|port| port.local_port.to_string().contains(query))
+            }
+            PortEntry::ForwardNew(_) => false,
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if let Ok(port) = query.parse::<u16>()
+        && port != 0
+        && !entries.iter().any(|entry| entry.remote_port() == port)
+    {
+        matches.insert(0, PortEntry::ForwardNew(port));
+    }
+    matches
+}
+
+impl PickerDelegate for RemotePortsDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Filter ports or type a port to forward…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No ports are listening. Type a port number to forward it.".into())
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        self.load_entries(cx);
+        self.matches = filter_entries(&self.entries, &query);
+        self.selected_index = self
+            .selected_index
+            .min(self.matches.len().saturating_sub(1));
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index).cloned() else {
+            return;
+        };
+        match entry {
+            PortEntry::ForwardNew(remote_port) => {
+                if let Err(error) = self.forward(remote_port, cx) {
+                    self.show_error(error, cx);
+                }
+                cx.defer_in(window, |picker, window, cx| {
+                    picker.set_query("", window, cx);
+                });
+            }
+            PortEntry::Port {
+                forwarded: Some(forwarded_port),
+                ..
+            } if secondary => {
+                self.remote_client
+                    .read(cx)
+                    .stop_forwarding_port(forwarded_port.local_port);
+                cx.defer_in(window, |picker, window, cx| picker.refresh(window, cx));
+            }
+            PortEntry::Port {
+                forwarded,
+                remote_port,
+                ..
+            } => {
+                let forwarded_port = match forwarded {
+                    Some(forwarded_port) => forwarded_port,
+                    None => match self.forward(remote_port, cx) {
+                        Ok(forwarded_port) => forwarded_port,
+                        Err(error) => {
+                            self.show_error(error, cx);
+                            return;
+                        }
+                    },
+                };
+                cx.open_url(&forwarded_port.local_url());
+                cx.emit(DismissEvent);
+            }
+        }
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = self.matches.get(ix)?;
+        let (label, detail, icon) = match entry {
+            PortEntry::ForwardNew(remote_port) => {
+                (format!("Forward Port {remote_port}"), None, IconName::Plus)
+            }
+            PortEntry::Port {
+                remote_port,
+                forwarded,
+                listening,
+            } => {
+                let detail = match (forwarded, listening) {
+                    (Some(forwarded_port), true) => forwarded_port.local_url(),
+                    (Some(forwarded_port), false) => {
+                        format!("{} (not listening)", forwarded_port.local_url())
+                    }
+                    (None, _) => "Not forwarded".to_string(),
+                };
+                let icon = if forwarded.is_some() {
+                    IconName::ArrowUpRight
+                } else {
+                    IconName::Circle
+                };
+                (format!("Port {remote_port}"), Some(detail), icon)
+            }
+        };
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(Icon::new(icon).color(Color::Muted))
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_2()
+                        .justify_between()
+                        .child(Label::new(label))
+                        .children(detail.map(|detail| Label::new(detail).color(Color::Muted))),
+                ),
+        )
+    }
+
+    fn render_footer(&self, _: &mut Window, cx: &mut Context<Picker<Self>>) -> Option<AnyElement> {
+        let selected_is_forwarded = matches!(
+            self.matches.get(self.selected_index),
+            Some(PortEntry::Port {
+                forwarded: Some(_),
+                ..
+            })
+        );
+
+        Some(
+            h_flex()
+                .w_full()
+                .p_1p5()
+                .gap_1()
+                .justify_end()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .when(selected_is_forwarded, |this| {
+                    this.child(
+                        Button::new("stop-forwarding", "Stop Forwarding")
+                            .key_binding(KeyBinding::for_action(&menu::SecondaryConfirm, cx))
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(menu::SecondaryConfirm.boxed_clone(), cx)
+                            }),
+                    )
+                })
+                .child(
+                    Button::new("open-in-browser", "Open in Browser")
+                        .key_binding(KeyBinding::for_action(&menu::Confirm, cx))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                        }),
+                )
+                .into_any(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forwarded(local_port: u16, remote_port: u16) -> ForwardedPort {
+        ForwardedPort {
+            local_port,
+            remote_host: "localhost".to_string(),
+            remote_port,
+        }
+    }
+
+    #[test]
+    fn test_port_entries() {
+        let entries = port_entries(
+            &[8080, 3000],
+            vec![forwarded(3001, 3000), forwarded(9000, 9000)],
+        );
+        assert_eq!(
+            entries,
+            vec![
+                PortEntry::Port {
+                    remote_port: 3000,
+                    forwarded: Some(forwarded(3001, 3000)),
+                    listening: true,
+                },
+                PortEntry::Port {
+                    remote_port: 8080,
+                    forwarded: None,
+                    listening: true,
+                },
+                PortEntry::Port {
+                    remote_port: 9000,
+                    forwarded: Some(forwarded(9000, 9000)),
+                    listening: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filter_entries() {
+        let entries = port_entries(&[8080, 3000], vec![forwarded(3001, 3000)]);
+
+        assert_eq!(filter_entries(&entries, "").len(), 2);
+        assert_eq!(
+            filter_entries(&entries, "3001"),
+            vec![PortEntry::ForwardNew(3001), entries[0].clone()]
+        );
+        assert_eq!(filter_entries(&entries, "8080"), vec![entries[1].clone()]);
+        assert_eq!(
+            filter_entries(&entries, "5432"),
+            vec![PortEntry::ForwardNew(5432)]
+        );
+        assert_eq!(
+            filter_entries(&entries, "0"),
+            vec![entries[0].clone(), entries[1].clone()]
+        );
+    }
+}
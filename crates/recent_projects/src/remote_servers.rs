@@ -24,8 +24,8 @@ use paths::{global_ssh_config_file, user_ssh_config_file};
 use picker::{Picker, PickerDelegate};
 use project::{Fs, Project};
 use remote::{
-    RemoteClient, RemoteConnectionOptions, SshConnectionOptions, WslConnectionOptions,
-    remote_client::ConnectionIdentifier,
+    CommandConnectionOptions, RemoteClient, RemoteConnectionOptions, SshConnectionOptions,
+    WslConnectionOptions, remote_client::ConnectionIdentifier,
 };
 use settings::{
     RemoteProject, RemoteSettingsContent, Settings as _, SettingsStore, update_settings_file,
@@ -413,6 +413,10 @@ impl ProjectPicker {
                 connection_string: "".into(),
                 nickname: None,
             },
+            RemoteConnectionOptions::Command(connection) => ProjectPickerData::Ssh {
+                connection_string: connection.name.clone().into(),
+                nickname: None,
+            },
             #[cfg(any(test, feature = "test-support"))]
             RemoteConnectionOptions::Mock(options) => ProjectPickerData::Ssh {
                 connection_string: format!("mock-{}", options.id).into(),
@@ -481,6 +485,16 @@ impl ProjectPicker {
                                         server.projects.insert(RemoteProject { paths });
                                     };
                                 }
+                                ServerIndex::Command(index) => {
+                                    if let Some(server) = settings
+                                        .remote
+                                        .command_connections
+                                        .as_mut()
+                                        .and_then(|connections| connections.get_mut(index.0))
+                                    {
+                                        server.projects.insert(RemoteProject { paths });
+                                    };
+                                }
                             }
                         });
                     })
@@ -598,10 +612,20 @@ impl std::fmt::Display for WslServerIndex {
     }
 }
 
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct CommandServerIndex(usize);
+impl std::fmt::Display for CommandServerIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 enum ServerIndex {
     Ssh(SshServerIndex),
     Wsl(WslServerIndex),
+    Command(CommandServerIndex),
 }
 impl From<SshServerIndex> for ServerIndex {
     fn from(index: SshServerIndex) -> Self {
@@ -613,6 +637,11 @@ impl From<WslServerIndex> for ServerIndex {
         Self::Wsl(index)
     }
 }
+impl From<CommandServerIndex> for ServerIndex {
+    fn from(index: CommandServerIndex) -> Self {
+        Self::Command(index)
+    }
+}
 
 #[derive(Clone)]
 enum RemoteEntry {
@@ -707,7 +736,31 @@ impl DefaultState {
                 }
             });
 
-        let mut servers = ssh_servers.chain(wsl_servers).collect::<Vec<RemoteEntry>>();
+        let command_servers =
+            ssh_settings
+                .command_connections()
+                .enumerate()
+                .map(|(index, connection)| {
+                    let open_folder = NavigableEntry::new(&handle, cx);
+                    let configure = NavigableEntry::new(&handle, cx);
+                    let projects = connection
+                        .projects
+                        .iter()
+                        .map(|project| (NavigableEntry::new(&handle, cx), project.clone()))
+                        .collect();
+                    RemoteEntry::Project {
+                        open_folder,
+                        configure,
+                        projects,
+                        index: ServerIndex::Command(CommandServerIndex(index)),
+                        connection: connection.into(),
+                    }
+                });
+
+        let mut servers = ssh_servers
+            .chain(wsl_servers)
+            .chain(command_servers)
+            .collect::<Vec<RemoteEntry>>();
 
         if read_ssh_config {
             let mut extra_servers_from_config = ssh_config_servers.clone();
@@ -750,6 +803,11 @@ enum ViewServerOptionsState {
         server_index: WslServerIndex,
         entries: [NavigableEntry; 2],
     },
+    Command {
+        connection: CommandConnectionOptions,
+        server_index: CommandServerIndex,
+        entries: [NavigableEntry; 2],
+    },
 }
 
 impl ViewServerOptionsState {
@@ -757,6 +815,7 @@ impl ViewServerOptionsState {
         match self {
             Self::Ssh { entries, .. } => entries,
             Self::Wsl { entries, .. } => entries,
+            Self::Command { entries, .. } => entries,
         }
     }
 }
@@ -1124,6 +1183,13 @@ impl RemoteServerProjects {
                     entries: std::array::from_fn(|_| NavigableEntry::focusable(cx)),
                 }
             }
+            (ServerIndex::Command(server_index), RemoteConnectionOptions::Command(connection)) => {
+                ViewServerOptionsState::Command {
+                    connection,
+                    server_index,
+                    entries: std::array::from_fn(|_| NavigableEntry::focusable(cx)),
+                }
+            }
             _ => {
                 log::error!("server index and connection options mismatch");
                 self.mode = Mode::default_mode(&BTreeSet::default(), cx);
@@ -1336,6 +1402,9 @@ impl RemoteServerProjects {
             Connection::DevContainer(dev_container_options) => {
                 (dev_container_options.name.clone(), None, false)
             }
+            Connection::Command(command_connection) => {
+                (command_connection.name.clone(), None, false)
+            }
         };
         v_flex()
             .w_full()
@@ -1529,6 +1598,7 @@ impl RemoteServerProjects {
             match server_ix {
                 ServerIndex::Ssh(index) => format!("ssh-{index}"),
                 ServerIndex::Wsl(index) => format!("wsl-{index}"),
+                ServerIndex::Command(index) => format!("command-{index}"),
             }
         ));
         let container_element_id_base =
@@ -1676,6 +1746,9 @@ impl RemoteServerProjects {
             ServerIndex::Wsl(server) => {
                 self.delete_wsl_project(server, project, cx);
             }
+            ServerIndex::Command(server) => {
+                self.delete_command_project(server, project, cx);
+            }
         }
     }
 
@@ -1723,6 +1796,32 @@ impl RemoteServerProjects {
         });
     }
 
+    fn delete_command_project(
+        &mut self,
+        server: CommandServerIndex,
+        project: &RemoteProject,
+        cx: &mut Context<Self>,
+    ) {
+        let project = project.clone();
+        self.update_settings_file(cx, move |setting, _| {
+            if let Some(server) = setting
+                .command_connections
+                .as_mut()
+                .and_then(|connections| connections.get_mut(server.0))
+            {
+                server.projects.remove(&project);
+            }
+        });
+    }
+
+    fn delete_command_connection(&mut self, server: CommandServerIndex, cx: &mut Context<Self>) {
+        self.update_settings_file(cx, move |setting, _| {
+            if let Some(connections) = setting.command_connections.as_mut() {
+                connections.remove(server.0);
+            }
+        });
+    }
+
     fn add_ssh_server(
         &mut self,
         connection_options: remote::SshConnectionOptions,
@@ -2188,6 +2287,15 @@ impl RemoteServerProjects {
                     }
                     .render(window, cx)
                     .into_any_element(),
+                    ViewServerOptionsState::Command { connection, .. } => SshConnectionHeader {
+                        connection_string: connection.name.clone().into(),
+                        paths: Default::default(),
+                        nickname: None,
+                        is_wsl: false,
+                        is_devcontainer: false,
+                    }
+                    .render(window, cx)
+                    .into_any_element(),
                 })
                 .child(
                     v_flex()
@@ -2216,6 +2324,17 @@ impl RemoteServerProjects {
                                 window,
                                 cx,
                             )),
+                            ViewServerOptionsState::Command {
+                                connection,
+                                entries,
+                                server_index,
+                            } => this.child(self.render_edit_command(
+                                connection,
+                                *server_index,
+                                entries,
+                                window,
+                                cx,
+                            )),
                         })
                         .child(ListSeparator)
                         .child({
@@ -2324,6 +2443,73 @@ impl RemoteServerProjects {
         })
     }
 
+    fn render_edit_command(
+        &self,
+        connection: &CommandConnectionOptions,
+        index: CommandServerIndex,
+        entries: &[NavigableEntry],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let name = SharedString::new(connection.name.clone());
+
+        v_flex().child({
+            fn remove_command_connection(
+                remote_servers: Entity<RemoteServerProjects>,
+                index: CommandServerIndex,
+                name: SharedString,
+                window: &mut Window,
+                cx: &mut App,
+            ) {
+                let prompt_message = format!("Remove connection `{}`?", name);
+
+                let confirmation = window.prompt(
+                    PromptLevel::Warning,
+                    &prompt_message,
+                    None,
+                    &["Yes, remove it", "No, keep it"],
+                    cx,
+                );
+
+                cx.spawn(async move |cx| {
+                    if confirmation.await.ok() == Some(0) {
+                        remote_servers.update(cx, |this, cx| {
+                            this.delete_command_connection(index, cx);
+                        });
+                        remote_servers.update(cx, |this, cx| {
+                            this.mode = Mode::default_mode(&this.ssh_config_servers, cx);
+                            cx.notify();
+                        });
+                    }
+                    anyhow::Ok(())
+                })
+                .detach_and_log_err(cx);
+            }
+            div()
+                .id("command-options-remove-connection")
+                .track_focus(&entries[0].focus_handle)
+                .on_action(cx.listener({
+                    let name = name.clone();
+                    move |_, _: &menu::Confirm, window, cx| {
+                        remove_command_connection(cx.entity(), index, name.clone(), window, cx);
+                        cx.focus_self(window);
+                    }
+                }))
+                .child(
+                    ListItem::new("remove-connection")
+                        .toggle_state(entries[0].focus_handle.contains_focused(window, cx))
+                        .inset(true)
+                        .spacing(ui::ListItemSpacing::Sparse)
+                        .start_slot(Icon::new(IconName::Trash).color(Color::Error))
+                        .child(Label::new("Remove Connection").color(Color::Error))
+                        .on_click(cx.listener(move |_, _, window, cx| {
+                            remove_command_connection(cx.entity(), index, name.clone(), window, cx);
+                            cx.focus_self(window);
+                        })),
+                )
+        })
+    }
+
     fn render_edit_ssh(
         &self,
         connection: &SshConnectionOptions,
@@ -2559,7 +2745,18 @@ impl RemoteServerProjects {
                 _ => None,
             }));
 
-        if ssh_connections_changed || wsl_connections_changed {
+        let command_connections_changed = ssh_settings.command_connections.0.iter().ne(state
+            .servers
+            .iter()
+            .filter_map(|server| match server {
+                RemoteEntry::Project {
+                    connection: Connection::Command(connection),
+                    ..
+                } => Some(connection),
+                _ => None,
+            }));
+
+        if ssh_connections_changed || wsl_connections_changed || command_connections_changed {
             should_rebuild = true;
         };
 
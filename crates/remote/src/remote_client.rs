@@ -5,7 +5,9 @@ use crate::{
     protocol::MessageId,
     proxy::ProxyLaunchError,
     transport::{
+        command::{CommandConnectionOptions, CommandRemoteConnection},
         docker::{DockerConnectionOptions, DockerExecConnection},
+        port_forward::ForwardedPort,
         ssh::SshRemoteConnection,
         wsl::{WslConnectionOptions, WslRemoteConnection},
     },
@@ -951,6 +953,41 @@ impl RemoteClient {
         connection.build_forward_ports_command(forwards)
     }
 
+    pub fn supports_port_forwarding(&self) -> bool {
+        self.remote_connection()
+            .map_or(false, |connection| connection.supports_port_forwarding())
+    }
+
+    pub fn forward_port(
+        &self,
+        local_port: u16,
+        remote_host: String,
+        remote_port: u16,
+    ) -> Result<ForwardedPort> {
+        let Some(connection) = self.remote_connection() else {
+            return Err(anyhow!("no remote connection"));
+        };
+        connection.forward_port(local_port, remote_host, remote_port)
+    }
+
+    pub fn stop_forwarding_port(&self, local_port: u16) {
+        if let Some(connection) = self.remote_connection() {
+            connection.stop_forwarding_port(local_port);
+        }
+    }
+
+    pub fn forwarded_ports(&self) -> Vec<ForwardedPort> {
+        self.remote_connection()
+            .map(|connection| connection.forwarded_ports())
+            .unwrap_or_default()
+    }
+
+    pub fn listening_ports(&self) -> Vec<u16> {
+        self.remote_connection()
+            .map(|connection| connection.listening_ports())
+            .unwrap_or_default()
+    }
+
     pub fn upload_directory(
         &self,
         src_path: PathBuf,
@@ -1231,6 +1268,11 @@ impl ConnectionPool {
                                 .await
                                 .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
                         }
+                        RemoteConnectionOptions::Command(opts) => {
+                            CommandRemoteConnection::new(opts, delegate, cx)
+                                .await
+                                .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
+                        }
                         #[cfg(any(test, feature = "test-support"))]
                         RemoteConnectionOptions::Mock(opts) => match cx.update(|cx| {
                             cx.default_global::<crate::transport::mock::MockConnectionRegistry>()
@@ -1278,6 +1320,7 @@ pub enum RemoteConnectionOptions {
     Ssh(SshConnectionOptions),
     Wsl(WslConnectionOptions),
     Docker(DockerConnectionOptions),
+    Command(CommandConnectionOptions),
     #[cfg(any(test, feature = "test-support"))]
     Mock(crate::transport::mock::MockConnectionOptions),
 }
@@ -1294,6 +1337,7 @@ impl RemoteConnectionOptions {
                     opts.name.clone()
                 }
             }
+            RemoteConnectionOptions::Command(opts) => opts.name.clone(),
             #[cfg(any(test, feature = "test-support"))]
             RemoteConnectionOptions::Mock(opts) => format!("mock-{}", opts.id),
         }
@@ -1312,6 +1356,12 @@ impl From<WslConnectionOptions> for RemoteConnectionOptions {
     }
 }
 
+impl From<CommandConnectionOptions> for RemoteConnectionOptions {
+    fn from(opts: CommandConnectionOptions) -> Self {
+        RemoteConnectionOptions::Command(opts)
+    }
+}
+
 #[cfg(any(test, feature = "test-support"))]
 impl From<crate::transport::mock::MockConnectionOptions> for RemoteConnectionOptions {
     fn from(opts: crate::transport::mock::MockConnectionOptions) -> Self {
@@ -1364,6 +1414,30 @@ pub trait RemoteConnection: Send + Sync {
         &self,
         forwards: Vec<(u16, String, u16)>,
     ) -> Result<CommandTemplate>;
+    /// Whether ports can be forwarded on demand with [`RemoteConnection::forward_port`].
+    fn supports_port_forwarding(&self) -> bool {
+        false
+    }
+    /// Forwards `local_port` to `remote_host:remote_port`, picking an unused local port when
+    /// `local_port` is 0.
+    fn forward_port(
+        &self,
+        _local_port: u16,
+        _remote_host: String,
+        _remote_port: u16,
+    ) -> Result<ForwardedPort> {
+        Err(anyhow!(
+            "port forwarding is not supported for this connection"
+        ))
+    }
+    fn stop_forwarding_port(&self, _local_port: u16) {}
+    fn forwarded_ports(&self) -> Vec<ForwardedPort> {
+        Vec::new()
+    }
+    /// The TCP ports that were last seen listening on the remote machine.
+    fn listening_ports(&self) -> Vec<u16> {
+        Vec::new()
+    }
     fn connection_options(&self) -> RemoteConnectionOptions;
     fn path_style(&self) -> PathStyle;
     fn shell(&self) -> String;
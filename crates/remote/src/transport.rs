@@ -14,9 +14,11 @@ use gpui::{AppContext as _, AsyncApp, Task};
 use rpc::proto::Envelope;
 use util::command::Child;
 
+pub mod command;
 pub mod docker;
 #[cfg(any(test, feature = "test-support"))]
 pub mod mock;
+pub mod port_forward;
 pub mod ssh;
 pub mod wsl;
 
@@ -0,0 +1,713 @@
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::{
+    AsyncWriteExt as _,
+    channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender},
+};
+use gpui::{App, AppContext as _, AsyncApp, Task};
+use parking_lot::Mutex;
+use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
+use rpc::proto::Envelope;
+use semver::Version as SemanticVersion;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+use util::{
+    command::{Command, Stdio},
+    paths::{PathStyle, RemotePathBuf},
+    rel_path::RelPath,
+    shell::ShellKind,
+};
+
+use crate::{
+    RemoteClientDelegate, RemoteConnection, RemoteConnectionOptions, RemotePlatform,
+    remote_client::{CommandTemplate, Interactive},
+    transport::{parse_platform, parse_shell},
+};
+
+/// Connects to a remote environment by running a user-configured command, such as
+/// `kubectl exec -i my-pod --` or `distrobox enter my-box --`, with the command to run
+/// in the environment appended to it.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct CommandConnectionOptions {
+    pub name: String,
+    pub command: Vec<String>,
+    pub interactive_command: Option<Vec<String>>,
+    #[serde(default)]
+    pub upload_binary: bool,
+}
+
+impl From<settings::CommandConnection> for CommandConnectionOptions {
+    fn from(val: settings::CommandConnection) -> Self {
+        CommandConnectionOptions {
+            name: val.name,
+            command: val.command,
+            interactive_command: val.interactive_command,
+            upload_binary: val.upload_binary.unwrap_or_default(),
+        }
+    }
+}
+
+impl CommandConnectionOptions {
+    fn template(&self, interactive: Interactive) -> Result<(&String, &[String])> {
+        let template = match (interactive, &self.interactive_command) {
+            (Interactive::Yes, Some(interactive_command)) => interactive_command,
+            _ => &self.command,
+        };
+        template
+            .split_first()
+            .with_context(|| format!("connection {:?} has an empty command", self.name))
+    }
+
+    /// Builds a command that runs `args` in the remote environment.
+    fn command_for(&self, interactive: Interactive, args: &[String]) -> Result<Command> {
+        let (program, template_args) = self.template(interactive)?;
+        let mut command = util::command::new_command(program);
+        command.args(template_args).args(args);
+        Ok(command)
+    }
+
+    fn template_for(&self, interactive: Interactive, args: Vec<String>) -> Result<CommandTemplate> {
+        let (program, template_args) = self.template(interactive)?;
+        Ok(CommandTemplate {
+            program: program.clone(),
+            args: template_args.iter().cloned().chain(args).collect(),
+            env: Default::default(),
+        })
+    }
+}
+
+pub(crate) struct CommandRemoteConnection {
+    proxy_process: Mutex<Option<u32>>,
+    remote_dir_for_server: String,
+    remote_binary_relpath: Option<Arc<RelPath>>,
+    connection_options: CommandConnectionOptions,
+    remote_platform: Option<RemotePlatform>,
+    shell: String,
+}
+
+impl CommandRemoteConnection {
+    pub async fn new(
+        connection_options: CommandConnectionOptions,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        let mut this = Self {
+            proxy_process: Mutex::new(None),
+            remote_dir_for_server: "/".to_string(),
+            remote_binary_relpath: None,
+            connection_options,
+            remote_platform: None,
+            shell: "sh".to_owned(),
+        };
+        let (release_channel, version, commit) = cx.update(|cx| {
+            (
+                ReleaseChannel::global(cx),
+                AppVersion::global(cx),
+                AppCommitSha::try_global(cx),
+            )
+        });
+
+        delegate.set_status(Some("Connecting"), cx);
+        let uname = this.run_command("uname", &["-sm"]).await?;
+        this.remote_platform = Some(parse_platform(&uname)?);
+        log::info!("Remote platform discovered: {:?}", this.remote_platform);
+
+        let shell = this
+            .run_command("sh", &["-c", "echo $SHELL"])
+            .await
+            .unwrap_or_default();
+        this.shell = parse_shell(&shell, "sh");
+        log::info!("Remote shell discovered: {}", this.shell);
+
+        this.remote_dir_for_server = this
+            .run_command("sh", &["-c", "echo $HOME"])
+            .await?
+            .trim()
+            .to_string();
+
+        this.remote_binary_relpath = Some(
+            this.ensure_server_binary(&delegate, release_channel, version, commit, cx)
+                .await?,
+        );
+
+        Ok(this)
+    }
+
+    /// Runs `program` in the remote environment and returns its stdout.
+    async fn run_command(&self, program: &str, args: &[impl AsRef<str>]) -> Result<String> {
+        let args = std::iter::once(program.to_string())
+            .chain(args.iter().map(|arg| arg.as_ref().to_string()))
+            .collect::<Vec<_>>();
+        let mut command = self
+            .connection_options
+            .command_for(Interactive::No, &args)?;
+        let output = command.output().await?;
+        log::debug!("{:?}: {:?}", command, output);
+        anyhow::ensure!(
+            output.status.success(),
+            "failed to run command {command:?}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Runs a POSIX shell script in the server directory of the remote environment.
+    async fn run_script(&self, script: &str) -> Result<String> {
+        let dir = ShellKind::Posix
+            .try_quote(&self.remote_dir_for_server)
+            .context("shell quoting")?;
+        self.run_command("sh", &["-c", &format!("cd {dir} && {script}")])
+            .await
+    }
+
+    async fn ensure_server_binary(
+        &self,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        release_channel: ReleaseChannel,
+        version: SemanticVersion,
+        commit: Option<AppCommitSha>,
+        cx: &mut AsyncApp,
+    ) -> Result<Arc<RelPath>> {
+        let remote_platform = self
+            .remote_platform
+            .context("No remote platform defined; cannot proceed.")?;
+
+        let version_str = match release_channel {
+            ReleaseChannel::Nightly => {
+                let commit = commit.map(|s| s.full()).unwrap_or_default();
+                format!("{}-{}", version, commit)
+            }
+            ReleaseChannel::Dev => "build".to_string(),
+            _ => version.to_string(),
+        };
+        let binary_name = format!(
+            "zed-remote-server-{}-{}",
+            release_channel.dev_name(),
+            version_str
+        );
+        let dst_path =
+            paths::remote_server_dir_relative().join(RelPath::unix(&binary_name).unwrap());
+
+        let binary_exists_on_server = self
+            .run_script(&format!("{} version", self.quote_path(&dst_path)?))
+            .await
+            .is_ok();
+        #[cfg(any(debug_assertions, feature = "build-remote-server-binary"))]
+        if let Some(remote_server_path) = super::build_remote_server_from_source(
+            &remote_platform,
+            delegate.as_ref(),
+            binary_exists_on_server,
+            cx,
+        )
+        .await?
+        {
+            let tmp_path = paths::remote_server_dir_relative().join(
+                RelPath::unix(&format!(
+                    "download-{}-{}",
+                    std::process::id(),
+                    remote_server_path.file_name().unwrap().to_string_lossy()
+                ))
+                .unwrap(),
+            );
+            self.upload_local_server_binary(&remote_server_path, &tmp_path, delegate, cx)
+                .await?;
+            self.extract_server_binary(&dst_path, &tmp_path, delegate, cx)
+                .await?;
+            return Ok(dst_path);
+        }
+
+        if binary_exists_on_server {
+            return Ok(dst_path);
+        }
+
+        let wanted_version = cx.update(|cx| match release_channel {
+            ReleaseChannel::Nightly => Ok(None),
+            ReleaseChannel::Dev => {
+                anyhow::bail!(
+                    "ZED_BUILD_REMOTE_SERVER is not set and no remote server exists at ({:?})",
+                    dst_path
+                )
+            }
+            _ => Ok(Some(AppVersion::global(cx))),
+        })?;
+
+        let tmp_path_gz = paths::remote_server_dir_relative().join(
+            RelPath::unix(&format!(
+                "{}-download-{}.gz",
+                binary_name,
+                std::process::id()
+            ))
+            .unwrap(),
+        );
+        if !self.connection_options.upload_binary
+            && let Some(url) = delegate
+                .get_download_url(remote_platform, release_channel, wanted_version.clone(), cx)
+                .await?
+        {
+            match self
+                .download_binary_on_server(&url, &tmp_path_gz, delegate, cx)
+                .await
+            {
+                Ok(_) => {
+                    self.extract_server_binary(&dst_path, &tmp_path_gz, delegate, cx)
+                        .await
+                        .context("extracting server binary")?;
+                    return Ok(dst_path);
+                }
+                Err(e) => {
+                    log::error!(
+                        "Failed to download binary on server, attempting to download locally and then upload it the server: {e:#}",
+                    )
+                }
+            }
+        }
+
+        let src_path = delegate
+            .download_server_binary_locally(remote_platform, release_channel, wanted_version, cx)
+            .await
+            .context("downloading server binary locally")?;
+        self.upload_local_server_binary(&src_path, &tmp_path_gz, delegate, cx)
+            .await
+            .context("uploading server binary")?;
+        self.extract_server_binary(&dst_path, &tmp_path_gz, delegate, cx)
+            .await
+            .context("extracting server binary")?;
+        Ok(dst_path)
+    }
+
+    fn quote_path(&self, path: &RelPath) -> Result<String> {
+        let path = path.display(PathStyle::Posix);
+        Ok(ShellKind::Posix
+            .try_quote(&path)
+            .context("shell quoting")?
+            .into_owned())
+    }
+
+    async fn download_binary_on_server(
+        &self,
+        url: &str,
+        tmp_path_gz: &RelPath,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        delegate.set_status(Some("Downloading remote development server on host"), cx);
+
+        let url = ShellKind::Posix.try_quote(url).context("shell quoting")?;
+        let tmp_path = self.quote_path(tmp_path_gz)?;
+        let mut script = String::new();
+        if let Some(parent) = tmp_path_gz.parent() {
+            script.push_str(&format!("mkdir -p {} && ", self.quote_path(parent)?));
+        }
+        script.push_str(&format!(
+            "if command -v curl >/dev/null 2>&1; then curl -f -L {url} -o {tmp_path}; \
+             elif command -v wget >/dev/null 2>&1; then wget {url} -O {tmp_path}; \
+             else echo 'Neither curl nor wget is available' >&2; exit 1; fi"
+        ));
+        self.run_script(&script).await?;
+        Ok(())
+    }
+
+    async fn upload_local_server_binary(
+        &self,
+        src_path: &Path,
+        tmp_path: &RelPath,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        if let Some(parent) = tmp_path.parent() {
+            self.run_script(&format!("mkdir -p {}", self.quote_path(parent)?))
+                .await?;
+        }
+
+        let size = smol::fs::metadata(&src_path).await?.len();
+        let t0 = Instant::now();
+        delegate.set_status(Some("Uploading remote development server"), cx);
+        log::info!(
+            "uploading remote development server to {:?} ({}kb)",
+            tmp_path,
+            size / 1024
+        );
+        self.upload_file(src_path, tmp_path)
+            .await
+            .context("failed to upload server binary")?;
+        log::info!("uploaded remote development server in {:?}", t0.elapsed());
+        Ok(())
+    }
+
+    /// Uploads a file by piping its contents into `cat` in the remote environment.
+    async fn upload_file(&self, src_path: &Path, dest_path: &RelPath) -> Result<()> {
+        log::debug!("uploading file {:?} to {:?}", src_path, dest_path);
+        let dir = ShellKind::Posix
+            .try_quote(&self.remote_dir_for_server)
+            .context("shell quoting")?;
+        let script = format!("cd {dir} && cat > {}", self.quote_path(dest_path)?);
+        let mut command = self.connection_options.command_for(
+            Interactive::No,
+            &["sh".to_string(), "-c".to_string(), script],
+        )?;
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let contents = smol::fs::read(src_path).await?;
+        let mut child = command.spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(&contents).await?;
+            stdin.flush().await?;
+        }
+        let output = child.output().await?;
+        anyhow::ensure!(
+            output.status.success(),
+            "failed to upload {} over {:?}: {}",
+            src_path.display(),
+            self.connection_options.name,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(())
+    }
+
+    async fn extract_server_binary(
+        &self,
+        dst_path: &RelPath,
+        tmp_path: &RelPath,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        delegate.set_status(Some("Extracting remote development server"), cx);
+        let dst_path = self.quote_path(dst_path)?;
+        let orig_tmp_path = tmp_path.display(PathStyle::Posix);
+        let script = if let Some(tmp_path) = orig_tmp_path.strip_suffix(".gz") {
+            let orig_tmp_path = ShellKind::Posix
+                .try_quote(&orig_tmp_path)
+                .context("shell quoting")?;
+            let tmp_path = ShellKind::Posix
+                .try_quote(tmp_path)
+                .context("shell quoting")?;
+            format!("gunzip -f {orig_tmp_path} && chmod 755 {tmp_path} && mv {tmp_path} {dst_path}")
+        } else {
+            let tmp_path = self.quote_path(tmp_path)?;
+            format!("chmod 755 {tmp_path} && mv {tmp_path} {dst_path}")
+        };
+        self.run_script(&script).await?;
+        Ok(())
+    }
+
+    fn kill_inner(&self) -> Result<()> {
+        if let Some(pid) = self.proxy_process.lock().take() {
+            util::command::new_command("kill")
+                .arg(pid.to_string())
+                .spawn()
+                .context("Failed to kill process")?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds a shell script that runs `program` with `args` and `env` in `working_dir`.
+fn build_script(
+    program: &str,
+    args: &[String],
+    env: &HashMap<String, String>,
+    working_dir: Option<&str>,
+) -> Result<String> {
+    let shell_kind = ShellKind::Posix;
+    let mut script = String::new();
+    if let Some(working_dir) = working_dir {
+        let working_dir = if working_dir == "~" {
+            "\"$HOME\"".to_string()
+        } else if let Some(relative) = working_dir.strip_prefix("~/") {
+            let relative = shell_kind.try_quote(relative).context("shell quoting")?;
+            format!("\"$HOME\"/{relative}")
+        } else {
+            shell_kind
+                .try_quote(working_dir)
+                .context("shell quoting")?
+                .into_owned()
+        };
+        script.push_str(&format!("cd {working_dir} && "));
+    }
+    script.push_str("exec");
+    if !env.is_empty() {
+        script.push_str(" env");
+        let mut env = env.iter().collect::<Vec<_>>();
+        env.sort();
+        for (key, value) in env {
+            let assignment = format!("{key}={value}");
+            let assignment = shell_kind.try_quote(&assignment).context("shell quoting")?;
+            script.push(' ');
+            script.push_str(&assignment);
+        }
+    }
+    for arg in std::iter::once(program).chain(args.iter().map(String::as_str)) {
+        let arg = shell_kind.try_quote(arg).context("shell quoting")?;
+        script.push(' ');
+        script.push_str(&arg);
+    }
+    Ok(script)
+}
+
+#[async_trait(?Send)]
+impl RemoteConnection for CommandRemoteConnection {
+    fn has_wsl_interop(&self) -> bool {
+        false
+    }
+
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<i32>> {
+        if !self.has_been_killed()
+            && let Err(e) = self.kill_inner()
+        {
+            return Task::ready(Err(e));
+        }
+
+        delegate.set_status(Some("Starting proxy"), cx);
+
+        let Some(remote_binary_relpath) = self.remote_binary_relpath.clone() else {
+            return Task::ready(Err(anyhow!("Remote binary path not set")));
+        };
+
+        let mut env = HashMap::default();
+        for env_var in ["RUST_LOG", "RUST_BACKTRACE", "ZED_GENERATE_MINIDUMPS"] {
+            if let Ok(value) = std::env::var(env_var) {
+                env.insert(env_var.to_string(), value);
+            }
+        }
+        let mut proxy_args = vec![
+            "proxy".to_string(),
+            "--identifier".to_string(),
+            unique_identifier,
+        ];
+        if reconnect {
+            proxy_args.push("--reconnect".to_string());
+        }
+        let script = match build_script(
+            &format!("./{}", remote_binary_relpath.display(PathStyle::Posix)),
+            &proxy_args,
+            &env,
+            Some(&self.remote_dir_for_server),
+        ) {
+            Ok(script) => script,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        let mut command = match self.connection_options.command_for(
+            Interactive::No,
+            &["sh".to_string(), "-c".to_string(), script],
+        ) {
+            Ok(command) => command,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        command
+            .kill_on_drop(true)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let child = match command.spawn() {
+            Ok(child) => child,
+            Err(error) => {
+                return Task::ready(Err(anyhow!(
+                    "Failed to start remote server process: {error}"
+                )));
+            }
+        };
+        *self.proxy_process.lock() = Some(child.id());
+
+        cx.spawn(async move |cx| {
+            super::handle_rpc_messages_over_child_process_stdio(
+                child,
+                incoming_tx,
+                outgoing_rx,
+                connection_activity_tx,
+                cx,
+            )
+            .await
+            .and_then(|status| {
+                if status != 0 {
+                    anyhow::bail!("Remote server exited with status {status}");
+                }
+                Ok(0)
+            })
+        })
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: RemotePathBuf,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let dest_path = dest_path.to_string();
+        let connection_options = self.connection_options.clone();
+        cx.background_spawn(async move {
+            let dest_path = ShellKind::Posix
+                .try_quote(&dest_path)
+                .context("shell quoting")?
+                .into_owned();
+            let mut tar = util::command::new_command("tar");
+            tar.arg("-C")
+                .arg(&src_path)
+                .args(["-cf", "-", "."])
+                .stdout(Stdio::piped());
+            let archive = tar.output().await?;
+            anyhow::ensure!(
+                archive.status.success(),
+                "failed to archive {}: {}",
+                src_path.display(),
+                String::from_utf8_lossy(&archive.stderr)
+            );
+
+            let script = format!("mkdir -p {dest_path} && tar -C {dest_path} -xf -");
+            let mut command = connection_options.command_for(
+                Interactive::No,
+                &["sh".to_string(), "-c".to_string(), script],
+            )?;
+            command
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+            let mut child = command.spawn()?;
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(&archive.stdout).await?;
+                stdin.flush().await?;
+            }
+            let output = child.output().await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "failed to upload {}: {}",
+                src_path.display(),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            Ok(())
+        })
+    }
+
+    async fn kill(&self) -> Result<()> {
+        self.kill_inner()
+    }
+
+    fn has_been_killed(&self) -> bool {
+        self.proxy_process.lock().is_none()
+    }
+
+    fn build_command(
+        &self,
+        program: Option<String>,
+        args: &[String],
+        env: &HashMap<String, String>,
+        working_dir: Option<String>,
+        _port_forward: Option<(u16, String, u16)>,
+        interactive: Interactive,
+    ) -> Result<CommandTemplate> {
+        let script = match program {
+            Some(program) => build_script(&program, args, env, working_dir.as_deref())?,
+            None => build_script(
+                &self.shell,
+                &["-l".to_string()],
+                env,
+                working_dir.as_deref(),
+            )?,
+        };
+        self.connection_options.template_for(
+            interactive,
+            vec!["sh".to_string(), "-c".to_string(), script],
+        )
+    }
+
+    fn build_forward_ports_command(
+        &self,
+        _forwards: Vec<(u16, String, u16)>,
+    ) -> Result<CommandTemplate> {
+        Err(anyhow!("Not currently supported for command connections"))
+    }
+
+    fn connection_options(&self) -> RemoteConnectionOptions {
+        RemoteConnectionOptions::Command(self.connection_options.clone())
+    }
+
+    fn path_style(&self) -> PathStyle {
+        PathStyle::Posix
+    }
+
+    fn shell(&self) -> String {
+        self.shell.clone()
+    }
+
+    fn default_system_shell(&self) -> String {
+        String::from("/bin/sh")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_script() {
+        assert_eq!(
+            build_script("ls", &["-la".to_string()], &HashMap::default(), None).unwrap(),
+            "exec ls -la"
+        );
+
+        let env = HashMap::from_iter([("GREETING".to_string(), "hello world".to_string())]);
+        assert_eq!(
+            build_script(
+                "echo",
+                &["$GREETING".to_string()],
+                &env,
+                Some("~/my project")
+            )
+            .unwrap(),
+            "cd \"$HOME\"/'my project' && exec env 'GREETING=hello world' echo '$GREETING'"
+        );
+    }
+
+    #[test]
+    fn test_template_for() {
+        let options = CommandConnectionOptions {
+            name: "pod".to_string(),
+            command: vec![
+                "kubectl".to_string(),
+                "exec".to_string(),
+                "-i".to_string(),
+                "pod".to_string(),
+                "--".to_string(),
+            ],
+            interactive_command: Some(vec![
+                "kubectl".to_string(),
+                "exec".to_string(),
+                "-it".to_string(),
+                "pod".to_string(),
+                "--".to_string(),
+            ]),
+            upload_binary: false,
+        };
+
+        let template = options
+            .template_for(Interactive::Yes, vec!["sh".to_string()])
+            .unwrap();
+        assert_eq!(template.program, "kubectl");
+        assert_eq!(template.args, ["exec", "-it", "pod", "--", "sh"]);
+
+        let template = options
+            .template_for(Interactive::No, vec!["sh".to_string()])
+            .unwrap();
+        assert_eq!(template.args, ["exec", "-i", "pod", "--", "sh"]);
+
+        let empty = CommandConnectionOptions::default();
+        assert!(empty.template_for(Interactive::No, Vec::new()).is_err());
+    }
+}
@@ -25,8 +25,12 @@ use rpc::proto::Envelope;
 
 use crate::{
     RemoteClientDelegate, RemoteConnection, RemoteConnectionOptions, RemoteOs, RemotePlatform,
+    SshPortForwardOption,
     remote_client::{CommandTemplate, Interactive},
-    transport::parse_platform,
+    transport::{
+        parse_platform,
+        port_forward::{ForwardedPort, PortForwarder},
+    },
 };
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
@@ -36,6 +40,7 @@ pub struct DockerConnectionOptions {
     pub remote_user: String,
     pub upload_binary_over_docker_exec: bool,
     pub use_podman: bool,
+    pub port_forwards: Option<Vec<SshPortForwardOption>>,
 }
 
 pub(crate) struct DockerExecConnection {
@@ -46,6 +51,8 @@ pub(crate) struct DockerExecConnection {
     remote_platform: Option<RemotePlatform>,
     path_style: Option<PathStyle>,
     shell: String,
+    port_forwarder: Option<Arc<PortForwarder>>,
+    port_watcher: Mutex<Option<Task<()>>>,
 }
 
 impl DockerExecConnection {
@@ -62,6 +69,8 @@ impl DockerExecConnection {
             remote_platform: None,
             path_style: None,
             shell: "sh".to_owned(),
+            port_forwarder: None,
+            port_watcher: Mutex::new(None),
         };
         let (release_channel, version, commit) = cx.update(|cx| {
             (
@@ -97,9 +106,82 @@ impl DockerExecConnection {
             .await?,
         );
 
+        this.start_port_forwarding(cx);
+
         Ok(this)
     }
 
+    /// Docker can't publish ports on a running container, so ports are forwarded by
+    /// relaying each local connection through `docker exec` and the remote server binary.
+    fn start_port_forwarding(&mut self, cx: &AsyncApp) {
+        let Some(remote_binary_relpath) = self.remote_binary_relpath.clone() else {
+            return;
+        };
+        let docker_cli = self.docker_cli().to_string();
+        let exec_args = vec![
+            "exec".to_string(),
+            "-u".to_string(),
+            self.connection_options.remote_user.clone(),
+            "-w".to_string(),
+            self.remote_dir_for_server.clone(),
+            "-i".to_string(),
+            self.connection_options.container_id.clone(),
+        ];
+        let remote_binary = remote_binary_relpath
+            .display(self.path_style())
+            .into_owned();
+
+        let relay = Arc::new({
+            let docker_cli = docker_cli.clone();
+            let exec_args = exec_args.clone();
+            move |host: &str, port: u16| {
+                let mut command = util::command::new_command(&docker_cli);
+                command.args(&exec_args).args([
+                    remote_binary.as_str(),
+                    "forward-port",
+                    "--host",
+                    host,
+                    "--port",
+                    port.to_string().as_str(),
+                ]);
+                command
+            }
+        });
+        let port_forwarder = Arc::new(PortForwarder::new(cx.background_executor().clone(), relay));
+
+        for port_forward in self.connection_options.port_forwards.iter().flatten() {
+            port_forwarder
+                .forward(
+                    port_forward.local_host.as_deref(),
+                    port_forward.local_port,
+                    port_forward
+                        .remote_host
+                        .clone()
+                        .unwrap_or_else(|| "localhost".to_string()),
+                    port_forward.remote_port,
+                )
+                .log_err();
+        }
+
+        let port_watcher = port_forwarder.watch_listening_ports(move || {
+            let mut command = util::command::new_command(&docker_cli);
+            command.args(&exec_args).args([
+                "sh",
+                "-c",
+                "cat /proc/net/tcp /proc/net/tcp6 2>/dev/null; true",
+            ]);
+            command
+        });
+        *self.port_watcher.lock() = Some(port_watcher);
+        self.port_forwarder = Some(port_forwarder);
+    }
+
+    fn port_forwarder(&self) -> Result<&Arc<PortForwarder>> {
+        self.port_forwarder
+            .as_ref()
+            .context("port forwarding has not started")
+    }
+
     fn docker_cli(&self) -> &str {
         if self.connection_options.use_podman {
             "podman"
@@ -713,6 +795,10 @@ impl RemoteConnection for DockerExecConnection {
     }
 
     async fn kill(&self) -> Result<()> {
+        self.port_watcher.lock().take();
+        if let Some(port_forwarder) = &self.port_forwarder {
+            port_forwarder.stop_all();
+        }
         self.kill_inner()
     }
 
@@ -726,9 +812,13 @@ impl RemoteConnection for DockerExecConnection {
         args: &[String],
         env: &HashMap<String, String>,
         working_dir: Option<String>,
-        _port_forward: Option<(u16, String, u16)>,
+        port_forward: Option<(u16, String, u16)>,
         interactive: Interactive,
     ) -> Result<CommandTemplate> {
+        if let Some((local_port, remote_host, remote_port)) = port_forward {
+            self.forward_port(local_port, remote_host, remote_port)?;
+        }
+
         let mut parsed_working_dir = None;
 
         let path_style = self.path_style();
@@ -791,9 +881,60 @@ impl RemoteConnection for DockerExecConnection {
 
     fn build_forward_ports_command(
         &self,
-        _forwards: Vec<(u16, String, u16)>,
+        forwards: Vec<(u16, String, u16)>,
     ) -> Result<CommandTemplate> {
-        Err(anyhow::anyhow!("Not currently supported for docker_exec"))
+        for (local_port, remote_host, remote_port) in forwards {
+            self.forward_port(local_port, remote_host, remote_port)?;
+        }
+
+        // The ports are forwarded by Zed itself for the rest of the session, so the
+        // command only has to stay running like the `ssh -N` used for SSH remotes.
+        Ok(CommandTemplate {
+            program: self.docker_cli().to_string(),
+            args: vec![
+                "exec".to_string(),
+                "-i".to_string(),
+                self.connection_options.container_id.clone(),
+                "tail".to_string(),
+                "-f".to_string(),
+                "/dev/null".to_string(),
+            ],
+            env: Default::default(),
+        })
+    }
+
+    fn supports_port_forwarding(&self) -> bool {
+        true
+    }
+
+    fn forward_port(
+        &self,
+        local_port: u16,
+        remote_host: String,
+        remote_port: u16,
+    ) -> Result<ForwardedPort> {
+        self.port_forwarder()?
+            .forward(None, local_port, remote_host, remote_port)
+    }
+
+    fn stop_forwarding_port(&self, local_port: u16) {
+        if let Some(port_forwarder) = &self.port_forwarder {
+            port_forwarder.stop(local_port);
+        }
+    }
+
+    fn forwarded_ports(&self) -> Vec<ForwardedPort> {
+        self.port_forwarder
+            .as_ref()
+            .map(|port_forwarder| port_forwarder.forwarded_ports())
+            .unwrap_or_default()
+    }
+
+    fn listening_ports(&self) -> Vec<u16> {
+        self.port_forwarder
+            .as_ref()
+            .map(|port_forwarder| port_forwarder.listening_ports())
+            .unwrap_or_default()
     }
 
     fn connection_options(&self) -> RemoteConnectionOptions {
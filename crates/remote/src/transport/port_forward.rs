@@ -0,0 +1,376 @@
+use anyhow::{Context as _, Result};
+use collections::{BTreeSet, HashMap};
+use futures::{AsyncWriteExt as _, future};
+use gpui::{BackgroundExecutor, Task};
+use parking_lot::Mutex;
+use smol::net::{TcpListener, TcpStream};
+use std::{pin::pin, sync::Arc, time::Duration};
+use util::command::{Command, Stdio};
+
+/// A local TCP port that is being forwarded to a port on the remote machine.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ForwardedPort {
+    pub local_port: u16,
+    pub remote_host: String,
+    pub remote_port: u16,
+}
+
+impl ForwardedPort {
+    pub fn local_url(&self) -> String {
+        format!("http://localhost:{}", self.local_port)
+    }
+}
+
+/// Builds the command that relays a single TCP connection to `host:port` on the
+/// remote machine over its stdin and stdout.
+pub(crate) type RelayCommand = Arc<dyn Fn(&str, u16) -> Command + Send + Sync>;
+
+/// How often the remote machine is checked for newly listening ports.
+const LISTENING_PORTS_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Forwards local TCP ports to a remote machine by running a relay command for each
+/// accepted connection. Used by transports that have no tunnel of their own, such as
+/// `docker exec`, which cannot publish ports on a container that is already running.
+pub(crate) struct PortForwarder {
+    executor: BackgroundExecutor,
+    relay: RelayCommand,
+    state: Mutex<PortForwarderState>,
+}
+
+#[derive(Default)]
+struct PortForwarderState {
+    forwards: HashMap<u16, (ForwardedPort, Task<()>)>,
+    listening_ports: BTreeSet<u16>,
+    /// Local ports that were forwarded because their remote port started listening.
+    automatic: BTreeSet<u16>,
+    /// Remote ports whose automatic forward was stopped by the user.
+    dismissed: BTreeSet<u16>,
+}
+
+impl PortForwarder {
+    pub fn new(executor: BackgroundExecutor, relay: RelayCommand) -> Self {
+        Self {
+            executor,
+            relay,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Starts listening on `local_host:local_port`, or on an unused port when
+    /// `local_port` is 0. Forwarding the same ports again is a no-op.
+    pub fn forward(
+        &self,
+        local_host: Option<&str>,
+        local_port: u16,
+        remote_host: String,
+        remote_port: u16,
+    ) -> Result<ForwardedPort> {
+        let mut state = self.state.lock();
+        state.dismissed.remove(&remote_port);
+        self.forward_locked(&mut state, local_host, local_port, remote_host, remote_port)
+    }
+
+    fn forward_locked(
+        &self,
+        state: &mut PortForwarderState,
+        local_host: Option<&str>,
+        local_port: u16,
+        remote_host: String,
+        remote_port: u16,
+    ) -> Result<ForwardedPort> {
+        if let Some((existing, _)) = state.forwards.get(&local_port)
+            && existing.remote_host == remote_host
+            && existing.remote_port == remote_port
+        {
+            return Ok(existing.clone());
+        }
+
+        let local_host = local_host.unwrap_or("127.0.0.1");
+        let listener = std::net::TcpListener::bind((local_host, local_port))
+            .with_context(|| format!("binding {local_host}:{local_port}"))?;
+        let local_port = listener.local_addr()?.port();
+        let listener = TcpListener::try_from(listener)?;
+
+        let forwarded_port = ForwardedPort {
+            local_port,
+            remote_host,
+            remote_port,
+        };
+        log::info!(
+            "forwarding localhost:{} to {}:{}",
+            local_port,
+            forwarded_port.remote_host,
+            remote_port
+        );
+        let task = self.executor.spawn(accept_connections(
+            listener,
+            self.executor.clone(),
+            self.relay.clone(),
+            forwarded_port.remote_host.clone(),
+            remote_port,
+        ));
+        state
+            .forwards
+            .insert(local_port, (forwarded_port.clone(), task));
+        Ok(forwarded_port)
+    }
+
+    pub fn stop(&self, local_port: u16) {
+        let mut state = self.state.lock();
+        if let Some((forwarded_port, _)) = state.forwards.remove(&local_port) {
+            log::info!("stopped forwarding localhost:{local_port}");
+            if state.automatic.remove(&local_port) {
+                state.dismissed.insert(forwarded_port.remote_port);
+            }
+        }
+    }
+
+    pub fn stop_all(&self) {
+        *self.state.lock() = PortForwarderState::default();
+    }
+
+    pub fn forwarded_ports(&self) -> Vec<ForwardedPort> {
+        let mut ports = self
+            .state
+            .lock()
+            .forwards
+            .values()
+            .map(|(port, _)| port.clone())
+            .collect::<Vec<_>>();
+        ports.sort_by_key(|port| (port.remote_port, port.local_port));
+        ports
+    }
+
+    pub fn listening_ports(&self) -> Vec<u16> {
+        self.state.lock().listening_ports.iter().copied().collect()
+    }
+
+    /// Forwards ports that started listening on the remote machine to the same local port,
+    /// or to an unused one if it is taken, and stops forwarding ports that stopped listening.
+    pub fn set_listening_ports(&self, listening_ports: BTreeSet<u16>) {
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        if state.listening_ports == listening_ports {
+            return;
+        }
+
+        let stale = state
+            .automatic
+            .iter()
+            .copied()
+            .filter(|local_port| {
+                state
+                    .forwards
+                    .get(local_port)
+                    .is_none_or(|(forwarded_port, _)| {
+                        !listening_ports.contains(&forwarded_port.remote_port)
+                    })
+            })
+            .collect::<Vec<_>>();
+        for local_port in stale {
+            state.automatic.remove(&local_port);
+            state.forwards.remove(&local_port);
+        }
+        state
+            .dismissed
+            .retain(|remote_port| listening_ports.contains(remote_port));
+
+        for &remote_port in &listening_ports {
+            let already_forwarded = state
+                .forwards
+                .values()
+                .any(|(forwarded_port, _)| forwarded_port.remote_port == remote_port);
+            if already_forwarded || state.dismissed.contains(&remote_port) {
+                continue;
+            }
+            let forwarded_port = self
+                .forward_locked(state, None, remote_port, "localhost".into(), remote_port)
+                .or_else(|_| self.forward_locked(state, None, 0, "localhost".into(), remote_port));
+            match forwarded_port {
+                Ok(forwarded_port) => {
+                    state.automatic.insert(forwarded_port.local_port);
+                }
+                Err(error) => log::warn!("failed to forward port {remote_port}: {error:#}"),
+            }
+        }
+        state.listening_ports = listening_ports;
+    }
+
+    /// Periodically runs `list_command`, which prints the contents of `/proc/net/tcp` on the
+    /// remote machine, and updates the forwarded ports with the ports that are listening.
+    pub fn watch_listening_ports(
+        self: &Arc<Self>,
+        list_command: impl Fn() -> Command + Send + 'static,
+    ) -> Task<()> {
+        let this = self.clone();
+        let executor = self.executor.clone();
+        self.executor.spawn(async move {
+            loop {
+                match list_command().output().await {
+                    Ok(output) if output.status.success() => {
+                        let output = String::from_utf8_lossy(&output.stdout);
+                        this.set_listening_ports(parse_listening_ports(&output));
+                    }
+                    Ok(output) => log::debug!(
+                        "failed to list listening ports: {}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ),
+                    Err(error) => log::debug!("failed to list listening ports: {error}"),
+                }
+                executor.timer(LISTENING_PORTS_POLL_INTERVAL).await;
+            }
+        })
+    }
+}
+
+async fn accept_connections(
+    listener: TcpListener,
+    executor: BackgroundExecutor,
+    relay: RelayCommand,
+    remote_host: String,
+    remote_port: u16,
+) {
+    // Connections are dropped, and their relays killed, when the forward is stopped.
+    let mut connections = Vec::new();
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(error) => {
+                log::error!("failed to accept forwarded connection: {error}");
+                return;
+            }
+        };
+        let command = relay(&remote_host, remote_port);
+        connections.retain(|task: &Task<()>| !task.is_ready());
+        connections.push(executor.spawn(async move {
+            if let Err(error) = relay_connection(stream, command).await {
+                log::warn!("forwarded connection to port {remote_port} failed: {error:#}");
+            }
+        }));
+    }
+}
+
+async fn relay_connection(stream: TcpStream, mut command: Command) -> Result<()> {
+    let mut child = command
+        .kill_on_drop(true)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("spawning port forwarding relay")?;
+    let mut stdin = child.stdin.take().context("relay has no stdin")?;
+    let stdout = child.stdout.take().context("relay has no stdout")?;
+
+    let mut read_half = stream.clone();
+    let mut write_half = stream;
+    let upload = async {
+        futures::io::copy(&mut read_half, &mut stdin).await?;
+        stdin.close().await
+    };
+    let download = async {
+        futures::io::copy(stdout, &mut write_half).await?;
+        write_half.close().await
+    };
+
+    // Either side closing ends the connection; dropping the child kills the relay.
+    match future::select(pin!(upload), pin!(download)).await {
+        future::Either::Left((result, _)) | future::Either::Right((result, _)) => {
+            result.map_err(Into::into)
+        }
+    }
+}
+
+/// Returns the TCP ports in the `LISTEN` state from the contents of
+/// `/proc/net/tcp` and `/proc/net/tcp6`.
+pub(crate) fn parse_listening_ports(proc_net_tcp: &str) -> BTreeSet<u16> {
+    const TCP_LISTEN: &str = "0A";
+
+    proc_net_tcp
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let local_address = fields.nth(1)?;
+            let state = fields.nth(1)?;
+            if state != TCP_LISTEN {
+                return None;
+            }
+            let (_, port) = local_address.rsplit_once(':')?;
+            u16::from_str_radix(port, 16).ok()
+        })
+        .filter(|port| *port != 0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    fn unused_port() -> u16 {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    #[gpui::test]
+    fn test_listening_ports_are_forwarded_automatically(cx: &mut TestAppContext) {
+        let forwarder = PortForwarder::new(
+            cx.executor(),
+            Arc::new(|_, _| util::command::new_command("true")),
+        );
+        let remote_port = unused_port();
+        let remote_ports = |forwarder: &PortForwarder| {
+            forwarder
+                .forwarded_ports()
+                .into_iter()
+                .map(|port| port.remote_port)
+                .collect::<Vec<_>>()
+        };
+
+        forwarder.set_listening_ports(BTreeSet::from_iter([remote_port]));
+        assert_eq!(remote_ports(&forwarder), vec![remote_port]);
+        assert_eq!(forwarder.listening_ports(), vec![remote_port]);
+
+        // Stopping an automatic forward keeps it stopped while the port is listening.
+        let local_port = forwarder.forwarded_ports()[0].local_port;
+        forwarder.stop(local_port);
+        forwarder.set_listening_ports(BTreeSet::from_iter([remote_port]));
+        assert_eq!(remote_ports(&forwarder), Vec::<u16>::new());
+
+        // Once the port stops listening, it is forwarded again the next time it listens.
+        forwarder.set_listening_ports(BTreeSet::default());
+        forwarder.set_listening_ports(BTreeSet::from_iter([remote_port]));
+        assert_eq!(remote_ports(&forwarder), vec![remote_port]);
+
+        forwarder.set_listening_ports(BTreeSet::default());
+        assert_eq!(remote_ports(&forwarder), Vec::<u16>::new());
+    }
+
+    #[test]
+    fn test_parse_listening_ports() {
+        let proc_net_tcp = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:0BB8 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 20716 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:1538 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 20717 1 0000000000000000 100 0 0 10 0
+   2: 0100007F:1538 0100007F:D2F0 01 00000000:00000000 00:00000000 00000000  1000        0 20718 1 0000000000000000 20 4 30 10 -1
+  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000000000000:0BB8 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 20719 1 0000000000000000 100 0 0 10 0
+   1: 00000000000000000000000001000000:1F90 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 20720 1 0000000000000000 100 0 0 10 0
+";
+        assert_eq!(
+            parse_listening_ports(proc_net_tcp),
+            BTreeSet::from_iter([3000, 5432, 8080])
+        );
+    }
+
+    #[test]
+    fn test_parse_listening_ports_ignores_malformed_lines() {
+        assert_eq!(parse_listening_ports(""), BTreeSet::default());
+        assert_eq!(
+            parse_listening_ports("garbage\n   0: 00000000:ZZZZ 00000000:0000 0A\n"),
+            BTreeSet::default()
+        );
+    }
+}
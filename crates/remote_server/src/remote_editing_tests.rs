@@ -28,6 +28,7 @@ use node_runtime::NodeRuntime;
 use project::{
     ProgressToken, Project,
     agent_server_store::AgentServerCommand,
+    offline_journal::OfflineBufferStatus,
     search::{SearchQuery, SearchResult},
 };
 use remote::RemoteClient;
@@ -1348,6 +1349,157 @@ async fn test_reconnect(cx: &mut TestAppContext, server_cx: &mut TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_remote_save_is_queued_while_disconnected(
+    cx: &mut TestAppContext,
+    server_cx: &mut TestAppContext,
+) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }"
+                }
+            },
+        }),
+    )
+    .await;
+
+    let (project, _headless) = init_test(&fs, cx, server_cx).await;
+
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+
+    let worktree_id = worktree.read_with(cx, |worktree, _| worktree.id());
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, rel_path("src/lib.rs")), cx)
+        })
+        .await
+        .unwrap();
+
+    let client = cx.read(|cx| project.read(cx).remote_client().unwrap());
+    client.update(cx, |client, cx| client.force_server_not_running(cx));
+    cx.run_until_parked();
+
+    buffer.update(cx, |buffer, cx| {
+        assert!(!buffer.read_only());
+        buffer.edit([(0..0, "// edited offline\n")], None, cx);
+    });
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+
+    let buffer_id = buffer.read_with(cx, |buffer, _| buffer.remote_id());
+    let offline_journal = project.read_with(cx, |project, _| project.offline_journal().unwrap());
+    offline_journal.read_with(cx, |offline_journal, _| {
+        assert!(offline_journal.is_offline());
+        assert_eq!(
+            offline_journal.status(buffer_id),
+            Some(OfflineBufferStatus::SaveQueued)
+        );
+        assert_eq!(offline_journal.queued_save_count(), 1);
+    });
+    assert!(buffer.read_with(cx, |buffer, _| buffer.is_dirty()));
+    assert_eq!(
+        fs.load(path!("/code/project1/src/lib.rs").as_ref())
+            .await
+            .unwrap(),
+        "fn one() -> usize { 1 }"
+    );
+}
+
+#[gpui::test]
+async fn test_queued_save_is_not_replayed_over_remote_changes(
+    cx: &mut TestAppContext,
+    server_cx: &mut TestAppContext,
+) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }"
+                }
+            },
+        }),
+    )
+    .await;
+
+    let (project, _headless) = init_test(&fs, cx, server_cx).await;
+
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+
+    let worktree_id = worktree.read_with(cx, |worktree, _| worktree.id());
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, rel_path("src/lib.rs")), cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "// edited offline\n")], None, cx);
+    });
+
+    // The file changes on the remote around the time the connection drops.
+    fs.save(
+        path!("/code/project1/src/lib.rs").as_ref(),
+        &"fn two() -> usize { 2 }".into(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    let client = cx.read(|cx| project.read(cx).remote_client().unwrap());
+    client.update(cx, |client, cx| client.force_heartbeat_timeout(0, cx));
+
+    let buffer_id = buffer.read_with(cx, |buffer, _| buffer.remote_id());
+    let offline_journal = project.read_with(cx, |project, _| project.offline_journal().unwrap());
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    offline_journal.read_with(cx, |offline_journal, _| {
+        assert!(offline_journal.is_offline());
+        assert_eq!(
+            offline_journal.status(buffer_id),
+            Some(OfflineBufferStatus::SaveQueued)
+        );
+    });
+
+    // Once reconnected, the remote changes are noticed instead of being overwritten.
+    cx.run_until_parked();
+    offline_journal.read_with(cx, |offline_journal, _| {
+        assert!(!offline_journal.is_offline());
+        assert_eq!(
+            offline_journal.status(buffer_id),
+            Some(OfflineBufferStatus::Conflict)
+        );
+    });
+    buffer.read_with(cx, |buffer, _| {
+        assert!(buffer.is_dirty());
+        assert!(buffer.has_conflict());
+    });
+    assert_eq!(
+        fs.load(path!("/code/project1/src/lib.rs").as_ref())
+            .await
+            .unwrap(),
+        "fn two() -> usize { 2 }"
+    );
+}
+
 #[gpui::test]
 async fn test_remote_root_rename(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
     let fs = FakeFs::new(server_cx.executor());
@@ -79,6 +79,14 @@ pub enum Commands {
         #[arg(long)]
         identifier: String,
     },
+    /// Connects to a TCP port on this machine and relays it over stdin and stdout.
+    /// Used to forward ports from remotes that have no tunnel of their own.
+    ForwardPort {
+        #[arg(long)]
+        host: String,
+        #[arg(long)]
+        port: u16,
+    },
     Version,
 }
 
@@ -104,6 +112,9 @@ pub fn run(command: Commands) -> anyhow::Result<()> {
             identifier,
             reconnect,
         } => execute_proxy(identifier, reconnect).context("running proxy on the remote server"),
+        Commands::ForwardPort { host, port } => {
+            execute_forward_port(&host, port).context("forwarding port on the remote server")
+        }
         Commands::Version => {
             let release_channel = *RELEASE_CHANNEL;
             match release_channel {
@@ -707,6 +718,32 @@ impl ExecuteProxyError {
     }
 }
 
+fn execute_forward_port(host: &str, port: u16) -> Result<()> {
+    use std::io::Read as _;
+    use std::net::{Shutdown, TcpStream};
+
+    let stream =
+        TcpStream::connect((host, port)).with_context(|| format!("connecting to {host}:{port}"))?;
+    let mut write_half = stream.try_clone()?;
+    std::thread::spawn(move || {
+        std::io::copy(&mut std::io::stdin().lock(), &mut write_half).ok();
+        write_half.shutdown(Shutdown::Write).ok();
+    });
+
+    let mut read_half = stream;
+    let mut stdout = std::io::stdout().lock();
+    let mut buffer = [0; 16 * 1024];
+    loop {
+        let len = read_half.read(&mut buffer)?;
+        if len == 0 {
+            break;
+        }
+        stdout.write_all(&buffer[..len])?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
 pub(crate) fn execute_proxy(
     identifier: String,
     is_reconnecting: bool,
@@ -0,0 +1,844 @@
+use crate::{
+    HighlightKey, SearchOption, SearchOptions, SearchSource, ToggleCaseSensitive, ToggleRegex,
+    ToggleWholeWord,
+    search_bar::{ActionButtonState, input_base_styles, render_action_button, render_text_input},
+};
+use collections::BTreeSet;
+use editor::{Editor, EditorEvent, MAX_TAB_TITLE_LEN, MultiBuffer, PathKey};
+use gpui::{
+    AnyEntity, App, Context, Entity, EntityId, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, ParentElement, Render, SharedString, Styled, Subscription,
+    Task, Window, actions, div,
+};
+use language::{Buffer, BufferSnapshot, Point};
+use menu::Confirm;
+use project::{Project, ProjectPath, search::SearchQuery};
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    sync::Arc,
+    time::Duration,
+};
+use ui::{Tooltip, prelude::*, utils::SearchInputWidth};
+use util::paths::PathMatcher;
+use workspace::{
+    ItemNavHistory, Workspace,
+    item::{Item, ItemEvent, SaveOptions},
+    searchable::{SearchableItem as _, SearchableItemHandle},
+};
+
+actions!(
+    filter_lines,
+    [
+        /// Opens a view that shows only the lines of the active buffer matching a pattern.
+        Deploy,
+        /// Toggles focus between the filter input and the filtered lines.
+        ToggleFocus,
+        /// Toggles between showing matching and non-matching lines.
+        ToggleInvert,
+        /// Shows one more line of context around each filtered line.
+        IncreaseContext,
+        /// Shows one less line of context around each filtered line.
+        DecreaseContext,
+    ]
+);
+
+const REFILTER_DEBOUNCE: Duration = Duration::from_millis(50);
+const MAX_CONTEXT_LINES: u32 = 10;
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(FilterLinesView::deploy);
+    })
+    .detach();
+}
+
+pub enum ViewEvent {
+    UpdateTab,
+    EditorEvent(EditorEvent),
+}
+
+/// Shows the lines of a single buffer that match (or don't match) a pattern.
+///
+/// The lines are excerpts of the source buffer, so edits made here are applied to the source
+/// buffer, and the view is refiltered whenever the source buffer changes.
+pub struct FilterLinesView {
+    source_buffer: Entity<Buffer>,
+    focus_handle: FocusHandle,
+    query_editor: Entity<Editor>,
+    excerpts: Entity<MultiBuffer>,
+    results_editor: Entity<Editor>,
+    search_options: SearchOptions,
+    inverted: bool,
+    context_lines: u32,
+    filtered_line_count: usize,
+    query_error: Option<String>,
+    pending_refilter: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl FilterLinesView {
+    fn deploy(
+        workspace: &mut Workspace,
+        _: &Deploy,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let (buffer, query) = editor.update(cx, |editor, cx| {
+            let multibuffer = editor.buffer().read(cx);
+            let buffer = multibuffer.as_singleton().or_else(|| {
+                let head = editor.selections.newest_anchor().head();
+                multibuffer.buffer_for_anchor(head, cx)
+            });
+            (buffer, editor.query_suggestion(window, cx))
+        });
+        let Some(buffer) = buffer else {
+            return;
+        };
+
+        let existing = workspace
+            .items_of_type::<FilterLinesView>(cx)
+            .find(|view| view.read(cx).source_buffer == buffer);
+        let view = if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+            existing
+        } else {
+            let project = workspace.project().clone();
+            let view = cx.new(|cx| FilterLinesView::new(project, buffer, window, cx));
+            workspace.add_item_to_active_pane(Box::new(view.clone()), None, true, window, cx);
+            view
+        };
+
+        view.update(cx, |view, cx| {
+            if !query.is_empty() {
+                view.set_query(&query, window, cx);
+            }
+            view.focus_query_editor(window, cx);
+        });
+    }
+
+    pub fn new(
+        project: Entity<Project>,
+        source_buffer: Entity<Buffer>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let query_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter lines…", window, cx);
+            editor
+        });
+        let capability = source_buffer.read(cx).capability();
+        let excerpts = cx.new(|_| MultiBuffer::new(capability));
+        let results_editor = cx.new(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(excerpts.clone(), Some(project.clone()), window, cx);
+            editor.set_searchable(false);
+            editor
+        });
+
+        let focus_handle = cx.focus_handle();
+        let subscriptions = vec![
+            cx.subscribe(&query_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.refilter(true, cx);
+                    cx.emit(ViewEvent::UpdateTab);
+                }
+            }),
+            cx.subscribe(&results_editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(ViewEvent::EditorEvent(event.clone()))
+            }),
+            cx.subscribe(
+                &source_buffer,
+                |this, _, event: &language::BufferEvent, cx| {
+                    if let language::BufferEvent::Edited = event {
+                        this.refilter(true, cx);
+                    }
+                },
+            ),
+            cx.on_focus(&focus_handle, window, |this, window, cx| {
+                this.focus_query_editor(window, cx);
+            }),
+        ];
+
+        Self {
+            source_buffer,
+            focus_handle,
+            query_editor,
+            excerpts,
+            results_editor,
+            search_options: SearchOptions::REGEX,
+            inverted: false,
+            context_lines: 0,
+            filtered_line_count: 0,
+            query_error: None,
+            pending_refilter: Task::ready(()),
+            _subscriptions: subscriptions,
+        }
+    }
+
+    pub fn set_query(&mut self, query: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.query_editor
+            .update(cx, |editor, cx| editor.set_text(query, window, cx));
+    }
+
+    fn focus_query_editor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.query_editor.update(cx, |editor, cx| {
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+        });
+        window.focus(&self.query_editor.focus_handle(cx), cx);
+    }
+
+    fn toggle_focus(&mut self, _: &ToggleFocus, window: &mut Window, cx: &mut Context<Self>) {
+        if self.query_editor.focus_handle(cx).is_focused(window) {
+            window.focus(&self.results_editor.focus_handle(cx), cx);
+        } else {
+            self.focus_query_editor(window, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.filtered_line_count > 0 {
+            window.focus(&self.results_editor.focus_handle(cx), cx);
+        }
+    }
+
+    fn toggle_search_option(&mut self, option: SearchOptions, cx: &mut Context<Self>) {
+        self.search_options.toggle(option);
+        self.refilter(false, cx);
+        cx.notify();
+    }
+
+    fn toggle_invert(&mut self, _: &ToggleInvert, _: &mut Window, cx: &mut Context<Self>) {
+        self.inverted = !self.inverted;
+        self.refilter(false, cx);
+        cx.emit(ViewEvent::UpdateTab);
+        cx.notify();
+    }
+
+    fn increase_context(&mut self, _: &IncreaseContext, _: &mut Window, cx: &mut Context<Self>) {
+        if self.context_lines < MAX_CONTEXT_LINES {
+            self.context_lines += 1;
+            self.refilter(false, cx);
+            cx.notify();
+        }
+    }
+
+    fn decrease_context(&mut self, _: &DecreaseContext, _: &mut Window, cx: &mut Context<Self>) {
+        if self.context_lines > 0 {
+            self.context_lines -= 1;
+            self.refilter(false, cx);
+            cx.notify();
+        }
+    }
+
+    fn build_query(&self, query: &str) -> anyhow::Result<SearchQuery> {
+        let whole_word = self.search_options.contains(SearchOptions::WHOLE_WORD);
+        let case_sensitive = self.search_options.contains(SearchOptions::CASE_SENSITIVE);
+        if self.search_options.contains(SearchOptions::REGEX) {
+            SearchQuery::regex(
+                query,
+                whole_word,
+                case_sensitive,
+                false,
+                false,
+                PathMatcher::default(),
+                PathMatcher::default(),
+                false,
+                None,
+            )
+        } else {
+            SearchQuery::text(
+                query,
+                whole_word,
+                case_sensitive,
+                false,
+                PathMatcher::default(),
+                PathMatcher::default(),
+                false,
+                None,
+            )
+        }
+    }
+
+    fn refilter(&mut self, debounce: bool, cx: &mut Context<Self>) {
+        let query = self.query_editor.read(cx).text(cx);
+        if query.is_empty() {
+            self.query_error = None;
+            self.pending_refilter = Task::ready(());
+            self.set_filtered_ranges(Vec::new(), 0, cx);
+            return;
+        }
+
+        let query = match self.build_query(&query) {
+            Ok(query) => query,
+            Err(error) => {
+                self.query_error = Some(error.to_string());
+                self.pending_refilter = Task::ready(());
+                cx.notify();
+                return;
+            }
+        };
+        self.query_error = None;
+
+        let snapshot = self.source_buffer.read(cx).snapshot();
+        let inverted = self.inverted;
+        self.pending_refilter = cx.spawn(async move |this, cx| {
+            if debounce {
+                cx.background_executor().timer(REFILTER_DEBOUNCE).await;
+            }
+            let (ranges, line_count) = cx
+                .background_spawn(async move {
+                    let matches = query.search(&snapshot, None).await;
+                    filtered_ranges(&snapshot, matches, inverted)
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.set_filtered_ranges(ranges, line_count, cx);
+            })
+            .ok();
+        });
+    }
+
+    fn set_filtered_ranges(
+        &mut self,
+        ranges: Vec<Range<Point>>,
+        line_count: usize,
+        cx: &mut Context<Self>,
+    ) {
+        let path_key = PathKey::for_buffer(&self.source_buffer, cx);
+        let match_ranges = self.excerpts.update(cx, |excerpts, cx| {
+            if ranges.is_empty() {
+                excerpts.remove_excerpts_for_path(path_key, cx);
+                Vec::new()
+            } else {
+                excerpts
+                    .set_excerpts_for_path(
+                        path_key,
+                        self.source_buffer.clone(),
+                        ranges,
+                        self.context_lines,
+                        cx,
+                    )
+                    .0
+            }
+        });
+
+        let inverted = self.inverted;
+        self.results_editor.update(cx, |editor, cx| {
+            if inverted {
+                editor.clear_background_highlights(HighlightKey::FilterLinesView, cx);
+            } else {
+                editor.highlight_background(
+                    HighlightKey::FilterLinesView,
+                    &match_ranges,
+                    |_, theme| theme.colors().search_match_background,
+                    cx,
+                );
+            }
+        });
+        self.filtered_line_count = line_count;
+        cx.notify();
+    }
+
+    fn render_query_row(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.query_editor.focus_handle(cx);
+        let input_width = SearchInputWidth::calc_width(window.viewport_size().width);
+        let query_border = if self.query_error.is_some() {
+            Color::Error.color(cx)
+        } else {
+            cx.theme().colors().border
+        };
+        let color_override = match (&self.query_error, self.filtered_line_count) {
+            (Some(_), _) => Some(Color::Error),
+            (None, 0) if !self.query_editor.read(cx).text(cx).is_empty() => Some(Color::Error),
+            _ => None,
+        };
+
+        let query_input = input_base_styles(query_border, |div| div.w(input_width))
+            .child(
+                div()
+                    .flex_1()
+                    .min_w(px(0.))
+                    .overflow_hidden()
+                    .child(render_text_input(&self.query_editor, color_override, cx)),
+            )
+            .child(
+                h_flex()
+                    .flex_none()
+                    .gap_1()
+                    .child(SearchOption::CaseSensitive.as_button(
+                        self.search_options,
+                        SearchSource::Buffer,
+                        focus_handle.clone(),
+                    ))
+                    .child(SearchOption::WholeWord.as_button(
+                        self.search_options,
+                        SearchSource::Buffer,
+                        focus_handle.clone(),
+                    ))
+                    .child(SearchOption::Regex.as_button(
+                        self.search_options,
+                        SearchSource::Buffer,
+                        focus_handle.clone(),
+                    )),
+            );
+
+        let line_label = if self.inverted {
+            "lines without matches"
+        } else {
+            "matching lines"
+        };
+        let context_label = match self.context_lines {
+            1 => "1 line of context".to_string(),
+            lines => format!("{lines} lines of context"),
+        };
+
+        h_flex()
+            .w_full()
+            .p_2()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .bg(cx.theme().colors().toolbar_background)
+            .child(query_input)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(render_action_button(
+                        "filter-lines",
+                        IconName::ArrowRightLeft,
+                        self.inverted.then_some(ActionButtonState::Toggled),
+                        "Show Lines Without Matches",
+                        &ToggleInvert,
+                        focus_handle.clone(),
+                    ))
+                    .child(render_action_button(
+                        "filter-lines",
+                        IconName::Dash,
+                        (self.context_lines == 0).then_some(ActionButtonState::Disabled),
+                        "Decrease Context Lines",
+                        &DecreaseContext,
+                        focus_handle.clone(),
+                    ))
+                    .child(render_action_button(
+                        "filter-lines",
+                        IconName::Plus,
+                        (self.context_lines == MAX_CONTEXT_LINES)
+                            .then_some(ActionButtonState::Disabled),
+                        "Increase Context Lines",
+                        &IncreaseContext,
+                        focus_handle,
+                    )),
+            )
+            .child(
+                Label::new(format!(
+                    "{} {line_label}, {context_label}",
+                    self.filtered_line_count
+                ))
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+            .when_some(self.query_error.clone(), |row, error| {
+                row.child(
+                    div()
+                        .id("filter-lines-query-error")
+                        .child(Icon::new(IconName::Warning).color(Color::Error))
+                        .tooltip(Tooltip::text(error)),
+                )
+            })
+    }
+}
+
+/// Returns the ranges of `snapshot` that the filter should show, along with the number of lines
+/// they cover. Matches are returned as-is so they can be highlighted; inverted filters return
+/// whole runs of lines that contain no match.
+fn filtered_ranges(
+    snapshot: &BufferSnapshot,
+    matches: Vec<Range<usize>>,
+    inverted: bool,
+) -> (Vec<Range<Point>>, usize) {
+    let mut matched_rows = BTreeSet::new();
+    let match_ranges = matches
+        .into_iter()
+        .map(|range| {
+            let start = snapshot.offset_to_point(range.start);
+            let end = snapshot.offset_to_point(range.end);
+            // A match that ends with a newline doesn't cover the following line.
+            let end_row = if end.column == 0 && end.row > start.row {
+                end.row - 1
+            } else {
+                end.row
+            };
+            matched_rows.extend(start.row..=end_row);
+            start..end
+        })
+        .collect::<Vec<_>>();
+
+    if !inverted {
+        return (match_ranges, matched_rows.len());
+    }
+
+    // Don't show the empty line after a trailing newline as an unmatched line.
+    let mut max_row = snapshot.max_point().row;
+    if max_row > 0 && snapshot.line_len(max_row) == 0 {
+        max_row -= 1;
+    }
+    let mut ranges: Vec<Range<Point>> = Vec::new();
+    let mut line_count = 0;
+    for row in (0..=max_row).filter(|row| !matched_rows.contains(row)) {
+        line_count += 1;
+        let end = Point::new(row, snapshot.line_len(row));
+        match ranges.last_mut() {
+            Some(last) if last.end.row + 1 == row => last.end = end,
+            _ => ranges.push(Point::new(row, 0)..end),
+        }
+    }
+    (ranges, line_count)
+}
+
+impl EventEmitter<ViewEvent> for FilterLinesView {}
+
+impl Focusable for FilterLinesView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for FilterLinesView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("FilterLines")
+            .size_full()
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::toggle_focus))
+            .on_action(cx.listener(Self::toggle_invert))
+            .on_action(cx.listener(Self::increase_context))
+            .on_action(cx.listener(Self::decrease_context))
+            .on_action(cx.listener(|this, _: &ToggleCaseSensitive, _, cx| {
+                this.toggle_search_option(SearchOptions::CASE_SENSITIVE, cx);
+            }))
+            .on_action(cx.listener(|this, _: &ToggleWholeWord, _, cx| {
+                this.toggle_search_option(SearchOptions::WHOLE_WORD, cx);
+            }))
+            .on_action(cx.listener(|this, _: &ToggleRegex, _, cx| {
+                this.toggle_search_option(SearchOptions::REGEX, cx);
+            }))
+            .child(
+                div()
+                    .on_action(cx.listener(Self::confirm))
+                    .child(self.render_query_row(window, cx)),
+            )
+            .child(div().flex_1().min_h_0().child(self.results_editor.clone()))
+    }
+}
+
+impl Item for FilterLinesView {
+    type Event = ViewEvent;
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let path = self.source_buffer.read(cx).file()?.full_path(cx);
+        Some(format!("Filter lines of {}", path.display()).into())
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.results_editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.results_editor.clone()))
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.results_editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Filter))
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        let query = self.query_editor.read(cx).text(cx).replace('\n', "");
+        if query.is_empty() {
+            let file_name = self
+                .source_buffer
+                .read(cx)
+                .file()
+                .map(|file| file.file_name(cx).to_string())
+                .unwrap_or_else(|| "untitled".to_string());
+            format!("Filter {file_name}").into()
+        } else {
+            let query = util::truncate_and_trailoff(&query, MAX_TAB_TITLE_LEN);
+            if self.inverted {
+                format!("Filter: !{query}").into()
+            } else {
+                format!("Filter: {query}").into()
+            }
+        }
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Filter Lines Opened")
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(EntityId, &dyn project::ProjectItem),
+    ) {
+        self.results_editor.for_each_project_item(cx, f)
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.results_editor.read(cx).is_dirty(cx)
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.results_editor.read(cx).has_conflict(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.results_editor
+            .update(cx, |editor, cx| editor.save(options, project, window, cx))
+    }
+
+    fn save_as(
+        &mut self,
+        _: Entity<Project>,
+        _: ProjectPath,
+        _window: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        unreachable!("save_as should not have been called")
+    }
+
+    fn reload(
+        &mut self,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.results_editor
+            .update(cx, |editor, cx| editor.reload(project, window, cx))
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.results_editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.results_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Arc<dyn Any + Send>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.results_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn to_item_events(event: &Self::Event, f: &mut dyn FnMut(ItemEvent)) {
+        match event {
+            ViewEvent::UpdateTab => f(ItemEvent::UpdateTab),
+            ViewEvent::EditorEvent(editor_event) => Editor::to_item_events(editor_event, f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use pretty_assertions::assert_eq;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+    use workspace::MultiWorkspace;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    async fn open_filter_lines(
+        text: &str,
+        cx: &mut TestAppContext,
+    ) -> (
+        Entity<FilterLinesView>,
+        Entity<Buffer>,
+        &mut VisualTestContext,
+    ) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "app.log": text }))
+            .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/app.log"), cx)
+            })
+            .await
+            .unwrap();
+        let (_, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let view = cx.update(|window, cx| {
+            cx.new(|cx| FilterLinesView::new(project.clone(), buffer.clone(), window, cx))
+        });
+        (view, buffer, cx)
+    }
+
+    fn settle(cx: &mut VisualTestContext) {
+        cx.executor().advance_clock(REFILTER_DEBOUNCE);
+        cx.run_until_parked();
+    }
+
+    fn results_text(view: &Entity<FilterLinesView>, cx: &mut VisualTestContext) -> String {
+        view.update(cx, |view, cx| view.excerpts.read(cx).snapshot(cx).text())
+    }
+
+    #[gpui::test]
+    async fn test_filter_lines(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (view, _, cx) = open_filter_lines(
+            "INFO start\nERROR disk full\nINFO retry\nWARN slow\nERROR disk full again\n",
+            cx,
+        )
+        .await;
+
+        view.update_in(cx, |view, window, cx| view.set_query("ERROR", window, cx));
+        settle(cx);
+        assert_eq!(
+            results_text(&view, cx),
+            "ERROR disk full\nERROR disk full again"
+        );
+        view.read_with(cx, |view, _| assert_eq!(view.filtered_line_count, 2));
+
+        view.update_in(cx, |view, window, cx| {
+            view.toggle_invert(&ToggleInvert, window, cx)
+        });
+        settle(cx);
+        assert_eq!(results_text(&view, cx), "INFO start\nINFO retry\nWARN slow");
+
+        view.update_in(cx, |view, window, cx| {
+            view.toggle_invert(&ToggleInvert, window, cx);
+            view.set_query("^WARN", window, cx);
+            view.increase_context(&IncreaseContext, window, cx);
+        });
+        settle(cx);
+        assert_eq!(
+            results_text(&view, cx),
+            "INFO retry\nWARN slow\nERROR disk full again"
+        );
+
+        view.update_in(cx, |view, window, cx| view.set_query("(", window, cx));
+        settle(cx);
+        view.read_with(cx, |view, _| assert!(view.query_error.is_some()));
+    }
+
+    #[gpui::test]
+    async fn test_filter_lines_follows_source_edits(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (view, buffer, cx) = open_filter_lines("keep one\ndrop two\nkeep three\n", cx).await;
+
+        view.update_in(cx, |view, window, cx| view.set_query("keep", window, cx));
+        settle(cx);
+        assert_eq!(results_text(&view, cx), "keep one\nkeep three");
+
+        // Edits made in the source buffer refilter the view.
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(9..13, "keep")], None, cx);
+        });
+        settle(cx);
+        assert_eq!(results_text(&view, cx), "keep one\nkeep two\nkeep three");
+
+        // Edits made in the view are applied to the source buffer.
+        view.update_in(cx, |view, window, cx| {
+            view.results_editor.update(cx, |editor, cx| {
+                editor.move_to_beginning(&editor::actions::MoveToBeginning, window, cx);
+                editor.insert("!", window, cx);
+            });
+        });
+        settle(cx);
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "!keep one\nkeep two\nkeep three\n"
+        );
+    }
+
+    #[gpui::test]
+    fn test_filtered_ranges(cx: &mut App) {
+        let buffer = cx.new(|cx| Buffer::local("a\nb\na\nb\nb\n", cx));
+        let snapshot = buffer.read(cx).snapshot();
+
+        let (ranges, line_count) = filtered_ranges(&snapshot, vec![0..1, 4..5], false);
+        assert_eq!(
+            ranges,
+            [
+                Point::new(0, 0)..Point::new(0, 1),
+                Point::new(2, 0)..Point::new(2, 1)
+            ]
+        );
+        assert_eq!(line_count, 2);
+
+        let (ranges, line_count) = filtered_ranges(&snapshot, vec![0..1, 4..5], true);
+        assert_eq!(
+            ranges,
+            [
+                Point::new(1, 0)..Point::new(1, 1),
+                Point::new(3, 0)..Point::new(4, 1)
+            ]
+        );
+        assert_eq!(line_count, 3);
+
+        // A match spanning a trailing newline only covers the lines it starts on.
+        let (_, line_count) = filtered_ranges(&snapshot, vec![0..2], true);
+        assert_eq!(line_count, 4);
+    }
+}
@@ -16,6 +16,7 @@ pub use search_status_button::SEARCH_ICON;
 use crate::project_search::ProjectSearchBar;
 
 pub mod buffer_search;
+pub mod filter_lines;
 pub mod project_search;
 pub(crate) mod search_bar;
 pub mod search_status_button;
@@ -23,6 +24,7 @@ pub mod search_status_button;
 pub fn init(cx: &mut App) {
     menu::init();
     buffer_search::init(cx);
+    filter_lines::init(cx);
     project_search::init(cx);
 }
 
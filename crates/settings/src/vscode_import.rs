@@ -177,6 +177,7 @@ impl VsCodeSettings {
             audio: None,
             auto_update: None,
             base_keymap: Some(BaseKeymapContent::VSCode),
+            bookmarks_panel: None,
             calls: None,
             collaboration_panel: None,
             debugger: None,
@@ -193,7 +194,9 @@ impl VsCodeSettings {
             helix_mode: None,
             image_viewer: None,
             journal: None,
+            keyboard_macros: None,
             language_models: None,
+            large_file_viewer: None,
             line_indicator_format: None,
             log: None,
             message_editor: None,
@@ -111,6 +111,11 @@ pub struct AgentSettingsContent {
     ///
     /// Default: false
     pub show_turn_stats: Option<bool>,
+    /// Whether to automatically compact agent threads when they approach the model's context window.
+    /// Older messages are replaced by a summary generated by the thread summary model.
+    ///
+    /// Default: true
+    pub auto_compact: Option<bool>,
     /// Per-tool permission rules for granular control over which tool actions
     /// require confirmation.
     ///
@@ -122,6 +127,12 @@ pub struct AgentSettingsContent {
     /// `always_confirm`) match against the tool's text input (command, path,
     /// URL, etc.).
     pub tool_permissions: Option<ToolPermissionsContent>,
+    /// Shell commands to run around agent events, e.g. to enforce repository
+    /// policy before a tool call or to run a formatter after the agent edits a file.
+    ///
+    /// Hooks run in the project's default shell, from the root of the first
+    /// worktree, and only apply to the Zed agent.
+    pub hooks: Option<AgentHooksContent>,
 }
 
 impl AgentSettingsContent {
@@ -541,6 +552,45 @@ pub enum ToolPermissionMode {
     Confirm,
 }
 
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentHooksContent {
+    /// Commands to run before a tool is called.
+    /// A command exiting with a non-zero status denies the tool call, and its
+    /// output is reported to the agent as the reason.
+    /// Default: []
+    pub pre_tool_call: Option<Vec<AgentHookContent>>,
+
+    /// Commands to run after a tool call succeeds, e.g. to format or lint the
+    /// files changed by `edit_file`.
+    /// When a command exits with a non-zero status, its output is appended to
+    /// the tool's result so the agent can address it.
+    /// Default: []
+    pub post_tool_call: Option<Vec<AgentHookContent>>,
+
+    /// Commands to run when the agent finishes its turn.
+    /// Default: []
+    pub on_stop: Option<Vec<AgentHookContent>>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentHookContent {
+    /// The shell command to run.
+    /// The event is described by the `ZED_AGENT_HOOK_EVENT`, `ZED_AGENT_TOOL_NAME`,
+    /// `ZED_AGENT_TOOL_INPUT_FILE` and `ZED_AGENT_TOOL_OUTPUT_FILE` environment variables.
+    #[serde(default)]
+    pub command: String,
+
+    /// Regex matched against the tool name (e.g. `^(edit_file|streaming_edit_file)$`).
+    /// Only applies to tool call hooks. When unset, the hook runs for every tool.
+    pub tool: Option<String>,
+
+    /// How long to wait for the command before stopping it, in milliseconds.
+    /// Default: 60000
+    pub timeout_ms: Option<u64>,
+}
+
 impl std::fmt::Display for ToolPermissionMode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -939,6 +939,8 @@ pub enum Formatter {
     },
     /// Files should be formatted using a code action executed by language servers.
     CodeAction(String),
+    /// Format code using a formatter provided by an extension.
+    Extension(String),
     /// Format code using a language server.
     #[serde(untagged)]
     LanguageServer(LanguageServerFormatterSpecifier),
@@ -1161,6 +1163,16 @@ mod test {
             ]))
         );
 
+        let raw = "{\"formatter\": [{\"extension\": \"shfmt\"}, \"language_server\"]}";
+        let settings: LanguageSettingsContent = serde_json::from_str(raw).unwrap();
+        assert_eq!(
+            settings.formatter,
+            Some(FormatterList::Vec(vec![
+                Formatter::Extension("shfmt".to_string()),
+                Formatter::LanguageServer(LanguageServerFormatterSpecifier::Current)
+            ]))
+        );
+
         assert_eq!(
             serde_json::to_string(&LanguageServerFormatterSpecifier::Current).unwrap(),
             "\"language_server\"",
@@ -125,6 +125,9 @@ pub struct SettingsContent {
     /// Default: VSCode
     pub base_keymap: Option<BaseKeymapContent>,
 
+    /// Configuration for the bookmarks panel.
+    pub bookmarks_panel: Option<PanelSettingsContent>,
+
     /// Configuration for the collab panel visual settings.
     pub collaboration_panel: Option<PanelSettingsContent>,
 
@@ -142,6 +145,9 @@ pub struct SettingsContent {
     /// The settings for the image viewer.
     pub image_viewer: Option<ImageViewerSettingsContent>,
 
+    /// The settings for viewing files that are too large to open in an editor.
+    pub large_file_viewer: Option<LargeFileViewerSettingsContent>,
+
     pub repl: Option<ReplSettingsContent>,
 
     /// Whether or not to enable Helix mode.
@@ -151,6 +157,11 @@ pub struct SettingsContent {
 
     pub journal: Option<JournalSettingsContent>,
 
+    /// Named keyboard macros, which can be bound in the keymap with `keyboard_macros::Run`.
+    ///
+    /// Example: {"keyboard_macros": {"quote_line": [{"action": "editor::MoveToBeginningOfLine"}, {"text": "\""}]}}
+    pub keyboard_macros: Option<HashMap<String, Vec<KeyboardMacroStepContent>>>,
+
     /// A map of log scopes to the desired log level.
     /// Useful for filtering out noisy logs or enabling more verbose logging.
     ///
@@ -772,6 +783,20 @@ pub enum FileFinderWidthContent {
     Full,
 }
 
+/// A single step of a keyboard macro.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, MergeFrom)]
+#[serde(untagged)]
+pub enum KeyboardMacroStepContent {
+    /// Dispatches an action, e.g. `{"action": "editor::SelectNext", "input": {"replace_newest": false}}`.
+    Action {
+        action: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        input: Option<serde_json::Value>,
+    },
+    /// Inserts text into the active editor, e.g. `{"text": "hello"}`.
+    Text { text: String },
+}
+
 #[with_fallible_options]
 #[derive(Clone, Default, Serialize, Deserialize, PartialEq, Debug, JsonSchema, MergeFrom)]
 pub struct VimSettingsContent {
@@ -1027,6 +1052,21 @@ pub struct ImageViewerSettingsContent {
     pub unit: Option<ImageFileSizeUnit>,
 }
 
+/// The settings for viewing files that are too large to open in an editor.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq)]
+pub struct LargeFileViewerSettingsContent {
+    /// Whether to open files above the size threshold in the large file viewer,
+    /// which pages the file from disk instead of loading it into an editor.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// The size, in megabytes, above which files are opened in the large file viewer.
+    ///
+    /// Default: 256
+    pub threshold_mb: Option<u64>,
+}
+
 #[with_fallible_options]
 #[derive(
     Clone,
@@ -1056,6 +1096,7 @@ pub struct RemoteSettingsContent {
     pub ssh_connections: Option<Vec<SshConnection>>,
     pub wsl_connections: Option<Vec<WslConnection>>,
     pub dev_container_connections: Option<Vec<DevContainerConnection>>,
+    pub command_connections: Option<Vec<CommandConnection>>,
     pub read_ssh_config: Option<bool>,
     pub use_podman: Option<bool>,
 }
@@ -1069,6 +1110,8 @@ pub struct DevContainerConnection {
     pub remote_user: String,
     pub container_id: String,
     pub use_podman: bool,
+    /// Ports to forward from the container, such as those in `forwardPorts`.
+    pub port_forwards: Option<Vec<SshPortForwardOption>>,
 }
 
 #[with_fallible_options]
@@ -1103,6 +1146,32 @@ pub struct WslConnection {
     pub projects: BTreeSet<RemoteProject>,
 }
 
+/// A remote environment that Zed reaches by running a command, such as
+/// `kubectl exec`, `podman exec` or `distrobox enter`.
+#[with_fallible_options]
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema, MergeFrom, Debug)]
+pub struct CommandConnection {
+    /// Name to use for this connection in UI.
+    pub name: String,
+    /// The program and arguments that run a command in the remote environment.
+    /// The command to run is appended to these, so the list usually ends with `--`.
+    ///
+    /// Example: `["kubectl", "exec", "-i", "my-pod", "--"]`
+    pub command: Vec<String>,
+    /// The program and arguments to use instead of `command` for interactive
+    /// sessions such as terminals, typically adding a flag that allocates a TTY.
+    ///
+    /// Example: `["kubectl", "exec", "-it", "my-pod", "--"]`
+    pub interactive_command: Option<Vec<String>>,
+    // By default Zed will download the binary inside the remote environment.
+    // If this is set to true, Zed will download the binary to your local machine,
+    // and then upload it over the command's stdin. Useful if the remote environment
+    // has limited outbound internet access.
+    pub upload_binary: Option<bool>,
+    #[serde(default)]
+    pub projects: BTreeSet<RemoteProject>,
+}
+
 #[with_fallible_options]
 #[derive(
     Clone, Debug, Default, Serialize, PartialEq, Eq, PartialOrd, Ord, Deserialize, JsonSchema,
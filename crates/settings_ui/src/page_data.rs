@@ -5299,6 +5299,70 @@ fn panels_page() -> SettingsPage {
         ]
     }
 
+    fn bookmarks_panel_section() -> [SettingsPageItem; 4] {
+        [
+            SettingsPageItem::SectionHeader("Bookmarks Panel"),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Bookmarks Panel Button",
+                description: "Show the bookmarks panel button in the status bar.",
+                field: Box::new(SettingField {
+                    json_path: Some("bookmarks_panel.button"),
+                    pick: |settings_content| {
+                        settings_content.bookmarks_panel.as_ref()?.button.as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .bookmarks_panel
+                            .get_or_insert_default()
+                            .button = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Bookmarks Panel Dock",
+                description: "Where to dock the bookmarks panel.",
+                field: Box::new(SettingField {
+                    json_path: Some("bookmarks_panel.dock"),
+                    pick: |settings_content| {
+                        settings_content.bookmarks_panel.as_ref()?.dock.as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .bookmarks_panel
+                            .get_or_insert_default()
+                            .dock = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Bookmarks Panel Default Width",
+                description: "Default width of the bookmarks panel in pixels.",
+                field: Box::new(SettingField {
+                    json_path: Some("bookmarks_panel.default_width"),
+                    pick: |settings_content| {
+                        settings_content
+                            .bookmarks_panel
+                            .as_ref()?
+                            .default_width
+                            .as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .bookmarks_panel
+                            .get_or_insert_default()
+                            .default_width = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+        ]
+    }
+
     fn collaboration_panel_section() -> [SettingsPageItem; 4] {
         [
             SettingsPageItem::SectionHeader("Collaboration Panel"),
@@ -5441,6 +5505,7 @@ fn panels_page() -> SettingsPage {
             outline_panel_section(),
             git_panel_section(),
             debugger_panel_section(),
+            bookmarks_panel_section(),
             notification_panel_section(),
             collaboration_panel_section(),
             agent_panel_section(),
@@ -8469,7 +8534,7 @@ fn language_settings_data() -> Box<[SettingsPageItem]> {
         ]
     }
 
-    fn global_only_miscellaneous_sub_section() -> [SettingsPageItem; 3] {
+    fn global_only_miscellaneous_sub_section() -> [SettingsPageItem; 5] {
         [
             SettingsPageItem::SettingItem(SettingItem {
                 title: "Image Viewer",
@@ -8489,6 +8554,48 @@ fn language_settings_data() -> Box<[SettingsPageItem]> {
                 metadata: None,
                 files: USER,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Large File Viewer",
+                description: "Whether to open files above the size threshold in the large file viewer.",
+                field: Box::new(SettingField {
+                    json_path: Some("large_file_viewer.enabled"),
+                    pick: |settings_content| {
+                        settings_content
+                            .large_file_viewer
+                            .as_ref()
+                            .and_then(|large_file_viewer| large_file_viewer.enabled.as_ref())
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .large_file_viewer
+                            .get_or_insert_default()
+                            .enabled = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Large File Threshold",
+                description: "The size, in megabytes, above which files are opened in the large file viewer.",
+                field: Box::new(SettingField {
+                    json_path: Some("large_file_viewer.threshold_mb"),
+                    pick: |settings_content| {
+                        settings_content
+                            .large_file_viewer
+                            .as_ref()
+                            .and_then(|large_file_viewer| large_file_viewer.threshold_mb.as_ref())
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .large_file_viewer
+                            .get_or_insert_default()
+                            .threshold_mb = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
             SettingsPageItem::SettingItem(SettingItem {
                 title: "Auto Replace Emoji Shortcode",
                 description: "Whether to automatically replace emoji shortcodes with emoji characters.",
@@ -8,7 +8,7 @@ use std::net::Ipv4Addr;
 use std::path::PathBuf;
 use util::{debug_panic, schemars::add_new_subschema};
 
-use crate::{TaskTemplate, adapter_schema::AdapterSchemas};
+use crate::{TaskInput, TaskTemplate, adapter_schema::AdapterSchemas};
 
 /// Represents the host information of the debug adapter
 #[derive(Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema, Clone, Debug)]
@@ -268,6 +268,10 @@ pub struct DebugScenario {
     /// A task to run prior to spawning the debuggee.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub build: Option<BuildTaskDefinition>,
+    /// Values to ask the user for before the session is started, referenced as `${input:<id>}`
+    /// in the scenario's label, configuration and build task.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<TaskInput>,
     /// The main arguments to be sent to the debug adapter
     #[serde(default, flatten)]
     pub config: serde_json::Value,
@@ -346,6 +350,8 @@ impl DebugTaskFile {
             build_task_value,
         );
 
+        let inputs_schema = generator.subschema_for::<Vec<TaskInput>>().to_value();
+
         let meta_schema = generator
             .settings()
             .meta_schema
@@ -379,6 +385,7 @@ impl DebugTaskFile {
                         "description": "The name of the debug configuration"
                     },
                     "build": build_task_definition_ref,
+                    "inputs": inputs_schema,
                     "tcp_connection": {
                         "type": "object",
                         "description": "Optional TCP connection information for connecting to an already running debug adapter",
@@ -0,0 +1,696 @@
+use anyhow::Context as _;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A problem matcher of a task template: either a name of a built-in matcher, or a custom one.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcherTemplate {
+    /// Name of a built-in matcher: `$rustc`, `$tsc`, `$gcc`, `$eslint` or `$pytest`.
+    BuiltIn(String),
+    /// A custom matcher.
+    Custom(ProblemMatcher),
+}
+
+impl ProblemMatcherTemplate {
+    /// Returns the matcher this template refers to, if it exists.
+    pub fn to_matcher(&self) -> Option<ProblemMatcher> {
+        match self {
+            Self::BuiltIn(name) => ProblemMatcher::built_in(name),
+            Self::Custom(matcher) => Some(matcher.clone()),
+        }
+    }
+}
+
+/// Turns lines of a task's output into [`Problem`]s, that are then shown as diagnostics.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemMatcher {
+    /// Name to show as the source of the diagnostics.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Patterns that match consecutive lines of the output, describing a single problem.
+    /// Only the last pattern may be `loop`ed.
+    pub pattern: Vec<ProblemPattern>,
+    /// Severity of the problems that have none captured by the pattern.
+    #[serde(default)]
+    pub severity: ProblemSeverity,
+    /// Directory to resolve relative file paths against, defaults to the task's working directory.
+    #[serde(default)]
+    pub base_dir: Option<String>,
+    /// Patterns that start and end a cycle of a background (watch mode) task.
+    /// Problems of a cycle replace the problems of the previous one once the cycle ends.
+    #[serde(default)]
+    pub background: Option<BackgroundPatterns>,
+}
+
+/// A regular expression that matches a line of the task's output, along with the indices of its capture groups.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// Regular expression to match the output line with.
+    pub regexp: String,
+    /// Capture group of the file path.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// Capture group of the 1-based line.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// Capture group of the 1-based column.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// Capture group of the 1-based end line.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// Capture group of the 1-based end column.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// Capture group of the severity: `error`, `warning`, `info`, `note` or `hint`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// Capture group of the error code.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// Capture group of the message.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Whether this pattern, being the last one, matches multiple lines, each producing a problem.
+    #[serde(default, rename = "loop")]
+    pub loop_: bool,
+}
+
+/// Patterns that delimit a compilation cycle in the output of a watch mode task.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct BackgroundPatterns {
+    /// Regular expression that matches the line starting a cycle.
+    pub begins_pattern: String,
+    /// Regular expression that matches the line ending a cycle.
+    pub ends_pattern: String,
+}
+
+/// How severe a [`Problem`] is.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    /// Shown as an error diagnostic.
+    #[default]
+    Error,
+    /// Shown as a warning diagnostic.
+    Warning,
+    /// Shown as an information diagnostic.
+    Info,
+    /// Shown as a hint diagnostic.
+    Hint,
+}
+
+impl ProblemSeverity {
+    pub(crate) fn parse(severity: &str) -> Option<Self> {
+        let severity = severity.to_ascii_lowercase();
+        if severity.starts_with("err") || severity == "fatal" {
+            Some(Self::Error)
+        } else if severity.starts_with("warn") {
+            Some(Self::Warning)
+        } else if severity.starts_with("info") || severity == "note" {
+            Some(Self::Info)
+        } else if severity == "hint" {
+            Some(Self::Hint)
+        } else {
+            None
+        }
+    }
+}
+
+/// A problem found in a task's output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// Path of the file, as printed by the task.
+    pub path: PathBuf,
+    /// 0-based line of the problem start.
+    pub line: u32,
+    /// 0-based column of the problem start.
+    pub column: u32,
+    /// 0-based line of the problem end.
+    pub end_line: Option<u32>,
+    /// 0-based column of the problem end.
+    pub end_column: Option<u32>,
+    /// Captured severity, or the matcher's default one.
+    pub severity: ProblemSeverity,
+    /// Error code, if captured.
+    pub code: Option<String>,
+    /// Message describing the problem.
+    pub message: String,
+    /// Source of the matcher that found the problem.
+    pub source: Option<String>,
+}
+
+impl ProblemMatcher {
+    /// Returns the built-in matcher with the given name, e.g. `$rustc`.
+    pub fn built_in(name: &str) -> Option<Self> {
+        let matcher = match name.strip_prefix('$').unwrap_or(name) {
+            "rustc" | "cargo" => Self {
+                source: Some("rustc".to_string()),
+                pattern: vec![
+                    ProblemPattern {
+                        regexp: r"^(warning|error)(?:\[(\w+)\])?: (.*)$".to_string(),
+                        severity: Some(1),
+                        code: Some(2),
+                        message: Some(3),
+                        ..ProblemPattern::default()
+                    },
+                    ProblemPattern {
+                        regexp: r"^\s*-->\s+(.*?):(\d+):(\d+)$".to_string(),
+                        file: Some(1),
+                        line: Some(2),
+                        column: Some(3),
+                        ..ProblemPattern::default()
+                    },
+                ],
+                severity: ProblemSeverity::Error,
+                base_dir: None,
+                background: None,
+            },
+            "tsc" => Self {
+                source: Some("tsc".to_string()),
+                pattern: vec![ProblemPattern {
+                    regexp: r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+TS(\d+)\s*:\s*(.*)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    code: Some(5),
+                    message: Some(6),
+                    ..ProblemPattern::default()
+                }],
+                severity: ProblemSeverity::Error,
+                base_dir: None,
+                background: None,
+            },
+            "tsc-watch" => Self {
+                background: Some(BackgroundPatterns {
+                    begins_pattern: r"^\s*(?:message TS6032:|\[?\D*.{1,2}[:.].{1,2}\D*(├\D*\d{1,2}\D+┤)?(?:\]| -)) (?:Starting compilation in watch mode|File change detected\. Starting incremental compilation)".to_string(),
+                    ends_pattern: r"^\s*(?:message TS6042:|\[?\D*.{1,2}[:.].{1,2}\D*(├\D*\d{1,2}\D+┤)?(?:\]| -)) (?:Compilation complete\.|Found \d+ errors?\.) Watching for file changes\.".to_string(),
+                }),
+                ..Self::built_in("tsc")?
+            },
+            "gcc" | "clang" => Self {
+                source: Some(name.trim_start_matches('$').to_string()),
+                pattern: vec![ProblemPattern {
+                    regexp: r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error|note):\s+(.*)$"
+                        .to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    message: Some(5),
+                    ..ProblemPattern::default()
+                }],
+                severity: ProblemSeverity::Error,
+                base_dir: None,
+                background: None,
+            },
+            "eslint" | "eslint-stylish" => Self {
+                source: Some("eslint".to_string()),
+                pattern: vec![
+                    ProblemPattern {
+                        regexp: r"^([^\s].*)$".to_string(),
+                        file: Some(1),
+                        ..ProblemPattern::default()
+                    },
+                    ProblemPattern {
+                        regexp: r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.*?)(?:\s\s+(.*))?$"
+                            .to_string(),
+                        line: Some(1),
+                        column: Some(2),
+                        severity: Some(3),
+                        message: Some(4),
+                        code: Some(5),
+                        loop_: true,
+                        ..ProblemPattern::default()
+                    },
+                ],
+                severity: ProblemSeverity::Error,
+                base_dir: None,
+                background: None,
+            },
+            "pytest" => Self {
+                source: Some("pytest".to_string()),
+                pattern: vec![ProblemPattern {
+                    regexp: r"^([^\s].*\.py):(\d+): (\w+(?:Error|Exception|Warning)\b.*|Failed\b.*)$"
+                        .to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    message: Some(3),
+                    ..ProblemPattern::default()
+                }],
+                severity: ProblemSeverity::Error,
+                base_dir: None,
+                background: None,
+            },
+            _ => return None,
+        };
+        Some(matcher)
+    }
+
+    /// Compiles the regular expressions of the matcher.
+    pub fn compile(&self) -> anyhow::Result<CompiledProblemMatcher> {
+        anyhow::ensure!(!self.pattern.is_empty(), "problem matcher has no patterns");
+        let patterns = self
+            .pattern
+            .iter()
+            .map(|pattern| {
+                let regex = Regex::new(&pattern.regexp)
+                    .with_context(|| format!("invalid problem pattern {:?}", pattern.regexp))?;
+                anyhow::Ok((regex, pattern.clone()))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let background = self
+            .background
+            .as_ref()
+            .map(|background| {
+                anyhow::Ok((
+                    Regex::new(&background.begins_pattern).with_context(|| {
+                        format!("invalid begins pattern {:?}", background.begins_pattern)
+                    })?,
+                    Regex::new(&background.ends_pattern).with_context(|| {
+                        format!("invalid ends pattern {:?}", background.ends_pattern)
+                    })?,
+                ))
+            })
+            .transpose()?;
+        Ok(CompiledProblemMatcher {
+            matcher: self.clone(),
+            patterns,
+            background,
+        })
+    }
+}
+
+/// A [`ProblemMatcher`] with its regular expressions compiled.
+pub struct CompiledProblemMatcher {
+    matcher: ProblemMatcher,
+    patterns: Vec<(Regex, ProblemPattern)>,
+    background: Option<(Regex, Regex)>,
+}
+
+/// The progress of a [`CompiledProblemMatcher`] through the output of a task, see
+/// [`CompiledProblemMatcher::match_lines`].
+#[derive(Debug, Default)]
+pub struct ProblemMatchState {
+    pending_lines: Vec<String>,
+    problems: Vec<Problem>,
+    finished_cycle_problems: Option<Vec<Problem>>,
+    cycle_in_progress: bool,
+}
+
+impl ProblemMatchState {
+    /// The problems found so far.
+    ///
+    /// For background matchers, these are the problems of the last finished cycle while a new one is in progress.
+    pub fn problems(&self) -> Vec<Problem> {
+        match &self.finished_cycle_problems {
+            Some(finished_cycle_problems) if self.cycle_in_progress => {
+                finished_cycle_problems.clone()
+            }
+            Some(finished_cycle_problems) => finished_cycle_problems
+                .iter()
+                .chain(&self.problems)
+                .cloned()
+                .collect(),
+            None => self.problems.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct ProblemFields {
+    file: Option<String>,
+    line: Option<String>,
+    column: Option<String>,
+    end_line: Option<String>,
+    end_column: Option<String>,
+    severity: Option<String>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl ProblemFields {
+    fn capture(&mut self, pattern: &ProblemPattern, captures: &Captures) {
+        let capture = |group: Option<usize>| {
+            group
+                .and_then(|group| captures.get(group))
+                .map(|capture| capture.as_str().to_string())
+                .filter(|capture| !capture.is_empty())
+        };
+        let fields = [
+            (&mut self.file, pattern.file),
+            (&mut self.line, pattern.line),
+            (&mut self.column, pattern.column),
+            (&mut self.end_line, pattern.end_line),
+            (&mut self.end_column, pattern.end_column),
+            (&mut self.severity, pattern.severity),
+            (&mut self.code, pattern.code),
+            (&mut self.message, pattern.message),
+        ];
+        for (field, group) in fields {
+            if let Some(value) = capture(group) {
+                *field = Some(value);
+            }
+        }
+    }
+
+    fn to_problem(&self, matcher: &ProblemMatcher) -> Option<Problem> {
+        let zero_based = |value: &Option<String>| {
+            value
+                .as_deref()
+                .and_then(|value| value.parse::<u32>().ok())
+                .map(|value| value.saturating_sub(1))
+        };
+        Some(Problem {
+            path: PathBuf::from(self.file.as_deref()?.trim()),
+            line: zero_based(&self.line).unwrap_or(0),
+            column: zero_based(&self.column).unwrap_or(0),
+            end_line: zero_based(&self.end_line),
+            end_column: zero_based(&self.end_column),
+            severity: self
+                .severity
+                .as_deref()
+                .and_then(ProblemSeverity::parse)
+                .unwrap_or(matcher.severity),
+            code: self.code.clone(),
+            message: self.message.clone().unwrap_or_default(),
+            source: matcher.source.clone(),
+        })
+    }
+}
+
+impl CompiledProblemMatcher {
+    /// Finds the problems in the output of a task.
+    ///
+    /// For background matchers, returns the problems of the last finished cycle while a new one is in progress.
+    pub fn match_output(&self, output: &str) -> Vec<Problem> {
+        let mut state = ProblemMatchState::default();
+        self.match_lines(&mut state, output.lines().map(str::to_string), true);
+        state.problems()
+    }
+
+    /// Finds the problems in the next lines of a task's output, adding them to `state`.
+    ///
+    /// Lines that may still be continued by a multi-line problem are kept in `state` until more output
+    /// arrives, or until the output is `finished`.
+    pub fn match_lines(
+        &self,
+        state: &mut ProblemMatchState,
+        lines: impl IntoIterator<Item = String>,
+        finished: bool,
+    ) {
+        state.pending_lines.extend(lines);
+        let lines = state
+            .pending_lines
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        let loops = self
+            .patterns
+            .last()
+            .is_some_and(|(_, pattern)| pattern.loop_);
+
+        let mut ix = 0;
+        while ix < lines.len() {
+            if let Some((begins, ends)) = &self.background {
+                if begins.is_match(lines[ix]) {
+                    // Problems printed outside of a cycle are kept until the new cycle finishes.
+                    if !state.problems.is_empty() {
+                        state.finished_cycle_problems = Some(std::mem::take(&mut state.problems));
+                    }
+                    state.cycle_in_progress = true;
+                    ix += 1;
+                    continue;
+                }
+                if ends.is_match(lines[ix]) {
+                    state.finished_cycle_problems = Some(std::mem::take(&mut state.problems));
+                    state.cycle_in_progress = false;
+                    ix += 1;
+                    continue;
+                }
+            }
+            if !finished && lines.len() - ix < self.patterns.len() {
+                break;
+            }
+            let mut problems = Vec::new();
+            let matched = self.match_problem(&lines[ix..], &mut problems);
+            // A looping pattern may go on matching the output still to come.
+            if !finished && loops && matched > 0 && ix + matched == lines.len() {
+                break;
+            }
+            state.problems.extend(problems);
+            ix += matched.max(1);
+        }
+        state.pending_lines.drain(..ix);
+    }
+
+    /// Matches a problem at the start of `lines`, returning the number of lines matched.
+    fn match_problem(&self, lines: &[&str], problems: &mut Vec<Problem>) -> usize {
+        let mut fields = ProblemFields::default();
+        let last_ix = self.patterns.len() - 1;
+        for (ix, (regex, pattern)) in self.patterns.iter().enumerate() {
+            let Some(captures) = lines.get(ix).and_then(|line| regex.captures(line)) else {
+                return 0;
+            };
+            if ix < last_ix {
+                fields.capture(pattern, &captures);
+                continue;
+            }
+
+            let mut problem_fields = fields.clone();
+            problem_fields.capture(pattern, &captures);
+            problems.extend(problem_fields.to_problem(&self.matcher));
+            if !pattern.loop_ {
+                return ix + 1;
+            }
+            let mut matched = ix + 1;
+            while let Some(captures) = lines.get(matched).and_then(|line| regex.captures(line)) {
+                let mut fields = fields.clone();
+                fields.capture(pattern, &captures);
+                problems.extend(fields.to_problem(&self.matcher));
+                matched += 1;
+            }
+            return matched;
+        }
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problems(matcher: &str, output: &str) -> Vec<(String, u32, u32, ProblemSeverity, String)> {
+        ProblemMatcher::built_in(matcher)
+            .unwrap()
+            .compile()
+            .unwrap()
+            .match_output(output)
+            .into_iter()
+            .map(|problem| {
+                (
+                    problem.path.to_string_lossy().into_owned(),
+                    problem.line,
+                    problem.column,
+                    problem.severity,
+                    problem.message,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rustc_matcher() {
+        let output = "   Compiling demo v0.1.0 (/tmp/demo)
+warning: unused variable: `x`
+ --> src/main.rs:2:9
+  |
+2 |     let x = 5;
+  |         ^ help: if this is intentional, prefix it with an underscore: `_x`
+
+error[E0308]: mismatched types
+  --> src/lib.rs:10:18
+   |
+error: could not compile `demo` (bin \"demo\") due to 1 previous error
+";
+        assert_eq!(
+            problems("$rustc", output),
+            vec![
+                (
+                    "src/main.rs".to_string(),
+                    1,
+                    8,
+                    ProblemSeverity::Warning,
+                    "unused variable: `x`".to_string()
+                ),
+                (
+                    "src/lib.rs".to_string(),
+                    9,
+                    17,
+                    ProblemSeverity::Error,
+                    "mismatched types".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_line_matchers() {
+        assert_eq!(
+            problems(
+                "$tsc",
+                "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'."
+            ),
+            vec![(
+                "src/index.ts".to_string(),
+                2,
+                6,
+                ProblemSeverity::Error,
+                "Type 'string' is not assignable to type 'number'.".to_string()
+            )]
+        );
+        assert_eq!(
+            problems(
+                "$gcc",
+                "main.c:4:5: warning: implicit declaration of function 'foo'\nmain.c: In function 'main':"
+            ),
+            vec![(
+                "main.c".to_string(),
+                3,
+                4,
+                ProblemSeverity::Warning,
+                "implicit declaration of function 'foo'".to_string()
+            )]
+        );
+        assert_eq!(
+            problems(
+                "$pytest",
+                "    assert 1 == 2\nE   assert 1 == 2\n\ntests/test_math.py:5: AssertionError"
+            ),
+            vec![(
+                "tests/test_math.py".to_string(),
+                4,
+                0,
+                ProblemSeverity::Error,
+                "AssertionError".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_looped_pattern() {
+        let output = "
+/project/src/a.js
+  1:10  error    'x' is defined but never used  no-unused-vars
+  3:1   warning  Unexpected console statement   no-console
+
+/project/src/b.js
+  7:3  error  Missing semicolon  semi
+
+✖ 3 problems (2 errors, 1 warning)
+";
+        assert_eq!(
+            problems("$eslint", output),
+            vec![
+                (
+                    "/project/src/a.js".to_string(),
+                    0,
+                    9,
+                    ProblemSeverity::Error,
+                    "'x' is defined but never used".to_string()
+                ),
+                (
+                    "/project/src/a.js".to_string(),
+                    2,
+                    0,
+                    ProblemSeverity::Warning,
+                    "Unexpected console statement".to_string()
+                ),
+                (
+                    "/project/src/b.js".to_string(),
+                    6,
+                    2,
+                    ProblemSeverity::Error,
+                    "Missing semicolon".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_background_matcher() {
+        let matcher = ProblemMatcher {
+            source: None,
+            pattern: vec![ProblemPattern {
+                regexp: r"^(.*):(\d+): (.*)$".to_string(),
+                file: Some(1),
+                line: Some(2),
+                message: Some(3),
+                ..ProblemPattern::default()
+            }],
+            severity: ProblemSeverity::Warning,
+            base_dir: None,
+            background: Some(BackgroundPatterns {
+                begins_pattern: "^Build started".to_string(),
+                ends_pattern: "^Build finished".to_string(),
+            }),
+        }
+        .compile()
+        .unwrap();
+        let messages = |output: &str| {
+            matcher
+                .match_output(output)
+                .into_iter()
+                .map(|problem| problem.message)
+                .collect::<Vec<_>>()
+        };
+
+        let first_cycle = "Build started\na.rs:1: first\nb.rs:2: second\n";
+        assert_eq!(messages(first_cycle), ["first", "second"]);
+
+        let first_cycle = format!("{first_cycle}Build finished\n");
+        assert_eq!(messages(&first_cycle), ["first", "second"]);
+
+        // Problems of a finished cycle are kept until the next one finishes.
+        let second_cycle = format!("{first_cycle}Build started\na.rs:1: third\n");
+        assert_eq!(messages(&second_cycle), ["first", "second"]);
+
+        let second_cycle = format!("{second_cycle}Build finished\n");
+        assert_eq!(messages(&second_cycle), ["third"]);
+    }
+
+    #[test]
+    fn test_matching_output_in_chunks() {
+        let output = "warning: unused variable: `x`
+ --> src/main.rs:2:9
+/project/src/a.js
+  1:10  error    'x' is defined but never used  no-unused-vars
+  3:1   warning  Unexpected console statement   no-console
+done
+";
+        for matcher in ["$rustc", "$eslint"] {
+            let matcher = ProblemMatcher::built_in(matcher)
+                .unwrap()
+                .compile()
+                .unwrap();
+            let expected = matcher.match_output(output);
+            assert!(!expected.is_empty());
+
+            // Feed the output one line at a time, splitting multi-line and looped problems
+            // across calls.
+            let mut state = ProblemMatchState::default();
+            for line in output.lines() {
+                matcher.match_lines(&mut state, [line.to_string()], false);
+            }
+            matcher.match_lines(&mut state, [], true);
+            assert_eq!(state.problems(), expected);
+        }
+    }
+}
@@ -2,8 +2,11 @@
 
 mod adapter_schema;
 mod debug_format;
+mod problem_matcher;
 mod serde_helpers;
 pub mod static_source;
+mod task_dependencies;
+mod task_input;
 mod task_template;
 mod vscode_debug_format;
 mod vscode_format;
@@ -22,9 +25,15 @@ pub use debug_format::{
     AttachRequest, BuildTaskDefinition, DebugRequest, DebugScenario, DebugTaskFile, LaunchRequest,
     Request, TcpArgumentsTemplate, ZedDebugConfig,
 };
+pub use problem_matcher::{
+    BackgroundPatterns, CompiledProblemMatcher, Problem, ProblemMatchState, ProblemMatcher,
+    ProblemMatcherTemplate, ProblemPattern, ProblemSeverity,
+};
+pub use task_dependencies::TaskDependencyTree;
+pub use task_input::{TaskInput, TaskInputType, substitute_input_values};
 pub use task_template::{
-    DebugArgsRequest, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
-    substitute_variables_in_map, substitute_variables_in_str,
+    DebugArgsRequest, DependsOrder, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
+    TaskTriggers, substitute_variables_in_map, substitute_variables_in_str,
 };
 pub use util::shell::{Shell, ShellKind};
 pub use util::shell_builder::ShellBuilder;
@@ -75,6 +84,11 @@ pub struct SpawnInTerminal {
     pub show_command: bool,
     /// Whether to show the rerun button in the terminal tab.
     pub show_rerun: bool,
+    /// Problem matchers to turn the task's output into diagnostics.
+    pub problem_matchers: Vec<ProblemMatcher>,
+    /// Label of the task whose dependencies this task belongs to, if any.
+    /// Terminals of the same group are shown together in one pane.
+    pub group: Option<String>,
 }
 
 impl SpawnInTerminal {
@@ -122,6 +136,8 @@ pub struct ResolvedTask {
     pub resolved_label: String,
     /// Variables that were substituted during the task template resolution.
     substituted_variables: HashSet<VariableName>,
+    /// The context the task got resolved with, used to resolve the tasks it depends on.
+    task_context: TaskContext,
     /// Further actions that need to take place after the resolved task is spawned,
     /// with all task variables resolved.
     pub resolved: SpawnInTerminal,
@@ -142,6 +158,16 @@ impl ResolvedTask {
     pub fn display_label(&self) -> &str {
         self.resolved.label.as_str()
     }
+
+    /// The context the task got resolved with.
+    pub fn task_context(&self) -> &TaskContext {
+        &self.task_context
+    }
+
+    /// Whether the task has no command of its own and only runs the tasks it depends on.
+    pub fn runs_dependencies_only(&self) -> bool {
+        self.original_task.command.trim().is_empty()
+    }
 }
 
 /// Variables, available for use in [`TaskContext`] when a Zed's [`TaskTemplate`] gets resolved into a [`ResolvedTask`].
@@ -0,0 +1,174 @@
+use anyhow::bail;
+use collections::HashSet;
+
+use crate::TaskTemplate;
+
+/// A task template together with all the tasks it (transitively) depends on,
+/// as declared with `depends_on` in the template.
+///
+/// `S` is the source the template came from, so that dependencies can be resolved
+/// the same way as the tasks picked by the user.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskDependencyTree<S> {
+    /// The source of the task template.
+    pub source: S,
+    /// The task template itself.
+    pub template: TaskTemplate,
+    /// Trees of the tasks this task depends on, in the order they are listed in `depends_on`.
+    ///
+    /// A task that several tasks depend on only has its own dependencies listed the first
+    /// time it appears in the tree, as it is only run once.
+    pub dependencies: Vec<TaskDependencyTree<S>>,
+}
+
+impl<S> TaskDependencyTree<S> {
+    /// Builds the dependency tree of the given template, looking up every dependency label with `lookup`.
+    ///
+    /// Fails if a dependency cannot be found, or if the dependencies form a cycle.
+    pub fn build(
+        source: S,
+        template: TaskTemplate,
+        lookup: &mut impl FnMut(&str) -> Option<(S, TaskTemplate)>,
+    ) -> anyhow::Result<Self> {
+        let mut stack = Vec::new();
+        let mut expanded = HashSet::default();
+        Self::build_inner(source, template, lookup, &mut stack, &mut expanded)
+    }
+
+    fn build_inner(
+        source: S,
+        template: TaskTemplate,
+        lookup: &mut impl FnMut(&str) -> Option<(S, TaskTemplate)>,
+        stack: &mut Vec<String>,
+        expanded: &mut HashSet<String>,
+    ) -> anyhow::Result<Self> {
+        stack.push(template.label.clone());
+        let mut dependencies = Vec::with_capacity(template.depends_on.len());
+        for label in &template.depends_on {
+            if let Some(cycle_start) = stack.iter().position(|parent| parent == label) {
+                let mut cycle = stack[cycle_start..].to_vec();
+                cycle.push(label.clone());
+                bail!("Task dependency cycle: {}", cycle.join(" → "));
+            }
+            let Some((dependency_source, dependency)) = lookup(label) else {
+                bail!(
+                    "Task `{}` depends on an unknown task `{label}`",
+                    template.label
+                );
+            };
+            if expanded.contains(label) {
+                dependencies.push(Self {
+                    source: dependency_source,
+                    template: dependency,
+                    dependencies: Vec::new(),
+                });
+                continue;
+            }
+            dependencies.push(Self::build_inner(
+                dependency_source,
+                dependency,
+                lookup,
+                stack,
+                expanded,
+            )?);
+        }
+        stack.pop();
+        expanded.insert(template.label.clone());
+        Ok(Self {
+            source,
+            template,
+            dependencies,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(label: &str, depends_on: &[&str]) -> TaskTemplate {
+        TaskTemplate {
+            label: label.to_string(),
+            command: format!("echo {label}"),
+            depends_on: depends_on.iter().map(|label| label.to_string()).collect(),
+            ..TaskTemplate::default()
+        }
+    }
+
+    fn build(root: &str, templates: &[TaskTemplate]) -> anyhow::Result<TaskDependencyTree<()>> {
+        let root = templates
+            .iter()
+            .find(|template| template.label == root)
+            .unwrap()
+            .clone();
+        TaskDependencyTree::build((), root, &mut |label: &str| {
+            templates
+                .iter()
+                .find(|template| template.label == label)
+                .map(|template| ((), template.clone()))
+        })
+    }
+
+    fn labels(tree: &TaskDependencyTree<()>) -> String {
+        if tree.dependencies.is_empty() {
+            tree.template.label.clone()
+        } else {
+            format!(
+                "{}({})",
+                tree.template.label,
+                tree.dependencies
+                    .iter()
+                    .map(labels)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        }
+    }
+
+    #[test]
+    fn test_dependency_tree() {
+        let templates = [
+            task("build", &["lint", "compile"]),
+            task("lint", &[]),
+            task("compile", &["codegen"]),
+            task("codegen", &[]),
+        ];
+        let tree = build("build", &templates).unwrap();
+        assert_eq!(labels(&tree), "build(lint, compile(codegen))");
+
+        // Shared dependencies are not cycles, and their own dependencies are only listed once.
+        let templates = [
+            task("all", &["a", "b"]),
+            task("a", &["common"]),
+            task("b", &["common"]),
+            task("common", &["setup"]),
+            task("setup", &[]),
+        ];
+        let tree = build("all", &templates).unwrap();
+        assert_eq!(labels(&tree), "all(a(common(setup)), b(common))");
+    }
+
+    #[test]
+    fn test_dependency_cycles() {
+        let templates = [task("a", &["b"]), task("b", &["c"]), task("c", &["a"])];
+        assert_eq!(
+            build("a", &templates).unwrap_err().to_string(),
+            "Task dependency cycle: a → b → c → a"
+        );
+
+        let templates = [task("a", &["a"])];
+        assert_eq!(
+            build("a", &templates).unwrap_err().to_string(),
+            "Task dependency cycle: a → a"
+        );
+    }
+
+    #[test]
+    fn test_unknown_dependency() {
+        let templates = [task("a", &["missing"])];
+        assert_eq!(
+            build("a", &templates).unwrap_err().to_string(),
+            "Task `a` depends on an unknown task `missing`"
+        );
+    }
+}
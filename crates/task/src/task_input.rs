@@ -0,0 +1,320 @@
+use collections::HashMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{BuildTaskDefinition, DebugScenario, ResolvedTask, TaskTemplate};
+
+/// A value to ask the user for right before the task is spawned.
+/// Task fields reference it as `${input:<id>}`.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TaskInput {
+    /// Name to reference the input with, as `${input:<id>}`.
+    pub id: String,
+    /// How to ask for the value:
+    /// * `prompt_string` — let the user type any text (default)
+    /// * `pick_string` — let the user pick one of the `options`
+    /// * `command` — let the user pick one of the lines printed by the `command`
+    #[serde(default, rename = "type")]
+    pub input_type: TaskInputType,
+    /// Text to show in the prompt.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Value to start with, if the task was not run with this input before.
+    #[serde(default)]
+    pub default: Option<String>,
+    /// Whether to hide the typed text, for `prompt_string` inputs.
+    #[serde(default)]
+    pub password: bool,
+    /// Values to pick from, for `pick_string` inputs.
+    #[serde(default)]
+    pub options: Vec<String>,
+    /// Command that lists the values to pick from, one per line, for `command` inputs.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Arguments to the `command`.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// How to ask the user for the value of a [`TaskInput`].
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskInputType {
+    /// Let the user type any text.
+    #[default]
+    PromptString,
+    /// Let the user pick one of the input's options.
+    PickString,
+    /// Let the user pick one of the lines printed by the input's command.
+    Command,
+}
+
+impl TaskInput {
+    /// The text that task fields use to reference this input.
+    pub fn reference(&self) -> String {
+        input_reference(&self.id)
+    }
+}
+
+fn input_reference(id: &str) -> String {
+    format!("${{input:{id}}}")
+}
+
+/// Replaces all `${input:<id>}` references in the string with the given input values.
+/// References to inputs without a value are left as is.
+pub fn substitute_input_values(text: &str, values: &HashMap<String, String>) -> String {
+    if !text.contains("${input:") {
+        return text.to_owned();
+    }
+    values.iter().fold(text.to_owned(), |text, (id, value)| {
+        text.replace(&input_reference(id), value)
+    })
+}
+
+fn substitute_input_values_in_json(
+    value: &mut serde_json::Value,
+    values: &HashMap<String, String>,
+) {
+    match value {
+        serde_json::Value::String(string) => *string = substitute_input_values(string, values),
+        serde_json::Value::Array(array) => array
+            .iter_mut()
+            .for_each(|value| substitute_input_values_in_json(value, values)),
+        serde_json::Value::Object(object) => object
+            .values_mut()
+            .for_each(|value| substitute_input_values_in_json(value, values)),
+        _ => {}
+    }
+}
+
+impl TaskTemplate {
+    /// Replaces the `${input:<id>}` references in the template's fields with the given input values.
+    pub fn with_input_values(&self, values: &HashMap<String, String>) -> Self {
+        let mut template = self.clone();
+        template.label = substitute_input_values(&template.label, values);
+        template.command = substitute_input_values(&template.command, values);
+        for arg in &mut template.args {
+            *arg = substitute_input_values(arg, values);
+        }
+        for value in template.env.values_mut() {
+            *value = substitute_input_values(value, values);
+        }
+        if let Some(cwd) = &mut template.cwd {
+            *cwd = substitute_input_values(cwd, values);
+        }
+        template.inputs.clear();
+        template
+    }
+}
+
+impl ResolvedTask {
+    /// Replaces the `${input:<id>}` references in the resolved task with the given input values.
+    ///
+    /// The task keeps its id, so that the same terminal tab is reused whatever values were entered.
+    pub fn with_input_values(&self, values: &HashMap<String, String>) -> Self {
+        let mut task = self.clone();
+        if values.is_empty() {
+            return task;
+        }
+        task.resolved_label = substitute_input_values(&task.resolved_label, values);
+        let resolved = &mut task.resolved;
+        resolved.full_label = substitute_input_values(&resolved.full_label, values);
+        resolved.label = substitute_input_values(&resolved.label, values);
+        resolved.command_label = substitute_input_values(&resolved.command_label, values);
+        if let Some(command) = &mut resolved.command {
+            *command = substitute_input_values(command, values);
+        }
+        for arg in &mut resolved.args {
+            *arg = substitute_input_values(arg, values);
+        }
+        for value in resolved.env.values_mut() {
+            *value = substitute_input_values(value, values);
+        }
+        if let Some(cwd) = &mut resolved.cwd
+            && let Some(cwd_str) = cwd.to_str()
+        {
+            *cwd = substitute_input_values(cwd_str, values).into();
+        }
+        task
+    }
+}
+
+impl DebugScenario {
+    /// Replaces the `${input:<id>}` references in the scenario's label, configuration and build task
+    /// with the given input values.
+    pub fn with_input_values(&self, values: &HashMap<String, String>) -> Self {
+        let mut scenario = self.clone();
+        scenario.label = substitute_input_values(&scenario.label, values).into();
+        substitute_input_values_in_json(&mut scenario.config, values);
+        if let Some(BuildTaskDefinition::Template { task_template, .. }) = &mut scenario.build {
+            *task_template = task_template.with_input_values(values);
+        }
+        scenario.inputs.clear();
+        scenario
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use serde_json::json;
+
+    use crate::{TaskContext, TaskTemplates};
+
+    use super::*;
+
+    #[test]
+    fn test_deserialize_task_inputs() {
+        let tasks: TaskTemplates = serde_json::from_value(json!([
+            {
+                "label": "deploy to ${input:environment}",
+                "command": "deploy",
+                "args": ["--env", "${input:environment}", "--tag", "${input:tag}"],
+                "inputs": [
+                    {
+                        "id": "environment",
+                        "type": "pick_string",
+                        "description": "Where to deploy",
+                        "options": ["staging", "production"],
+                        "default": "staging"
+                    },
+                    { "id": "tag" },
+                    {
+                        "id": "branch",
+                        "type": "command",
+                        "command": "git",
+                        "args": ["branch", "--format=%(refname:short)"]
+                    }
+                ]
+            }
+        ]))
+        .unwrap();
+        assert_eq!(
+            tasks.0[0].inputs,
+            vec![
+                TaskInput {
+                    id: "environment".to_string(),
+                    input_type: TaskInputType::PickString,
+                    description: Some("Where to deploy".to_string()),
+                    default: Some("staging".to_string()),
+                    options: vec!["staging".to_string(), "production".to_string()],
+                    ..TaskInput::default()
+                },
+                TaskInput {
+                    id: "tag".to_string(),
+                    ..TaskInput::default()
+                },
+                TaskInput {
+                    id: "branch".to_string(),
+                    input_type: TaskInputType::Command,
+                    command: Some("git".to_string()),
+                    args: vec![
+                        "branch".to_string(),
+                        "--format=%(refname:short)".to_string()
+                    ],
+                    ..TaskInput::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolved_task_input_values() {
+        let template = TaskTemplate {
+            label: "deploy to ${input:environment}".to_string(),
+            command: "deploy".to_string(),
+            args: vec![
+                "--env".to_string(),
+                "${input:environment}".to_string(),
+                "--tag".to_string(),
+                "${input:tag}".to_string(),
+            ],
+            env: HashMap::from_iter([("TAG".to_string(), "${input:tag}".to_string())]),
+            cwd: Some("/deploy/${input:environment}".to_string()),
+            ..TaskTemplate::default()
+        };
+        let resolved_task = template
+            .resolve_task("test", &TaskContext::default())
+            .unwrap();
+        assert_eq!(
+            resolved_task.resolved.args,
+            vec!["--env", "${input:environment}", "--tag", "${input:tag}"],
+            "Input references should survive the task resolution"
+        );
+
+        let values = HashMap::from_iter([
+            ("environment".to_string(), "staging".to_string()),
+            ("tag".to_string(), "v1.2".to_string()),
+        ]);
+        let with_values = resolved_task.with_input_values(&values);
+        assert_eq!(with_values.id, resolved_task.id);
+        assert_eq!(with_values.resolved_label, "deploy to staging");
+        assert_eq!(with_values.resolved.label, "deploy to staging");
+        assert_eq!(
+            with_values.resolved.args,
+            vec!["--env", "staging", "--tag", "v1.2"]
+        );
+        assert_eq!(
+            with_values.resolved.command_label,
+            "deploy --env staging --tag v1.2"
+        );
+        assert_eq!(
+            with_values.resolved.env.get("TAG").map(String::as_str),
+            Some("v1.2")
+        );
+        assert_eq!(
+            with_values.resolved.cwd,
+            Some(PathBuf::from("/deploy/staging"))
+        );
+
+        let partial_values = HashMap::from_iter([("tag".to_string(), "v1.2".to_string())]);
+        assert_eq!(
+            resolved_task
+                .with_input_values(&partial_values)
+                .resolved
+                .args,
+            vec!["--env", "${input:environment}", "--tag", "v1.2"],
+            "References to inputs without values should be left as is"
+        );
+    }
+
+    #[test]
+    fn test_debug_scenario_input_values() {
+        let scenario: DebugScenario = serde_json::from_value(json!({
+            "label": "Debug ${input:binary}",
+            "adapter": "CodeLLDB",
+            "request": "launch",
+            "program": "target/debug/${input:binary}",
+            "args": ["--port", "${input:port}"],
+            "inputs": [
+                { "id": "binary", "type": "pick_string", "options": ["server", "client"] },
+                { "id": "port", "default": "8080" }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(scenario.inputs.len(), 2);
+        assert_eq!(
+            scenario.config.get("inputs"),
+            None,
+            "Inputs should not be passed to the debug adapter"
+        );
+
+        let values = HashMap::from_iter([
+            ("binary".to_string(), "server".to_string()),
+            ("port".to_string(), "8080".to_string()),
+        ]);
+        let scenario = scenario.with_input_values(&values);
+        assert_eq!(scenario.label.as_ref(), "Debug server");
+        assert_eq!(
+            scenario.config,
+            json!({
+                "request": "launch",
+                "program": "target/debug/server",
+                "args": ["--port", "8080"],
+            })
+        );
+        assert!(scenario.inputs.is_empty());
+    }
+}
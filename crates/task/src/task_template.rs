@@ -9,8 +9,9 @@ use util::serde::default_true;
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachRequest, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX, serde_helpers::non_empty_string_vec,
+    AttachRequest, ProblemMatcherTemplate, ResolvedTask, RevealTarget, Shell, SpawnInTerminal,
+    TaskContext, TaskId, TaskInput, VariableName, ZED_VARIABLE_NAME_PREFIX,
+    serde_helpers::non_empty_string_vec,
 };
 
 /// A template definition of a Zed task to run.
@@ -25,6 +26,8 @@ pub struct TaskTemplate {
     /// Human readable name of the task to display in the UI.
     pub label: String,
     /// Executable command to spawn.
+    /// May be omitted for tasks that only run their `depends_on` tasks.
+    #[serde(default)]
     pub command: String,
     /// Arguments to the command.
     #[serde(default)]
@@ -45,6 +48,7 @@ pub struct TaskTemplate {
     /// * `always` — always show the task's pane, and focus the corresponding tab in it (default)
     // * `no_focus` — always show the task's pane, add the task's tab in it, but don't focus it
     // * `never` — do not alter focus, but still add/reuse the task's tab in its pane
+    // * `on_failure` — behave as `never`, but show the task's pane and tab if the task fails
     #[serde(default)]
     pub reveal: RevealStrategy,
     /// Where to place the task's terminal item after starting the task.
@@ -72,6 +76,25 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Labels of the tasks that have to finish successfully before this task is started.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// How to run the tasks listed in `depends_on`:
+    /// * `parallel` — start all of them at once (default)
+    /// * `sequence` — run them one after another, in the order they are listed
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// Problem matchers to turn the task's output into diagnostics.
+    /// Either names of built-in matchers (`$rustc`, `$tsc`, `$gcc`, `$eslint`, `$pytest`) or custom matchers.
+    #[serde(default)]
+    pub problem_matchers: Vec<ProblemMatcherTemplate>,
+    /// Values to ask the user for before the task is spawned, referenced as `${input:<id>}` in the task's fields.
+    /// The values entered the last time are suggested when the task is run again.
+    #[serde(default)]
+    pub inputs: Vec<TaskInput>,
+    /// When to run the task automatically, in addition to running it manually.
+    #[serde(default)]
+    pub triggers: TaskTriggers,
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -94,6 +117,8 @@ pub enum RevealStrategy {
     NoFocus,
     /// Do not alter focus, but still add/reuse the task's tab in its pane.
     Never,
+    /// Behave as `Never` while the task runs, and show the task's pane and tab if the task fails.
+    OnFailure,
 }
 
 /// What to do with the terminal pane and tab, after the command has finished.
@@ -109,6 +134,60 @@ pub enum HideStrategy {
     OnSuccess,
 }
 
+/// When to run a task automatically.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TaskTriggers {
+    /// Globs of worktree-relative paths: saving a matching buffer in Zed runs the task.
+    #[serde(default)]
+    pub on_save: Vec<String>,
+    /// Globs of worktree-relative paths: a matching file changing on disk runs the task,
+    /// whatever changed it.
+    #[serde(default)]
+    pub on_file_change: Vec<String>,
+    /// Globs of worktree-relative paths whose changes never run the task,
+    /// such as the files the task itself writes.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// How long to wait for more triggers before running the task, in milliseconds.
+    /// A run that is still in progress when the task is triggered again is cancelled.
+    #[serde(default = "default_trigger_debounce_ms")]
+    pub debounce_ms: u64,
+}
+
+fn default_trigger_debounce_ms() -> u64 {
+    300
+}
+
+impl Default for TaskTriggers {
+    fn default() -> Self {
+        Self {
+            on_save: Vec::new(),
+            on_file_change: Vec::new(),
+            exclude: Vec::new(),
+            debounce_ms: default_trigger_debounce_ms(),
+        }
+    }
+}
+
+impl TaskTriggers {
+    /// Whether the task only runs when spawned manually.
+    pub fn is_empty(&self) -> bool {
+        self.on_save.is_empty() && self.on_file_change.is_empty()
+    }
+}
+
+/// How to run the tasks a task depends on.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Start all dependencies at once.
+    #[default]
+    Parallel,
+    /// Run dependencies one after another, in the order they are listed.
+    Sequence,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -132,7 +211,9 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty()
+            || (self.command.trim().is_empty() && self.depends_on.is_empty())
+        {
             return None;
         }
 
@@ -212,6 +293,32 @@ impl TaskTemplate {
             &mut substituted_variables,
         )?;
 
+        let problem_matchers = self
+            .problem_matchers
+            .iter()
+            .filter_map(|problem_matcher| {
+                let matcher = problem_matcher.to_matcher();
+                if matcher.is_none() {
+                    log::warn!(
+                        "Unknown problem matcher {problem_matcher:?} in task `{}`",
+                        self.label
+                    );
+                }
+                matcher
+            })
+            .map(|mut matcher| {
+                if let Some(base_dir) = &matcher.base_dir {
+                    matcher.base_dir = Some(substitute_all_template_variables_in_str(
+                        base_dir,
+                        &task_variables,
+                        &variable_names,
+                        &mut substituted_variables,
+                    )?);
+                }
+                Some(matcher)
+            })
+            .collect::<Option<Vec<_>>>()?;
+
         let task_hash = to_hex_hash(self)
             .context("hashing task template")
             .log_err()?;
@@ -244,6 +351,7 @@ impl TaskTemplate {
             id: id.clone(),
             substituted_variables,
             original_task: self.clone(),
+            task_context: cx.clone(),
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
                 id,
@@ -270,6 +378,8 @@ impl TaskTemplate {
                 show_summary: self.show_summary,
                 show_command: self.show_command,
                 show_rerun: true,
+                problem_matchers,
+                group: None,
             },
         })
     }
@@ -484,6 +594,8 @@ fn substitute_all_template_variables_in_map(
 mod tests {
     use std::{borrow::Cow, path::Path};
 
+    use serde_json::json;
+
     use crate::{TaskVariables, VariableName};
 
     use super::*;
@@ -1074,4 +1186,46 @@ mod tests {
 
         assert!(task.unknown_variables().is_empty());
     }
+
+    #[test]
+    fn test_deserialize_triggers() {
+        let tasks: TaskTemplates = serde_json::from_value(json!([
+            { "label": "manual", "command": "make" },
+            {
+                "label": "test on save",
+                "command": "cargo test",
+                "reveal": "on_failure",
+                "triggers": { "on_save": ["src/**/*.rs"] }
+            },
+            {
+                "label": "rebuild docs",
+                "command": "mdbook build",
+                "triggers": {
+                    "on_file_change": ["docs/**"],
+                    "exclude": ["docs/book/**"],
+                    "debounce_ms": 1000
+                }
+            }
+        ]))
+        .unwrap();
+
+        assert!(tasks.0[0].triggers.is_empty());
+        assert_eq!(tasks.0[0].triggers, TaskTriggers::default());
+
+        assert_eq!(tasks.0[1].reveal, RevealStrategy::OnFailure);
+        assert_eq!(
+            tasks.0[1].triggers,
+            TaskTriggers {
+                on_save: vec!["src/**/*.rs".to_string()],
+                on_file_change: Vec::new(),
+                exclude: Vec::new(),
+                debounce_ms: 300,
+            }
+        );
+
+        assert!(!tasks.0[2].triggers.is_empty());
+        assert_eq!(tasks.0[2].triggers.on_file_change, vec!["docs/**"]);
+        assert_eq!(tasks.0[2].triggers.exclude, vec!["docs/book/**"]);
+        assert_eq!(tasks.0[2].triggers.debounce_ms, 1000);
+    }
 }
@@ -4,6 +4,7 @@ use util::ResultExt as _;
 
 use crate::{
     DebugScenario, DebugTaskFile, EnvVariableReplacer, TcpArgumentsTemplate, VariableName,
+    vscode_format::{VsCodeInput, referenced_inputs},
 };
 
 // TODO support preLaunchTask linkage with other tasks
@@ -35,6 +36,7 @@ impl VsCodeDebugTaskDefinition {
             label: label.into(),
             build: None,
             adapter: adapter.into(),
+            inputs: Vec::new(),
             tcp_connection: self.port.map(|port| TcpArgumentsTemplate {
                 port: Some(port),
                 host: None,
@@ -52,6 +54,8 @@ pub struct VsCodeDebugTaskFile {
     #[serde(default)]
     version: Option<String>,
     configurations: Vec<VsCodeDebugTaskDefinition>,
+    #[serde(default)]
+    inputs: Vec<VsCodeInput>,
 }
 
 impl TryFrom<VsCodeDebugTaskFile> for DebugTaskFile {
@@ -73,10 +77,19 @@ impl TryFrom<VsCodeDebugTaskFile> for DebugTaskFile {
             "pickMyProcess".to_owned(),
             VariableName::PickProcessId.to_string(),
         )]);
+        let inputs = file
+            .inputs
+            .into_iter()
+            .filter_map(VsCodeInput::into_zed_format)
+            .collect::<Vec<_>>();
         let templates = file
             .configurations
             .into_iter()
             .filter_map(|config| config.try_to_zed(&replacer).log_err())
+            .map(|mut scenario| {
+                scenario.inputs = referenced_inputs(&inputs, &scenario);
+                scenario
+            })
             .collect::<Vec<_>>();
         Ok(DebugTaskFile(templates))
     }
@@ -151,6 +164,7 @@ mod tests {
                     "type": "node",
                     "port": 17,
                 }),
+                inputs: Vec::new(),
                 tcp_connection: None,
                 build: None
             }])
@@ -186,6 +200,7 @@ mod tests {
                     "request": "attach",
                     "processId": expected_placeholder,
                 }),
+                inputs: Vec::new(),
                 tcp_connection: None,
                 build: None
             }])
@@ -1,9 +1,13 @@
-use anyhow::bail;
+use anyhow::{anyhow, bail};
 use collections::HashMap;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    BackgroundPatterns, DependsOrder, EnvVariableReplacer, ProblemMatcher, ProblemMatcherTemplate,
+    ProblemPattern, ProblemSeverity, TaskInput, TaskInputType, TaskTemplate, TaskTemplates,
+    VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -90,19 +94,52 @@ impl VsCodeTaskDefinition {
         self,
         replacer: &EnvVariableReplacer,
     ) -> anyhow::Result<Option<TaskTemplate>> {
-        if self.other_attributes.contains_key("dependsOn") {
-            log::warn!(
-                "Skipping deserializing of a task `{}` with the unsupported `dependsOn` key",
+        let depends_on = match self.other_attributes.get("dependsOn") {
+            None => Vec::new(),
+            Some(serde_json_lenient::Value::String(label)) => vec![label.clone()],
+            Some(serde_json_lenient::Value::Array(labels)) => labels
+                .iter()
+                .map(|label| match label {
+                    serde_json_lenient::Value::String(label) => Ok(label.clone()),
+                    other => Err(anyhow!(
+                        "Unsupported `dependsOn` entry {other} in task `{}`",
+                        self.label
+                    )),
+                })
+                .collect::<anyhow::Result<_>>()?,
+            Some(other) => bail!(
+                "Unsupported `dependsOn` value {other} in task `{}`",
                 self.label
-            );
-            return Ok(None);
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
+            ),
+        };
+        let depends_order = match self.other_attributes.get("dependsOrder") {
+            Some(serde_json_lenient::Value::String(order)) if order == "sequence" => {
+                DependsOrder::Sequence
+            }
+            _ => DependsOrder::Parallel,
+        };
+        let problem_matchers = match self.other_attributes.get("problemMatcher") {
+            None => Vec::new(),
+            Some(serde_json_lenient::Value::Array(matchers)) => matchers
+                .iter()
+                .filter_map(|matcher| problem_matcher_from_vscode(matcher, replacer))
+                .collect(),
+            Some(matcher) => problem_matcher_from_vscode(matcher, replacer)
+                .into_iter()
+                .collect(),
+        };
+        // `type` might not be set in tasks that only use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option).
         let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+            if depends_on.is_empty() {
+                bail!("Missing `type` field in task");
+            }
+            return Ok(Some(TaskTemplate {
+                label: self.label,
+                depends_on,
+                depends_order,
+                problem_matchers,
+                ..TaskTemplate::default()
+            }));
         };
 
         let (command, args) = match command {
@@ -117,6 +154,9 @@ impl VsCodeTaskDefinition {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order,
+            problem_matchers,
             ..TaskTemplate::default()
         };
         if let Some(options) = self.options {
@@ -127,10 +167,190 @@ impl VsCodeTaskDefinition {
     }
 }
 
+/// Converts a VS Code `problemMatcher` entry: either a matcher name, or a matcher object
+/// that may extend a named `base` matcher.
+fn problem_matcher_from_vscode(
+    value: &serde_json_lenient::Value,
+    replacer: &EnvVariableReplacer,
+) -> Option<ProblemMatcherTemplate> {
+    use serde_json_lenient::Value;
+
+    let object = match value {
+        Value::String(name) => return Some(ProblemMatcherTemplate::BuiltIn(name.clone())),
+        Value::Object(object) => object,
+        other => {
+            log::warn!("Skipping unsupported problem matcher {other}");
+            return None;
+        }
+    };
+
+    let base = object.get("base").and_then(Value::as_str);
+    let pattern = match object.get("pattern") {
+        Some(Value::Object(pattern)) => Some(vec![problem_pattern_from_vscode(pattern)?]),
+        Some(Value::Array(patterns)) => Some(
+            patterns
+                .iter()
+                .map(|pattern| problem_pattern_from_vscode(pattern.as_object()?))
+                .collect::<Option<_>>()?,
+        ),
+        _ => None,
+    };
+    let mut matcher = match (base.and_then(ProblemMatcher::built_in), pattern) {
+        (Some(mut matcher), Some(pattern)) => {
+            matcher.pattern = pattern;
+            matcher
+        }
+        (Some(matcher), None) => matcher,
+        (None, Some(pattern)) => ProblemMatcher {
+            source: None,
+            pattern,
+            severity: ProblemSeverity::Error,
+            base_dir: None,
+            background: None,
+        },
+        (None, None) => match base {
+            Some(base) => return Some(ProblemMatcherTemplate::BuiltIn(base.to_string())),
+            None => {
+                log::warn!("Skipping problem matcher without a `base` or a `pattern`");
+                return None;
+            }
+        },
+    };
+
+    if let Some(source) = object
+        .get("source")
+        .or_else(|| object.get("owner"))
+        .and_then(Value::as_str)
+    {
+        matcher.source = Some(source.to_string());
+    }
+    if let Some(severity) = object.get("severity").and_then(Value::as_str)
+        && let Some(severity) = ProblemSeverity::parse(severity)
+    {
+        matcher.severity = severity;
+    }
+    // `fileLocation` is either a mode, or a mode followed by the directory to resolve paths against.
+    if let Some(Value::Array(file_location)) = object.get("fileLocation")
+        && let Some(base_dir) = file_location.get(1).and_then(Value::as_str)
+    {
+        matcher.base_dir = Some(replacer.replace(base_dir));
+    }
+    if let Some(Value::Object(background)) = object.get("background") {
+        let pattern = |key: &str| match background.get(key)? {
+            Value::String(regexp) => Some(regexp.clone()),
+            Value::Object(pattern) => Some(pattern.get("regexp")?.as_str()?.to_string()),
+            _ => None,
+        };
+        if let Some(begins_pattern) = pattern("beginsPattern")
+            && let Some(ends_pattern) = pattern("endsPattern")
+        {
+            matcher.background = Some(BackgroundPatterns {
+                begins_pattern,
+                ends_pattern,
+            });
+        }
+    }
+    Some(ProblemMatcherTemplate::Custom(matcher))
+}
+
+fn problem_pattern_from_vscode(
+    pattern: &serde_json_lenient::Map<String, serde_json_lenient::Value>,
+) -> Option<ProblemPattern> {
+    let group = |key: &str| {
+        pattern
+            .get(key)
+            .and_then(|group| group.as_u64())
+            .map(|group| group as usize)
+    };
+    Some(ProblemPattern {
+        regexp: pattern.get("regexp")?.as_str()?.to_string(),
+        file: group("file"),
+        line: group("line"),
+        column: group("column"),
+        end_line: group("endLine"),
+        end_column: group("endColumn"),
+        severity: group("severity"),
+        code: group("code"),
+        message: group("message"),
+        loop_: pattern
+            .get("loop")
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false),
+    })
+}
+
+/// An entry of the `inputs` array in Code's `tasks.json` and `launch.json`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub(crate) struct VsCodeInput {
+    id: String,
+    r#type: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    default: Option<String>,
+    #[serde(default)]
+    password: bool,
+    #[serde(default)]
+    options: Vec<VsCodeInputOption>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeInputOption {
+    Value(String),
+    Labeled { value: String },
+}
+
+impl VsCodeInput {
+    /// Converts the input, unless it is a `command` input: those run Code's commands, which Zed does not have.
+    pub(crate) fn into_zed_format(self) -> Option<TaskInput> {
+        let input_type = match self.r#type.as_str() {
+            "promptString" => TaskInputType::PromptString,
+            "pickString" => TaskInputType::PickString,
+            other => {
+                log::warn!("Skipping unsupported input `{}` of type `{other}`", self.id);
+                return None;
+            }
+        };
+        Some(TaskInput {
+            id: self.id,
+            input_type,
+            description: self.description,
+            default: self.default,
+            password: self.password,
+            options: self
+                .options
+                .into_iter()
+                .map(|option| match option {
+                    VsCodeInputOption::Value(value) | VsCodeInputOption::Labeled { value } => value,
+                })
+                .collect(),
+            ..TaskInput::default()
+        })
+    }
+}
+
+/// Code declares inputs once per file, Zed declares them on every task that uses them.
+pub(crate) fn referenced_inputs(
+    inputs: &[TaskInput],
+    definition: &impl Serialize,
+) -> Vec<TaskInput> {
+    let Ok(definition) = serde_json::to_string(definition) else {
+        return Vec::new();
+    };
+    inputs
+        .iter()
+        .filter(|input| definition.contains(&input.reference()))
+        .cloned()
+        .collect()
+}
+
 /// [`VsCodeTaskFile`] is a superset of Code's task definition format.
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct VsCodeTaskFile {
     tasks: Vec<VsCodeTaskDefinition>,
+    #[serde(default)]
+    inputs: Vec<VsCodeInput>,
 }
 
 impl TryFrom<VsCodeTaskFile> for TaskTemplates {
@@ -149,6 +369,11 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
                 VariableName::SelectedText.to_string(),
             ),
         ]));
+        let inputs = value
+            .inputs
+            .into_iter()
+            .filter_map(VsCodeInput::into_zed_format)
+            .collect::<Vec<_>>();
         let templates = value
             .tasks
             .into_iter()
@@ -158,6 +383,10 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
                     .log_err()
                     .flatten()
             })
+            .map(|mut template| {
+                template.inputs = referenced_inputs(&inputs, &template);
+                template
+            })
             .collect();
         Ok(Self(templates))
     }
@@ -168,7 +397,8 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        BackgroundPatterns, DependsOrder, ProblemMatcher, ProblemMatcherTemplate, ProblemPattern,
+        ProblemSeverity, TaskInput, TaskInputType, TaskTemplate, TaskTemplates, VsCodeTaskFile,
         vscode_format::{Command, VsCodeTaskDefinition},
     };
 
@@ -271,6 +501,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcherTemplate::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
@@ -282,18 +513,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matchers: vec![ProblemMatcherTemplate::BuiltIn("$tsc-watch".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matchers: vec![ProblemMatcherTemplate::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcherTemplate::BuiltIn("$tsc".to_string())],
                 ..Default::default()
             },
         ];
@@ -369,33 +603,60 @@ mod tests {
             .iter()
             .zip(expected)
             .for_each(|(lhs, rhs)| compare_without_other_attributes(lhs.clone(), rhs));
+        let editors_code_matcher = |base: &str| {
+            ProblemMatcherTemplate::Custom(ProblemMatcher {
+                base_dir: Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string()),
+                ..ProblemMatcher::built_in(base).unwrap()
+            })
+        };
+        let rustc_matcher = || vec![ProblemMatcherTemplate::BuiltIn("$rustc".to_string())];
         let expected = vec![
             TaskTemplate {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matchers: vec![editors_code_matcher("$tsc-watch")],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matchers: vec![editors_code_matcher("$tsc")],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matchers: rustc_matcher(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matchers: rustc_matcher(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matchers: vec![editors_code_matcher("$tsc")],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                problem_matchers: rustc_matcher(),
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                problem_matchers: rustc_matcher(),
                 ..Default::default()
             },
         ];
@@ -403,6 +664,204 @@ mod tests {
         assert_eq!(tasks.0, expected);
     }
 
+    #[test]
+    fn can_deserialize_depends_order() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "Release",
+                        "type": "shell",
+                        "command": "./release.sh",
+                        "dependsOn": "Test",
+                        "dependsOrder": "sequence"
+                    },
+                    {
+                        "label": "Test",
+                        "dependsOn": ["Unit Tests", "Integration Tests"],
+                        "dependsOrder": "parallel"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "Release".to_string(),
+                    command: "./release.sh".to_string(),
+                    depends_on: vec!["Test".to_string()],
+                    depends_order: DependsOrder::Sequence,
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "Test".to_string(),
+                    depends_on: vec!["Unit Tests".to_string(), "Integration Tests".to_string()],
+                    depends_order: DependsOrder::Parallel,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn can_deserialize_custom_problem_matchers() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "lint",
+                        "type": "shell",
+                        "command": "./lint.sh",
+                        "problemMatcher": {
+                            "owner": "lint",
+                            "severity": "warning",
+                            "fileLocation": ["relative", "${workspaceFolder}/src"],
+                            "pattern": {
+                                "regexp": "^(.*):(\\d+):(\\d+): (.*)$",
+                                "file": 1,
+                                "line": 2,
+                                "column": 3,
+                                "message": 4
+                            },
+                            "background": {
+                                "activeBegins": true,
+                                "beginsPattern": "^Linting",
+                                "endsPattern": { "regexp": "^Done" }
+                            }
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0[0].problem_matchers,
+            vec![ProblemMatcherTemplate::Custom(ProblemMatcher {
+                source: Some("lint".to_string()),
+                pattern: vec![ProblemPattern {
+                    regexp: r"^(.*):(\d+):(\d+): (.*)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    message: Some(4),
+                    ..ProblemPattern::default()
+                }],
+                severity: ProblemSeverity::Warning,
+                base_dir: Some("${ZED_WORKTREE_ROOT}/src".to_string()),
+                background: Some(BackgroundPatterns {
+                    begins_pattern: "^Linting".to_string(),
+                    ends_pattern: "^Done".to_string(),
+                }),
+            })]
+        );
+    }
+
+    #[test]
+    fn custom_pattern_keeps_base_matcher_fields() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "watch",
+                        "type": "shell",
+                        "command": "tsc --watch",
+                        "problemMatcher": {
+                            "base": "$tsc",
+                            "pattern": {
+                                "regexp": "^(.*)\\((\\d+),(\\d+)\\): (.*)$",
+                                "file": 1,
+                                "line": 2,
+                                "column": 3,
+                                "message": 4
+                            }
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0[0].problem_matchers,
+            vec![ProblemMatcherTemplate::Custom(ProblemMatcher {
+                pattern: vec![ProblemPattern {
+                    regexp: r"^(.*)\((\d+),(\d+)\): (.*)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    message: Some(4),
+                    ..ProblemPattern::default()
+                }],
+                ..ProblemMatcher::built_in("$tsc").unwrap()
+            })]
+        );
+    }
+
+    #[test]
+    fn can_deserialize_inputs() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "deploy",
+                        "type": "shell",
+                        "command": "./deploy.sh",
+                        "args": ["${input:environment}", "${input:tag}"]
+                    },
+                    {
+                        "label": "build",
+                        "type": "shell",
+                        "command": "./build.sh"
+                    }
+                ],
+                "inputs": [
+                    {
+                        "id": "environment",
+                        "type": "pickString",
+                        "description": "Where to deploy",
+                        "options": ["staging", { "label": "Production", "value": "production" }],
+                        "default": "staging"
+                    },
+                    {
+                        "id": "tag",
+                        "type": "promptString",
+                        "default": "latest"
+                    },
+                    {
+                        "id": "workspace",
+                        "type": "command",
+                        "command": "extension.pickWorkspace"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0[0].inputs,
+            vec![
+                TaskInput {
+                    id: "environment".to_string(),
+                    input_type: TaskInputType::PickString,
+                    description: Some("Where to deploy".to_string()),
+                    default: Some("staging".to_string()),
+                    options: vec!["staging".to_string(), "production".to_string()],
+                    ..TaskInput::default()
+                },
+                TaskInput {
+                    id: "tag".to_string(),
+                    default: Some("latest".to_string()),
+                    ..TaskInput::default()
+                },
+            ]
+        );
+        assert_eq!(tasks.0[1].inputs, Vec::new());
+    }
+
     #[test]
     fn can_deserialize_tasks_without_labels() {
         const TASKS_WITHOUT_LABELS: &str = include_str!("../test_data/tasks-without-labels.json");
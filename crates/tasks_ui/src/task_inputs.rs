@@ -0,0 +1,387 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, bail};
+use collections::HashMap;
+use editor::{Editor, actions::SelectAll};
+use futures::channel::oneshot;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    App, AsyncWindowContext, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    SharedString, Task, WeakEntity, Window, rems,
+};
+use picker::{Picker, PickerDelegate, highlighted_match_with_paths::HighlightedMatch};
+use task::{TaskContext, TaskInput, TaskInputType, substitute_variables_in_str};
+use ui::{ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::{ModalView, Toast, Workspace, notifications::NotificationId};
+
+/// Asks for the values of task inputs in modals: a text prompt for `prompt_string` inputs,
+/// and a picker for `pick_string` and `command` ones.
+pub(crate) struct TaskInputPrompts;
+
+impl workspace::TaskInputProvider for TaskInputPrompts {
+    fn prompt(
+        &self,
+        workspace: &mut Workspace,
+        title: SharedString,
+        inputs: Vec<TaskInput>,
+        mut values: HashMap<String, String>,
+        task_context: TaskContext,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Option<HashMap<String, String>>> {
+        let is_local = workspace.project().read(cx).is_local();
+        cx.spawn_in(window, async move |workspace, cx| {
+            for input in inputs {
+                let last_value = values.get(&input.id).cloned();
+                let value = match input.input_type {
+                    TaskInputType::PromptString => {
+                        prompt_string(&workspace, &title, &input, last_value, cx).await?
+                    }
+                    TaskInputType::PickString => {
+                        let options = input.options.clone();
+                        pick_string(&workspace, &title, &input, options, last_value, cx).await?
+                    }
+                    TaskInputType::Command if is_local => {
+                        let options = match command_options(&input, &task_context, cx).await {
+                            Ok(options) => options,
+                            Err(e) => {
+                                show_error(&workspace, e, cx);
+                                return None;
+                            }
+                        };
+                        match options.as_slice() {
+                            [value] => value.clone(),
+                            _ => {
+                                pick_string(&workspace, &title, &input, options, last_value, cx)
+                                    .await?
+                            }
+                        }
+                    }
+                    TaskInputType::Command => {
+                        log::warn!(
+                            "Input commands only run in local projects, prompting for `{}`",
+                            input.id
+                        );
+                        prompt_string(&workspace, &title, &input, last_value, cx).await?
+                    }
+                };
+                values.insert(input.id, value);
+            }
+            Some(values)
+        })
+    }
+}
+
+fn show_error(
+    workspace: &WeakEntity<Workspace>,
+    error: anyhow::Error,
+    cx: &mut AsyncWindowContext,
+) {
+    log::error!("Failed to prompt for task inputs: {error:#}");
+    workspace
+        .update(cx, |workspace, cx| {
+            let id = NotificationId::unique::<TaskInputPrompts>();
+            workspace.show_toast(Toast::new(id, format!("{error:#}")), cx);
+        })
+        .ok();
+}
+
+fn prompt_title(title: &SharedString, input: &TaskInput) -> String {
+    format!(
+        "{title}: {}",
+        input.description.as_deref().unwrap_or(&input.id)
+    )
+}
+
+async fn prompt_string(
+    workspace: &WeakEntity<Workspace>,
+    title: &SharedString,
+    input: &TaskInput,
+    value: Option<String>,
+    cx: &mut AsyncWindowContext,
+) -> Option<String> {
+    let (tx, rx) = oneshot::channel();
+    let title = prompt_title(title, input);
+    let password = input.password;
+    workspace
+        .update_in(cx, |workspace, window, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                TaskInputPrompt::new(title, password, value, tx, window, cx)
+            });
+        })
+        .ok()?;
+    rx.await.ok()
+}
+
+async fn pick_string(
+    workspace: &WeakEntity<Workspace>,
+    title: &SharedString,
+    input: &TaskInput,
+    options: Vec<String>,
+    value: Option<String>,
+    cx: &mut AsyncWindowContext,
+) -> Option<String> {
+    let (tx, rx) = oneshot::channel();
+    let selected_index = value
+        .and_then(|value| options.iter().position(|option| option == &value))
+        .unwrap_or(0);
+    let delegate = TaskInputPickerDelegate {
+        placeholder: prompt_title(title, input).into(),
+        options,
+        matches: Vec::new(),
+        selected_index,
+        tx: Some(tx),
+    };
+    workspace
+        .update_in(cx, |workspace, window, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                Picker::uniform_list(delegate, window, cx)
+            });
+        })
+        .ok()?;
+    rx.await.ok()
+}
+
+/// Runs the command of a `command` input, returning the lines it printed.
+async fn command_options(
+    input: &TaskInput,
+    task_context: &TaskContext,
+    cx: &mut AsyncWindowContext,
+) -> Result<Vec<String>> {
+    let command = input
+        .command
+        .as_deref()
+        .with_context(|| format!("Input `{}` has no command", input.id))?;
+    let command = substitute_variables_in_str(command, task_context)
+        .with_context(|| format!("Failed to resolve the command of input `{}`", input.id))?;
+    let args = input
+        .args
+        .iter()
+        .map(|arg| substitute_variables_in_str(arg, task_context))
+        .collect::<Option<Vec<_>>>()
+        .with_context(|| format!("Failed to resolve the arguments of input `{}`", input.id))?;
+    let cwd = task_context.cwd.clone();
+    let env = task_context.project_env.clone();
+    let output = cx
+        .background_spawn(async move {
+            let mut command = util::command::new_command(command);
+            command.args(args).envs(env);
+            if let Some(cwd) = cwd {
+                command.current_dir(cwd);
+            }
+            command.output().await
+        })
+        .await
+        .with_context(|| format!("Failed to run the command of input `{}`", input.id))?;
+    if !output.status.success() {
+        bail!(
+            "The command of input `{}` failed with {}: {}",
+            input.id,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let options = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+    if options.is_empty() {
+        bail!("The command of input `{}` printed no values", input.id);
+    }
+    Ok(options)
+}
+
+/// A modal to type the value of a `prompt_string` input.
+struct TaskInputPrompt {
+    title: SharedString,
+    editor: Entity<Editor>,
+    tx: Option<oneshot::Sender<String>>,
+}
+
+impl TaskInputPrompt {
+    fn new(
+        title: String,
+        password: bool,
+        value: Option<String>,
+        tx: oneshot::Sender<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_masked(password, cx);
+            if let Some(value) = value {
+                editor.set_text(value, window, cx);
+                editor.select_all(&SelectAll, window, cx);
+            }
+            editor
+        });
+        Self {
+            title: title.into(),
+            editor,
+            tx: Some(tx),
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let value = self.editor.read(cx).text(cx);
+        if let Some(tx) = self.tx.take() {
+            tx.send(value).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<DismissEvent> for TaskInputPrompt {}
+
+impl Focusable for TaskInputPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl ModalView for TaskInputPrompt {}
+
+impl Render for TaskInputPrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .elevation_2(cx)
+            .key_context("TaskInputPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(self.title.clone()).color(Color::Muted)),
+            )
+            .child(div().px_2().py_1().child(self.editor.clone()))
+    }
+}
+
+/// Lets the user pick the value of a `pick_string` or `command` input.
+struct TaskInputPickerDelegate {
+    placeholder: Arc<str>,
+    options: Vec<String>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    tx: Option<oneshot::Sender<String>>,
+}
+
+impl PickerDelegate for TaskInputPickerDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _: &mut App) -> Arc<str> {
+        self.placeholder.clone()
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let candidates = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(id, option)| StringMatchCandidate::new(id, option))
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        score: 0.,
+                        positions: Vec::new(),
+                        string: candidate.string,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    true,
+                    1000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(selected) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        if let Some(tx) = self.tx.take() {
+            tx.send(selected.string.clone()).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = self.matches.get(ix)?;
+        let highlighted_option = HighlightedMatch {
+            text: hit.string.clone(),
+            highlight_positions: hit.positions.clone(),
+            color: Color::Default,
+        };
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(highlighted_option.render(window, cx)),
+        )
+    }
+}
@@ -0,0 +1,558 @@
+use std::{path::Path, sync::Arc, time::Duration};
+
+use collections::HashMap;
+use futures::{FutureExt as _, future::Shared};
+use gpui::{AppContext as _, Context, Entity, Subscription, Task, WeakEntity, Window};
+use language::{Buffer, BufferEvent};
+use project::{PathChange, Project, TaskSourceKind, WorktreeId, buffer_store::BufferStoreEvent};
+use task::{TaskContext, TaskTemplate, VariableName};
+use util::{
+    paths::{PathMatcher, PathStyle},
+    rel_path::RelPath,
+};
+use workspace::Workspace;
+
+use crate::worktree_context;
+
+/// Runs the tasks that have `triggers`, when files matching them are saved or changed.
+struct TriggeredTasks {
+    workspace: WeakEntity<Workspace>,
+    project: WeakEntity<Project>,
+    /// The tasks with triggers of every worktree, dropped when the task inventory changes.
+    tasks_by_worktree: HashMap<WorktreeId, Shared<Task<Arc<[TriggeredTask]>>>>,
+    /// Runs waiting for their debounce timeout, by worktree and task label.
+    pending_runs: HashMap<(WorktreeId, String), Task<()>>,
+    _inventory_subscription: Subscription,
+}
+
+/// A task with triggers, and the matchers of its globs.
+struct TriggeredTask {
+    task_source_kind: TaskSourceKind,
+    template: TaskTemplate,
+    on_save: Option<PathMatcher>,
+    on_file_change: Option<PathMatcher>,
+    exclude: Option<PathMatcher>,
+}
+
+impl TriggeredTask {
+    /// The first of the paths that triggers the task.
+    fn triggering_path<'a>(
+        &self,
+        trigger: Trigger,
+        paths: &'a [Arc<RelPath>],
+    ) -> Option<&'a Arc<RelPath>> {
+        let matcher = trigger.matcher(self)?;
+        paths.iter().find(|path| {
+            matcher.is_match(path)
+                && !self
+                    .exclude
+                    .as_ref()
+                    .is_some_and(|exclude| exclude.is_match(path))
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Trigger {
+    Save,
+    FileChange,
+}
+
+impl Trigger {
+    fn matcher(self, task: &TriggeredTask) -> Option<&PathMatcher> {
+        match self {
+            Trigger::Save => task.on_save.as_ref(),
+            Trigger::FileChange => task.on_file_change.as_ref(),
+        }
+    }
+}
+
+pub(crate) fn watch(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let project = workspace.project().clone();
+    if project.read(cx).is_via_collab() {
+        return;
+    }
+    let Some(inventory) = project
+        .read(cx)
+        .task_store()
+        .read(cx)
+        .task_inventory()
+        .cloned()
+    else {
+        return;
+    };
+    let triggered_tasks = cx.new(|cx| TriggeredTasks {
+        workspace: workspace.weak_handle(),
+        project: project.downgrade(),
+        tasks_by_worktree: HashMap::default(),
+        pending_runs: HashMap::default(),
+        _inventory_subscription: cx.observe(&inventory, |triggered_tasks, _, _| {
+            triggered_tasks.tasks_by_worktree.clear();
+        }),
+    });
+
+    cx.subscribe_in(&project, window, {
+        let triggered_tasks = triggered_tasks.clone();
+        move |_, _, event, window, cx| {
+            if let project::Event::WorktreeUpdatedEntries(worktree_id, entries) = event {
+                let paths = entries
+                    .iter()
+                    .filter(|(_, _, change)| *change != PathChange::Loaded)
+                    .map(|(path, _, _)| path.clone())
+                    .collect::<Vec<_>>();
+                if paths.is_empty() {
+                    return;
+                }
+                triggered_tasks.update(cx, |triggered_tasks, cx| {
+                    triggered_tasks.paths_changed(
+                        *worktree_id,
+                        paths,
+                        Trigger::FileChange,
+                        window,
+                        cx,
+                    )
+                });
+            }
+        }
+    })
+    .detach();
+
+    let buffer_store = project.read(cx).buffer_store().clone();
+    for buffer in buffer_store.read(cx).buffers() {
+        watch_buffer_saves(&triggered_tasks, &buffer, window, cx);
+    }
+    cx.subscribe_in(&buffer_store, window, move |_, _, event, window, cx| {
+        if let BufferStoreEvent::BufferAdded(buffer) = event {
+            watch_buffer_saves(&triggered_tasks, buffer, window, cx);
+        }
+    })
+    .detach();
+}
+
+fn watch_buffer_saves(
+    triggered_tasks: &Entity<TriggeredTasks>,
+    buffer: &Entity<Buffer>,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let triggered_tasks = triggered_tasks.clone();
+    cx.subscribe_in(buffer, window, move |_, buffer, event, window, cx| {
+        if !matches!(event, BufferEvent::Saved) {
+            return;
+        }
+        let Some(file) = buffer.read(cx).file() else {
+            return;
+        };
+        let worktree_id = file.worktree_id(cx);
+        let path = file.path().clone();
+        triggered_tasks.update(cx, |triggered_tasks, cx| {
+            triggered_tasks.paths_changed(worktree_id, vec![path], Trigger::Save, window, cx)
+        });
+    })
+    .detach();
+}
+
+impl TriggeredTasks {
+    fn paths_changed(
+        &mut self,
+        worktree_id: WorktreeId,
+        paths: Vec<Arc<RelPath>>,
+        trigger: Trigger,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project) = self.project.upgrade() else {
+            return;
+        };
+        let project = project.read(cx);
+        let Some(worktree) = project.worktree_for_id(worktree_id, cx) else {
+            return;
+        };
+        let worktree_abs_path = worktree.read(cx).abs_path();
+        let path_style = project.path_style(cx);
+        let Some(tasks) = self.tasks(worktree_id, path_style, cx) else {
+            return;
+        };
+
+        cx.spawn_in(window, async move |triggered_tasks, cx| {
+            let tasks = tasks.await;
+            triggered_tasks
+                .update_in(cx, |triggered_tasks, window, cx| {
+                    for task in tasks.iter() {
+                        let Some(path) = task.triggering_path(trigger, &paths) else {
+                            continue;
+                        };
+                        let task_context = trigger_context(&worktree_abs_path, path, path_style);
+                        triggered_tasks.schedule_run(
+                            worktree_id,
+                            task.task_source_kind.clone(),
+                            task.template.clone(),
+                            task_context,
+                            window,
+                            cx,
+                        );
+                    }
+                })
+                .ok();
+        })
+        .detach();
+    }
+
+    /// The tasks of the worktree that have triggers, listed once per change of the task inventory.
+    fn tasks(
+        &mut self,
+        worktree_id: WorktreeId,
+        path_style: PathStyle,
+        cx: &mut Context<Self>,
+    ) -> Option<Shared<Task<Arc<[TriggeredTask]>>>> {
+        if let Some(tasks) = self.tasks_by_worktree.get(&worktree_id) {
+            return Some(tasks.clone());
+        }
+        let project = self.project.upgrade()?;
+        let inventory = project
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned()?;
+        let templates = inventory
+            .read(cx)
+            .list_tasks(None, None, Some(worktree_id), cx);
+        let tasks = cx
+            .background_spawn(async move {
+                templates
+                    .await
+                    .into_iter()
+                    .filter_map(|(task_source_kind, template)| {
+                        triggered_task(task_source_kind, template, path_style)
+                    })
+                    .collect::<Arc<[_]>>()
+            })
+            .shared();
+        self.tasks_by_worktree.insert(worktree_id, tasks.clone());
+        Some(tasks)
+    }
+
+    /// Runs the task once no more triggers arrived for its debounce time,
+    /// replacing the pending run scheduled by the previous trigger, if any.
+    fn schedule_run(
+        &mut self,
+        worktree_id: WorktreeId,
+        task_source_kind: TaskSourceKind,
+        template: TaskTemplate,
+        task_context: TaskContext,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let debounce = Duration::from_millis(template.triggers.debounce_ms);
+        let key = (worktree_id, template.label.clone());
+        let workspace = self.workspace.clone();
+        let run = cx.spawn_in(window, async move |_, cx| {
+            cx.background_executor().timer(debounce).await;
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    let Some(mut resolved_task) =
+                        template.resolve_task(&task_source_kind.to_id_base(), &task_context)
+                    else {
+                        return;
+                    };
+                    // Reuse the terminal of the previous run, killing it if it's still in progress.
+                    resolved_task.resolved.allow_concurrent_runs = true;
+                    resolved_task.resolved.use_new_terminal = false;
+                    workspace.schedule_resolved_task(
+                        task_source_kind,
+                        resolved_task,
+                        true,
+                        window,
+                        cx,
+                    );
+                })
+                .ok();
+        });
+        self.pending_runs.insert(key, run);
+    }
+}
+
+/// The task with the matchers of its triggers, if it has any and can be triggered.
+fn triggered_task(
+    task_source_kind: TaskSourceKind,
+    template: TaskTemplate,
+    path_style: PathStyle,
+) -> Option<TriggeredTask> {
+    let triggers = &template.triggers;
+    if triggers.on_save.is_empty() && triggers.on_file_change.is_empty() {
+        return None;
+    }
+    if !template.inputs.is_empty() {
+        log::warn!(
+            "Not triggering task `{}`: tasks with inputs only run manually",
+            template.label
+        );
+        return None;
+    }
+    let matcher = |globs: &[String]| {
+        if globs.is_empty() {
+            return Ok(None);
+        }
+        PathMatcher::new(globs, path_style).map(Some)
+    };
+    let (on_save, on_file_change, exclude) = match (
+        matcher(&triggers.on_save),
+        matcher(&triggers.on_file_change),
+        matcher(&triggers.exclude),
+    ) {
+        (Ok(on_save), Ok(on_file_change), Ok(exclude)) => (on_save, on_file_change, exclude),
+        (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+            log::error!("Invalid triggers of task `{}`: {e}", template.label);
+            return None;
+        }
+    };
+    Some(TriggeredTask {
+        task_source_kind,
+        template,
+        on_save,
+        on_file_change,
+        exclude,
+    })
+}
+
+/// The context of a triggered task: its worktree, and the file that triggered it.
+fn trigger_context(worktree_abs_path: &Path, path: &RelPath, path_style: PathStyle) -> TaskContext {
+    let mut task_context = worktree_context(worktree_abs_path);
+    let variables = &mut task_context.task_variables;
+    variables.insert(
+        VariableName::File,
+        worktree_abs_path
+            .join(path.as_std_path())
+            .to_string_lossy()
+            .into_owned(),
+    );
+    variables.insert(
+        VariableName::RelativeFile,
+        path.display(path_style).into_owned(),
+    );
+    if let Some(file_name) = path.file_name() {
+        variables.insert(VariableName::Filename, file_name.to_owned());
+    }
+    task_context
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, process::ExitStatus, rc::Rc, sync::Arc, time::Duration};
+
+    use anyhow::Result;
+    use futures::channel::oneshot;
+    use gpui::{App, Entity, Task, TestAppContext, VisualTestContext, Window};
+    use language::Buffer;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use task::SpawnInTerminal;
+    use util::path;
+    use workspace::{MultiWorkspace, TerminalProvider};
+
+    use crate::tests::init_test;
+
+    /// Records the spawned tasks, which run until the test finishes them.
+    #[derive(Clone, Default)]
+    struct FakeTerminalProvider {
+        spawned: Rc<RefCell<Vec<SpawnInTerminal>>>,
+        exit_senders: Rc<RefCell<Vec<oneshot::Sender<()>>>>,
+    }
+
+    impl TerminalProvider for FakeTerminalProvider {
+        fn spawn(
+            &self,
+            task: SpawnInTerminal,
+            _: &mut Window,
+            cx: &mut App,
+        ) -> Task<Option<Result<ExitStatus>>> {
+            self.spawned.borrow_mut().push(task);
+            let (exit_tx, exit_rx) = oneshot::channel();
+            self.exit_senders.borrow_mut().push(exit_tx);
+            cx.background_spawn(async move {
+                exit_rx.await.ok();
+                None
+            })
+        }
+    }
+
+    impl FakeTerminalProvider {
+        fn spawned_files(&self) -> Vec<String> {
+            self.spawned
+                .borrow()
+                .iter()
+                .map(|task| task.env.get("ZED_FILENAME").cloned().unwrap_or_default())
+                .collect()
+        }
+    }
+
+    async fn setup<'a>(
+        tasks_json: &str,
+        cx: &'a mut TestAppContext,
+    ) -> (
+        Entity<Project>,
+        FakeTerminalProvider,
+        Arc<FakeFs>,
+        &'a mut VisualTestContext,
+    ) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                ".zed": { "tasks.json": tasks_json },
+                "src": { "main.rs": "fn main() {}", "lib.rs": "" },
+                "out": { "report.txt": "" },
+                "README.md": "",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let (multi_workspace, cx) =
+            cx.add_window_view(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = multi_workspace.read_with(cx, |mw, _| mw.workspace().clone());
+        let terminal_provider = FakeTerminalProvider::default();
+        workspace.update(cx, |workspace, _| {
+            workspace.set_terminal_provider(terminal_provider.clone())
+        });
+        cx.run_until_parked();
+        (project, terminal_provider, fs, cx)
+    }
+
+    async fn save(project: &Entity<Project>, path: &str, cx: &mut VisualTestContext) {
+        let buffer: Entity<Buffer> = project
+            .update(cx, |project, cx| project.open_local_buffer(path, cx))
+            .await
+            .unwrap();
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, " ")], None, cx));
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer, cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+    }
+
+    #[gpui::test]
+    async fn test_on_save_triggers_match_globs(cx: &mut TestAppContext) {
+        let (project, terminal_provider, _, cx) = setup(
+            r#"[
+                {
+                    "label": "test",
+                    "command": "cargo test",
+                    "triggers": { "on_save": ["src/**/*.rs"], "exclude": ["src/lib.rs"] }
+                }
+            ]"#,
+            cx,
+        )
+        .await;
+
+        save(&project, path!("/dir/README.md"), cx).await;
+        save(&project, path!("/dir/src/lib.rs"), cx).await;
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.run_until_parked();
+        assert_eq!(
+            terminal_provider.spawned_files(),
+            Vec::<String>::new(),
+            "files that don't match the globs, or are excluded, should not trigger the task"
+        );
+
+        save(&project, path!("/dir/src/main.rs"), cx).await;
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.run_until_parked();
+        assert_eq!(terminal_provider.spawned_files(), vec!["main.rs"]);
+        let spawned = terminal_provider.spawned.borrow();
+        assert_eq!(spawned[0].label, "test");
+        assert!(spawned[0].allow_concurrent_runs && !spawned[0].use_new_terminal);
+    }
+
+    #[gpui::test]
+    async fn test_burst_of_saves_runs_task_once(cx: &mut TestAppContext) {
+        let (project, terminal_provider, _, cx) = setup(
+            r#"[
+                {
+                    "label": "test",
+                    "command": "cargo test",
+                    "triggers": { "on_save": ["src/**/*.rs"], "debounce_ms": 200 }
+                }
+            ]"#,
+            cx,
+        )
+        .await;
+
+        for path in [
+            path!("/dir/src/main.rs"),
+            path!("/dir/src/lib.rs"),
+            path!("/dir/src/main.rs"),
+        ] {
+            save(&project, path, cx).await;
+            cx.executor().advance_clock(Duration::from_millis(100));
+            cx.run_until_parked();
+        }
+        assert_eq!(
+            terminal_provider.spawned_files(),
+            Vec::<String>::new(),
+            "the task should wait for the saves to settle"
+        );
+
+        cx.executor().advance_clock(Duration::from_millis(100));
+        cx.run_until_parked();
+        assert_eq!(
+            terminal_provider.spawned_files(),
+            vec!["main.rs"],
+            "the burst of saves should run the task once, for the last saved file"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_file_change_during_run_restarts_task(cx: &mut TestAppContext) {
+        let (_, terminal_provider, fs, cx) = setup(
+            r#"[
+                {
+                    "label": "report",
+                    "command": "./report.sh",
+                    "triggers": { "on_file_change": ["src/**", "out/**"], "exclude": ["out/**"] }
+                }
+            ]"#,
+            cx,
+        )
+        .await;
+
+        fs.insert_file(path!("/dir/src/main.rs"), b"fn main() { }".to_vec())
+            .await;
+        cx.run_until_parked();
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.run_until_parked();
+        assert_eq!(terminal_provider.spawned_files(), vec!["main.rs"]);
+
+        // The task writing its output, which is excluded, doesn't trigger it again.
+        fs.insert_file(path!("/dir/out/report.txt"), b"ok".to_vec())
+            .await;
+        cx.run_until_parked();
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.run_until_parked();
+        assert_eq!(terminal_provider.spawned_files(), vec!["main.rs"]);
+
+        // A change made while the first run is in progress replaces it.
+        fs.insert_file(path!("/dir/src/lib.rs"), b"pub fn lib() {}".to_vec())
+            .await;
+        cx.run_until_parked();
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.run_until_parked();
+        assert_eq!(terminal_provider.spawned_files(), vec!["main.rs", "lib.rs"]);
+        assert!(
+            terminal_provider
+                .exit_senders
+                .borrow()
+                .iter()
+                .all(|exit_tx| !exit_tx.is_canceled()),
+            "the new run should start while the first one is still in progress"
+        );
+        let spawned = terminal_provider.spawned.borrow();
+        assert_eq!(spawned[0].full_label, spawned[1].full_label);
+        assert!(
+            spawned[1].allow_concurrent_runs && !spawned[1].use_new_terminal,
+            "the new run should replace the terminal of the previous one"
+        );
+    }
+}
@@ -890,6 +890,16 @@ pub struct TaskState {
     pub spawned_task: SpawnInTerminal,
 }
 
+/// How far the output of a terminal was read with [`Terminal::read_output_lines`].
+#[derive(Debug, Default)]
+pub struct OutputPosition {
+    /// Index of the first unread line, counting from the top of the scrollback history.
+    line: usize,
+    /// The last lines read, to find the unread ones again after the full history drops its oldest lines.
+    anchor: String,
+    anchor_lines: usize,
+}
+
 /// A status of the current terminal tab's task.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TaskStatus {
@@ -1670,6 +1680,64 @@ impl Terminal {
         lines
     }
 
+    /// Returns the lines of output completed since `position`, and moves `position` past them.
+    ///
+    /// The line with the cursor may still change, so it's only read when `include_cursor_line` is set,
+    /// e.g. once the terminal's task has exited.
+    pub fn read_output_lines(
+        &self,
+        position: &mut OutputPosition,
+        include_cursor_line: bool,
+    ) -> Vec<String> {
+        const ANCHOR_LINES: usize = 4;
+
+        let term = self.term.lock_unfair();
+        let grid = term.grid();
+        let history_size = grid.history_size();
+        let line_at = |ix: usize| Line(ix as i32 - history_size as i32);
+        let text_between = |start: usize, end: usize| {
+            term.bounds_to_string(
+                AlacPoint::new(line_at(start), Column(0)),
+                AlacPoint::new(line_at(end - 1), term.last_column()),
+            )
+        };
+
+        let cursor_ix = (history_size as i32 + grid.cursor.point.line.0).max(0) as usize;
+        let mut end = cursor_ix;
+        if include_cursor_line {
+            end += 1;
+        } else {
+            // A line wrapped onto the cursor line is not complete either.
+            while end > 0
+                && grid[line_at(end - 1)][term.last_column()]
+                    .flags
+                    .contains(Flags::WRAPLINE)
+            {
+                end -= 1;
+            }
+        }
+
+        let mut start = position.line.min(end);
+        if history_size >= self.term_config.scrolling_history && position.anchor_lines > 0 {
+            // Once the history is full, each new line drops the oldest one, shifting the unread lines up.
+            start = (position.anchor_lines..=start)
+                .rev()
+                .find(|&ix| text_between(ix - position.anchor_lines, ix) == position.anchor)
+                .unwrap_or(0);
+        }
+        if start >= end {
+            return Vec::new();
+        }
+
+        position.line = end;
+        position.anchor_lines = end.min(ANCHOR_LINES);
+        position.anchor = text_between(end - position.anchor_lines, end);
+        text_between(start, end)
+            .lines()
+            .map(|line| line.trim_end().to_string())
+            .collect()
+    }
+
     fn find_logical_line_start(&self, grid: &Grid<Cell>, current: i32, topmost: i32) -> i32 {
         let mut line_start = current;
         while line_start > topmost {
@@ -2981,6 +3049,47 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    async fn test_read_output_lines(cx: &mut TestAppContext) {
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new_display_only(
+                CursorShape::default(),
+                AlternateScroll::On,
+                Some(10),
+                0,
+                cx.background_executor(),
+                PathStyle::local(),
+            )
+            .unwrap()
+            .subscribe(cx)
+        });
+        let mut position = OutputPosition::default();
+        let mut read = |output: &str, include_cursor_line: bool, cx: &mut TestAppContext| {
+            terminal.update(cx, |terminal, cx| {
+                terminal.write_output(output.as_bytes(), cx);
+                terminal.read_output_lines(&mut position, include_cursor_line)
+            })
+        };
+
+        assert_eq!(read("a\nb\npartial", false, cx), ["a", "b"]);
+        assert_eq!(read("ly done\nc\n", false, cx), ["partially done", "c"]);
+        assert_eq!(read("", false, cx), Vec::<String>::new());
+
+        // Lines keep being read in order once the full history drops the oldest ones.
+        for batch in 0..20 {
+            let lines = (0..3)
+                .map(|ix| format!("line {}", batch * 3 + ix))
+                .collect::<Vec<_>>();
+            let output = lines
+                .iter()
+                .map(|line| format!("{line}\n"))
+                .collect::<String>();
+            assert_eq!(read(&output, false, cx), lines);
+        }
+
+        assert_eq!(read("last", true, cx), ["last"]);
+    }
+
     #[gpui::test]
     async fn test_write_output_converts_lf_to_crlf(cx: &mut TestAppContext) {
         let terminal = cx.new(|cx| {
@@ -84,6 +84,7 @@ pub struct TerminalPanel {
     pending_serialization: Task<Option<()>>,
     pending_terminals_to_add: usize,
     deferred_tasks: HashMap<TaskId, Task<()>>,
+    task_group_panes: HashMap<String, WeakEntity<Pane>>,
     assistant_enabled: bool,
     assistant_tab_bar_button: Option<AnyView>,
     active: bool,
@@ -104,6 +105,7 @@ impl TerminalPanel {
             height: None,
             pending_terminals_to_add: 0,
             deferred_tasks: HashMap::default(),
+            task_group_panes: HashMap::default(),
             assistant_enabled: false,
             assistant_tab_bar_button: None,
             active: false,
@@ -714,6 +716,48 @@ impl TerminalPanel {
             .collect()
     }
 
+    /// Shows the terminal of a task spawned with [`RevealStrategy::OnFailure`], after the task failed.
+    fn reveal_failed_task(
+        &self,
+        terminal: &WeakEntity<Terminal>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let terminal_view = |pane: &Entity<Pane>, cx: &App| {
+            pane.read(cx).items().find_map(|item| {
+                let terminal_view = item.act_as::<TerminalView>(cx)?;
+                (terminal_view.read(cx).terminal().downgrade() == *terminal)
+                    .then_some(terminal_view)
+            })
+        };
+
+        let dock_terminal = self.center.panes().into_iter().find_map(|pane| {
+            let terminal_view = terminal_view(pane, cx)?;
+            let index = pane.read(cx).index_for_item(&terminal_view)?;
+            Some((pane.clone(), index))
+        });
+        if let Some((pane, index)) = dock_terminal {
+            self.activate_terminal_view(&pane, index, false, window, cx);
+            workspace.update(cx, |workspace, cx| {
+                workspace.open_panel::<Self>(window, cx);
+            });
+            return;
+        }
+
+        workspace.update(cx, |workspace, cx| {
+            let center_terminal = workspace
+                .panes()
+                .iter()
+                .find_map(|pane| terminal_view(pane, cx));
+            if let Some(terminal_view) = center_terminal {
+                workspace.activate_item(&terminal_view, true, false, window, cx);
+            }
+        });
+    }
+
     fn activate_terminal_view(
         &self,
         pane: &Entity<Pane>,
@@ -775,9 +819,13 @@ impl TerminalPanel {
             if workspace.update(cx, |workspace, cx| !is_enabled_in_workspace(workspace, cx))? {
                 anyhow::bail!("terminal not yet supported for remote projects");
             }
-            let pane = terminal_panel.update(cx, |terminal_panel, _| {
+            let pane = terminal_panel.update_in(cx, |terminal_panel, window, cx| {
+                let pane = match &task.group {
+                    Some(group) => terminal_panel.task_group_pane(group, window, cx),
+                    None => terminal_panel.active_pane.clone(),
+                };
                 terminal_panel.pending_terminals_to_add += 1;
-                terminal_panel.active_pane.clone()
+                pane
             })?;
             let project = workspace.read_with(cx, |workspace, _| workspace.project().clone())?;
             let terminal = project
@@ -802,7 +850,7 @@ impl TerminalPanel {
                     RevealStrategy::NoFocus => {
                         workspace.open_panel::<Self>(window, cx);
                     }
-                    RevealStrategy::Never => {}
+                    RevealStrategy::Never | RevealStrategy::OnFailure => {}
                 }
 
                 pane.update(cx, |pane, cx| {
@@ -821,6 +869,44 @@ impl TerminalPanel {
         })
     }
 
+    /// Returns the pane that holds the terminals of a task and its dependencies,
+    /// splitting a new one off the active pane the first time the group is spawned.
+    fn task_group_pane(
+        &mut self,
+        group: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<Pane> {
+        if let Some(pane) = self
+            .task_group_panes
+            .get(group)
+            .and_then(|pane| pane.upgrade())
+            .filter(|pane| self.center.panes().contains(&pane))
+        {
+            return pane;
+        }
+
+        let pane = if self.has_no_terminals(cx) {
+            self.active_pane.clone()
+        } else if let Ok(project) = self
+            .workspace
+            .read_with(cx, |workspace, _| workspace.project().clone())
+        {
+            let is_zoomed = self.active_pane.read(cx).is_zoomed();
+            let new_pane =
+                new_terminal_pane(self.workspace.clone(), project, is_zoomed, window, cx);
+            self.apply_tab_bar_buttons(&new_pane, cx);
+            self.center
+                .split(&self.active_pane, &new_pane, SplitDirection::Right, cx);
+            new_pane
+        } else {
+            return self.active_pane.clone();
+        };
+        self.task_group_panes
+            .insert(group.to_string(), pane.downgrade());
+        pane
+    }
+
     fn add_terminal_shell(
         &mut self,
         cwd: Option<PathBuf>,
@@ -890,7 +976,7 @@ impl TerminalPanel {
                             RevealStrategy::NoFocus => {
                                 workspace.open_panel::<Self>(window, cx);
                             }
-                            RevealStrategy::Never => {}
+                            RevealStrategy::Never | RevealStrategy::OnFailure => {}
                         }
 
                         pane.update(cx, |pane, cx| {
@@ -1067,7 +1153,7 @@ impl TerminalPanel {
                         .detach();
                     }
                 },
-                RevealStrategy::Never => {}
+                RevealStrategy::Never | RevealStrategy::OnFailure => {}
             }
 
             Ok(new_terminal.downgrade())
@@ -1802,6 +1888,13 @@ impl workspace::TerminalProvider for TerminalProvider {
                         .read_with(cx, |terminal, cx| terminal.wait_for_completed_task(cx))
                         .ok()?
                         .await?;
+                    if task.reveal == RevealStrategy::OnFailure && !exit_status.success() {
+                        terminal_panel
+                            .update_in(cx, |terminal_panel, window, cx| {
+                                terminal_panel.reveal_failed_task(&terminal, window, cx)
+                            })
+                            .ok();
+                    }
                     Some(Ok(exit_status))
                 }
                 Err(e) => Some(Err(e)),
@@ -2025,6 +2118,165 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_reveal_on_failure(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let window_handle =
+            cx.add_window(|window, cx| MultiWorkspace::test_new(project, window, cx));
+        let workspace = window_handle
+            .read_with(cx, |multi_workspace, _| multi_workspace.workspace().clone())
+            .unwrap();
+        let terminal_panel = window_handle
+            .update(cx, |_, window, cx| {
+                workspace.update(cx, |workspace, cx| {
+                    let terminal_panel = cx.new(|cx| TerminalPanel::new(workspace, window, cx));
+                    workspace.add_panel(terminal_panel.clone(), window, cx);
+                    terminal_panel
+                })
+            })
+            .unwrap();
+        let dock_is_open = |cx: &mut TestAppContext| {
+            workspace.read_with(cx, |workspace, cx| {
+                workspace
+                    .dock_at_position(DockPosition::Bottom)
+                    .read(cx)
+                    .is_open()
+            })
+        };
+
+        let task = SpawnInTerminal {
+            reveal: RevealStrategy::OnFailure,
+            ..test_task("pass", "exit 0")
+        };
+        let exit_status = run_task(task, &terminal_panel, window_handle, cx).await;
+        assert!(exit_status.success());
+        assert!(
+            !dock_is_open(cx),
+            "a successful task should stay in the background"
+        );
+
+        let task = SpawnInTerminal {
+            reveal: RevealStrategy::OnFailure,
+            ..test_task("fail", "exit 1")
+        };
+        let exit_status = run_task(task, &terminal_panel, window_handle, cx).await;
+        assert!(!exit_status.success());
+        assert!(dock_is_open(cx), "a failed task should reveal its terminal");
+        let active_task_label = terminal_panel.read_with(cx, |terminal_panel, cx| {
+            let terminal_view = terminal_panel
+                .active_pane
+                .read(cx)
+                .active_item()?
+                .act_as::<TerminalView>(cx)?;
+            let terminal = terminal_view.read(cx).terminal().read(cx);
+            Some(terminal.task()?.spawned_task.label.clone())
+        });
+        assert_eq!(active_task_label.as_deref(), Some("fail"));
+    }
+
+    #[gpui::test]
+    async fn test_task_group_shares_a_pane(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let window_handle =
+            cx.add_window(|window, cx| MultiWorkspace::test_new(project, window, cx));
+        let terminal_panel = window_handle
+            .update(cx, |multi_workspace, window, cx| {
+                multi_workspace.workspace().update(cx, |workspace, cx| {
+                    cx.new(|cx| TerminalPanel::new(workspace, window, cx))
+                })
+            })
+            .unwrap();
+        let task_panes = |cx: &mut TestAppContext| {
+            terminal_panel.read_with(cx, |terminal_panel, cx| {
+                terminal_panel
+                    .center
+                    .panes()
+                    .into_iter()
+                    .map(|pane| {
+                        pane.read(cx)
+                            .items()
+                            .filter_map(|item| {
+                                let terminal_view = item.act_as::<TerminalView>(cx)?;
+                                let terminal = terminal_view.read(cx).terminal().read(cx);
+                                Some(terminal.task()?.spawned_task.label.clone())
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        run_task(
+            test_task("watch", "exit 0"),
+            &terminal_panel,
+            window_handle,
+            cx,
+        )
+        .await;
+        for (label, group) in [("build › lint", "build"), ("build", "build")] {
+            let task = SpawnInTerminal {
+                group: Some(group.to_string()),
+                ..test_task(label, "exit 0")
+            };
+            run_task(task, &terminal_panel, window_handle, cx).await;
+        }
+        assert_eq!(
+            task_panes(cx),
+            vec![vec!["watch"], vec!["build › lint", "build"]],
+            "a task and its dependencies should share a pane split off the active one"
+        );
+
+        let task = SpawnInTerminal {
+            group: Some("build".to_string()),
+            ..test_task("build › test", "exit 0")
+        };
+        run_task(task, &terminal_panel, window_handle, cx).await;
+        assert_eq!(
+            task_panes(cx),
+            vec![vec!["watch"], vec!["build › lint", "build", "build › test"]],
+            "later runs of the group should reuse its pane"
+        );
+    }
+
+    fn test_task(label: &str, command: &str) -> SpawnInTerminal {
+        SpawnInTerminal {
+            id: TaskId(label.to_string()),
+            label: label.to_string(),
+            full_label: label.to_string(),
+            command: Some(command.to_string()),
+            ..SpawnInTerminal::default()
+        }
+    }
+
+    async fn run_task(
+        task: SpawnInTerminal,
+        terminal_panel: &Entity<TerminalPanel>,
+        window_handle: gpui::WindowHandle<MultiWorkspace>,
+        cx: &mut TestAppContext,
+    ) -> ExitStatus {
+        window_handle
+            .update(cx, |_, window, cx| {
+                workspace::TerminalProvider::spawn(
+                    &TerminalProvider(terminal_panel.clone()),
+                    task,
+                    window,
+                    cx,
+                )
+            })
+            .unwrap()
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
     fn set_max_tabs(cx: &mut TestAppContext, value: Option<usize>) {
         cx.update_global(|store: &mut SettingsStore, cx| {
             store.update_user_settings(cx, |settings| {
@@ -1,12 +1,18 @@
-use std::process::ExitStatus;
+use std::{cell::RefCell, process::ExitStatus, rc::Rc};
 
-use anyhow::Result;
-use gpui::{AppContext, Context, Entity, Task};
+use anyhow::{Context as _, Result, anyhow, bail};
+use collections::{HashMap, HashSet};
+use futures::{
+    FutureExt as _,
+    future::{LocalBoxFuture, Shared},
+};
+use gpui::{AppContext, AsyncWindowContext, Context, Entity, Task, WeakEntity};
 use language::Buffer;
 use project::{TaskSourceKind, WorktreeId};
 use remote::ConnectionState;
 use task::{
-    DebugScenario, ResolvedTask, SharedTaskContext, SpawnInTerminal, TaskContext, TaskTemplate,
+    DebugScenario, DependsOrder, ResolvedTask, SharedTaskContext, SpawnInTerminal, TaskContext,
+    TaskDependencyTree, TaskInput, TaskTemplate,
 };
 use ui::Window;
 
@@ -57,6 +63,10 @@ impl Workspace {
         cx: &mut Context<Workspace>,
     ) {
         let spawn_in_terminal = resolved_task.resolved.clone();
+        let has_dependencies = !resolved_task.original_task().depends_on.is_empty();
+        let has_inputs = !resolved_task.original_task().inputs.is_empty();
+        let scheduled_task = (has_dependencies || has_inputs)
+            .then(|| (task_source_kind.clone(), resolved_task.clone()));
         if !omit_history {
             if let Some(debugger_provider) = self.debugger_provider.as_ref() {
                 debugger_provider.task_scheduled(cx);
@@ -73,6 +83,25 @@ impl Workspace {
             });
         }
 
+        if let Some((task_source_kind, resolved_task)) = scheduled_task {
+            self.schedule_task_with_inputs_and_dependencies(
+                task_source_kind,
+                resolved_task,
+                window,
+                cx,
+            );
+            return;
+        }
+
+        self.spawn_scheduled_task(spawn_in_terminal, window, cx);
+    }
+
+    fn spawn_scheduled_task(
+        &mut self,
+        spawn_in_terminal: SpawnInTerminal,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         if let Some(terminal_provider) = self.terminal_provider.as_ref() {
             let task_status = terminal_provider.spawn(spawn_in_terminal, window, cx);
 
@@ -100,6 +129,193 @@ impl Workspace {
         }
     }
 
+    /// Asks for the inputs of the task and of the tasks it depends on, runs those tasks,
+    /// then the task itself, stopping at the first failure.
+    fn schedule_task_with_inputs_and_dependencies(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let templates = if resolved_task.original_task().depends_on.is_empty() {
+            None
+        } else {
+            let Some(task_inventory) = self
+                .project
+                .read(cx)
+                .task_store()
+                .read(cx)
+                .task_inventory()
+                .cloned()
+            else {
+                return;
+            };
+            let worktree_id = match &task_source_kind {
+                TaskSourceKind::Worktree { id, .. } => Some(*id),
+                _ => None,
+            };
+            Some(
+                task_inventory
+                    .read(cx)
+                    .list_tasks(None, None, worktree_id, cx),
+            )
+        };
+
+        let task = cx.spawn_in(window, async move |workspace, cx| {
+            let label = resolved_task.resolved.label.clone();
+            let result = async {
+                let dependencies = match templates {
+                    Some(templates) => {
+                        let templates = templates.await;
+                        TaskDependencyTree::build(
+                            task_source_kind,
+                            resolved_task.original_task().clone(),
+                            &mut |label: &str| {
+                                templates
+                                    .iter()
+                                    .find(|(_, template)| template.label == label)
+                                    .cloned()
+                            },
+                        )?
+                        .dependencies
+                    }
+                    None => Vec::new(),
+                };
+
+                let mut inputs = Vec::new();
+                collect_task_inputs(resolved_task.original_task(), &dependencies, &mut inputs);
+                let input_values = if inputs.is_empty() {
+                    HashMap::default()
+                } else {
+                    let prompt = workspace.update_in(cx, |workspace, window, cx| {
+                        workspace.prompt_for_task_inputs(
+                            &resolved_task.original_task().label,
+                            inputs,
+                            resolved_task.task_context(),
+                            window,
+                            cx,
+                        )
+                    })?;
+                    let Some(input_values) = prompt.await else {
+                        log::debug!("Task `{label}` got cancelled");
+                        return Ok(());
+                    };
+                    input_values
+                };
+                let resolved_task = resolved_task.with_input_values(&input_values);
+
+                if dependencies.is_empty() {
+                    workspace.update_in(cx, |workspace, window, cx| {
+                        workspace.spawn_scheduled_task(resolved_task.resolved, window, cx)
+                    })?;
+                    return Ok(());
+                }
+
+                let label = resolved_task.resolved.label.clone();
+                run_task_dependencies(
+                    workspace.clone(),
+                    dependencies,
+                    resolved_task.original_task().depends_order,
+                    label.clone(),
+                    resolved_task.task_context().clone(),
+                    input_values,
+                    DependencyRuns::default(),
+                    cx.clone(),
+                )
+                .await?;
+                if resolved_task.runs_dependencies_only() {
+                    return Ok(());
+                }
+                let mut spawn_in_terminal = resolved_task.resolved;
+                spawn_in_terminal.group = Some(label.clone());
+                let task_status = workspace.update_in(cx, |workspace, window, cx| {
+                    workspace.spawn_in_terminal(spawn_in_terminal, window, cx)
+                })?;
+                task_status_to_result(&label, task_status.await)
+            }
+            .await;
+
+            if let Err(e) = result {
+                log::error!("Task `{label}` failed: {e:#}");
+                workspace
+                    .update(cx, |workspace, cx| {
+                        let id = NotificationId::unique::<ResolvedTask>();
+                        workspace.show_toast(Toast::new(id, format!("{e:#}")), cx);
+                    })
+                    .ok();
+            }
+        });
+        self.scheduled_tasks.push(task);
+    }
+
+    /// Asks the user for the values of the inputs of the task or debug scenario with the given label,
+    /// suggesting the values entered the last time it was run.
+    pub fn prompt_for_task_inputs(
+        &mut self,
+        label: &str,
+        inputs: Vec<TaskInput>,
+        task_context: &TaskContext,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<HashMap<String, String>>> {
+        let task_inventory = self
+            .project
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned();
+        let mut values = inputs
+            .iter()
+            .filter_map(|input| Some((input.id.clone(), input.default.clone()?)))
+            .collect::<HashMap<_, _>>();
+        if let Some(last_values) = task_inventory
+            .as_ref()
+            .and_then(|inventory| inventory.read(cx).last_input_values(label))
+        {
+            values.extend(
+                inputs.iter().filter_map(|input| {
+                    Some((input.id.clone(), last_values.get(&input.id)?.clone()))
+                }),
+            );
+        }
+        let Some(provider) = self.task_input_provider.clone() else {
+            return Task::ready(Some(values));
+        };
+
+        // Passwords are never remembered.
+        let password_ids = inputs
+            .iter()
+            .filter(|input| input.password)
+            .map(|input| input.id.clone())
+            .collect::<HashSet<_>>();
+        let label = label.to_string();
+        let prompt = provider.prompt(
+            self,
+            label.clone().into(),
+            inputs,
+            values,
+            task_context.clone(),
+            window,
+            cx,
+        );
+        cx.spawn(async move |_, cx| {
+            let values = prompt.await?;
+            if let Some(task_inventory) = task_inventory {
+                let remembered_values = values
+                    .iter()
+                    .filter(|(id, _)| !password_ids.contains(*id))
+                    .map(|(id, value)| (id.clone(), value.clone()))
+                    .collect();
+                task_inventory.update(cx, |inventory, _| {
+                    inventory.inputs_entered(label, remembered_values)
+                });
+            }
+            Some(values)
+        })
+    }
+
     pub fn start_debug_session(
         &mut self,
         scenario: DebugScenario,
@@ -134,3 +350,139 @@ impl Workspace {
         }
     }
 }
+
+/// Gathers the inputs declared by the task and the tasks it depends on, the first declaration of every id winning.
+fn collect_task_inputs(
+    template: &TaskTemplate,
+    dependencies: &[TaskDependencyTree<TaskSourceKind>],
+    inputs: &mut Vec<TaskInput>,
+) {
+    for input in &template.inputs {
+        if !inputs.iter().any(|known_input| known_input.id == input.id) {
+            inputs.push(input.clone());
+        }
+    }
+    for dependency in dependencies {
+        collect_task_inputs(&dependency.template, &dependency.dependencies, inputs);
+    }
+}
+
+/// The runs of the dependencies of a task, by label, so that a dependency shared by several
+/// tasks is only run once and all of them wait for it.
+type DependencyRuns =
+    Rc<RefCell<HashMap<String, Shared<LocalBoxFuture<'static, Result<(), Rc<anyhow::Error>>>>>>>;
+
+fn run_task_dependencies(
+    workspace: WeakEntity<Workspace>,
+    dependencies: Vec<TaskDependencyTree<TaskSourceKind>>,
+    order: DependsOrder,
+    group_label: String,
+    task_context: TaskContext,
+    input_values: HashMap<String, String>,
+    runs: DependencyRuns,
+    cx: AsyncWindowContext,
+) -> LocalBoxFuture<'static, Result<()>> {
+    async move {
+        let dependencies = dependencies.into_iter().map(|dependency| {
+            let label = dependency.template.label.clone();
+            let workspace = workspace.clone();
+            let group_label = group_label.clone();
+            let task_context = task_context.clone();
+            let input_values = input_values.clone();
+            let runs = runs.clone();
+            let cx = cx.clone();
+            async move {
+                // The first time a dependency is reached, it's the occurrence in the tree that
+                // lists its own dependencies.
+                let run = runs
+                    .borrow_mut()
+                    .entry(label)
+                    .or_insert_with(|| {
+                        run_task_dependency(
+                            workspace,
+                            dependency,
+                            group_label,
+                            task_context,
+                            input_values,
+                            runs.clone(),
+                            cx,
+                        )
+                        .map(|result| result.map_err(Rc::new))
+                        .boxed_local()
+                        .shared()
+                    })
+                    .clone();
+                run.await.map_err(|error| anyhow!("{error:#}"))
+            }
+        });
+        match order {
+            DependsOrder::Sequence => {
+                for dependency in dependencies {
+                    dependency.await?;
+                }
+            }
+            DependsOrder::Parallel => {
+                futures::future::try_join_all(dependencies).await?;
+            }
+        }
+        Ok(())
+    }
+    .boxed_local()
+}
+
+fn run_task_dependency(
+    workspace: WeakEntity<Workspace>,
+    dependency: TaskDependencyTree<TaskSourceKind>,
+    group_label: String,
+    task_context: TaskContext,
+    input_values: HashMap<String, String>,
+    runs: DependencyRuns,
+    mut cx: AsyncWindowContext,
+) -> LocalBoxFuture<'static, Result<()>> {
+    async move {
+        let TaskDependencyTree {
+            source,
+            template,
+            dependencies,
+        } = dependency;
+        run_task_dependencies(
+            workspace.clone(),
+            dependencies,
+            template.depends_order,
+            group_label.clone(),
+            task_context.clone(),
+            input_values.clone(),
+            runs,
+            cx.clone(),
+        )
+        .await?;
+        if template.command.trim().is_empty() {
+            return Ok(());
+        }
+
+        let resolved_task = template
+            .resolve_task(&source.to_id_base(), &task_context)
+            .with_context(|| format!("Failed to resolve task `{}`", template.label))?
+            .with_input_values(&input_values);
+        let mut spawn_in_terminal = resolved_task.resolved;
+        // Dependency terminals are shown in the parent task's pane, with the tab label prefixed
+        // by the task that pulled the dependency in, to tell where it came from.
+        spawn_in_terminal.label = format!("{group_label} › {}", spawn_in_terminal.label);
+        spawn_in_terminal.group = Some(group_label);
+        let label = spawn_in_terminal.label.clone();
+        let task_status = workspace.update_in(&mut cx, |workspace, window, cx| {
+            workspace.spawn_in_terminal(spawn_in_terminal, window, cx)
+        })?;
+        task_status_to_result(&label, task_status.await)
+    }
+    .boxed_local()
+}
+
+fn task_status_to_result(label: &str, status: Option<Result<ExitStatus>>) -> Result<()> {
+    match status {
+        Some(Ok(status)) if status.success() => Ok(()),
+        Some(Ok(status)) => bail!("Task `{label}` failed with {status}"),
+        Some(Err(e)) => Err(e.context(format!("Failed to spawn task `{label}`"))),
+        None => bail!("Task `{label}` was cancelled"),
+    }
+}
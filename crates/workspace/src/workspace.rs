@@ -84,8 +84,9 @@ pub use persistence::{
 };
 use postage::stream::Stream;
 use project::{
-    DirectoryLister, Project, ProjectEntryId, ProjectPath, ResolvedPath, Worktree, WorktreeId,
-    WorktreeSettings,
+    DeclinedProjectItem, DirectoryLister, Project, ProjectEntryId, ProjectPath, ResolvedPath,
+    Worktree, WorktreeId, WorktreeSettings,
+    bookmark_store::BookmarkStoreEvent,
     debugger::{breakpoint_store::BreakpointStoreEvent, session::ThreadStatus},
     project_settings::ProjectSettings,
     toolchain_store::ToolchainStoreEvent,
@@ -125,7 +126,7 @@ use std::{
     },
     time::Duration,
 };
-use task::{DebugScenario, SharedTaskContext, SpawnInTerminal};
+use task::{DebugScenario, SharedTaskContext, SpawnInTerminal, TaskContext, TaskInput};
 use theme::{ActiveTheme, GlobalTheme, SystemAppearance, ThemeSettings};
 pub use toolbar::{
     PaneSearchBarCallbacks, Toolbar, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView,
@@ -206,6 +207,21 @@ pub trait DebuggerProvider {
     fn active_thread_state(&self, cx: &App) -> Option<ThreadStatus>;
 }
 
+pub trait TaskInputProvider {
+    /// Asks the user for the values of the inputs, one after another, starting with the `values` given.
+    /// Resolves to `None` if the user dismissed any of the prompts.
+    fn prompt(
+        &self,
+        workspace: &mut Workspace,
+        title: SharedString,
+        inputs: Vec<TaskInput>,
+        values: HashMap<String, String>,
+        task_context: TaskContext,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Option<HashMap<String, String>>>;
+}
+
 actions!(
     workspace,
     [
@@ -783,6 +799,7 @@ impl ProjectItemRegistry {
                             ) as Box<_>;
                             Ok((project_entry_id, build_workspace_item))
                         }
+                        Err(e) if e.is::<DeclinedProjectItem>() => Err(e),
                         Err(e) => {
                             log::warn!("Failed to open a project item: {e:#}");
                             if e.error_code() == ErrorCode::Internal {
@@ -820,15 +837,39 @@ impl ProjectItemRegistry {
         window: &mut Window,
         cx: &mut App,
     ) -> Task<Result<(Option<ProjectEntryId>, WorkspaceItemBuilder)>> {
-        let Some(open_project_item) = self
-            .build_project_item_for_path_fns
-            .iter()
-            .rev()
-            .find_map(|open_project_item| open_project_item(project, path, window, cx))
+        fn open_next(
+            open_fns: &mut Vec<BuildProjectItemForPathFn>,
+            project: &Entity<Project>,
+            path: &ProjectPath,
+            window: &mut Window,
+            cx: &mut App,
+        ) -> Option<Task<Result<(Option<ProjectEntryId>, WorkspaceItemBuilder)>>> {
+            // The project items that were registered last get the first chance to open the path.
+            std::iter::from_fn(|| open_fns.pop())
+                .find_map(|open_project_item| open_project_item(project, path, window, cx))
+        }
+
+        let mut open_fns = self.build_project_item_for_path_fns.clone();
+        let Some(mut open_project_item) = open_next(&mut open_fns, project, path, window, cx)
         else {
             return Task::ready(Err(anyhow!("cannot open file {:?}", path.path)));
         };
-        open_project_item
+        let project = project.clone();
+        let path = path.clone();
+        window.spawn(cx, async move |cx| {
+            loop {
+                match open_project_item.await {
+                    Err(error) if error.is::<DeclinedProjectItem>() => {
+                        open_project_item = cx
+                            .update(|window, cx| {
+                                open_next(&mut open_fns, &project, &path, window, cx)
+                            })?
+                            .with_context(|| format!("cannot open file {:?}", path.path))?;
+                    }
+                    result => return result,
+                }
+            }
+        })
     }
 
     fn build_item<T: project::ProjectItem>(
@@ -1284,6 +1325,7 @@ pub struct Workspace {
     on_prompt_for_open_path: Option<PromptForOpenPath>,
     terminal_provider: Option<Box<dyn TerminalProvider>>,
     debugger_provider: Option<Arc<dyn DebuggerProvider>>,
+    task_input_provider: Option<Arc<dyn TaskInputProvider>>,
     serializable_items_tx: UnboundedSender<Box<dyn SerializableItemHandle>>,
     _items_serializer: Task<Result<()>>,
     session_id: Option<String>,
@@ -1471,6 +1513,17 @@ impl Workspace {
             },
         )
         .detach();
+        cx.subscribe_in(
+            &project.read(cx).bookmark_store(),
+            window,
+            |workspace, _, event, window, cx| match event {
+                BookmarkStoreEvent::BookmarksUpdated(_)
+                | BookmarkStoreEvent::BookmarksCleared(_) => {
+                    workspace.serialize_workspace(window, cx);
+                }
+            },
+        )
+        .detach();
         if let Some(toolchain_store) = project.read(cx).toolchain_store() {
             cx.subscribe_in(
                 &toolchain_store,
@@ -1687,6 +1740,7 @@ impl Workspace {
             on_prompt_for_open_path: None,
             terminal_provider: None,
             debugger_provider: None,
+            task_input_provider: None,
             serializable_items_tx,
             _items_serializer,
             session_id: Some(session_id),
@@ -2400,6 +2454,10 @@ impl Workspace {
         self.debugger_provider.clone()
     }
 
+    pub fn set_task_input_provider(&mut self, provider: impl TaskInputProvider + 'static) {
+        self.task_input_provider = Some(Arc::new(provider));
+    }
+
     pub fn prompt_for_open_path(
         &mut self,
         path_prompt_options: PathPromptOptions,
@@ -2865,7 +2923,9 @@ impl Workspace {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Task<Result<bool>> {
-        if self.project.read(cx).is_disconnected(cx) {
+        // Remote server projects queue saves while disconnected, and replay them on reconnect.
+        let project = self.project.read(cx);
+        if project.is_disconnected(cx) && !project.is_via_remote_server() {
             return Task::ready(Ok(true));
         }
         let dirty_items = self
@@ -6069,6 +6129,12 @@ impl Workspace {
                         .read(cx)
                         .all_source_breakpoints(cx)
                 });
+                let bookmarks = self
+                    .project
+                    .read(cx)
+                    .bookmark_store()
+                    .read(cx)
+                    .all_serialized_bookmarks(cx);
                 let user_toolchains = self
                     .project
                     .read(cx)
@@ -6090,6 +6156,7 @@ impl Workspace {
                     centered_layout: self.centered_layout,
                     session_id: self.session_id.clone(),
                     breakpoints,
+                    bookmarks,
                     window_id: Some(window.window_handle().window_id().as_u64()),
                     user_toolchains,
                 };
@@ -6304,6 +6371,13 @@ impl Workspace {
                         })
                 })
                 .await;
+            let _ = project
+                .update(cx, |project, cx| {
+                    project.bookmark_store().update(cx, |bookmark_store, cx| {
+                        bookmark_store.with_serialized_bookmarks(serialized_workspace.bookmarks, cx)
+                    })
+                })
+                .await;
 
             // Clean up all the items that have _not_ been loaded. Our ItemIds aren't stable. That means
             // after loading the items, we might have different items and in order to avoid
@@ -8507,6 +8581,9 @@ pub fn workspace_windows_for_location(
                 (RemoteConnectionOptions::Docker(a), RemoteConnectionOptions::Docker(b)) => {
                     a.container_id == b.container_id
                 }
+                (RemoteConnectionOptions::Command(a), RemoteConnectionOptions::Command(b)) => {
+                    a.command == b.command
+                }
                 #[cfg(any(test, feature = "test-support"))]
                 (RemoteConnectionOptions::Mock(a), RemoteConnectionOptions::Mock(b)) => {
                     a.id == b.id
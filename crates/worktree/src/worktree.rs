@@ -757,6 +757,77 @@ impl Worktree {
         }
     }
 
+    /// Loads the raw bytes of a file, or only its first `max_len` bytes when given.
+    pub fn load_bytes(
+        &self,
+        path: &RelPath,
+        max_len: Option<usize>,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<Vec<u8>>> {
+        match self {
+            Worktree::Local(this) => {
+                let fs = this.fs.clone();
+                let abs_path = this.absolutize(path);
+                cx.background_spawn(async move {
+                    match max_len {
+                        Some(max_len) => fs.load_byte_range(&abs_path, 0..max_len as u64).await,
+                        None => fs.load_bytes(&abs_path).await,
+                    }
+                    .with_context(|| format!("reading {abs_path:?}"))
+                })
+            }
+            Worktree::Remote(this) => {
+                let request = this.client.request(proto::LoadBinaryFile {
+                    project_id: this.project_id,
+                    worktree_id: this.id().to_proto(),
+                    path: path.to_proto(),
+                    max_len: max_len.map(|max_len| max_len as u64),
+                });
+                cx.spawn(async move |_, _| Ok(request.await?.content))
+            }
+        }
+    }
+
+    /// Writes raw bytes to a file, replacing its contents.
+    pub fn write_bytes(
+        &self,
+        path: Arc<RelPath>,
+        content: Vec<u8>,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<()>> {
+        match self {
+            Worktree::Local(this) => {
+                let fs = this.fs.clone();
+                let abs_path = this.absolutize(&path);
+                let write = cx.background_spawn(async move {
+                    fs.write(&abs_path, &content)
+                        .await
+                        .with_context(|| format!("writing {abs_path:?}"))
+                });
+                cx.spawn(async move |this, cx| {
+                    write.await?;
+                    this.update(cx, |this, cx| {
+                        this.as_local().unwrap().refresh_entry(path, None, cx)
+                    })?
+                    .await?;
+                    Ok(())
+                })
+            }
+            Worktree::Remote(this) => {
+                let request = this.client.request(proto::WriteBinaryFile {
+                    project_id: this.project_id,
+                    worktree_id: this.id().to_proto(),
+                    path: path.to_proto(),
+                    content,
+                });
+                cx.spawn(async move |_, _| {
+                    request.await?;
+                    Ok(())
+                })
+            }
+        }
+    }
+
     pub fn create_entry(
         &mut self,
         path: Arc<RelPath>,
@@ -939,6 +1010,35 @@ impl Worktree {
         })
     }
 
+    pub async fn handle_load_binary_file(
+        this: Entity<Self>,
+        request: proto::LoadBinaryFile,
+        mut cx: AsyncApp,
+    ) -> Result<proto::LoadBinaryFileResponse> {
+        let content = this.update(&mut cx, |this, cx| {
+            let path = RelPath::from_proto(&request.path)
+                .with_context(|| format!("received invalid relative path {:?}", request.path))?;
+            anyhow::Ok(this.load_bytes(&path, request.max_len.map(|max_len| max_len as usize), cx))
+        })?;
+        Ok(proto::LoadBinaryFileResponse {
+            content: content.await?,
+        })
+    }
+
+    pub async fn handle_write_binary_file(
+        this: Entity<Self>,
+        request: proto::WriteBinaryFile,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let write = this.update(&mut cx, |this, cx| {
+            let path = RelPath::from_proto(&request.path)
+                .with_context(|| format!("received invalid relative path {:?}", request.path))?;
+            anyhow::Ok(this.write_bytes(path, request.content, cx))
+        })?;
+        write.await?;
+        Ok(proto::Ack {})
+    }
+
     pub async fn handle_delete_entry(
         this: Entity<Self>,
         request: proto::DeleteProjectEntry,
@@ -5929,6 +6029,9 @@ impl fs::Watcher for NullWatcher {
 
 const FILE_ANALYSIS_BYTES: usize = 1024;
 
+/// The error that loading a file as text fails with when the file turns out to be binary.
+pub const BINARY_FILE_ERROR: &str = "Binary files are not supported";
+
 async fn decode_file_text(
     fs: &dyn Fs,
     abs_path: &Path,
@@ -5957,10 +6060,7 @@ async fn decode_file_text(
         file_first_bytes.extend_from_slice(&buf[..n]);
     }
     let (bom_encoding, byte_content) = decode_byte_header(&file_first_bytes);
-    anyhow::ensure!(
-        byte_content != ByteContent::Binary,
-        "Binary files are not supported"
-    );
+    anyhow::ensure!(byte_content != ByteContent::Binary, BINARY_FILE_ERROR);
 
     // If the file is eligible for opening, read the rest of the file.
     let mut content = file_first_bytes;
@@ -6008,7 +6108,7 @@ fn decode_byte_full(
             return Ok((cow.into_owned(), encoding, false));
         }
         ByteContent::Binary => {
-            anyhow::bail!("Binary files are not supported");
+            anyhow::bail!(BINARY_FILE_ERROR);
         }
         ByteContent::Unknown => {}
     }
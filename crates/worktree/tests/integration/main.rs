@@ -2737,6 +2737,61 @@ async fn test_load_file_encoding(cx: &mut TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_load_and_write_bytes(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/root"), json!({ "text.txt": "hello" }))
+        .await;
+    fs.insert_file(
+        path!("/root/app.bin"),
+        b"\x7fELF\x02\x01\x01\x00\x00\x00".to_vec(),
+    )
+    .await;
+
+    let worktree = Worktree::local(
+        Path::new(path!("/root")),
+        true,
+        fs.clone(),
+        Default::default(),
+        true,
+        WorktreeId::from_proto(0),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| worktree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+
+    let load_bytes = |path: &'static str, max_len: Option<usize>, cx: &mut TestAppContext| {
+        worktree.update(cx, |worktree, cx| {
+            worktree.load_bytes(rel_path(path), max_len, cx)
+        })
+    };
+    assert_eq!(load_bytes("text.txt", None, cx).await.unwrap(), b"hello");
+    assert_eq!(load_bytes("text.txt", Some(2), cx).await.unwrap(), b"he");
+    assert_eq!(
+        load_bytes("app.bin", None, cx).await.unwrap(),
+        b"\x7fELF\x02\x01\x01\x00\x00\x00"
+    );
+
+    worktree
+        .update(cx, |worktree, cx| {
+            worktree.write_bytes(rel_path("new.bin").into(), vec![0x00, 0xFF], cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes(Path::new(path!("/root/new.bin")))
+            .await
+            .unwrap(),
+        vec![0x00, 0xFF]
+    );
+    worktree.read_with(cx, |worktree, _| {
+        assert!(worktree.entry_for_path(rel_path("new.bin")).is_some());
+    });
+}
+
 #[gpui::test]
 async fn test_write_file_encoding(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -531,6 +531,7 @@ fn main() {
         let node_runtime = NodeRuntime::new(client.http_client(), Some(shell_env_loaded_rx), rx);
 
         debug_adapter_extension::init(extension_host_proxy.clone(), cx);
+        editor_command_extension::init(extension_host_proxy.clone(), cx);
         languages::init(languages.clone(), fs.clone(), node_runtime.clone(), cx);
         let user_store = cx.new(|cx| UserStore::new(client.clone(), cx));
         let workspace_store = cx.new(|cx| WorkspaceStore::new(client.clone(), cx));
@@ -668,8 +669,12 @@ fn main() {
 
         load_embedded_fonts(cx);
 
+        hex_editor::init(cx);
         editor::init(cx);
         image_viewer::init(cx);
+        large_file_viewer::init(cx);
+        bookmarks_ui::init(cx);
+        keyboard_macros::init(cx);
         // repl::notebook::init(cx);  // Temporarily disabled due to upstream dependency issue
         diagnostics::init(cx);
 
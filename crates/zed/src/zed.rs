@@ -466,6 +466,9 @@ pub fn initialize_workspace(
         let active_toolchain_language =
             cx.new(|cx| toolchain_selector::ActiveToolchain::new(workspace, window, cx));
         let vim_mode_indicator = cx.new(|cx| vim::ModeIndicator::new(window, cx));
+        let macro_recording_indicator = cx.new(keyboard_macros::RecordingIndicator::new);
+        let offline_status_indicator =
+            cx.new(|cx| recent_projects::OfflineStatusIndicator::new(workspace, cx));
         let image_info = cx.new(|_cx| ImageInfo::new(workspace));
 
         let lsp_button_menu_handle = PopoverMenuHandle::default();
@@ -486,11 +489,13 @@ pub fn initialize_workspace(
             status_bar.add_left_item(lsp_button, window, cx);
             status_bar.add_left_item(diagnostic_summary, window, cx);
             status_bar.add_left_item(activity_indicator, window, cx);
+            status_bar.add_left_item(offline_status_indicator, window, cx);
             status_bar.add_right_item(edit_prediction_ui, window, cx);
             status_bar.add_right_item(active_buffer_encoding, window, cx);
             status_bar.add_right_item(active_buffer_language, window, cx);
             status_bar.add_right_item(active_toolchain_language, window, cx);
             status_bar.add_right_item(line_ending_indicator, window, cx);
+            status_bar.add_right_item(macro_recording_indicator, window, cx);
             status_bar.add_right_item(vim_mode_indicator, window, cx);
             status_bar.add_right_item(cursor_position, window, cx);
             status_bar.add_right_item(image_info, window, cx);
@@ -632,6 +637,8 @@ fn initialize_panels(
             workspace_handle.clone(),
             cx.clone(),
         );
+        let bookmarks_panel =
+            bookmarks_ui::BookmarksPanel::load(workspace_handle.clone(), cx.clone());
         let debug_panel = DebugPanel::load(workspace_handle.clone(), cx);
 
         async fn add_panel_when_ready(
@@ -656,6 +663,7 @@ fn initialize_panels(
             add_panel_when_ready(git_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(channels_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(notification_panel, workspace_handle.clone(), cx.clone()),
+            add_panel_when_ready(bookmarks_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(debug_panel, workspace_handle.clone(), cx.clone()),
             initialize_agent_panel(workspace_handle, prompt_builder, cx.clone()).map(|r| r.log_err()),
         );
@@ -4798,6 +4806,8 @@ mod tests {
                 "auto_update",
                 "branch_picker",
                 "bedrock",
+                "bookmarks",
+                "bookmarks_panel",
                 "branches",
                 "buffer_search",
                 "channel_modal",
@@ -4818,25 +4828,30 @@ mod tests {
                 "encoding_selector",
                 "feedback",
                 "file_finder",
+                "filter_lines",
                 "git",
                 "git_graph",
                 "git_onboarding",
                 "git_panel",
                 "git_picker",
                 "go_to_line",
+                "hex_editor",
                 "highlights_tree_view",
                 "icon_theme_selector",
                 "image_viewer",
                 "inline_assistant",
                 "journal",
+                "keyboard_macros",
                 "keymap_editor",
                 "keystroke_input",
                 "language_selector",
+                "large_file_viewer",
                 "welcome",
                 "line_ending_selector",
                 "lsp_tool",
                 "markdown",
                 "menu",
+                "merge_editor",
                 "multi_workspace",
                 "new_process_modal",
                 "notebook",
@@ -4853,6 +4868,7 @@ mod tests {
                 "projects",
                 "recent_projects",
                 "remote_debug",
+                "remote_ports",
                 "repl",
                 "rules_library",
                 "search",
@@ -5003,6 +5019,7 @@ mod tests {
             workspace::init(app_state.clone(), cx);
             release_channel::init(Version::new(0, 0, 0), cx);
             command_palette::init(cx);
+            hex_editor::init(cx);
             editor::init(cx);
             collab_ui::init(&app_state, cx);
             git_ui::init(cx);
@@ -5016,6 +5033,9 @@ mod tests {
                 cx,
             );
             image_viewer::init(cx);
+            large_file_viewer::init(cx);
+            bookmarks_ui::init(cx);
+            keyboard_macros::init(cx);
             language_model::init(app_state.client.clone(), cx);
             language_models::init(app_state.user_store.clone(), app_state.client.clone(), cx);
             web_search::init(cx);
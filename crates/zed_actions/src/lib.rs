@@ -737,6 +737,18 @@ pub mod preview {
     }
 }
 
+pub mod hex_editor {
+    use gpui::actions;
+
+    actions!(
+        hex_editor,
+        [
+            /// Opens the active file in the hex editor.
+            OpenInHexEditor,
+        ]
+    );
+}
+
 pub mod notebook {
     use gpui::actions;
 